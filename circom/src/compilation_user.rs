@@ -1,7 +1,7 @@
 use crate::VERSION;
 use ansi_term::Colour;
 use compiler::compiler_interface;
-use compiler::compiler_interface::{Config, VCP};
+use compiler::compiler_interface::{Circuit, Config, OptimizationLevel, VCP};
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::Report;
 use program_structure::file_definition::FileLibrary;
@@ -15,25 +15,50 @@ pub struct CompilerConfig {
     pub c_run_name: String,
     pub c_file: String,
     pub dat_file: String,
+    pub rust_folder: String,
+    pub rust_run_name: String,
+    pub rust_file: String,
     pub wat_flag: bool,
     pub wasm_flag: bool,
     pub c_flag: bool,
-    pub debug_output: bool,
+    pub rust_flag: bool,
+    pub ir_output: Option<String>,
+    pub ir_input: Option<String>,
     pub produce_input_log: bool,
+    pub trace: Option<String>,
+    pub ir_optimization: OptimizationLevel,
     pub vcp: VCP,
 }
 
 pub fn compile(config: CompilerConfig) -> Result<(), ()> {
-    if config.c_flag || config.wat_flag || config.wasm_flag {
-        let circuit = compiler_interface::run_compiler(
+    if config.c_flag
+        || config.rust_flag
+        || config.wat_flag
+        || config.wasm_flag
+        || config.ir_output.is_some()
+    {
+        let mut circuit = compiler_interface::run_compiler(
             config.vcp,
             Config {
-                debug_output: config.debug_output,
                 produce_input_log: config.produce_input_log,
                 wat_flag: config.wat_flag,
+                trace: config.trace,
+                ir_optimization: config.ir_optimization,
             },
             VERSION,
         )?;
+        if let Some(file) = &config.ir_input {
+            load_ir(&mut circuit, file)?;
+        }
+        if let Some(file) = &config.ir_output {
+            compiler_interface::write_ir(&circuit, file).map_err(|_| {
+                eprintln!(
+                    "{}",
+                    Colour::Red.paint("Could not write the IR in the given path")
+                )
+            })?;
+            println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        }
 
         if config.c_flag {
             compiler_interface::write_c(
@@ -50,12 +75,31 @@ pub fn compile(config: CompilerConfig) -> Result<(), ()> {
                 config.dat_file
             );
             println!(
-                "{} {}/main.cpp, circom.hpp, calcwit.hpp, calcwit.cpp, fr.hpp, fr.cpp, fr.asm and Makefile",
+                "{} {}/main.cpp, circom.hpp, calcwit.hpp, calcwit.cpp, fr.hpp, fr.cpp and Makefile",
                 Colour::Green.paint("Written successfully:"),
                 &config.c_folder
             );
         }
 
+        if config.rust_flag {
+            compiler_interface::write_rust(
+                &circuit,
+                &config.rust_folder,
+                &config.rust_run_name,
+                &config.rust_file,
+            )?;
+            println!(
+                "{} {}",
+                Colour::Green.paint("Written successfully:"),
+                config.rust_file
+            );
+            println!(
+                "{} {}/Cargo.toml, src/field.rs and src/calcwit.rs",
+                Colour::Green.paint("Written successfully:"),
+                &config.rust_folder
+            );
+        }
+
         match (config.wat_flag, config.wasm_flag) {
             (true, true) => {
                 compiler_interface::write_wasm(
@@ -127,47 +171,33 @@ pub fn compile(config: CompilerConfig) -> Result<(), ()> {
     Ok(())
 }
 
+// Replaces the code of the circuit by the one of an IR file written with --irout
+pub fn load_ir(circuit: &mut Circuit, file: &str) -> Result<(), ()> {
+    compiler_interface::read_ir(circuit, file).map_err(|message| {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!("Could not read the IR file {}", message))
+        )
+    })
+}
+
 fn wat_to_wasm(wat_file: &str, wasm_file: &str) -> Result<(), Report> {
     use std::fs::read_to_string;
     use std::fs::File;
     use std::io::BufWriter;
     use std::io::Write;
-    use wast::parser::{self, ParseBuffer};
-    use wast::Wat;
 
     let wat_contents = read_to_string(wat_file).unwrap();
-    let buf = ParseBuffer::new(&wat_contents).unwrap();
-    let result_wasm_contents = parser::parse::<Wat>(&buf);
-    match result_wasm_contents {
-        Result::Err(error) => {
-            Result::Err(Report::error(
-                format!("Error translating the circuit from wat to wasm.\n\nException encountered when parsing WAT: {}", error),
-                ReportCode::ErrorWat2Wasm,
-            ))
-        }
-        Result::Ok(mut wat) => {
-            let wasm_contents = wat.module.encode();
-            match wasm_contents {
-                Result::Err(error) => {
-                    Result::Err(Report::error(
-                        format!("Error translating the circuit from wat to wasm.\n\nException encountered when encoding WASM: {}", error),
-                        ReportCode::ErrorWat2Wasm,
-                    ))
-                }
-                Result::Ok(wasm_contents) => {
-                    let file = File::create(wasm_file).unwrap();
-                    let mut writer = BufWriter::new(file);
-                    writer.write_all(&wasm_contents).map_err(|_err| Report::error(
-                        format!("Error writing the circuit. Exception generated: {}", _err),
-                        ReportCode::ErrorWat2Wasm,
-                    ))?;
-                    writer.flush().map_err(|_err| Report::error(
-                        format!("Error writing the circuit. Exception generated: {}", _err),
-                        ReportCode::ErrorWat2Wasm,
-                    ))?;
-                    Ok(())
-                }
-            }
-        }
-    }
+    let wasm_contents = circom::encode_wat(&wat_contents)?;
+    let file = File::create(wasm_file).unwrap();
+    let mut writer = BufWriter::new(file);
+    writer.write_all(&wasm_contents).map_err(|_err| Report::error(
+        format!("Error writing the circuit. Exception generated: {}", _err),
+        ReportCode::ErrorWat2Wasm,
+    ))?;
+    writer.flush().map_err(|_err| Report::error(
+        format!("Error writing the circuit. Exception generated: {}", _err),
+        ReportCode::ErrorWat2Wasm,
+    ))?;
+    Ok(())
 }
@@ -0,0 +1,629 @@
+use circom_algebra::algebra::Constraint;
+use compiler::compiler_interface::{self, Circuit, Config, OptimizationLevel};
+use constraint_generation::{generate_circuit, BuildConfig};
+use constraint_writers::ccs_reader::{parse_ccs, ConstraintSystemFile};
+use constraint_writers::ccs_writer::CCSEncoding;
+use constraint_writers::r1cs_reader::R1CSReader;
+use constraint_writers::r1cs_writer::{CustomGatesAppliedData, CustomGatesUsedData, HeaderData};
+use constraint_writers::sym_writer::SymElem;
+use parser::source_provider::{
+    FileSystemProvider, LayeredProvider, MemoryProvider, SourceProvider,
+};
+use program_structure::constants::is_valid_prime;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::FileLibrary;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Simplification {
+    O0,
+    O1,
+    O2(usize),
+}
+
+// Compiles a circuit in-process. The session is configured with the builder methods
+// and run() returns every artifact in memory instead of writing them to disk.
+pub struct CompilerSession {
+    main: PathBuf,
+    sources: MemoryProvider,
+    provider: Box<dyn SourceProvider>,
+    link_libraries: Vec<PathBuf>,
+    prime: String,
+    simplification: Simplification,
+    parallel: bool,
+    old_heuristics: bool,
+    verbose: bool,
+    inspect: bool,
+    cache_dir: Option<PathBuf>,
+    threads: usize,
+    plonk: bool,
+    ccs: bool,
+    wasm: bool,
+    c: bool,
+    ir_optimization: OptimizationLevel,
+}
+
+pub struct ConstraintSystem {
+    pub header: HeaderData,
+    pub constraints: Vec<Constraint<usize>>,
+    pub wire_to_label: Vec<usize>,
+    pub custom_gates_used: CustomGatesUsedData,
+    pub custom_gates_applied: CustomGatesAppliedData,
+    // The constraint system encoded as an r1cs file
+    pub r1cs: Vec<u8>,
+}
+
+pub struct WasmArtifacts {
+    pub wasm: Vec<u8>,
+    pub wat: String,
+    // generate_witness.js and witness_calculator.js
+    pub support_files: BTreeMap<String, String>,
+}
+
+pub struct CArtifacts {
+    // Every file of the C++ folder, including the circuit sources, the .dat file and the Makefile
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+pub struct CompilationArtifacts {
+    pub name: String,
+    pub file_library: FileLibrary,
+    pub warnings: ReportCollection,
+    pub template_instances: usize,
+    pub constraints: ConstraintSystem,
+    // The sym entries, one for each signal of the circuit
+    pub witness_layout: Vec<SymElem>,
+    // The sym file as it is written
+    pub sym: Vec<u8>,
+    // The Plonkish gates in the json format of the --plonk option
+    pub plonk: Option<String>,
+    // The constraint system file of the --ccs option, read back from its binary encoding
+    pub ccs: Option<ConstraintSystemFile>,
+    pub circuit: Circuit,
+    pub wasm: Option<WasmArtifacts>,
+    pub c: Option<CArtifacts>,
+}
+
+impl CompilationArtifacts {
+    // Names of the outputs that are not byte-for-byte identical in both compilations,
+    // the C++ files are named one by one
+    pub fn differing_outputs(&self, other: &CompilationArtifacts) -> Vec<String> {
+        let mut differing = Vec::new();
+        if self.constraints.r1cs != other.constraints.r1cs {
+            differing.push(format!("{}.r1cs", self.name));
+        }
+        if self.sym != other.sym {
+            differing.push(format!("{}.sym", self.name));
+        }
+        let wasm = self.wasm.as_ref().map(|wasm| &wasm.wasm);
+        if wasm != other.wasm.as_ref().map(|wasm| &wasm.wasm) {
+            differing.push(format!("{}.wasm", self.name));
+        }
+        let no_files = BTreeMap::new();
+        let files = self.c.as_ref().map_or(&no_files, |c| &c.files);
+        let other_files = other.c.as_ref().map_or(&no_files, |c| &c.files);
+        let names: BTreeSet<&String> = files.keys().chain(other_files.keys()).collect();
+        for name in names {
+            if files.get(name) != other_files.get(name) {
+                differing.push(name.clone());
+            }
+        }
+        differing
+    }
+}
+
+pub struct CompilationFailure {
+    pub file_library: FileLibrary,
+    pub reports: ReportCollection,
+}
+
+impl CompilationFailure {
+    fn new(file_library: FileLibrary, reports: ReportCollection) -> CompilationFailure {
+        CompilationFailure {
+            file_library,
+            reports,
+        }
+    }
+    fn artifact_error(file_library: FileLibrary, message: String) -> CompilationFailure {
+        let report = Report::error(message, ReportCode::ErrorProducingArtifacts);
+        CompilationFailure::new(file_library, vec![report])
+    }
+}
+
+impl CompilerSession {
+    pub fn from_file<P: Into<PathBuf>>(file: P) -> CompilerSession {
+        CompilerSession::new(file.into())
+    }
+
+    // The main file is kept in memory. Every source, including the main file, is searched
+    // first among the sources added with add_source and then in the source provider.
+    pub fn from_source(name: &str, contents: &str) -> CompilerSession {
+        CompilerSession::new(PathBuf::from(name)).add_source(name, contents)
+    }
+
+    fn new(main: PathBuf) -> CompilerSession {
+        CompilerSession {
+            main,
+            sources: MemoryProvider::new(),
+            provider: Box::new(FileSystemProvider::new()),
+            link_libraries: Vec::new(),
+            prime: "bn128".to_string(),
+            simplification: Simplification::O2(usize::MAX),
+            parallel: false,
+            old_heuristics: false,
+            verbose: false,
+            inspect: false,
+            cache_dir: None,
+            threads: 1,
+            plonk: false,
+            ccs: false,
+            wasm: false,
+            c: false,
+            ir_optimization: OptimizationLevel::O0,
+        }
+    }
+
+    pub fn add_source(mut self, name: &str, contents: &str) -> CompilerSession {
+        self.sources.add_file(name, contents.to_string());
+        self
+    }
+
+    // Replaces the filesystem as the origin of the sources not added with add_source
+    pub fn source_provider(mut self, provider: Box<dyn SourceProvider>) -> CompilerSession {
+        self.provider = provider;
+        self
+    }
+
+    pub fn link_library<P: Into<PathBuf>>(mut self, library: P) -> CompilerSession {
+        self.link_libraries.push(library.into());
+        self
+    }
+
+    pub fn prime(mut self, prime: &str) -> CompilerSession {
+        self.prime = prime.to_string();
+        self
+    }
+
+    pub fn simplification(mut self, simplification: Simplification) -> CompilerSession {
+        self.simplification = simplification;
+        self
+    }
+
+    pub fn parallel_simplification(mut self, parallel: bool) -> CompilerSession {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn old_heuristics(mut self, old_heuristics: bool) -> CompilerSession {
+        self.old_heuristics = old_heuristics;
+        self
+    }
+
+    pub fn verbose(mut self, verbose: bool) -> CompilerSession {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn inspect(mut self, inspect: bool) -> CompilerSession {
+        self.inspect = inspect;
+        self
+    }
+
+    // Reuses the template instances executed by previous sessions with the same directory,
+    // which must already exist
+    pub fn cache_dir<P: Into<PathBuf>>(mut self, directory: P) -> CompilerSession {
+        self.cache_dir = Some(directory.into());
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> CompilerSession {
+        self.threads = threads;
+        self
+    }
+
+    pub fn plonk(mut self, plonk: bool) -> CompilerSession {
+        self.plonk = plonk;
+        self
+    }
+
+    pub fn ccs(mut self, ccs: bool) -> CompilerSession {
+        self.ccs = ccs;
+        self
+    }
+
+    pub fn wasm(mut self, wasm: bool) -> CompilerSession {
+        self.wasm = wasm;
+        self
+    }
+
+    pub fn c(mut self, c: bool) -> CompilerSession {
+        self.c = c;
+        self
+    }
+
+    pub fn ir_optimization(mut self, level: OptimizationLevel) -> CompilerSession {
+        self.ir_optimization = level;
+        self
+    }
+
+    pub fn run(self) -> Result<CompilationArtifacts, CompilationFailure> {
+        if !is_valid_prime(&self.prime) {
+            return Result::Err(CompilationFailure::artifact_error(
+                FileLibrary::new(),
+                format!("Invalid prime number {}", self.prime),
+            ));
+        }
+        let name = match self.main.file_stem() {
+            Some(stem) => stem.to_string_lossy().to_string(),
+            None => "main".to_string(),
+        };
+        let provider = LayeredProvider::new(self.sources, self.provider);
+        let (mut program, mut warnings) = parser::run_parser_with_provider(
+            self.main.to_string_lossy().to_string(),
+            VERSION,
+            self.link_libraries.clone(),
+            &provider,
+        )
+        .map_err(|(file_library, reports)| CompilationFailure::new(file_library, reports))?;
+        let file_library = program.file_library.clone();
+        match type_analysis::check_types::check_types(&mut program) {
+            Result::Ok(mut type_warnings) => warnings.append(&mut type_warnings),
+            Result::Err(mut errors) => {
+                warnings.append(&mut errors);
+                return Result::Err(CompilationFailure::new(file_library, warnings));
+            }
+        }
+
+        let custom_gates = program.custom_gates;
+        let build_config = BuildConfig {
+            no_rounds: match self.simplification {
+                Simplification::O2(rounds) => rounds,
+                _ => 0,
+            },
+            flag_json_sub: false,
+            flag_s: self.simplification == Simplification::O1,
+            flag_f: self.simplification == Simplification::O0,
+            flag_p: self.parallel,
+            flag_verbose: self.verbose,
+            flag_old_heuristics: self.old_heuristics,
+            inspect_constraints: self.inspect,
+            prime: self.prime.clone(),
+            cache_dir: self.cache_dir.clone(),
+            threads: self.threads,
+        };
+        let generated = match generate_circuit(program, build_config) {
+            Result::Ok(generated) => generated,
+            Result::Err(mut errors) => {
+                warnings.append(&mut errors);
+                return Result::Err(CompilationFailure::new(file_library, warnings));
+            }
+        };
+        warnings.extend(generated.warnings);
+        let error = |msg| CompilationFailure::artifact_error(file_library.clone(), msg);
+        let constraints =
+            load_constraint_system(generated.constraints.as_ref(), custom_gates).map_err(error)?;
+        let (witness_layout, sym) =
+            load_witness_layout(generated.constraints.as_ref()).map_err(error)?;
+        let plonk = if self.plonk {
+            Some(load_plonk(generated.constraints.as_ref(), custom_gates).map_err(error)?)
+        } else {
+            None
+        };
+        let ccs = if self.ccs {
+            Some(load_ccs(generated.constraints.as_ref()).map_err(error)?)
+        } else {
+            None
+        };
+
+        let config = Config {
+            produce_input_log: false,
+            wat_flag: false,
+            trace: None,
+            ir_optimization: self.ir_optimization,
+        };
+        let circuit = compiler_interface::run_compiler(generated.vcp, config, VERSION)
+            .map_err(|_| error("Could not generate the code of the circuit".to_string()))?;
+        let wasm = if self.wasm {
+            Some(load_wasm(&circuit).map_err(error)?)
+        } else {
+            None
+        };
+        let c = if self.c {
+            Some(load_c(&circuit, &name).map_err(error)?)
+        } else {
+            None
+        };
+
+        Result::Ok(CompilationArtifacts {
+            name,
+            file_library,
+            warnings,
+            template_instances: generated.template_instances,
+            constraints,
+            witness_layout,
+            sym,
+            plonk,
+            ccs,
+            circuit,
+            wasm,
+            c,
+        })
+    }
+}
+
+// Translates the text format of a wasm module into its binary encoding
+pub fn encode_wat(wat: &str) -> Result<Vec<u8>, Report> {
+    use wast::parser::{self, ParseBuffer};
+    use wast::Wat;
+
+    let buf = ParseBuffer::new(wat).unwrap();
+    let mut wat = parser::parse::<Wat>(&buf).map_err(|error| Report::error(
+        format!("Error translating the circuit from wat to wasm.\n\nException encountered when parsing WAT: {}", error),
+        ReportCode::ErrorWat2Wasm,
+    ))?;
+    wat.module.encode().map_err(|error| Report::error(
+        format!("Error translating the circuit from wat to wasm.\n\nException encountered when encoding WASM: {}", error),
+        ReportCode::ErrorWat2Wasm,
+    ))
+}
+
+fn load_constraint_system(
+    exporter: &dyn constraint_writers::ConstraintExporter,
+    custom_gates: bool,
+) -> Result<ConstraintSystem, String> {
+    let error = || "Could not produce the constraint system".to_string();
+    let r1cs = exporter.r1cs_bytes(custom_gates).map_err(|_| error())?;
+    let reader = R1CSReader::from_reader(Cursor::new(&r1cs)).map_err(|_| error())?;
+    let header = reader.get_header().clone();
+    let has_custom_gates = reader.has_custom_gates();
+
+    let mut section = R1CSReader::start_constraints_section(reader).map_err(|_| error())?;
+    let mut constraints = Vec::with_capacity(header.number_of_constraints);
+    while let Some(constraint) = section.read_constraint().map_err(|_| error())? {
+        constraints.push(constraint);
+    }
+    let reader = section.end_section().map_err(|_| error())?;
+    let mut section = R1CSReader::start_signal_section(reader).map_err(|_| error())?;
+    let mut wire_to_label = Vec::with_capacity(header.total_wires);
+    while let Some(label) = section.read_signal().map_err(|_| error())? {
+        wire_to_label.push(label);
+    }
+    let reader = section.end_section().map_err(|_| error())?;
+    let (custom_gates_used, custom_gates_applied) = if has_custom_gates {
+        let mut section =
+            R1CSReader::start_custom_gates_used_section(reader).map_err(|_| error())?;
+        let used = section.read_custom_gates_usages().map_err(|_| error())?;
+        let reader = section.end_section().map_err(|_| error())?;
+        let mut section =
+            R1CSReader::start_custom_gates_applied_section(reader).map_err(|_| error())?;
+        let applied = section
+            .read_custom_gates_applications()
+            .map_err(|_| error())?;
+        section.end_section().map_err(|_| error())?;
+        (used, applied)
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    Result::Ok(ConstraintSystem {
+        header,
+        constraints,
+        wire_to_label,
+        custom_gates_used,
+        custom_gates_applied,
+        r1cs,
+    })
+}
+
+fn load_plonk(
+    exporter: &dyn constraint_writers::ConstraintExporter,
+    custom_gates: bool,
+) -> Result<String, String> {
+    let error = || "Could not produce the Plonkish gates".to_string();
+    let plonk = exporter.plonk_bytes(custom_gates).map_err(|_| error())?;
+    String::from_utf8(plonk).map_err(|_| error())
+}
+
+fn load_ccs(
+    exporter: &dyn constraint_writers::ConstraintExporter,
+) -> Result<ConstraintSystemFile, String> {
+    let error = || "Could not produce the constraint system file".to_string();
+    let ccs = exporter
+        .ccs_bytes(CCSEncoding::Binary)
+        .map_err(|_| error())?;
+    parse_ccs(&ccs).map_err(|_| error())
+}
+
+fn load_witness_layout(
+    exporter: &dyn constraint_writers::ConstraintExporter,
+) -> Result<(Vec<SymElem>, Vec<u8>), String> {
+    let error = || "Could not produce the witness layout".to_string();
+    let sym = exporter.sym_bytes().map_err(|_| error())?;
+    let contents = std::str::from_utf8(&sym).map_err(|_| error())?;
+    let mut layout = Vec::new();
+    for line in contents.lines().filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.splitn(4, ',').collect();
+        if fields.len() != 4 {
+            return Result::Err(error());
+        }
+        layout.push(SymElem {
+            original: fields[0].parse().map_err(|_| error())?,
+            witness: fields[1].parse().map_err(|_| error())?,
+            node_id: fields[2].parse().map_err(|_| error())?,
+            symbol: fields[3].to_string(),
+        });
+    }
+    Result::Ok((layout, sym))
+}
+
+fn load_wasm(circuit: &Circuit) -> Result<WasmArtifacts, String> {
+    let error = || "Could not produce the wasm code".to_string();
+    let wat = circuit.produce_wat().map_err(|_| error())?;
+    let wasm = encode_wat(&wat).map_err(|report| report.get_message().clone())?;
+    let mut support_files = BTreeMap::new();
+    for (file, contents) in circuit.produce_js_files().map_err(|_| error())? {
+        let contents = String::from_utf8(contents).map_err(|_| error())?;
+        support_files.insert(file, contents);
+    }
+    Result::Ok(WasmArtifacts {
+        wasm,
+        wat,
+        support_files,
+    })
+}
+
+fn load_c(circuit: &Circuit, name: &str) -> Result<CArtifacts, String> {
+    let error = || "Could not produce the C++ code".to_string();
+    let files = circuit.produce_c_files(name).map_err(|_| error())?;
+    Result::Ok(CArtifacts { files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = r#"
+pragma circom 2.0.0;
+include "lib/square.circom";
+
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c;
+    component s = Square();
+    s.in <== a * b;
+    c <== s.out;
+}
+
+component main {public [a]} = Main();
+"#;
+
+    const SQUARE: &str = r#"
+pragma circom 2.0.0;
+
+template Square() {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}
+"#;
+
+    #[test]
+    fn compile_sources_in_memory() {
+        let artifacts = CompilerSession::from_source("main.circom", MAIN)
+            .add_source("lib/square.circom", SQUARE)
+            .plonk(true)
+            .ccs(true)
+            .wasm(true)
+            .c(true)
+            .run()
+            .ok()
+            .unwrap();
+        assert_eq!(artifacts.name, "main");
+        let header = &artifacts.constraints.header;
+        assert_eq!(header.public_outputs, 1);
+        assert_eq!(header.public_inputs, 1);
+        assert_eq!(header.private_inputs, 1);
+        assert_eq!(artifacts.constraints.constraints.len(), 2);
+        assert_eq!(&artifacts.constraints.r1cs[0..4], b"r1cs");
+        assert!(artifacts
+            .witness_layout
+            .iter()
+            .any(|elem| elem.symbol == "main.s.out"));
+        assert!(artifacts.plonk.unwrap().contains("\"permutation\""));
+        let ccs = artifacts.ccs.unwrap();
+        assert_eq!(ccs.public_wires, vec![1, 2]);
+        assert_eq!(ccs.header.wires, header.total_wires);
+        assert_eq!(ccs.constraints.len(), 2);
+        assert!(ccs
+            .signals
+            .iter()
+            .any(|signal| signal.name == "main.c" && signal.wire == Some(1)));
+        let wasm = artifacts.wasm.unwrap();
+        assert_eq!(&wasm.wasm[0..4], b"\0asm");
+        assert!(wasm.support_files.contains_key("witness_calculator.js"));
+        let c = artifacts.c.unwrap();
+        assert!(c.files.contains_key("main.cpp"));
+        assert!(c.files.contains_key("main.dat"));
+    }
+
+    #[test]
+    fn compare_outputs_of_two_compilations() {
+        let compile = |main: &str, square: &str| {
+            CompilerSession::from_source("main.circom", main)
+                .add_source("lib/square.circom", square)
+                .wasm(true)
+                .c(true)
+                .run()
+                .ok()
+                .unwrap()
+        };
+        let first = compile(MAIN, SQUARE);
+        let second = compile(MAIN, SQUARE);
+        assert!(first.differing_outputs(&second).is_empty());
+        let changed = SQUARE.replace("out <== in * in;", "out <== in * in + in;");
+        let third = compile(MAIN, &changed);
+        let differing = third.differing_outputs(&first);
+        assert!(differing.contains(&"main.r1cs".to_string()));
+        assert!(differing.contains(&"main.wasm".to_string()));
+        assert!(differing.contains(&"main.cpp".to_string()));
+        // the names of the signals are not in the r1cs file
+        let main = MAIN
+            .replace("signal input b;", "signal input d;")
+            .replace("a * b", "a * d");
+        let differing = compile(&main, SQUARE).differing_outputs(&first);
+        assert!(differing.contains(&"main.sym".to_string()));
+        assert!(!differing.contains(&"main.r1cs".to_string()));
+    }
+
+    #[test]
+    fn anonymous_components_are_reproducible() {
+        let main = r#"
+pragma circom 2.1.0;
+
+template Sum(n) {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal input d;
+    signal input e;
+    signal input f;
+    signal output out;
+    out <== a * b + c + d + e + f + n;
+}
+
+template Main() {
+    signal input in[6];
+    signal output out;
+    out <== Sum(7)(in[0], in[1], in[2], in[3], in[4], in[5]);
+}
+
+component main = Main();
+"#;
+        let compile = || {
+            CompilerSession::from_source("main.circom", main)
+                .wasm(true)
+                .c(true)
+                .run()
+                .ok()
+                .unwrap()
+        };
+        let first = compile();
+        for _ in 0..8 {
+            assert!(compile().differing_outputs(&first).is_empty());
+        }
+    }
+
+    #[test]
+    fn report_missing_include() {
+        let failure = CompilerSession::from_source("main.circom", MAIN)
+            .run()
+            .err()
+            .unwrap();
+        assert!(!failure.reports.is_empty());
+    }
+}
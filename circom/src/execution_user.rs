@@ -1,12 +1,17 @@
 use ansi_term::Colour;
 use compiler::hir::very_concrete_program::VCP;
+use constraint_writers::ccs_writer::CCSEncoding;
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
 use program_structure::program_archive::ProgramArchive;
+use std::path::PathBuf;
 
 pub struct ExecutionConfig {
     pub r1cs: String,
     pub sym: String,
+    pub plonk: String,
+    pub ccs: String,
+    pub ccs_json: String,
     pub json_constraints: String,
     pub no_rounds: usize,
     pub flag_s: bool,
@@ -15,8 +20,13 @@ pub struct ExecutionConfig {
     pub flag_old_heuristics: bool,
     pub flag_verbose: bool,
     pub inspect_constraints_flag: bool,
+    pub cache_dir: Option<PathBuf>,
+    pub threads: usize,
     pub sym_flag: bool,
     pub r1cs_flag: bool,
+    pub plonk_flag: bool,
+    pub ccs_flag: bool,
+    pub ccs_json_flag: bool,
     pub json_substitution_flag: bool,
     pub json_constraint_flag: bool,
     pub prime: String,
@@ -38,12 +48,23 @@ pub fn execute_project(
         inspect_constraints: config.inspect_constraints_flag,
         flag_old_heuristics: config.flag_old_heuristics,
         prime: config.prime,
+        cache_dir: config.cache_dir,
+        threads: config.threads,
     };
     let custom_gates = program_archive.custom_gates;
     let (exporter, vcp) = build_circuit(program_archive, build_config)?;
     if config.r1cs_flag {
         generate_output_r1cs(&config.r1cs, exporter.as_ref(), custom_gates)?;
     }
+    if config.plonk_flag {
+        generate_output_plonk(&config.plonk, exporter.as_ref(), custom_gates)?;
+    }
+    if config.ccs_flag {
+        generate_output_ccs(&config.ccs, exporter.as_ref(), CCSEncoding::Binary)?;
+    }
+    if config.ccs_json_flag {
+        generate_output_ccs(&config.ccs_json, exporter.as_ref(), CCSEncoding::Json)?;
+    }
     if config.sym_flag {
         generate_output_sym(&config.sym, exporter.as_ref())?;
     }
@@ -70,6 +91,40 @@ fn generate_output_r1cs(
     }
 }
 
+fn generate_output_plonk(
+    file: &str,
+    exporter: &dyn ConstraintExporter,
+    custom_gates: bool,
+) -> Result<(), ()> {
+    if let Result::Ok(()) = exporter.plonk(file, custom_gates) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        Result::Ok(())
+    } else {
+        eprintln!(
+            "{}",
+            Colour::Red.paint("Could not write the output in the given path")
+        );
+        Result::Err(())
+    }
+}
+
+fn generate_output_ccs(
+    file: &str,
+    exporter: &dyn ConstraintExporter,
+    encoding: CCSEncoding,
+) -> Result<(), ()> {
+    if let Result::Ok(()) = exporter.ccs(file, encoding) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        Result::Ok(())
+    } else {
+        eprintln!(
+            "{}",
+            Colour::Red.paint("Could not write the output in the given path")
+        );
+        Result::Err(())
+    }
+}
+
 fn generate_output_sym(file: &str, exporter: &dyn ConstraintExporter) -> Result<(), ()> {
     if let Result::Ok(()) = exporter.sym(file) {
         println!("{} {}", Colour::Green.paint("Written successfully:"), file);
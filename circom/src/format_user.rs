@@ -0,0 +1,49 @@
+use ansi_term::Colour;
+use parser::formatter;
+use program_structure::error_definition::Report;
+use program_structure::file_definition::FileLibrary;
+use std::path::PathBuf;
+
+// Formats the files in place. With the check flag the files are not written and the
+// command fails if any of them would change.
+pub fn format_files(files: &[PathBuf], check_flag: bool) -> Result<(), ()> {
+    let mut file_library = FileLibrary::new();
+    let mut failed = false;
+    for file in files {
+        let src = std::fs::read_to_string(file).map_err(|_err| {
+            eprintln!(
+                "{}",
+                Colour::Red.paint(format!("Could not read the file {}", file.display()))
+            )
+        })?;
+        let file_id = file_library.add_file(format!("{:?}", file), src.clone());
+        let formatted = match formatter::format_source(&src, file_id) {
+            Result::Ok(formatted) => formatted,
+            Result::Err(reports) => {
+                Report::print_reports(&reports, &file_library);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == src {
+            continue;
+        }
+        if check_flag {
+            eprintln!("{} {}", Colour::Red.paint("Not formatted:"), file.display());
+            failed = true;
+        } else if std::fs::write(file, formatted).is_ok() {
+            println!("{} {}", Colour::Green.paint("Formatted:"), file.display());
+        } else {
+            eprintln!(
+                "{}",
+                Colour::Red.paint(format!("Could not write the file {}", file.display()))
+            );
+            failed = true;
+        }
+    }
+    if failed {
+        Result::Err(())
+    } else {
+        Result::Ok(())
+    }
+}
@@ -1,7 +1,26 @@
+use compiler::compiler_interface::OptimizationLevel;
+use program_structure::diagnostics_writer::DiagnosticsFormat;
 use std::path::{PathBuf, Path};
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Command {
+    Compile,
+    Witness,
+    Check,
+    Format,
+    Lint,
+}
+
 pub struct Input {
+    pub command: Command,
     pub input_program: PathBuf,
+    pub input_json: PathBuf,
+    pub out_wtns: PathBuf,
+    pub check_r1cs: PathBuf,
+    pub check_wtns: PathBuf,
+    pub check_sym: Option<PathBuf>,
+    pub check_witness_flag: bool,
+    pub witness_from_ir_flag: bool,
     pub out_r1cs: PathBuf,
     pub out_json_constraints: PathBuf,
     pub out_wat_code: PathBuf,
@@ -12,13 +31,24 @@ pub struct Input {
     pub out_c_folder: PathBuf,
     pub out_c_code: PathBuf,
     pub out_c_dat: PathBuf,
+    pub out_rust_run_name: String,
+    pub out_rust_folder: PathBuf,
+    pub out_rust_code: PathBuf,
     pub out_sym: PathBuf,
+    pub out_plonk: PathBuf,
+    pub out_ccs: PathBuf,
+    pub out_ccs_json: PathBuf,
+    pub out_ir: PathBuf,
     //pub field: &'static str,
     pub c_flag: bool,
+    pub rust_flag: bool,
     pub wasm_flag: bool,
     pub wat_flag: bool,
     pub r1cs_flag: bool,
     pub sym_flag: bool,
+    pub plonk_flag: bool,
+    pub ccs_flag: bool,
+    pub ccs_json_flag: bool,
     pub json_constraint_flag: bool,
     pub json_substitution_flag: bool,
     pub main_inputs_flag: bool,
@@ -28,10 +58,21 @@ pub struct Input {
     pub parallel_simplification_flag: bool,
     pub flag_old_heuristics: bool,
     pub inspect_constraints_flag: bool,
+    pub cache_dir: Option<PathBuf>,
+    pub threads: usize,
+    pub check_reproducible_flag: bool,
+    pub trace: Option<String>,
+    pub ir_optimization: OptimizationLevel,
+    pub ir_input: Option<String>,
     pub no_rounds: usize,
     pub flag_verbose: bool,
     pub prime: String,
     pub link_libraries: Vec<PathBuf>,
+    pub diagnostics_format: DiagnosticsFormat,
+    pub format_files: Vec<PathBuf>,
+    pub format_check_flag: bool,
+    pub lint_rules: Vec<String>,
+    pub lint_config: Option<PathBuf>,
 }
 
 const R1CS: &str = "r1cs";
@@ -39,20 +80,61 @@ const WAT: &str = "wat";
 const WASM: &str = "wasm";
 const CPP: &str = "cpp";
 const JS: &str = "js";
+const RUST: &str = "rust";
 const DAT: &str = "dat";
 const SYM: &str = "sym";
 const JSON: &str = "json";
+const CCS: &str = "ccs";
+const IR: &str = "ir";
 
 impl Input {
     pub fn new() -> Result<Input, ()> {
         use ansi_term::Colour;
         use input_processing::SimplificationStyle;
-        let matches = input_processing::view();
-        let input = input_processing::get_input(&matches)?;
+        let all_matches = input_processing::view();
+        let (command, matches) = input_processing::get_command(&all_matches);
+        let check_witness_flag = input_processing::get_check_witness(matches);
+        let format_files = if command == Command::Format {
+            input_processing::get_format_files(matches)?
+        } else {
+            Vec::new()
+        };
+        // The circuit is not needed to check a witness against an existing r1cs
+        let input = if check_witness_flag {
+            PathBuf::from(matches.value_of("input").unwrap())
+        } else if command == Command::Format {
+            format_files[0].clone()
+        } else {
+            input_processing::get_input(matches)?
+        };
+        // The witness can be computed from an IR file written with --irout instead of a circuit
+        let witness_from_ir_flag =
+            command == Command::Witness && input.extension().and_then(|ext| ext.to_str()) == Some(IR);
         let mut file_name = input.file_stem().unwrap().to_str().unwrap().to_string();
-        let output_path = input_processing::get_output_path(&matches)?;
+        let output_path = input_processing::get_output_path(matches)?;
+        let (input_json, out_wtns) = if command == Command::Witness {
+            (
+                input_processing::get_input_json(matches)?,
+                input_processing::get_output_wtns(matches),
+            )
+        } else {
+            (PathBuf::new(), PathBuf::new())
+        };
+        let (check_r1cs, check_wtns, check_sym) = if check_witness_flag {
+            (
+                input_processing::get_existing_file(matches, "check_r1cs")?,
+                input_processing::get_existing_file(matches, "check_wtns")?,
+                if matches.is_present("check_sym") {
+                    Some(input_processing::get_existing_file(matches, "check_sym")?)
+                } else {
+                    None
+                },
+            )
+        } else {
+            (PathBuf::new(), PathBuf::new(), None)
+        };
 
-        let c_flag = input_processing::get_c(&matches);
+        let c_flag = input_processing::get_c(matches);
 
         if c_flag && (file_name == "main" || file_name == "fr" || file_name == "calcwit") {
             println!("{}", Colour::Yellow.paint(format!("The name {} is reserved in Circom when using de --c flag. The files generated for your circuit will use the name {}_c instead of {}.", file_name, file_name, file_name)));
@@ -60,11 +142,25 @@ impl Input {
         };
         let output_c_path = Input::build_folder(&output_path, &file_name, CPP);
         let output_js_path = Input::build_folder(&output_path, &file_name, JS);
-        let o_style = input_processing::get_simplification_style(&matches)?;
-        let link_libraries = input_processing::get_link_libraries(&matches);
+        let output_rust_path = Input::build_folder(&output_path, &file_name, RUST);
+        let lint_config = if command == Command::Lint && matches.is_present("lint_config") {
+            Some(input_processing::get_existing_file(matches, "lint_config")?)
+        } else {
+            None
+        };
+        let o_style = input_processing::get_simplification_style(matches)?;
+        let link_libraries = input_processing::get_link_libraries(matches);
         Result::Ok(Input {
             //field: P_BN128,
+            command,
             input_program: input,
+            input_json,
+            out_wtns,
+            check_r1cs,
+            check_wtns,
+            check_sym,
+            check_witness_flag,
+            witness_from_ir_flag,
             out_r1cs: Input::build_output(&output_path, &file_name, R1CS),
             out_wat_code: Input::build_output(&output_js_path, &file_name, WAT),
             out_wasm_code: Input::build_output(&output_js_path, &file_name, WASM),
@@ -74,21 +170,32 @@ impl Input {
             out_c_run_name: file_name.clone(),
             out_c_code: Input::build_output(&output_c_path, &file_name, CPP),
             out_c_dat: Input::build_output(&output_c_path, &file_name, DAT),
+            out_rust_folder: output_rust_path.clone(),
+            out_rust_run_name: file_name.clone(),
+            out_rust_code: output_rust_path.join("src").join("lib.rs"),
             out_sym: Input::build_output(&output_path, &file_name, SYM),
+            out_plonk: Input::build_output(&output_path, &format!("{}_plonk", file_name), JSON),
+            out_ccs: Input::build_output(&output_path, &file_name, CCS),
+            out_ccs_json: Input::build_output(&output_path, &format!("{}_ccs", file_name), JSON),
+            out_ir: Input::build_output(&output_path, &file_name, IR),
             out_json_constraints: Input::build_output(
                 &output_path,
                 &format!("{}_constraints", file_name),
                 JSON,
             ),
-            wat_flag: input_processing::get_wat(&matches),
-            wasm_flag: input_processing::get_wasm(&matches),
+            wat_flag: input_processing::get_wat(matches),
+            wasm_flag: input_processing::get_wasm(matches),
             c_flag,
-            r1cs_flag: input_processing::get_r1cs(&matches),
-            sym_flag: input_processing::get_sym(&matches),
-            main_inputs_flag: input_processing::get_main_inputs_log(&matches),
-            json_constraint_flag: input_processing::get_json_constraints(&matches),
-            json_substitution_flag: input_processing::get_json_substitutions(&matches),
-            print_ir_flag: input_processing::get_ir(&matches),
+            rust_flag: input_processing::get_rust(matches),
+            r1cs_flag: input_processing::get_r1cs(matches),
+            sym_flag: input_processing::get_sym(matches),
+            plonk_flag: input_processing::get_plonk(matches),
+            ccs_flag: input_processing::get_ccs(matches),
+            ccs_json_flag: input_processing::get_ccs_json(matches),
+            main_inputs_flag: input_processing::get_main_inputs_log(matches),
+            json_constraint_flag: input_processing::get_json_constraints(matches),
+            json_substitution_flag: input_processing::get_json_substitutions(matches),
+            print_ir_flag: input_processing::get_ir(matches),
             no_rounds: if let SimplificationStyle::O2(r) = o_style {
                 r
             } else {
@@ -96,12 +203,23 @@ impl Input {
             },
             fast_flag: o_style == SimplificationStyle::O0,
             reduced_simplification_flag: o_style == SimplificationStyle::O1,
-            parallel_simplification_flag: input_processing::get_parallel_simplification(&matches),
-            inspect_constraints_flag: input_processing::get_inspect_constraints(&matches),
-            flag_old_heuristics: input_processing::get_flag_old_heuristics(&matches),
-            flag_verbose: input_processing::get_flag_verbose(&matches),
-            prime: input_processing::get_prime(&matches)?,
+            parallel_simplification_flag: input_processing::get_parallel_simplification(matches),
+            inspect_constraints_flag: input_processing::get_inspect_constraints(matches),
+            cache_dir: input_processing::get_cache_dir(matches)?,
+            threads: input_processing::get_threads(matches)?,
+            check_reproducible_flag: input_processing::get_check_reproducible(matches),
+            trace: input_processing::get_trace(matches),
+            ir_optimization: input_processing::get_ir_optimization(matches),
+            ir_input: input_processing::get_ir_input(matches),
+            flag_old_heuristics: input_processing::get_flag_old_heuristics(matches),
+            flag_verbose: input_processing::get_flag_verbose(matches),
+            prime: input_processing::get_prime(matches)?,
             link_libraries,
+            diagnostics_format: input_processing::get_diagnostics_format(matches),
+            format_files,
+            format_check_flag: input_processing::get_format_check(matches),
+            lint_rules: input_processing::get_lint_rules(matches),
+            lint_config,
         })
     }
 
@@ -122,15 +240,52 @@ impl Input {
         &self.link_libraries
     }
 
+    pub fn command(&self) -> Command {
+        self.command
+    }
+
     pub fn input_file(&self) -> &str {
         self.input_program.to_str().unwrap()
     }
+    pub fn input_json_file(&self) -> &str {
+        self.input_json.to_str().unwrap()
+    }
+    pub fn wtns_file(&self) -> &str {
+        self.out_wtns.to_str().unwrap()
+    }
+    pub fn check_witness_flag(&self) -> bool {
+        self.check_witness_flag
+    }
+    pub fn witness_from_ir_flag(&self) -> bool {
+        self.witness_from_ir_flag
+    }
+    pub fn check_r1cs_file(&self) -> &str {
+        self.check_r1cs.to_str().unwrap()
+    }
+    pub fn check_wtns_file(&self) -> &str {
+        self.check_wtns.to_str().unwrap()
+    }
+    pub fn check_sym_file(&self) -> Option<&str> {
+        self.check_sym.as_ref().map(|sym| sym.to_str().unwrap())
+    }
     pub fn r1cs_file(&self) -> &str {
         self.out_r1cs.to_str().unwrap()
     }
     pub fn sym_file(&self) -> &str {
         self.out_sym.to_str().unwrap()
     }
+    pub fn plonk_file(&self) -> &str {
+        self.out_plonk.to_str().unwrap()
+    }
+    pub fn ccs_file(&self) -> &str {
+        self.out_ccs.to_str().unwrap()
+    }
+    pub fn ccs_json_file(&self) -> &str {
+        self.out_ccs_json.to_str().unwrap()
+    }
+    pub fn ir_file(&self) -> &str {
+        self.out_ir.to_str().unwrap()
+    }
     pub fn wat_file(&self) -> &str {
         self.out_wat_code.to_str().unwrap()
     }
@@ -157,6 +312,15 @@ impl Input {
     pub fn dat_file(&self) -> &str {
         self.out_c_dat.to_str().unwrap()
     }
+    pub fn rust_folder(&self) -> &str {
+        self.out_rust_folder.to_str().unwrap()
+    }
+    pub fn rust_run_name(&self) -> String {
+        self.out_rust_run_name.clone()
+    }
+    pub fn rust_file(&self) -> &str {
+        self.out_rust_code.to_str().unwrap()
+    }
     pub fn json_constraints_file(&self) -> &str {
         self.out_json_constraints.to_str().unwrap()
     }
@@ -169,6 +333,9 @@ impl Input {
     pub fn c_flag(&self) -> bool {
         self.c_flag
     }
+    pub fn rust_flag(&self) -> bool {
+        self.rust_flag
+    }
     pub fn unsimplified_flag(&self) -> bool {
         self.fast_flag
     }
@@ -184,6 +351,15 @@ impl Input {
     pub fn main_inputs_flag(&self) -> bool {
         self.main_inputs_flag
     }
+    pub fn plonk_flag(&self) -> bool {
+        self.plonk_flag
+    }
+    pub fn ccs_flag(&self) -> bool {
+        self.ccs_flag
+    }
+    pub fn ccs_json_flag(&self) -> bool {
+        self.ccs_json_flag
+    }
     pub fn sym_flag(&self) -> bool {
         self.sym_flag
     }
@@ -193,6 +369,24 @@ impl Input {
     pub fn inspect_constraints_flag(&self) -> bool {
         self.inspect_constraints_flag
     }
+    pub fn cache_dir(&self) -> Option<&PathBuf> {
+        self.cache_dir.as_ref()
+    }
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+    pub fn trace(&self) -> Option<&str> {
+        self.trace.as_deref()
+    }
+    pub fn ir_optimization(&self) -> OptimizationLevel {
+        self.ir_optimization
+    }
+    pub fn ir_input(&self) -> Option<&str> {
+        self.ir_input.as_deref()
+    }
+    pub fn check_reproducible_flag(&self) -> bool {
+        self.check_reproducible_flag
+    }
     pub fn flag_verbose(&self) -> bool {
         self.flag_verbose
     }
@@ -211,13 +405,44 @@ impl Input {
     pub fn prime(&self) -> String {
         self.prime.clone()
     }
+    pub fn format_files(&self) -> &Vec<PathBuf> {
+        &self.format_files
+    }
+    pub fn format_check_flag(&self) -> bool {
+        self.format_check_flag
+    }
+    pub fn lint_rules(&self) -> &Vec<String> {
+        &self.lint_rules
+    }
+    pub fn lint_config(&self) -> Option<&PathBuf> {
+        self.lint_config.as_ref()
+    }
+    pub fn diagnostics_format(&self) -> DiagnosticsFormat {
+        self.diagnostics_format
+    }
 }
 mod input_processing {
+    use super::Command;
     use crate::VERSION;
+    use program_structure::constants::is_valid_prime;
+    use program_structure::diagnostics_writer::DiagnosticsFormat;
     use ansi_term::Colour;
-    use clap::{App, Arg, ArgMatches};
+    use clap::{App, Arg, ArgMatches, SubCommand};
+    use compiler::compiler_interface::OptimizationLevel;
     use std::path::{Path, PathBuf};
 
+    // Without a subcommand the arguments are read as in the compile subcommand
+    pub fn get_command<'a>(matches: &'a ArgMatches<'static>) -> (Command, &'a ArgMatches<'static>) {
+        match matches.subcommand() {
+            ("compile", Some(sub_matches)) => (Command::Compile, sub_matches),
+            ("witness", Some(sub_matches)) => (Command::Witness, sub_matches),
+            ("check", Some(sub_matches)) => (Command::Check, sub_matches),
+            ("fmt", Some(sub_matches)) => (Command::Format, sub_matches),
+            ("lint", Some(sub_matches)) => (Command::Lint, sub_matches),
+            _ => (Command::Compile, matches),
+        }
+    }
+
     pub fn get_input(matches: &ArgMatches) -> Result<PathBuf, ()> {
         let route = Path::new(matches.value_of("input").unwrap()).to_path_buf();
         if route.is_file() {
@@ -235,8 +460,66 @@ mod input_processing {
         }
     }
 
+    pub fn get_input_json(matches: &ArgMatches) -> Result<PathBuf, ()> {
+        let route = Path::new(matches.value_of("input_json").unwrap()).to_path_buf();
+        if route.is_file() {
+            Result::Ok(route)
+        } else {
+            Result::Err(eprintln!(
+                "{}",
+                Colour::Red.paint(format!("Input json file does not exist: {}", route.display()))
+            ))
+        }
+    }
+
+    pub fn get_existing_file(matches: &ArgMatches, name: &str) -> Result<PathBuf, ()> {
+        let route = Path::new(matches.value_of(name).unwrap()).to_path_buf();
+        if route.is_file() {
+            Result::Ok(route)
+        } else {
+            Result::Err(eprintln!(
+                "{}",
+                Colour::Red.paint(format!("File does not exist: {}", route.display()))
+            ))
+        }
+    }
+
+    pub fn get_format_files(matches: &ArgMatches) -> Result<Vec<PathBuf>, ()> {
+        let mut files = Vec::new();
+        for file in matches.values_of("input").unwrap() {
+            let route = Path::new(file).to_path_buf();
+            if !route.is_file() {
+                return Result::Err(eprintln!(
+                    "{}",
+                    Colour::Red.paint(format!("Input file does not exist: {}", route.display()))
+                ));
+            }
+            files.push(route);
+        }
+        Result::Ok(files)
+    }
+
+    pub fn get_format_check(matches: &ArgMatches) -> bool {
+        matches.is_present("format_check")
+    }
+
+    pub fn get_lint_rules(matches: &ArgMatches) -> Vec<String> {
+        match matches.values_of("lint_rules") {
+            Some(settings) => settings.map(|setting| setting.to_string()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_check_witness(matches: &ArgMatches) -> bool {
+        matches.is_present("check_r1cs")
+    }
+
+    pub fn get_output_wtns(matches: &ArgMatches) -> PathBuf {
+        Path::new(matches.value_of("output_wtns").unwrap()).to_path_buf()
+    }
+
     pub fn get_output_path(matches: &ArgMatches) -> Result<PathBuf, ()> {
-        let route = Path::new(matches.value_of("output").unwrap()).to_path_buf();
+        let route = Path::new(matches.value_of("output").unwrap_or(".")).to_path_buf();
         if route.is_dir() {
             Result::Ok(route)
         } else {
@@ -244,6 +527,54 @@ mod input_processing {
         }
     }
 
+    pub fn get_cache_dir(matches: &ArgMatches) -> Result<Option<PathBuf>, ()> {
+        match matches.value_of("cache_dir") {
+            Some(route) => {
+                let route = Path::new(route).to_path_buf();
+                if std::fs::create_dir_all(&route).is_ok() {
+                    Result::Ok(Some(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid cache path")))
+                }
+            }
+            None => Result::Ok(None),
+        }
+    }
+
+    pub fn get_threads(matches: &ArgMatches) -> Result<usize, ()> {
+        match matches.value_of("threads").unwrap_or("1").parse::<usize>() {
+            Ok(threads) if threads > 0 => Result::Ok(threads),
+            _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of threads"))),
+        }
+    }
+
+    pub fn get_check_reproducible(matches: &ArgMatches) -> bool {
+        matches.is_present("check_reproducible")
+    }
+
+    // The component path whose signals are traced, main when only --trace is given
+    pub fn get_trace(matches: &ArgMatches) -> Option<String> {
+        if let Some(path) = matches.value_of("trace_component") {
+            Some(path.to_string())
+        } else if matches.is_present("trace") {
+            Some("main".to_string())
+        } else {
+            None
+        }
+    }
+
+    pub fn get_ir_optimization(matches: &ArgMatches) -> OptimizationLevel {
+        match matches.value_of("ir_optimization") {
+            Some("1") => OptimizationLevel::O1,
+            Some("2") => OptimizationLevel::O2,
+            _ => OptimizationLevel::O0,
+        }
+    }
+
+    pub fn get_ir_input(matches: &ArgMatches) -> Option<String> {
+        matches.value_of("ir_input").map(str::to_string)
+    }
+
     #[derive(Copy, Clone, Eq, PartialEq)]
     pub enum SimplificationStyle {
         O0,
@@ -292,6 +623,18 @@ mod input_processing {
         matches.is_present("print_sym")
     }
 
+    pub fn get_plonk(matches: &ArgMatches) -> bool {
+        matches.is_present("print_plonk")
+    }
+
+    pub fn get_ccs(matches: &ArgMatches) -> bool {
+        matches.is_present("print_ccs")
+    }
+
+    pub fn get_ccs_json(matches: &ArgMatches) -> bool {
+        matches.is_present("print_ccs_json")
+    }
+
     pub fn get_r1cs(matches: &ArgMatches) -> bool {
         matches.is_present("print_r1cs")
     }
@@ -308,6 +651,10 @@ mod input_processing {
         matches.is_present("print_c")
     }
 
+    pub fn get_rust(matches: &ArgMatches) -> bool {
+        matches.is_present("print_rust")
+    }
+
     pub fn get_main_inputs_log(matches: &ArgMatches) -> bool {
         matches.is_present("main_inputs_log")
     }
@@ -330,17 +677,16 @@ mod input_processing {
     pub fn get_flag_old_heuristics(matches: &ArgMatches) -> bool {
         matches.is_present("flag_old_heuristics")
     }
+    pub fn get_diagnostics_format(matches: &ArgMatches) -> DiagnosticsFormat {
+        let format = matches.value_of("diagnostics_format").unwrap();
+        DiagnosticsFormat::from_name(format).unwrap()
+    }
+
     pub fn get_prime(matches: &ArgMatches) -> Result<String, ()> {
         match matches.is_present("prime") {
             true => {
                 let prime_value = matches.value_of("prime").unwrap();
-                if prime_value == "bn128"
-                    || prime_value == "bls12381"
-                    || prime_value == "goldilocks"
-                    || prime_value == "grumpkin"
-                    || prime_value == "pallas"
-                    || prime_value == "vesta"
-                {
+                if is_valid_prime(prime_value) {
                     Ok(String::from(matches.value_of("prime").unwrap()))
                 } else {
                     Result::Err(eprintln!("{}", Colour::Red.paint("invalid prime number")))
@@ -356,168 +702,325 @@ mod input_processing {
             .version(VERSION)
             .author("IDEN3")
             .about("Compiler for the circom programming language")
-            .arg(
-                Arg::with_name("input")
-                    .multiple(false)
-                    .default_value("./circuit.circom")
-                    .help("Path to a circuit with a main component"),
-            )
-            .arg(
-                Arg::with_name("no_simplification")
-                    .long("O0")
-                    .hidden(false)
-                    .takes_value(false)
-                    .help("No simplification is applied")
-                    .display_order(420)
-            )
-            .arg(
-                Arg::with_name("reduced_simplification")
-                    .long("O1")
-                    .hidden(false)
-                    .takes_value(false)
-                    .help("Only applies var to var and var to constant simplification")
-                    .display_order(460)
-            )
-            .arg(
-                Arg::with_name("full_simplification")
-                    .long("O2")
-                    .takes_value(false)
-                    .hidden(false)
-                    .help("Full constraint simplification")
-                    .display_order(480)
+            .arg(input_arg().default_value("./circuit.circom"))
+            .args(&compile_args())
+            .subcommand(
+                SubCommand::with_name("compile")
+                    .about("Compiles a circuit, this is the default when no subcommand is given")
+                    .arg(input_arg().default_value("./circuit.circom"))
+                    .args(&compile_args()),
             )
-            .arg(
-                Arg::with_name("simplification_rounds")
-                    .long("O2round")
-                    .takes_value(true)
-                    .hidden(false)
-                    .help("Maximum number of rounds of the simplification process")
-                    .display_order(500)
+            .subcommand(
+                SubCommand::with_name("witness")
+                    .about("Computes the witness of a circuit for the given inputs and writes it in wtns format")
+                    .arg(
+                        input_arg()
+                            .required(true)
+                            .help("Path to a circuit with a main component, or to an IR file written with --irout"),
+                    )
+                    .arg(
+                        Arg::with_name("input_json")
+                            .required(true)
+                            .help("Path to a json file with the values of the main inputs"),
+                    )
+                    .arg(
+                        Arg::with_name("output_wtns")
+                            .default_value("./witness.wtns")
+                            .help("Path to the wtns file where the witness will be written"),
+                    )
+                    .args(&simplification_args())
+                    .args(&witness_code_args())
+                    .args(&common_args()),
             )
-            .arg(
-                Arg::with_name("output")
-                    .short("o")
-                    .long("output")
-                    .takes_value(true)
-                    .default_value(".")
-                    .display_order(1)
-                    .help("Path to the directory where the output will be written"),
+            .subcommand(
+                SubCommand::with_name("check")
+                    .about("Checks that a circuit is correct without producing any output, or that a witness satisfies an r1cs")
+                    .arg(input_arg().default_value("./circuit.circom"))
+                    .arg(
+                        Arg::with_name("check_r1cs")
+                            .long("r1cs")
+                            .takes_value(true)
+                            .requires("check_wtns")
+                            .display_order(30)
+                            .help("Checks the constraints of the given r1cs file instead of the circuit"),
+                    )
+                    .arg(
+                        Arg::with_name("check_wtns")
+                            .long("wtns")
+                            .takes_value(true)
+                            .requires("check_r1cs")
+                            .display_order(40)
+                            .help("Witness in wtns format that must satisfy the constraints of the r1cs"),
+                    )
+                    .arg(
+                        Arg::with_name("check_sym")
+                            .long("sym")
+                            .takes_value(true)
+                            .requires("check_r1cs")
+                            .display_order(60)
+                            .help("Sym file used to show the names of the signals of the unsatisfied constraints"),
+                    )
+                    .args(&simplification_args())
+                    .args(&common_args()),
             )
-            .arg(
-                Arg::with_name("print_json_c")
-                    .long("json")
-                    .takes_value(false)
-                    .display_order(120)
-                    .help("Outputs the constraints in json format"),
+            .subcommand(
+                SubCommand::with_name("fmt")
+                    .about("Rewrites circom files with the canonical layout")
+                    .arg(
+                        Arg::with_name("input")
+                            .multiple(true)
+                            .required(true)
+                            .help("Paths to the circom files to format"),
+                    )
+                    .arg(
+                        Arg::with_name("format_check")
+                            .long("check")
+                            .takes_value(false)
+                            .display_order(30)
+                            .help("Does not write the files, fails if any of them is not formatted"),
+                    )
+                    .arg(diagnostics_format_arg()),
             )
-            .arg(
-                Arg::with_name("print_ir")
-                    .long("irout")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(360)
-                    .help("Outputs the low-level IR of the given circom program"),
+            .subcommand(
+                SubCommand::with_name("lint")
+                    .about("Looks for common mistakes in a circuit, such as signals assigned with <-- that are never constrained")
+                    .arg(input_arg().default_value("./circuit.circom"))
+                    .arg(
+                        Arg::with_name("lint_rules")
+                            .long("rule")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .display_order(30)
+                            .help("Sets the severity of a rule with rule=severity, the severity is off, warning or error"),
+                    )
+                    .arg(
+                        Arg::with_name("lint_config")
+                            .long("config")
+                            .takes_value(true)
+                            .display_order(40)
+                            .help("Json file with the severities of the rules, in the form {\"rules\": {\"rule\": \"severity\"}}"),
+                    )
+                    .args(&common_args()),
             )
-            .arg(
-                Arg::with_name("inspect_constraints")
-                    .long("inspect")
-                    .takes_value(false)
-                    .display_order(801)
-                    .help("Does an additional check over the constraints produced"),
-            )
-            .arg(
-                Arg::with_name("print_json_sub")
-                    .long("jsons")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(100)
-                    .help("Outputs the substitution in json format"),
-            )
-            .arg(
-                Arg::with_name("print_sym")
-                    .long("sym")
-                    .takes_value(false)
-                    .display_order(60)
-                    .help("Outputs witness in sym format"),
-            )
-            .arg(
-                Arg::with_name("print_r1cs")
-                    .long("r1cs")
-                    .takes_value(false)
-                    .display_order(30)
-                    .help("Outputs the constraints in r1cs format"),
-            )
-            .arg(
-                Arg::with_name("print_wasm")
-                    .long("wasm")
-                    .takes_value(false)
-                    .display_order(90)
-                    .help("Compiles the circuit to wasm"),
-            )
-            .arg(
-                Arg::with_name("print_wat")
-                    .long("wat")
-                    .takes_value(false)
-                    .display_order(120)
-                    .help("Compiles the circuit to wat"),
-            )
-            .arg(
-                Arg::with_name("link_libraries")
+            .get_matches()
+    }
+
+    fn input_arg() -> Arg<'static, 'static> {
+        Arg::with_name("input")
+            .multiple(false)
+            .help("Path to a circuit with a main component")
+    }
+
+    fn compile_args() -> Vec<Arg<'static, 'static>> {
+        let mut args = output_args();
+        args.append(&mut simplification_args());
+        args.append(&mut witness_code_args());
+        args.append(&mut common_args());
+        args
+    }
+
+    fn witness_code_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("trace")
+                .long("trace")
+                .takes_value(false)
+                .display_order(220)
+                .help("Prints the name and the value of every signal assigned during the witness generation"),
+            Arg::with_name("trace_component")
+                .long("trace-component")
+                .takes_value(true)
+                .display_order(230)
+                .help("Only traces the signals of the given component and its subcomponents, e.g. main.c[1]. Implies --trace"),
+            Arg::with_name("ir_optimization")
+                .long("ir-opt")
+                .takes_value(true)
+                .possible_values(&["0", "1", "2"])
+                .default_value("0")
+                .display_order(240)
+                .help("Optimizes the code that computes the witness: 0 does not optimize it, 1 folds constants and branches and removes unused stores of variables, 2 also unrolls small loops"),
+            Arg::with_name("ir_input")
+                .long("irin")
+                .takes_value(true)
+                .display_order(250)
+                .help("Computes the witness with the code of the given IR file, written with --irout, instead of the code generated for the circuit"),
+        ]
+    }
+
+    fn output_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .default_value(".")
+                .display_order(1)
+                .help("Path to the directory where the output will be written"),
+            Arg::with_name("print_json_c")
+                .long("json")
+                .takes_value(false)
+                .display_order(120)
+                .help("Outputs the constraints in json format"),
+            Arg::with_name("print_ir")
+                .long("irout")
+                .takes_value(false)
+                .display_order(360)
+                .help("Outputs the code that computes the witness in the textual IR format"),
+            Arg::with_name("inspect_constraints")
+                .long("inspect")
+                .takes_value(false)
+                .display_order(801)
+                .help("Does an additional check over the constraints produced"),
+            Arg::with_name("cache_dir")
+                .long("cache")
+                .takes_value(true)
+                .display_order(802)
+                .help("Directory where the executed template instances are kept to reuse them in the next compilations"),
+            Arg::with_name("threads")
+                .long("threads")
+                .takes_value(true)
+                .default_value("1")
+                .display_order(803)
+                .help("Number of threads used to execute the template instances"),
+            Arg::with_name("check_reproducible")
+                .long("check-reproducible")
+                .takes_value(false)
+                .display_order(804)
+                .help("Compiles the circuit twice more and fails if the r1cs, sym, wasm or C++ outputs differ"),
+            Arg::with_name("print_json_sub")
+                .long("jsons")
+                .takes_value(false)
+                .hidden(true)
+                .display_order(100)
+                .help("Outputs the substitution in json format"),
+            Arg::with_name("print_sym")
+                .long("sym")
+                .takes_value(false)
+                .display_order(60)
+                .help("Outputs witness in sym format"),
+            Arg::with_name("print_r1cs")
+                .long("r1cs")
+                .takes_value(false)
+                .display_order(30)
+                .help("Outputs the constraints in r1cs format"),
+            Arg::with_name("print_plonk")
+                .long("plonk")
+                .takes_value(false)
+                .display_order(40)
+                .help("Outputs the constraints as Plonkish gates with their copy constraints, in json format"),
+            Arg::with_name("print_ccs")
+                .long("ccs")
+                .takes_value(false)
+                .display_order(45)
+                .help("Outputs the constraints, the witness layout and the signal names in the binary ccs format"),
+            Arg::with_name("print_ccs_json")
+                .long("ccs-json")
+                .takes_value(false)
+                .display_order(46)
+                .help("Outputs the constraints, the witness layout and the signal names in the json ccs format"),
+            Arg::with_name("print_wasm")
+                .long("wasm")
+                .takes_value(false)
+                .display_order(90)
+                .help("Compiles the circuit to wasm"),
+            Arg::with_name("print_wat")
+                .long("wat")
+                .takes_value(false)
+                .display_order(120)
+                .help("Compiles the circuit to wat"),
+            Arg::with_name("print_c")
+                .long("c")
+                .short("c")
+                .takes_value(false)
+                .display_order(150)
+                .help("Compiles the circuit to c"),
+            Arg::with_name("print_rust")
+                .long("rust")
+                .takes_value(false)
+                .display_order(160)
+                .help("Compiles the circuit to a Rust crate"),
+            Arg::with_name("main_inputs_log")
+                .long("inputs")
+                .takes_value(false)
+                .hidden(true)
+                .display_order(210)
+                .help("Produces a log_inputs.txt file"),
+        ]
+    }
+
+    fn simplification_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("no_simplification")
+                .long("O0")
+                .hidden(false)
+                .takes_value(false)
+                .help("No simplification is applied")
+                .display_order(420),
+            Arg::with_name("reduced_simplification")
+                .long("O1")
+                .hidden(false)
+                .takes_value(false)
+                .help("Only applies var to var and var to constant simplification")
+                .display_order(460),
+            Arg::with_name("full_simplification")
+                .long("O2")
+                .takes_value(false)
+                .hidden(false)
+                .help("Full constraint simplification")
+                .display_order(480),
+            Arg::with_name("simplification_rounds")
+                .long("O2round")
+                .takes_value(true)
+                .hidden(false)
+                .help("Maximum number of rounds of the simplification process")
+                .display_order(500),
+            Arg::with_name("parallel_simplification")
+                .long("parallel")
+                .takes_value(false)
+                .hidden(true)
+                .display_order(180)
+                .help("Runs non-linear simplification in parallel"),
+            Arg::with_name("flag_old_heuristics")
+                .long("use_old_simplification_heuristics")
+                .takes_value(false)
+                .display_order(980)
+                .help("Applies the old version of the heuristics when performing linear simplification"),
+        ]
+    }
+
+    fn common_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("link_libraries")
                 .short("l")
                 .takes_value(true)
                 .multiple(true)
                 .number_of_values(1)
                 .display_order(330)
                 .help("Adds directory to library search path"),
-            )
-            .arg(
-                Arg::with_name("print_c")
-                    .long("c")
-                    .short("c")
-                    .takes_value(false)
-                    .display_order(150)
-                    .help("Compiles the circuit to c"),
-            )
-            .arg(
-                Arg::with_name("parallel_simplification")
-                    .long("parallel")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(180)
-                    .help("Runs non-linear simplification in parallel"),
-            )
-            .arg(
-                Arg::with_name("main_inputs_log")
-                    .long("inputs")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(210)
-                    .help("Produces a log_inputs.txt file"),
-            )
-            .arg(
-                Arg::with_name("flag_verbose")
-                    .long("verbose")
-                    .takes_value(false)
-                    .display_order(800)
-                    .help("Shows logs during compilation"),
-            )
-            .arg(
-                Arg::with_name("flag_old_heuristics")
-                    .long("use_old_simplification_heuristics")
-                    .takes_value(false)
-                    .display_order(980)
-                    .help("Applies the old version of the heuristics when performing linear simplification"),
-            )
-            .arg (
-                Arg::with_name("prime")
-                    .short("prime")
-                    .long("prime")
-                    .takes_value(true)
-                    .default_value("bn128")
-                    .display_order(300)
-                    .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta)"),
-            )
-            .get_matches()
+            Arg::with_name("prime")
+                .short("prime")
+                .long("prime")
+                .takes_value(true)
+                .default_value("bn128")
+                .display_order(300)
+                .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta) or the prime itself in decimal or hexadecimal (0x)"),
+            Arg::with_name("flag_verbose")
+                .long("verbose")
+                .takes_value(false)
+                .display_order(800)
+                .help("Shows logs during compilation"),
+            diagnostics_format_arg(),
+        ]
+    }
+
+    fn diagnostics_format_arg() -> Arg<'static, 'static> {
+        Arg::with_name("diagnostics_format")
+            .long("diagnostics-format")
+            .takes_value(true)
+            .possible_values(&["human", "json", "sarif"])
+            .default_value("human")
+            .display_order(810)
+            .help("Format of the errors and warnings. With json or sarif they are written as a single document to the standard error when the compiler finishes")
     }
 
     pub fn get_link_libraries(matches: &ArgMatches) -> Vec<PathBuf> {
@@ -0,0 +1,8 @@
+// Library interface of the compiler, used to embed circom in other tools
+mod compiler_session;
+
+pub use compiler::compiler_interface::OptimizationLevel;
+pub use compiler_session::{
+    encode_wat, CArtifacts, CompilationArtifacts, CompilationFailure, CompilerSession,
+    ConstraintSystem, Simplification, WasmArtifacts, VERSION,
+};
@@ -0,0 +1,81 @@
+use crate::input_user::Input;
+use ansi_term::Colour;
+use lint::{LintConfig, Linter};
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::program_archive::ProgramArchive;
+
+// Runs the lint rules over the program and over the constraints it generates. The
+// command fails if any rule configured as an error reports something.
+pub fn lint_project(program_archive: ProgramArchive, user_input: &Input) -> Result<(), ()> {
+    use constraint_generation::{generate_dag, BuildConfig};
+    let linter = Linter::new(build_config(user_input)?);
+    linter
+        .check_config()
+        .map_err(|msg| eprintln!("{}", Colour::Red.paint(msg)))?;
+    let mut found_errors = print_lints(linter.lint_program(&program_archive), &program_archive);
+
+    let build_config = BuildConfig {
+        no_rounds: user_input.no_rounds(),
+        flag_json_sub: false,
+        flag_s: user_input.reduced_simplification_flag(),
+        flag_f: user_input.unsimplified_flag(),
+        flag_p: user_input.parallel_simplification_flag(),
+        flag_verbose: user_input.flag_verbose(),
+        inspect_constraints: false,
+        flag_old_heuristics: user_input.flag_old_heuristics(),
+        prime: user_input.prime(),
+        cache_dir: None,
+        threads: user_input.threads(),
+    };
+    match generate_dag(program_archive.clone(), build_config) {
+        Result::Ok((dag, warnings)) => {
+            Report::print_reports(&warnings, program_archive.get_file_library());
+            found_errors |= print_lints(linter.lint_dag(&dag, &program_archive), &program_archive);
+        }
+        Result::Err(reports) => {
+            Report::print_reports(&reports, program_archive.get_file_library());
+            return Result::Err(());
+        }
+    }
+    if found_errors {
+        Result::Err(())
+    } else {
+        Result::Ok(())
+    }
+}
+
+// The file given with --config is read first, the --rule settings take precedence
+fn build_config(user_input: &Input) -> Result<LintConfig, ()> {
+    let mut config = match user_input.lint_config() {
+        Some(file) => {
+            let contents = std::fs::read_to_string(file).map_err(|_err| {
+                eprintln!(
+                    "{}",
+                    Colour::Red.paint(format!("Could not read the file {}", file.display()))
+                )
+            })?;
+            LintConfig::from_json(&contents).map_err(|msg| {
+                eprintln!(
+                    "{}",
+                    Colour::Red.paint(format!(
+                        "Invalid lint configuration {}: {}",
+                        file.display(),
+                        msg
+                    ))
+                )
+            })?
+        }
+        None => LintConfig::new(),
+    };
+    for setting in user_input.lint_rules() {
+        config
+            .add_setting(setting)
+            .map_err(|msg| eprintln!("{}", Colour::Red.paint(msg)))?;
+    }
+    Result::Ok(config)
+}
+
+fn print_lints(reports: ReportCollection, program_archive: &ProgramArchive) -> bool {
+    Report::print_reports(&reports, program_archive.get_file_library());
+    reports.iter().any(|report| report.is_error())
+}
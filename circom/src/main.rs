@@ -1,17 +1,26 @@
 mod compilation_user;
 mod execution_user;
+mod format_user;
 mod input_user;
+mod lint_user;
 mod parser_user;
+mod reproducibility_user;
 mod type_analysis_user;
+mod witness_user;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 use ansi_term::Colour;
-use input_user::Input;
+use input_user::{Command, Input};
+use program_structure::diagnostics_writer::{self, DiagnosticsFormat};
 fn main() {
     let result = start();
+    diagnostics_writer::emit_diagnostics(VERSION);
     if result.is_err() {
-        eprintln!("{}", Colour::Red.paint("previous errors were found"));
+        // Keep the standard error a valid document in the machine-readable formats
+        if diagnostics_writer::diagnostics_format() == DiagnosticsFormat::Human {
+            eprintln!("{}", Colour::Red.paint("previous errors were found"));
+        }
         std::process::exit(1);
     } else {
         println!(
@@ -25,9 +34,35 @@ fn main() {
 fn start() -> Result<(), ()> {
     use compilation_user::CompilerConfig;
     use execution_user::ExecutionConfig;
+    use witness_user::WitnessConfig;
     let user_input = Input::new()?;
+    diagnostics_writer::set_diagnostics_format(user_input.diagnostics_format());
+    if user_input.command() == Command::Format {
+        return format_user::format_files(
+            user_input.format_files(),
+            user_input.format_check_flag(),
+        );
+    }
+    if user_input.check_witness_flag() {
+        return witness_user::check_witness(
+            user_input.check_r1cs_file(),
+            user_input.check_wtns_file(),
+            user_input.check_sym_file(),
+        );
+    }
+    if user_input.witness_from_ir_flag() {
+        return witness_user::generate_witness_from_ir(
+            user_input.input_file(),
+            user_input.input_json_file(),
+            user_input.wtns_file(),
+        );
+    }
     let mut program_archive = parser_user::parse_project(&user_input)?;
     type_analysis_user::analyse_project(&mut program_archive)?;
+    if user_input.command() == Command::Lint {
+        return lint_user::lint_project(program_archive, &user_input);
+    }
+    let command = user_input.command();
 
     let config = ExecutionConfig {
         no_rounds: user_input.no_rounds(),
@@ -37,20 +72,49 @@ fn start() -> Result<(), ()> {
         flag_old_heuristics: user_input.flag_old_heuristics(),
         flag_verbose: user_input.flag_verbose(),
         inspect_constraints_flag: user_input.inspect_constraints_flag(),
+        cache_dir: user_input.cache_dir().cloned(),
+        threads: user_input.threads(),
         r1cs_flag: user_input.r1cs_flag(),
         json_constraint_flag: user_input.json_constraints_flag(),
         json_substitution_flag: user_input.json_substitutions_flag(),
         sym_flag: user_input.sym_flag(),
         sym: user_input.sym_file().to_string(),
+        plonk_flag: user_input.plonk_flag(),
+        plonk: user_input.plonk_file().to_string(),
+        ccs_flag: user_input.ccs_flag(),
+        ccs: user_input.ccs_file().to_string(),
+        ccs_json_flag: user_input.ccs_json_flag(),
+        ccs_json: user_input.ccs_json_file().to_string(),
         r1cs: user_input.r1cs_file().to_string(),
         json_constraints: user_input.json_constraints_file().to_string(),
         prime: user_input.prime(),
     };
     let circuit = execution_user::execute_project(program_archive, config)?;
+    match command {
+        Command::Check => return Result::Ok(()),
+        Command::Witness => {
+            let witness_config = WitnessConfig {
+                vcp: circuit,
+                input_file: user_input.input_json_file().to_string(),
+                wtns_file: user_input.wtns_file().to_string(),
+                trace: user_input.trace().map(str::to_string),
+                ir_optimization: user_input.ir_optimization(),
+                ir_input: user_input.ir_input().map(str::to_string),
+            };
+            return witness_user::generate_witness(witness_config);
+        }
+        Command::Compile | Command::Format | Command::Lint => {}
+    }
     let compilation_config = CompilerConfig {
         vcp: circuit,
-        debug_output: user_input.print_ir_flag(),
+        ir_output: if user_input.print_ir_flag() {
+            Some(user_input.ir_file().to_string())
+        } else {
+            None
+        },
+        ir_input: user_input.ir_input().map(str::to_string),
         c_flag: user_input.c_flag(),
+        rust_flag: user_input.rust_flag(),
         wasm_flag: user_input.wasm_flag(),
         wat_flag: user_input.wat_flag(),
         js_folder: user_input.js_folder().to_string(),
@@ -59,10 +123,18 @@ fn start() -> Result<(), ()> {
         c_run_name: user_input.c_run_name(),
         c_file: user_input.c_file().to_string(),
         dat_file: user_input.dat_file().to_string(),
+        rust_folder: user_input.rust_folder().to_string(),
+        rust_run_name: user_input.rust_run_name(),
+        rust_file: user_input.rust_file().to_string(),
         wat_file: user_input.wat_file().to_string(),
         wasm_file: user_input.wasm_file().to_string(),
         produce_input_log: user_input.main_inputs_flag(),
+        trace: user_input.trace().map(str::to_string),
+        ir_optimization: user_input.ir_optimization(),
     };
     compilation_user::compile(compilation_config)?;
+    if user_input.check_reproducible_flag() {
+        reproducibility_user::check_reproducible(&user_input)?;
+    }
     Result::Ok(())
 }
@@ -0,0 +1,57 @@
+use crate::input_user::Input;
+use ansi_term::Colour;
+use circom::{CompilationArtifacts, CompilerSession, Simplification};
+
+fn compile_in_memory(user_input: &Input) -> Result<CompilationArtifacts, ()> {
+    let simplification = if user_input.unsimplified_flag() {
+        Simplification::O0
+    } else if user_input.reduced_simplification_flag() {
+        Simplification::O1
+    } else {
+        Simplification::O2(user_input.no_rounds())
+    };
+    // The cache is not used, otherwise the second compilation would reuse the
+    // instances of the first one
+    let mut session = CompilerSession::from_file(user_input.input_file())
+        .prime(&user_input.prime())
+        .simplification(simplification)
+        .parallel_simplification(user_input.parallel_simplification_flag())
+        .old_heuristics(user_input.flag_old_heuristics())
+        .threads(user_input.threads())
+        .ir_optimization(user_input.ir_optimization())
+        .wasm(true)
+        .c(true);
+    for library in user_input.get_link_libraries() {
+        session = session.link_library(library.clone());
+    }
+    session.run().map_err(|_| {
+        eprintln!(
+            "{}",
+            Colour::Red
+                .paint("The circuit could not be compiled again to check its reproducibility")
+        )
+    })
+}
+
+// Compiles the circuit twice and compares the r1cs, sym, wasm and C++ outputs of both
+pub fn check_reproducible(user_input: &Input) -> Result<(), ()> {
+    let first = compile_in_memory(user_input)?;
+    let second = compile_in_memory(user_input)?;
+    let differing = first.differing_outputs(&second);
+    if differing.is_empty() {
+        println!(
+            "{} {}.r1cs, {}.sym, {}.wasm and the C++ files",
+            Colour::Green.paint("Reproducible outputs:"),
+            first.name,
+            first.name,
+            first.name
+        );
+        Result::Ok(())
+    } else {
+        Result::Err(eprintln!(
+            "{} {}",
+            Colour::Red.paint("Two compilations of the circuit produced different outputs:"),
+            differing.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::compilation_user::load_ir;
+use crate::VERSION;
+use ansi_term::Colour;
+use compiler::compiler_interface;
+use compiler::compiler_interface::{Circuit, Config, OptimizationLevel, VCP};
+use program_structure::error_definition::Report;
+use program_structure::file_definition::FileLibrary;
+use witness::constraint_checker;
+use witness::input_reader;
+use witness::witness_calculator::WitnessCalculator;
+use witness::wtns_writer;
+
+pub struct WitnessConfig {
+    pub input_file: String,
+    pub wtns_file: String,
+    pub trace: Option<String>,
+    pub ir_optimization: OptimizationLevel,
+    pub ir_input: Option<String>,
+    pub vcp: VCP,
+}
+
+pub fn generate_witness(config: WitnessConfig) -> Result<(), ()> {
+    let mut circuit = compiler_interface::run_compiler(
+        config.vcp,
+        Config {
+            produce_input_log: false,
+            wat_flag: false,
+            trace: config.trace,
+            ir_optimization: config.ir_optimization,
+        },
+        VERSION,
+    )?;
+    if let Some(file) = &config.ir_input {
+        load_ir(&mut circuit, file)?;
+    }
+    write_witness(&circuit, &config.input_file, &config.wtns_file)
+}
+
+// Computes the witness with the circuit of an IR file written with --irout, without
+// compiling the circuit again
+pub fn generate_witness_from_ir(ir_file: &str, input_file: &str, wtns_file: &str) -> Result<(), ()> {
+    let circuit = compiler_interface::read_ir_circuit(ir_file).map_err(|message| {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!("Could not read the IR file {}", message))
+        )
+    })?;
+    write_witness(&circuit, input_file, wtns_file)
+}
+
+fn write_witness(circuit: &Circuit, input_file: &str, wtns_file: &str) -> Result<(), ()> {
+    let mut calculator = WitnessCalculator::new(circuit);
+    let prime = calculator.get_prime().clone();
+    let witness = input_reader::read_inputs(input_file, &prime)
+        .and_then(|inputs| calculator.calculate_witness(&inputs))
+        .map_err(|report| Report::print_reports(&[report], &FileLibrary::new()))?;
+
+    if let Result::Ok(()) = wtns_writer::write_wtns(wtns_file, &prime, &witness) {
+        println!(
+            "{} {}",
+            Colour::Green.paint("Written successfully:"),
+            wtns_file
+        );
+        Result::Ok(())
+    } else {
+        eprintln!(
+            "{}",
+            Colour::Red.paint("Could not write the output in the given path")
+        );
+        Result::Err(())
+    }
+}
+
+pub fn check_witness(r1cs_file: &str, wtns_file: &str, sym_file: Option<&str>) -> Result<(), ()> {
+    let reports = constraint_checker::check_constraints(r1cs_file, wtns_file, sym_file)
+        .map_err(|report| Report::print_reports(&[report], &FileLibrary::new()))?;
+    if reports.is_empty() {
+        println!(
+            "{}",
+            Colour::Green.paint("The witness satisfies all the constraints")
+        );
+        Result::Ok(())
+    } else {
+        Report::print_reports(&reports, &FileLibrary::new());
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!(
+                "Errors found checking the witness: {}",
+                reports.len()
+            ))
+        );
+        Result::Err(())
+    }
+}
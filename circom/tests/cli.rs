@@ -0,0 +1,548 @@
+// Runs the circom binary with the compile, witness and check subcommands
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const CIRCUIT: &str = "pragma circom 2.0.0;
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c;
+    signal d;
+    d <== a * b;
+    c <== d * a;
+}
+component main = Main();
+";
+
+// Computes its outputs with variables, loops and a failing assert, which the optimizations
+// of the code of the witness fold and unroll
+const LOOPS: &str = "pragma circom 2.0.0;
+template Square() {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}
+template Main(n) {
+    signal input a[n];
+    signal output b[n];
+    signal output c;
+    component sq[n];
+    var acc = 0;
+    var unused = 0;
+    for (var i = 0; i < n; i++) {
+        sq[i] = Square();
+        sq[i].in <== a[i];
+        b[i] <== sq[i].out;
+        acc += a[i];
+        unused = acc * 2;
+    }
+    if (n > 2) {
+        c <-- acc \\ 2;
+    } else {
+        c <-- acc;
+    }
+    assert(c != 7);
+}
+component main = Main(3);
+";
+
+// A directory of the test, removed when the test ends
+struct TestDir(PathBuf);
+
+impl TestDir {
+    fn new(test: &str) -> TestDir {
+        let dir = std::env::temp_dir().join(format!("circom_cli_{}_{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("circuit.circom"), CIRCUIT).unwrap();
+        std::fs::write(dir.join("input.json"), "{\"a\": \"3\", \"b\": \"4\"}").unwrap();
+        TestDir(dir)
+    }
+
+    fn path(&self, file: &str) -> String {
+        self.0.join(file).to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// The exit status and the output of both streams
+fn circom(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_circom"))
+        .args(args)
+        .output()
+        .unwrap();
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    (output.status.success(), text)
+}
+
+fn compile(dir: &TestDir) {
+    let circuit = dir.path("circuit.circom");
+    let output = dir.path("");
+    let (ok, text) = circom(&[&circuit, "--r1cs", "--sym", "--irout", "-o", &output]);
+    assert!(ok, "{}", text);
+}
+
+#[test]
+fn compile_writes_the_outputs() {
+    let dir = TestDir::new("compile");
+    compile(&dir);
+    for file in ["circuit.r1cs", "circuit.sym", "circuit.ir"] {
+        assert!(Path::new(&dir.path(file)).is_file(), "{}", file);
+    }
+
+    std::fs::write(dir.path("wrong.circom"), CIRCUIT.replace("d * a;", "d * a")).unwrap();
+    let (ok, _) = circom(&["compile", &dir.path("wrong.circom"), "-o", &dir.path("")]);
+    assert!(!ok);
+}
+
+#[test]
+fn session_produces_the_files_written_by_the_compiler() {
+    let dir = TestDir::new("session");
+    let circuit = dir.path("circuit.circom");
+    let output = dir.path("");
+    let (ok, text) = circom(&[&circuit, "--r1cs", "--sym", "--wasm", "--c", "-o", &output]);
+    assert!(ok, "{}", text);
+    let artifacts = circom::CompilerSession::from_file(&circuit)
+        .wasm(true)
+        .c(true)
+        .run()
+        .ok()
+        .unwrap();
+    let read = |file: &str| std::fs::read(dir.path(file)).unwrap();
+    assert!(artifacts.constraints.r1cs == read("circuit.r1cs"));
+    assert!(artifacts.sym == read("circuit.sym"));
+    let wasm = artifacts.wasm.unwrap();
+    assert!(wasm.wasm == read("circuit_js/circuit.wasm"));
+    for (file, contents) in &wasm.support_files {
+        assert!(
+            contents.as_bytes() == read(&format!("circuit_js/{}", file)),
+            "{}",
+            file
+        );
+    }
+    let files = artifacts.c.unwrap().files;
+    let written = std::fs::read_dir(dir.path("circuit_cpp")).unwrap().count();
+    assert_eq!(files.len(), written);
+    for (file, contents) in &files {
+        assert!(
+            *contents == read(&format!("circuit_cpp/{}", file)),
+            "{}",
+            file
+        );
+    }
+}
+
+#[test]
+fn witness_from_the_circuit_and_from_the_ir() {
+    let dir = TestDir::new("witness");
+    compile(&dir);
+    let input = dir.path("input.json");
+    let (ok, text) = circom(&[
+        "witness",
+        &dir.path("circuit.circom"),
+        &input,
+        &dir.path("a.wtns"),
+    ]);
+    assert!(ok, "{}", text);
+    let (ok, text) = circom(&[
+        "witness",
+        &dir.path("circuit.ir"),
+        &input,
+        &dir.path("b.wtns"),
+    ]);
+    assert!(ok, "{}", text);
+    let from_circuit = std::fs::read(dir.path("a.wtns")).unwrap();
+    assert_eq!(from_circuit, std::fs::read(dir.path("b.wtns")).unwrap());
+
+    std::fs::write(dir.path("missing.json"), "{\"a\": \"3\"}").unwrap();
+    let (ok, text) = circom(&[
+        "witness",
+        &dir.path("circuit.ir"),
+        &dir.path("missing.json"),
+    ]);
+    assert!(!ok);
+    assert!(text.contains("Missing signal b"), "{}", text);
+
+    std::fs::write(dir.path("wrong.ir"), "(version 1)").unwrap();
+    let (ok, text) = circom(&[
+        "witness",
+        &dir.path("wrong.ir"),
+        &input,
+        &dir.path("c.wtns"),
+    ]);
+    assert!(!ok);
+    assert!(text.contains("Could not read the IR file"), "{}", text);
+}
+
+#[test]
+fn check_the_circuit_and_a_witness() {
+    let dir = TestDir::new("check");
+    let (ok, text) = circom(&["check", &dir.path("circuit.circom")]);
+    assert!(ok, "{}", text);
+    compile(&dir);
+    let witness = dir.path("w.wtns");
+    let (ok, text) = circom(&[
+        "witness",
+        &dir.path("circuit.ir"),
+        &dir.path("input.json"),
+        &witness,
+    ]);
+    assert!(ok, "{}", text);
+    let r1cs = dir.path("circuit.r1cs");
+    let sym = dir.path("circuit.sym");
+    let (ok, text) = circom(&["check", "--r1cs", &r1cs, "--wtns", &witness, "--sym", &sym]);
+    assert!(ok, "{}", text);
+    assert!(
+        text.contains("The witness satisfies all the constraints"),
+        "{}",
+        text
+    );
+
+    // the last value of the witness is d = 12, which becomes 13
+    let mut tampered = std::fs::read(&witness).unwrap();
+    let last = tampered.len() - 32;
+    tampered[last] ^= 1;
+    let witness = dir.path("tampered.wtns");
+    std::fs::write(&witness, tampered).unwrap();
+    let (ok, text) = circom(&["check", "--r1cs", &r1cs, "--wtns", &witness, "--sym", &sym]);
+    assert!(!ok);
+    assert!(text.contains("Constraint 0 is not satisfied"), "{}", text);
+    assert!(text.contains("main.d = 13"), "{}", text);
+}
+
+#[test]
+fn optimized_code_computes_the_same_witness() {
+    let dir = TestDir::new("ir_opt");
+    let circuit = dir.path("loops.circom");
+    std::fs::write(&circuit, LOOPS).unwrap();
+    let inputs = [
+        ("a.json", "{\"a\": [\"3\", 4, -2]}"),
+        ("b.json", "{\"a\": [0, 1, 5]}"),
+        ("failing.json", "{\"a\": [10, 4, 0]}"),
+    ];
+    for (file, input) in inputs {
+        std::fs::write(dir.path(file), input).unwrap();
+    }
+    let witness = |input: &str, level: &str| {
+        let output = dir.path(&format!("{}_{}.wtns", input, level));
+        let (ok, text) = circom(&[
+            "witness",
+            &circuit,
+            &dir.path(input),
+            &output,
+            "--ir-opt",
+            level,
+        ]);
+        (ok, text, std::fs::read(output).ok())
+    };
+    for input in ["a.json", "b.json"] {
+        let (ok, text, expected) = witness(input, "0");
+        assert!(ok, "{}", text);
+        for level in ["1", "2"] {
+            let (ok, text, optimized) = witness(input, level);
+            assert!(ok, "{}", text);
+            assert_eq!(optimized, expected, "--ir-opt {}", level);
+        }
+    }
+    for level in ["0", "1", "2"] {
+        let (ok, text, _) = witness("failing.json", level);
+        assert!(!ok, "--ir-opt {}: {}", level, text);
+    }
+}
+
+// Builds the crate written with --rust together with a program that prints the witness of the
+// given inputs, one value per line. The dependencies are built once in the target folder of
+// the tests.
+fn witness_of_the_rust_crate(
+    crate_folder: &str,
+    crate_name: &str,
+    inputs: &[(&str, &[&str])],
+) -> String {
+    let mut main = format!(
+        "use std::collections::HashMap;\nuse {}::{{calculate_witness, Fr}};\n\nfn main() {{\n    let mut inputs = HashMap::new();\n",
+        crate_name
+    );
+    for (name, values) in inputs {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| format!("\"{}\".parse::<Fr>().unwrap()", value))
+            .collect();
+        main.push_str(&format!(
+            "    inputs.insert(\"{}\".to_string(), vec![{}]);\n",
+            name,
+            values.join(", ")
+        ));
+    }
+    main.push_str("    for value in calculate_witness(&inputs) {\n        println!(\"{}\", value);\n    }\n}\n");
+    std::fs::write(Path::new(crate_folder).join("src").join("main.rs"), main).unwrap();
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(Path::new(crate_folder).join("Cargo.toml"))
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated_crates"),
+        )
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// The values of a wtns file, one per line
+fn witness_of_the_file(wtns: &str) -> String {
+    let data = witness::wtns_reader::read_wtns(wtns).unwrap();
+    data.witness
+        .iter()
+        .map(|value| format!("{}\n", value))
+        .collect()
+}
+
+#[test]
+fn rust_crate_computes_the_witness_of_the_interpreter() {
+    let dir = TestDir::new("rust");
+    let circuit = dir.path("loops.circom");
+    std::fs::write(&circuit, LOOPS).unwrap();
+    let (ok, text) = circom(&[&circuit, "--rust", "-o", &dir.path("")]);
+    assert!(ok, "{}", text);
+
+    std::fs::write(dir.path("a.json"), "{\"a\": [\"3\", 4, -2]}").unwrap();
+    let (ok, text) = circom(&[
+        "witness",
+        &circuit,
+        &dir.path("a.json"),
+        &dir.path("a.wtns"),
+    ]);
+    assert!(ok, "{}", text);
+    let witness = witness_of_the_rust_crate(
+        &dir.path("loops_rust"),
+        "loops",
+        &[("a", &["3", "4", "-2"])],
+    );
+    assert_eq!(witness, witness_of_the_file(&dir.path("a.wtns")));
+}
+
+// Uses the operations of the field whose results depend on the prime
+const FIELD_OPERATIONS: &str = "pragma circom 2.0.0;
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c[9];
+    c[0] <== a * b;
+    c[1] <-- a / b;
+    c[2] <-- a \\ b;
+    c[3] <-- a % b;
+    c[4] <-- a ** 5;
+    c[5] <-- -a + 7;
+    c[6] <-- (b << 3) ^ (a >> 2);
+    c[7] <-- a < b;
+    c[8] <-- a + 1000000000 * b;
+}
+component main = Main();
+";
+
+// Whether the program runs, the tests that need it are skipped otherwise
+fn is_available(program: &str, args: &[&str], stdin: &str) -> bool {
+    use std::io::Write;
+    use std::process::Stdio;
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+            child.wait().is_ok_and(|status| status.success())
+        }
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn field_code_of_a_custom_prime_computes_the_witness_of_the_interpreter() {
+    let dir = TestDir::new("prime");
+    let circuit = dir.path("field.circom");
+    std::fs::write(&circuit, FIELD_OPERATIONS).unwrap();
+    std::fs::write(
+        dir.path("field.json"),
+        "{\"a\": \"-1\", \"b\": \"123456789\"}",
+    )
+    .unwrap();
+    let output = dir.path("");
+    let prime = "2013265921";
+    let (ok, text) = circom(&[&circuit, "--wasm", "--c", "--prime", prime, "-o", &output]);
+    assert!(ok, "{}", text);
+    let (ok, text) = circom(&[
+        "witness",
+        &circuit,
+        &dir.path("field.json"),
+        &dir.path("expected.wtns"),
+        "--prime",
+        prime,
+    ]);
+    assert!(ok, "{}", text);
+    let expected = witness_of_the_file(&dir.path("expected.wtns"));
+    assert!(expected.lines().all(|value| value.len() <= prime.len()));
+
+    if is_available("node", &["--version"], "") {
+        let output = Command::new("node")
+            .arg(dir.path("field_js/generate_witness.js"))
+            .arg(dir.path("field_js/field.wasm"))
+            .arg(dir.path("field.json"))
+            .arg(dir.path("wasm.wtns"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(witness_of_the_file(&dir.path("wasm.wtns")), expected);
+    }
+
+    let headers = "#include <gmp.h>\n#include <nlohmann/json.hpp>\n";
+    let has_cpp_toolchain = is_available("make", &["--version"], "")
+        && is_available("g++", &["-x", "c++", "-E", "-o", "/dev/null", "-"], headers);
+    if has_cpp_toolchain {
+        let output = Command::new("make")
+            .current_dir(dir.path("field_cpp"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let output = Command::new(dir.path("field_cpp/field"))
+            .arg(dir.path("field.json"))
+            .arg(dir.path("cpp.wtns"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(witness_of_the_file(&dir.path("cpp.wtns")), expected);
+    }
+}
+
+// Uses the operations over the bits of the field elements and the comparisons, which depend on
+// the number of bits of the prime
+const BIT_OPERATIONS: &str = "pragma circom 2.0.0;
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c[10];
+    c[0] <-- ~a;
+    c[1] <-- ~b;
+    c[2] <-- a << 5;
+    c[3] <-- b >> 3;
+    c[4] <-- a >> b;
+    c[5] <-- (~a) & b;
+    c[6] <-- a < b;
+    c[7] <-- a >= b;
+    c[8] <-- -a > b;
+    c[9] <-- ~(a ^ b) | 1;
+}
+component main = Main();
+";
+
+// The witness that the WebAssembly code written with --wasm in the folder of the test computes
+fn witness_of_the_wasm(dir: &TestDir, name: &str, input: &str) -> String {
+    let wtns = dir.path(&format!("{}_wasm.wtns", name));
+    let output = Command::new("node")
+        .arg(dir.path(&format!("{}_js/generate_witness.js", name)))
+        .arg(dir.path(&format!("{}_js/{}.wasm", name, name)))
+        .arg(input)
+        .arg(&wtns)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    witness_of_the_file(&wtns)
+}
+
+#[test]
+fn interpreter_computes_the_bit_operations_of_the_wasm_code() {
+    if !is_available("node", &["--version"], "") {
+        return;
+    }
+    let dir = TestDir::new("bits");
+    let circuit = dir.path("bits.circom");
+    std::fs::write(&circuit, BIT_OPERATIONS).unwrap();
+    let input = dir.path("bits.json");
+    std::fs::write(&input, "{\"a\": \"123457\", \"b\": \"7\"}").unwrap();
+    for prime in ["bn128", "2013265921", "2147483647"] {
+        let (ok, text) = circom(&[&circuit, "--wasm", "--prime", prime, "-o", &dir.path("")]);
+        assert!(ok, "{}", text);
+        let wtns = dir.path("bits.wtns");
+        let (ok, text) = circom(&["witness", &circuit, &input, &wtns, "--prime", prime]);
+        assert!(ok, "{}", text);
+        assert_eq!(
+            witness_of_the_file(&wtns),
+            witness_of_the_wasm(&dir, "bits", &input),
+            "{}",
+            prime
+        );
+    }
+}
+
+#[test]
+fn rust_crate_computes_the_bit_operations_of_the_wasm_code() {
+    let dir = TestDir::new("rust_bits");
+    let circuit = dir.path("bits.circom");
+    std::fs::write(&circuit, BIT_OPERATIONS).unwrap();
+    let input = dir.path("bits.json");
+    std::fs::write(&input, "{\"a\": \"123457\", \"b\": \"7\"}").unwrap();
+    let has_node = is_available("node", &["--version"], "");
+    // the last one is 2^521 - 1, wider than 256 bits
+    let primes = [
+        "bn128",
+        "2013265921",
+        "6864797660130609714981900799081393217269435300143305409394463459185543183397656052122559640661454554977296311391480858037121987999716643812574028291115057151",
+    ];
+    for prime in primes {
+        let (ok, text) = circom(&[
+            &circuit,
+            "--rust",
+            "--wasm",
+            "--prime",
+            prime,
+            "-o",
+            &dir.path(""),
+        ]);
+        assert!(ok, "{}", text);
+        let witness = witness_of_the_rust_crate(
+            &dir.path("bits_rust"),
+            "bits",
+            &[("a", &["123457"]), ("b", &["7"])],
+        );
+        let wtns = dir.path("bits.wtns");
+        let (ok, text) = circom(&["witness", &circuit, &input, &wtns, "--prime", prime]);
+        assert!(ok, "{}", text);
+        assert_eq!(witness, witness_of_the_file(&wtns), "{}", prime);
+        if has_node {
+            assert_eq!(
+                witness,
+                witness_of_the_wasm(&dir, "bits", &input),
+                "{}",
+                prime
+            );
+        }
+    }
+}
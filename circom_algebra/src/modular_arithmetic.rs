@@ -112,6 +112,11 @@ pub fn complement_256(elem: &BigInt, field: &BigInt) -> BigInt {
     modulus(&cp, field)
 }
 
+// Complement of the bits of the field elements, as the generated witness calculators compute it
+pub fn complement(elem: &BigInt, field: &BigInt) -> BigInt {
+    modulus(&(mask(field) ^ modulus(elem, field)), field)
+}
+
 pub fn shift_l(left: &BigInt, right: &BigInt, field: &BigInt) -> Result<BigInt, ArithmeticError> {
     let two = BigInt::from(2);
     let top = field / &two;
@@ -269,6 +274,15 @@ mod tests {
         assert_eq!(big_num_complement_complement, big_num_modulus);
     }
     #[test]
+    fn complement_of_the_bits_of_the_field() {
+        let field = BigInt::parse_bytes(FIELD.as_bytes(), 10)
+            .expect("generating the big int was not possible");
+        // the elements have 9 bits, 1234 = 206 and 511 ^ 206 = 305 = 48
+        assert_eq!(complement(&BigInt::from(1234), &field), BigInt::from(48));
+        assert_eq!(complement(&BigInt::from(0), &field), BigInt::from(254));
+        assert_eq!(complement(&BigInt::from(-1), &field), BigInt::from(255));
+    }
+    #[test]
     fn lesser_eq_test() {
         let field = BigInt::parse_bytes(FIELD.as_bytes(), 10)
             .expect("generating the big int was not possible");
@@ -399,18 +399,21 @@ fn treat_constraint_4(
 #[allow(dead_code)]
 fn take_signal_1(signals: &SignalDefinition, constraint: &C) -> Option<usize> {
     let mut ret = Option::None;
+    // Signals already deleted are preferred, the greatest one is taken so that the
+    // choice does not depend on the iteration order of the constraint
+    let mut deleted = Option::None;
     for k in constraint.c().keys() {
         if signals.can_be_taken(*k) {
             if signals.is_deleted(*k) {
-                ret = Some(*k);
-                break;
+                let new_v = deleted.map_or(*k, |v| std::cmp::max(*k, v));
+                deleted = Some(new_v);
             } else {
                 let new_v = ret.map_or(*k, |v| std::cmp::max(*k, v));
                 ret = Some(new_v);
             }
         }
     }
-    ret
+    deleted.or(ret)
 }
 
 fn take_signal_3(signals: &SignalDefinition, constraint: &C) -> Option<usize> {
@@ -430,12 +433,13 @@ fn take_signal_4(
     constraint: &C,
 ) -> Option<usize> {
     let mut ret = Option::None;
+    let mut deleted = Option::None;
     let mut ocurrences_ret: Option<usize> = Option::None;
     for k in constraint.c().keys() {
         if signals.can_be_taken(*k) {
             if signals.is_deleted(*k) {
-                ret = Some(*k);
-                break;
+                let new_v = deleted.map_or(*k, |v| std::cmp::max(*k, v));
+                deleted = Some(new_v);
             } else {
                 let new_ocurrences = info_ocurrences.signal_to_ocurrences.get(k).unwrap();
                 match ocurrences_ret {
@@ -455,7 +459,7 @@ fn take_signal_4(
             }
         }
     }
-    ret
+    deleted.or(ret)
 }
 
 fn normalize_substitutions(substitutions: SHNotNormalized, field: &BigInt) -> SH {
@@ -635,6 +639,8 @@ where
     }
     let mut substitutions = LinkedList::new();
     let mut removed = LinkedList::new();
+    let mut non_overlapping: Vec<_> = non_overlapping.into_iter().collect();
+    non_overlapping.sort_by_key(|(s, _)| *s);
     for (s, v) in non_overlapping {
         LinkedList::push_back(&mut removed, s);
         LinkedList::push_back(&mut substitutions, v);
@@ -0,0 +1,252 @@
+use codespan_reporting::files::Files;
+use parser::source_provider::SourceProvider;
+use program_structure::ast::{Expression, Meta, SignalType, Statement, VariableType};
+use program_structure::error_definition::ReportCollection;
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use program_structure::program_archive::ProgramArchive;
+use std::path::PathBuf;
+use type_analysis::check_types::check_types;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Result of parsing and type checking a project from one of its files. Files without a
+// main component can only be parsed, so they have no archive.
+pub struct Analysis {
+    pub file_library: FileLibrary,
+    pub reports: ReportCollection,
+    pub archive: Option<ProgramArchive>,
+}
+
+pub fn analyse(file: &str, link_libraries: &[PathBuf], provider: &dyn SourceProvider) -> Analysis {
+    let result = parser::run_parser_with_provider(
+        file.to_string(),
+        VERSION,
+        link_libraries.to_vec(),
+        provider,
+    );
+    match result {
+        Result::Err((file_library, reports)) => {
+            // Included files do not need a main component
+            let reports = reports
+                .into_iter()
+                .filter(|report| report.get_code().to_string() != "P1001")
+                .collect();
+            Analysis {
+                file_library,
+                reports,
+                archive: None,
+            }
+        }
+        Result::Ok((mut archive, mut reports)) => {
+            match check_types(&mut archive) {
+                Result::Ok(mut warnings) => reports.append(&mut warnings),
+                Result::Err(mut errors) => reports.append(&mut errors),
+            }
+            let file_library = archive.file_library.clone();
+            Analysis {
+                file_library,
+                reports,
+                archive: Some(archive),
+            }
+        }
+    }
+}
+
+// File names are stored quoted by the parser
+pub fn file_path(file_library: &FileLibrary, file_id: FileID) -> Option<String> {
+    let name = file_library.to_storage().name(file_id)?;
+    Some(name.trim_matches('"').to_string())
+}
+
+pub fn file_id(file_library: &FileLibrary, path: &str) -> Option<FileID> {
+    let mut file_id = 0;
+    while let Some(name) = file_path(file_library, file_id) {
+        if name == path {
+            return Some(file_id);
+        }
+        file_id += 1;
+    }
+    None
+}
+
+pub fn source(file_library: &FileLibrary, file_id: FileID) -> Option<&str> {
+    file_library.to_storage().source(file_id)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+pub fn identifier_at(source: &str, offset: usize) -> Option<(String, FileLocation)> {
+    if offset > source.len() || !source.is_char_boundary(offset) {
+        return None;
+    }
+    let start = source[..offset]
+        .rfind(|c: char| !is_identifier_char(c))
+        .map_or(0, |i| i + 1);
+    let end = source[offset..]
+        .find(|c: char| !is_identifier_char(c))
+        .map_or(source.len(), |i| offset + i);
+    if start == end {
+        None
+    } else {
+        Some((source[start..end].to_string(), start..end))
+    }
+}
+
+// Templates and functions only record where their parameters are, their name is the
+// identifier right before them
+fn name_location(source: &str, param_location: &FileLocation, name: &str) -> FileLocation {
+    let before = &source[..param_location.start.min(source.len())];
+    match before.rfind(name) {
+        Some(start) => start..start + name.len(),
+        None => param_location.clone(),
+    }
+}
+
+struct Declaration<'a> {
+    meta: &'a Meta,
+    xtype: &'a VariableType,
+    dimensions: &'a [Expression],
+}
+
+fn find_declaration<'a>(statement: &'a Statement, searched: &str) -> Option<Declaration<'a>> {
+    use Statement::*;
+    match statement {
+        Declaration {
+            meta,
+            xtype,
+            name,
+            dimensions,
+            ..
+        } if name == searched => Some(self::Declaration {
+            meta,
+            xtype,
+            dimensions,
+        }),
+        IfThenElse {
+            if_case, else_case, ..
+        } => find_declaration(if_case, searched).or_else(|| {
+            else_case
+                .as_ref()
+                .and_then(|s| find_declaration(s, searched))
+        }),
+        While { stmt, .. } => find_declaration(stmt, searched),
+        InitializationBlock {
+            initializations, ..
+        } => initializations
+            .iter()
+            .find_map(|s| find_declaration(s, searched)),
+        Block { stmts, .. } => stmts.iter().find_map(|s| find_declaration(s, searched)),
+        _ => None,
+    }
+}
+
+fn contains(location: &FileLocation, offset: usize) -> bool {
+    location.start <= offset && offset <= location.end
+}
+
+// Body of the template or function of the file that contains the offset
+fn enclosing_body(archive: &ProgramArchive, file_id: FileID, offset: usize) -> Option<&Statement> {
+    let templates = archive
+        .get_templates()
+        .values()
+        .filter(|t| t.get_file_id() == file_id)
+        .map(|t| t.get_body());
+    let functions = archive
+        .get_functions()
+        .values()
+        .filter(|f| f.get_file_id() == file_id)
+        .map(|f| f.get_body());
+    templates
+        .chain(functions)
+        .find(|body| contains(&body.get_meta().location, offset))
+}
+
+// Location of the definition of the identifier found at the given offset
+pub fn definition(
+    archive: &ProgramArchive,
+    file_id: FileID,
+    offset: usize,
+) -> Option<(FileID, FileLocation)> {
+    let file_library = archive.get_file_library();
+    let (name, _) = identifier_at(source(file_library, file_id)?, offset)?;
+    if let Some(body) = enclosing_body(archive, file_id, offset) {
+        if let Some(declaration) = find_declaration(body, &name) {
+            return Some((file_id, declaration.meta.location.clone()));
+        }
+    }
+    let (target, param_location) = if archive.contains_template(&name) {
+        let template = archive.get_template_data(&name);
+        (template.get_file_id(), template.get_param_location())
+    } else if archive.contains_function(&name) {
+        let function = archive.get_function_data(&name);
+        (function.get_file_id(), function.get_param_location())
+    } else {
+        return None;
+    };
+    let location = name_location(source(file_library, target)?, &param_location, &name);
+    Some((target, location))
+}
+
+// Description of the identifier found at the given offset
+pub fn hover(archive: &ProgramArchive, file_id: FileID, offset: usize) -> Option<String> {
+    let file_library = archive.get_file_library();
+    let source = source(file_library, file_id)?;
+    let (name, _) = identifier_at(source, offset)?;
+    if let Some(body) = enclosing_body(archive, file_id, offset) {
+        if let Some(declaration) = find_declaration(body, &name) {
+            return Some(describe_declaration(source, &name, &declaration));
+        }
+    }
+    if archive.contains_template(&name) {
+        let template = archive.get_template_data(&name);
+        let kind = if template.is_custom_gate() {
+            "template custom"
+        } else if template.is_parallel() {
+            "template parallel"
+        } else {
+            "template"
+        };
+        Some(format!(
+            "{} {}({})",
+            kind,
+            name,
+            template.get_name_of_params().join(", ")
+        ))
+    } else if archive.contains_function(&name) {
+        let function = archive.get_function_data(&name);
+        Some(format!(
+            "function {}({})",
+            name,
+            function.get_name_of_params().join(", ")
+        ))
+    } else {
+        None
+    }
+}
+
+fn describe_declaration(source: &str, name: &str, declaration: &Declaration) -> String {
+    let kind = match declaration.xtype {
+        VariableType::Var => "var".to_string(),
+        VariableType::Component | VariableType::AnonymousComponent => "component".to_string(),
+        VariableType::Signal(signal_type, _) => match signal_type {
+            SignalType::Input => "signal input".to_string(),
+            SignalType::Output => "signal output".to_string(),
+            SignalType::Intermediate => "signal".to_string(),
+        },
+        VariableType::Bus(bus, ..) => bus.name.clone(),
+    };
+    let mut description = format!("{} {}", kind, name);
+    for dimension in declaration.dimensions {
+        let location = &dimension.get_meta().location;
+        let text = source.get(location.clone()).unwrap_or("?");
+        description = format!("{}[{}]", description, text);
+    }
+    if let VariableType::Signal(_, tags) = declaration.xtype {
+        if !tags.is_empty() {
+            description = format!("{} {{{}}}", description, tags.join(", "));
+        }
+    }
+    description
+}
@@ -0,0 +1,15 @@
+mod analysis;
+mod server;
+mod transport;
+
+use server::Server;
+
+// Language server for circom, speaks the language server protocol over stdio
+fn main() {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let result = Server::new().run(&mut stdin.lock(), &mut stdout.lock());
+    if result.is_err() {
+        std::process::exit(1);
+    }
+}
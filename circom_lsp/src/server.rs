@@ -0,0 +1,465 @@
+use super::analysis::{self, Analysis};
+use super::transport::{read_message, write_message};
+use json::{object, JsonValue};
+use parser::source_provider::{FileSystemProvider, LayeredProvider, MemoryProvider};
+use program_structure::error_definition::Report;
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+const METHOD_NOT_FOUND: i32 = -32601;
+const SEVERITY_ERROR: i32 = 1;
+const SEVERITY_WARNING: i32 = 2;
+
+pub struct Server {
+    // Text of the open documents by uri
+    documents: BTreeMap<String, String>,
+    analyses: HashMap<String, Analysis>,
+    link_libraries: Vec<PathBuf>,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server {
+            documents: BTreeMap::new(),
+            analyses: HashMap::new(),
+            link_libraries: Vec::new(),
+            shutdown: false,
+        }
+    }
+
+    // Serves the messages of the reader until the client asks to exit. Fails if the
+    // client exits without a shutdown request or the connection is broken.
+    pub fn run<R: BufRead, W: Write>(&mut self, reader: &mut R, writer: &mut W) -> Result<(), ()> {
+        while let Some(message) = read_message(reader)? {
+            if message["method"] == "exit" {
+                return if self.shutdown {
+                    Result::Ok(())
+                } else {
+                    Result::Err(())
+                };
+            }
+            for response in self.handle(&message) {
+                write_message(writer, &response)?;
+            }
+        }
+        Result::Err(())
+    }
+
+    fn handle(&mut self, message: &JsonValue) -> Vec<JsonValue> {
+        let id = &message["id"];
+        let params = &message["params"];
+        let method = message["method"].as_str().unwrap_or_default();
+        match method {
+            "initialize" => {
+                for library in params["initializationOptions"]["linkLibraries"].members() {
+                    if let Some(library) = library.as_str() {
+                        self.link_libraries.push(PathBuf::from(library));
+                    }
+                }
+                let capabilities = object! {
+                    "textDocumentSync" => 1,
+                    "hoverProvider" => true,
+                    "definitionProvider" => true,
+                };
+                vec![response(id, object! { "capabilities" => capabilities })]
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                vec![response(id, JsonValue::Null)]
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                self.update_document(&document["uri"], &document["text"]);
+                self.analyse_documents()
+            }
+            "textDocument/didChange" => {
+                let changes = &params["contentChanges"];
+                self.update_document(
+                    &params["textDocument"]["uri"],
+                    &changes[changes.len().max(1) - 1]["text"],
+                );
+                self.analyse_documents()
+            }
+            "textDocument/didSave" => self.analyse_documents(),
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                self.documents.remove(&uri);
+                self.analyses.remove(&uri);
+                let mut notifications = vec![publish_diagnostics(&uri, JsonValue::new_array())];
+                notifications.append(&mut self.analyse_documents());
+                notifications
+            }
+            "textDocument/hover" => {
+                let result = self.hover(params).unwrap_or(JsonValue::Null);
+                vec![response(id, result)]
+            }
+            "textDocument/definition" => {
+                let result = self.definition(params).unwrap_or(JsonValue::Null);
+                vec![response(id, result)]
+            }
+            _ if id.is_null() => Vec::new(),
+            _ => vec![object! {
+                "jsonrpc" => "2.0",
+                "id" => id.clone(),
+                "error" => object! {
+                    "code" => METHOD_NOT_FOUND,
+                    "message" => format!("Unknown method {}", method),
+                },
+            }],
+        }
+    }
+
+    fn update_document(&mut self, uri: &JsonValue, text: &JsonValue) {
+        if let (Some(uri), Some(text)) = (uri.as_str(), text.as_str()) {
+            self.documents.insert(uri.to_string(), text.to_string());
+        }
+    }
+
+    // Every open document is analysed again, a change in one of them can affect the
+    // diagnostics of the files that include it
+    fn analyse_documents(&mut self) -> Vec<JsonValue> {
+        let mut analyses = HashMap::new();
+        for uri in self.documents.keys() {
+            let mut memory = MemoryProvider::new();
+            for (uri, text) in &self.documents {
+                memory.add_file(uri_to_path(uri), text.clone());
+            }
+            let provider = LayeredProvider::new(memory, FileSystemProvider::new());
+            let analysis = analysis::analyse(&uri_to_path(uri), &self.link_libraries, &provider);
+            analyses.insert(uri.clone(), analysis);
+        }
+        self.analyses = analyses;
+        self.documents
+            .keys()
+            .map(|uri| publish_diagnostics(uri, self.diagnostics(uri)))
+            .collect()
+    }
+
+    // Analysis of a project that has the document as one of its files. The analysis of
+    // the document itself is used when it has a main component.
+    fn project_of(&self, uri: &str) -> Option<(&Analysis, FileID)> {
+        let path = uri_to_path(uri);
+        let own = self.analyses.get(uri)?;
+        if own.archive.is_some() {
+            return analysis::file_id(&own.file_library, &path).map(|id| (own, id));
+        }
+        let including = self.analyses.values().find_map(|analysis| {
+            analysis.archive.as_ref()?;
+            analysis::file_id(&analysis.file_library, &path).map(|id| (analysis, id))
+        });
+        including.or_else(|| analysis::file_id(&own.file_library, &path).map(|id| (own, id)))
+    }
+
+    fn diagnostics(&self, uri: &str) -> JsonValue {
+        let mut diagnostics = JsonValue::new_array();
+        let (analysis, file_id) = match self.project_of(uri) {
+            Some(project) => project,
+            None => match self.analyses.get(uri) {
+                // The document could not be read by the parser, the reports have no file
+                Some(analysis) => {
+                    for report in &analysis.reports {
+                        diagnostics
+                            .push(to_diagnostic(report, &analysis.file_library, None))
+                            .unwrap();
+                    }
+                    return diagnostics;
+                }
+                None => return diagnostics,
+            },
+        };
+        let is_root = std::ptr::eq(analysis, &self.analyses[uri]);
+        for report in &analysis.reports {
+            let label = report.get_primary().first();
+            let in_document = match label {
+                Some(label) => label.file_id == file_id,
+                None => is_root,
+            };
+            if in_document {
+                let location = label.map(|label| label.range.clone());
+                let source = analysis::source(&analysis.file_library, file_id).unwrap_or_default();
+                let range = location.map(|location| range(source, &location));
+                diagnostics
+                    .push(to_diagnostic(report, &analysis.file_library, range))
+                    .unwrap();
+            }
+        }
+        diagnostics
+    }
+
+    fn position_params(&self, params: &JsonValue) -> Option<(&Analysis, FileID, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let (analysis, file_id) = self.project_of(uri)?;
+        let source = analysis::source(&analysis.file_library, file_id)?;
+        let position = &params["position"];
+        let offset = offset(
+            source,
+            position["line"].as_usize()?,
+            position["character"].as_usize()?,
+        );
+        Some((analysis, file_id, offset))
+    }
+
+    fn hover(&self, params: &JsonValue) -> Option<JsonValue> {
+        let (analysis, file_id, offset) = self.position_params(params)?;
+        let text = analysis::hover(analysis.archive.as_ref()?, file_id, offset)?;
+        Some(object! {
+            "contents" => object! {
+                "kind" => "markdown",
+                "value" => format!("```circom\n{}\n```", text),
+            },
+        })
+    }
+
+    fn definition(&self, params: &JsonValue) -> Option<JsonValue> {
+        let (analysis, file_id, offset) = self.position_params(params)?;
+        let (target, location) = analysis::definition(analysis.archive.as_ref()?, file_id, offset)?;
+        let source = analysis::source(&analysis.file_library, target)?;
+        Some(object! {
+            "uri" => path_to_uri(&analysis::file_path(&analysis.file_library, target)?),
+            "range" => range(source, &location),
+        })
+    }
+}
+
+fn response(id: &JsonValue, result: JsonValue) -> JsonValue {
+    object! {
+        "jsonrpc" => "2.0",
+        "id" => id.clone(),
+        "result" => result,
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: JsonValue) -> JsonValue {
+    object! {
+        "jsonrpc" => "2.0",
+        "method" => "textDocument/publishDiagnostics",
+        "params" => object! {
+            "uri" => uri,
+            "diagnostics" => diagnostics,
+        },
+    }
+}
+
+fn to_diagnostic(
+    report: &Report,
+    file_library: &FileLibrary,
+    document_range: Option<JsonValue>,
+) -> JsonValue {
+    let severity = if report.is_error() {
+        SEVERITY_ERROR
+    } else {
+        SEVERITY_WARNING
+    };
+    let mut message = report.get_message().clone();
+    if let Some(label) = report.get_primary().first() {
+        if !label.message.is_empty() {
+            message = format!("{}: {}", message, label.message);
+        }
+    }
+    for note in report.get_notes() {
+        message = format!("{}\n{}", message, note);
+    }
+    let mut related = JsonValue::new_array();
+    for label in report.get_secondary() {
+        let source = analysis::source(file_library, label.file_id);
+        let path = analysis::file_path(file_library, label.file_id);
+        if let (Some(source), Some(path)) = (source, path) {
+            let information = object! {
+                "location" => object! {
+                    "uri" => path_to_uri(&path),
+                    "range" => range(source, &label.range),
+                },
+                "message" => label.message.as_str(),
+            };
+            related.push(information).unwrap();
+        }
+    }
+    let mut diagnostic = object! {
+        "range" => document_range.unwrap_or_else(range_at_start),
+        "severity" => severity,
+        "code" => Report::error_code_to_diagnostic_code(report.get_code()),
+        "source" => "circom",
+        "message" => message,
+    };
+    if !related.is_empty() {
+        diagnostic["relatedInformation"] = related;
+    }
+    diagnostic
+}
+
+fn range_at_start() -> JsonValue {
+    let start = object! { "line" => 0, "character" => 0 };
+    object! { "start" => start.clone(), "end" => start }
+}
+
+// Positions count lines and UTF-16 code units in the line
+fn position(source: &str, offset: usize) -> JsonValue {
+    let offset = offset.min(source.len());
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = source[..line_start].matches('\n').count();
+    let character: usize = source[line_start..offset]
+        .chars()
+        .map(char::len_utf16)
+        .sum();
+    object! { "line" => line, "character" => character }
+}
+
+fn range(source: &str, location: &FileLocation) -> JsonValue {
+    object! {
+        "start" => position(source, location.start),
+        "end" => position(source, location.end),
+    }
+}
+
+fn offset(source: &str, line: usize, character: usize) -> usize {
+    let mut line_start = 0;
+    for _ in 0..line {
+        match source[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return source.len(),
+        }
+    }
+    let mut units = 0;
+    for (i, c) in source[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    source.len()
+}
+
+fn uri_to_path(uri: &str) -> String {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok());
+        match escaped.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn path_to_uri(path: &str) -> String {
+    let mut uri = "file://".to_string();
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const MAIN: &str = "pragma circom 2.0.0;\n\ntemplate Square() {\n    signal input in[2];\n    signal output {binary} out;\n    out <== in[0] * in[1];\n}\n\ncomponent main = Square();\n";
+
+    fn frame(message: JsonValue) -> String {
+        let content = message.dump();
+        format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+    }
+
+    fn request(id: usize, method: &str, params: JsonValue) -> String {
+        frame(object! { "jsonrpc" => "2.0", "id" => id, "method" => method, "params" => params })
+    }
+
+    fn notification(method: &str, params: JsonValue) -> String {
+        frame(object! { "jsonrpc" => "2.0", "method" => method, "params" => params })
+    }
+
+    fn text_position(uri: &str, line: usize, character: usize) -> JsonValue {
+        object! {
+            "textDocument" => object! { "uri" => uri },
+            "position" => object! { "line" => line, "character" => character },
+        }
+    }
+
+    // Runs a session through the stdio transport and returns the messages sent back
+    fn serve(input: String) -> Vec<JsonValue> {
+        let mut reader = Cursor::new(input.into_bytes());
+        let mut output = Vec::new();
+        let result = Server::new().run(&mut reader, &mut output);
+        assert!(result.is_ok());
+        let mut output = Cursor::new(output);
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut output).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn stdio_session() {
+        let uri = "file:///memory/main%20file.circom";
+        let broken = MAIN.replace("out <== in[0] * in[1];", "out <== in[0] * x;");
+        let mut input = request(1, "initialize", object! { "capabilities" => object! {} });
+        input += &notification("initialized", object! {});
+        input += &notification(
+            "textDocument/didOpen",
+            object! { "textDocument" => object! {
+                "uri" => uri, "languageId" => "circom", "version" => 1, "text" => broken,
+            }},
+        );
+        input += &notification(
+            "textDocument/didChange",
+            object! {
+                "textDocument" => object! { "uri" => uri, "version" => 2 },
+                "contentChanges" => JsonValue::Array(vec![object! { "text" => MAIN }]),
+            },
+        );
+        input += &request(2, "textDocument/hover", text_position(uri, 5, 13));
+        input += &request(3, "textDocument/hover", text_position(uri, 5, 5));
+        input += &request(4, "textDocument/definition", text_position(uri, 8, 19));
+        input += &request(5, "shutdown", JsonValue::Null);
+        input += &notification("exit", JsonValue::Null);
+        let messages = serve(input);
+        assert_eq!(messages.len(), 7);
+        assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], true);
+
+        let diagnostics = &messages[1]["params"];
+        assert_eq!(diagnostics["uri"], uri);
+        assert_eq!(diagnostics["diagnostics"][0]["severity"], SEVERITY_ERROR);
+        assert_eq!(diagnostics["diagnostics"][0]["range"]["start"]["line"], 5);
+        assert_eq!(messages[2]["params"]["diagnostics"].len(), 0);
+
+        let hover = messages[3]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(hover.contains("signal input in[2]"));
+        let hover = messages[4]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(hover.contains("signal output out {binary}"));
+        let definition = &messages[5]["result"];
+        assert_eq!(definition["uri"], uri);
+        assert_eq!(definition["range"]["start"]["line"], 2);
+        assert_eq!(definition["range"]["start"]["character"], 9);
+    }
+
+    #[test]
+    fn utf16_positions() {
+        let source = "a\u{1F600}b\nc";
+        assert_eq!(offset(source, 0, 3), 5);
+        assert_eq!(position(source, 5)["character"], 3);
+        assert_eq!(offset(source, 1, 0), 7);
+    }
+}
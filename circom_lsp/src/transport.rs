@@ -0,0 +1,40 @@
+use json::JsonValue;
+use std::io::{BufRead, Write};
+
+// Reads the next message of the base protocol: a set of headers, an empty line and a
+// json content of Content-Length bytes. Returns None when the input is closed.
+pub fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<JsonValue>, ()> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        let read = reader.read_line(&mut header).map_err(|_err| {})?;
+        if read == 0 {
+            return Result::Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(value.trim().parse::<usize>().map_err(|_err| {})?);
+            }
+        }
+    }
+    let mut content = vec![0; content_length.ok_or(())?];
+    reader.read_exact(&mut content).map_err(|_err| {})?;
+    let content = String::from_utf8(content).map_err(|_err| {})?;
+    json::parse(&content).map(Some).map_err(|_err| {})
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &JsonValue) -> Result<(), ()> {
+    let content = message.dump();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .map_err(|_err| {})?;
+    writer.flush().map_err(|_err| {})
+}
@@ -1,9 +1,9 @@
 use super::*;
+use crate::field_constants::FieldConstants;
 use num_bigint_dig::{BigInt, Sign};
 use serde_json::json;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
 
 // Types
 const T_U64: &str = "u64";
@@ -778,6 +778,22 @@ pub fn generate_function_release_memory_component() -> Vec<String> {
     instructions
 }
 
+// Name of a traced signal, called by Circom_CalcWit::traceSignals after each assignment
+pub fn generate_function_get_trace_signal_name(producer: &CProducer) -> Vec<String> {
+    let mut instructions = vec![];
+    instructions.push("const char* get_trace_signal_name(u64 signal) {".to_string());
+    if !producer.get_trace_signal_names().is_empty() {
+        instructions.push("switch (signal) {".to_string());
+        for (signal, name) in producer.get_trace_signal_names() {
+            instructions.push(format!("case {}: return \"{}\";", signal, name));
+        }
+        instructions.push("}".to_string());
+    }
+    instructions.push("return nullptr;".to_string());
+    instructions.push("}\n".to_string());
+    instructions
+}
+
 pub fn generate_function_release_memory_circuit() -> Vec<String> {
     // deleting each one of the components
     let mut instructions = vec![];
@@ -789,13 +805,7 @@ pub fn generate_function_release_memory_circuit() -> Vec<String> {
     instructions
 }
 
-pub fn generate_main_cpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("main");
-    file_path.set_extension("cpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_main_cpp_file(c_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
     let file = include_str!("common/main.cpp");
     for line in file.lines() {
@@ -806,13 +816,7 @@ pub fn generate_main_cpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn generate_circom_hpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("circom");
-    file_path.set_extension("hpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_circom_hpp_file(c_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
     let file = include_str!("common/circom.hpp");
     for line in file.lines() {
@@ -823,66 +827,37 @@ pub fn generate_circom_hpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn generate_fr_hpp_file(c_folder: &PathBuf, prime: &String) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("fr");
-    file_path.set_extension("hpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
-    let mut code = "".to_string();
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr.hpp"),
-        "bls12381" => include_str!("bls12381/fr.hpp"),
-        "goldilocks" => include_str!("goldilocks/fr.hpp"),
-        "grumpkin" => include_str!("grumpkin/fr.hpp"),
-        "pallas" => include_str!("pallas/fr.hpp"),
-        "vesta" => include_str!("vesta/fr.hpp"),
-        _ => unreachable!(),
-    };
-    for line in file.lines() {
-        code = format!("{}{}\n", code, line);
-    }
+pub fn generate_fr_hpp_file(c_file: &mut dyn Write, producer: &CProducer) -> std::io::Result<()> {
+    let fr_template: &str = include_str!("common/fr.hpp");
+    let field = FieldConstants::new(&producer.get_prime().parse::<BigInt>().unwrap());
+    let template = handlebars::Handlebars::new();
+    let code = template
+        .render_template(
+            fr_template,
+            &json!({
+                "n64": field.get_n64(),
+                "bits": field.get_bits(),
+            }),
+        )
+        .expect("must render");
     c_file.write_all(code.as_bytes())?;
     c_file.flush()?;
     Ok(())
 }
 
-pub fn generate_calcwit_hpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("calcwit");
-    file_path.set_extension("hpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
-    let mut code = "".to_string();
-    let file = include_str!("common/calcwit.hpp");
-    for line in file.lines() {
-        code = format!("{}{}\n", code, line);
-    }
-    c_file.write_all(code.as_bytes())?;
-    c_file.flush()?;
-    Ok(())
+// Initializer of a raw element with the 64 bit words of the number
+fn raw_element(field: &FieldConstants, value: &BigInt) -> String {
+    let words: Vec<String> = field
+        .get_words64(value)
+        .iter()
+        .map(|word| format!("0x{:016x}ULL", word))
+        .collect();
+    words.join(", ")
 }
 
-pub fn generate_fr_cpp_file(c_folder: &PathBuf, prime: &String) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("fr");
-    file_path.set_extension("cpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_calcwit_hpp_file(c_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr.cpp"),
-        "bls12381" => include_str!("bls12381/fr.cpp"),
-        "goldilocks" => include_str!("goldilocks/fr.cpp"),
-        "grumpkin" => include_str!("grumpkin/fr.cpp"),
-        "pallas" => include_str!("pallas/fr.cpp"),
-        "vesta" => include_str!("vesta/fr.cpp"),
-
-        _ => unreachable!(),
-    };
+    let file = include_str!("common/calcwit.hpp");
     for line in file.lines() {
         code = format!("{}{}\n", code, line);
     }
@@ -891,40 +866,34 @@ pub fn generate_fr_cpp_file(c_folder: &PathBuf, prime: &String) -> std::io::Resu
     Ok(())
 }
 
-pub fn generate_calcwit_cpp_file(c_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("calcwit");
-    file_path.set_extension("cpp");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
-    let mut code = "".to_string();
-    let file = include_str!("common/calcwit.cpp");
-    for line in file.lines() {
-        code = format!("{}{}\n", code, line);
-    }
+// The field operations are generated for the prime of the circuit, with the Montgomery
+// constants computed here
+pub fn generate_fr_cpp_file(c_file: &mut dyn Write, producer: &CProducer) -> std::io::Result<()> {
+    let fr_template: &str = include_str!("common/fr.cpp");
+    let field = FieldConstants::new(&producer.get_prime().parse::<BigInt>().unwrap());
+    let template = handlebars::Handlebars::new();
+    let code = template
+        .render_template(
+            fr_template,
+            &json!({
+                "q": raw_element(&field, field.get_prime()),
+                "r2": raw_element(&field, &field.get_r2()),
+                "r3": raw_element(&field, &field.get_r3()),
+                "half": raw_element(&field, &field.get_half()),
+                "mask": raw_element(&field, &field.get_mask()),
+                "np": format!("0x{:016x}ULL", field.get_np64()),
+                "bits": field.get_bits(),
+            }),
+        )
+        .expect("must render");
     c_file.write_all(code.as_bytes())?;
     c_file.flush()?;
     Ok(())
 }
 
-pub fn generate_fr_asm_file(c_folder: &PathBuf, prime: &String) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = c_folder.clone();
-    file_path.push("fr");
-    file_path.set_extension("asm");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_calcwit_cpp_file(c_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr.asm"),
-        "bls12381" => include_str!("bls12381/fr.asm"),
-        "goldilocks" => include_str!("goldilocks/fr.asm"),
-        "grumpkin" => include_str!("grumpkin/fr.asm"),
-        "pallas" => include_str!("pallas/fr.asm"),
-        "vesta" => include_str!("vesta/fr.asm"),
-        _ => unreachable!(),
-    };
+    let file = include_str!("common/calcwit.cpp");
     for line in file.lines() {
         code = format!("{}{}\n", code, line);
     }
@@ -934,12 +903,10 @@ pub fn generate_fr_asm_file(c_folder: &PathBuf, prime: &String) -> std::io::Resu
 }
 
 pub fn generate_make_file(
-    c_folder: &PathBuf,
+    c_file: &mut dyn Write,
     run_name: &str,
     producer: &CProducer,
 ) -> std::io::Result<()> {
-    use std::io::BufWriter;
-
     let makefile_template: &str = include_str!("common/makefile");
 
     let template = handlebars::Handlebars::new();
@@ -953,10 +920,6 @@ pub fn generate_make_file(
         )
         .expect("must render");
 
-    let mut file_path = c_folder.clone();
-    file_path.push("Makefile");
-    let file_name = file_path.to_str().unwrap();
-    let mut c_file = BufWriter::new(File::create(file_name).unwrap());
     c_file.write_all(code.as_bytes())?;
     c_file.flush()?;
     Ok(())
@@ -112,6 +112,17 @@ std::string Circom_CalcWit::getTrace(u64 id_cmp){
 
 }
 
+void Circom_CalcWit::traceSignals(u64 signal, uint size){
+  for (uint i = 0; i < size; i++){
+    const char* name = get_trace_signal_name(signal + i);
+    if (name != nullptr){
+      char* value = Fr_element2str(&signalValues[signal + i]);
+      printf("[trace] %s = %s\n", name, value);
+      delete [] value;
+    }
+  }
+}
+
 std::string Circom_CalcWit::generate_position_array(uint* dimensions, uint size_dimensions, uint index){
   std::string positions = "";
 
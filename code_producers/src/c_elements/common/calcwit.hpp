@@ -56,6 +56,8 @@ public:
 
   std::string getTrace(u64 id_cmp);
 
+  void traceSignals(u64 signal, uint size);
+
   std::string generate_position_array(uint* dimensions, uint size_dimensions, uint index);
 
 private:
@@ -81,5 +81,6 @@ uint get_size_of_input_hashmap();
 uint get_size_of_witness();
 uint get_size_of_constants();
 uint get_size_of_io_map();
+const char* get_trace_signal_name(u64 signal);
 
 #endif  // __CIRCOM_H
@@ -0,0 +1,781 @@
+#include "fr.hpp"
+#include <stdio.h>
+#include <stdlib.h>
+#include <gmp.h>
+#include <assert.h>
+#include <string>
+
+
+static mpz_t q;
+static mpz_t zero;
+static mpz_t one;
+static mpz_t mask;
+static size_t nBits;
+static bool initialized = false;
+
+
+// Constants of the field, computed by the compiler for the prime of the circuit. The
+// Montgomery form of x is x * R mod q, where R = 2^(64 * Fr_N64)
+FrElement Fr_q = {0, Fr_LONG, { {{q}} }};
+FrElement Fr_R3 = {0, Fr_LONG, { {{r3}} }};
+FrRawElement Fr_rawq = { {{q}} };
+FrRawElement Fr_rawR3 = { {{r3}} };
+static FrRawElement Fr_rawR2 = { {{r2}} };
+static FrRawElement Fr_rawHalf = { {{half}} };
+static FrRawElement Fr_rawMask = { {{mask}} };
+static const uint64_t Fr_np = {{np}};
+static const int Fr_nBits = {{bits}};
+
+typedef unsigned __int128 uint128_t;
+
+
+// Operations on the raw numbers, words of 64 bits in little endian order
+
+static int Fr_rawCmp(const FrRawElement pRawA, const FrRawElement pRawB) {
+    for (int i=Fr_N64-1; i>=0; i--) {
+        if (pRawA[i] != pRawB[i]) return pRawA[i] > pRawB[i] ? 1 : -1;
+    }
+    return 0;
+}
+
+static uint64_t Fr_rawAddCarry(FrRawElement pRawResult, const FrRawElement pRawA, const FrRawElement pRawB) {
+    uint64_t carry = 0;
+    for (int i=0; i<Fr_N64; i++) {
+        uint128_t sum = (uint128_t)pRawA[i] + pRawB[i] + carry;
+        pRawResult[i] = (uint64_t)sum;
+        carry = (uint64_t)(sum >> 64);
+    }
+    return carry;
+}
+
+static uint64_t Fr_rawSubBorrow(FrRawElement pRawResult, const FrRawElement pRawA, const FrRawElement pRawB) {
+    uint64_t borrow = 0;
+    for (int i=0; i<Fr_N64; i++) {
+        uint128_t difference = (uint128_t)pRawA[i] - pRawB[i] - borrow;
+        pRawResult[i] = (uint64_t)difference;
+        borrow = (uint64_t)(difference >> 64) & 1;
+    }
+    return borrow;
+}
+
+static void Fr_rawZero(FrRawElement pRawResult) {
+    for (int i=0; i<Fr_N64; i++) pRawResult[i] = 0;
+}
+
+// Value in [0, q) of a short element, whose values are in (-q, q)
+static void Fr_rawFromShort(FrRawElement pRawResult, int32_t value) {
+    Fr_rawZero(pRawResult);
+    pRawResult[0] = value < 0 ? -(int64_t)value : value;
+    if (Fr_rawCmp(pRawResult, Fr_rawq) >= 0) Fr_rawSubBorrow(pRawResult, pRawResult, Fr_rawq);
+    if (value < 0 && !Fr_rawIsZero(pRawResult)) Fr_rawSubBorrow(pRawResult, Fr_rawq, pRawResult);
+}
+
+// Value of x << n, keeping the lower Fr_N64 * 64 bits
+static void Fr_rawShl(FrRawElement pRawResult, const FrRawElement pRawA, int n) {
+    int words = n / 64;
+    int bits = n % 64;
+    for (int i=Fr_N64-1; i>=0; i--) {
+        uint64_t value = 0;
+        if (i - words >= 0) {
+            value = pRawA[i - words] << bits;
+            if (bits > 0 && i - words - 1 >= 0) value |= pRawA[i - words - 1] >> (64 - bits);
+        }
+        pRawResult[i] = value;
+    }
+}
+
+static void Fr_rawShr(FrRawElement pRawResult, const FrRawElement pRawA, int n) {
+    int words = n / 64;
+    int bits = n % 64;
+    for (int i=0; i<Fr_N64; i++) {
+        uint64_t value = 0;
+        if (i + words < Fr_N64) {
+            value = pRawA[i + words] >> bits;
+            if (bits > 0 && i + words + 1 < Fr_N64) value |= pRawA[i + words + 1] << (64 - bits);
+        }
+        pRawResult[i] = value;
+    }
+}
+
+// Reduces the result of a bitwise operation to the bits of q and then modulo q
+static void Fr_rawAdjustBinResult(FrRawElement pRawResult) {
+    for (int i=0; i<Fr_N64; i++) pRawResult[i] &= Fr_rawMask[i];
+    if (Fr_rawCmp(pRawResult, Fr_rawq) >= 0) Fr_rawSubBorrow(pRawResult, pRawResult, Fr_rawq);
+}
+
+void Fr_rawCopy(FrRawElement pRawResult, const FrRawElement pRawA) {
+    for (int i=0; i<Fr_N64; i++) pRawResult[i] = pRawA[i];
+}
+
+void Fr_rawSwap(FrRawElement pRawResult, FrRawElement pRawA) {
+    for (int i=0; i<Fr_N64; i++) {
+        uint64_t tmp = pRawResult[i];
+        pRawResult[i] = pRawA[i];
+        pRawA[i] = tmp;
+    }
+}
+
+void Fr_rawAdd(FrRawElement pRawResult, const FrRawElement pRawA, const FrRawElement pRawB) {
+    uint64_t carry = Fr_rawAddCarry(pRawResult, pRawA, pRawB);
+    if (carry || Fr_rawCmp(pRawResult, Fr_rawq) >= 0) Fr_rawSubBorrow(pRawResult, pRawResult, Fr_rawq);
+}
+
+void Fr_rawSub(FrRawElement pRawResult, const FrRawElement pRawA, const FrRawElement pRawB) {
+    if (Fr_rawSubBorrow(pRawResult, pRawA, pRawB)) Fr_rawAddCarry(pRawResult, pRawResult, Fr_rawq);
+}
+
+void Fr_rawNeg(FrRawElement pRawResult, const FrRawElement pRawA) {
+    if (Fr_rawIsZero(pRawA)) {
+        Fr_rawZero(pRawResult);
+    } else {
+        Fr_rawSubBorrow(pRawResult, Fr_rawq, pRawA);
+    }
+}
+
+// Montgomery product a * b / R mod q, with the CIOS method
+void Fr_rawMMul(FrRawElement pRawResult, const FrRawElement pRawA, const FrRawElement pRawB) {
+    uint64_t t[Fr_N64 + 2] = {0};
+    for (int i=0; i<Fr_N64; i++) {
+        uint64_t carry = 0;
+        for (int j=0; j<Fr_N64; j++) {
+            uint128_t sum = (uint128_t)pRawA[j] * pRawB[i] + t[j] + carry;
+            t[j] = (uint64_t)sum;
+            carry = (uint64_t)(sum >> 64);
+        }
+        uint128_t sum = (uint128_t)t[Fr_N64] + carry;
+        t[Fr_N64] = (uint64_t)sum;
+        t[Fr_N64 + 1] = (uint64_t)(sum >> 64);
+
+        uint64_t m = t[0] * Fr_np;
+        sum = (uint128_t)m * Fr_rawq[0] + t[0];
+        carry = (uint64_t)(sum >> 64);
+        for (int j=1; j<Fr_N64; j++) {
+            sum = (uint128_t)m * Fr_rawq[j] + t[j] + carry;
+            t[j - 1] = (uint64_t)sum;
+            carry = (uint64_t)(sum >> 64);
+        }
+        sum = (uint128_t)t[Fr_N64] + carry;
+        t[Fr_N64 - 1] = (uint64_t)sum;
+        t[Fr_N64] = t[Fr_N64 + 1] + (uint64_t)(sum >> 64);
+    }
+    if (t[Fr_N64] || Fr_rawCmp(t, Fr_rawq) >= 0) Fr_rawSubBorrow(t, t, Fr_rawq);
+    Fr_rawCopy(pRawResult, t);
+}
+
+void Fr_rawMSquare(FrRawElement pRawResult, const FrRawElement pRawA) {
+    Fr_rawMMul(pRawResult, pRawA, pRawA);
+}
+
+void Fr_rawMMul1(FrRawElement pRawResult, const FrRawElement pRawA, uint64_t pRawB) {
+    FrRawElement b;
+    Fr_rawZero(b);
+    b[0] = pRawB;
+    Fr_rawMMul(pRawResult, pRawA, b);
+}
+
+void Fr_rawToMontgomery(FrRawElement pRawResult, const FrRawElement &pRawA) {
+    Fr_rawMMul(pRawResult, pRawA, Fr_rawR2);
+}
+
+void Fr_rawFromMontgomery(FrRawElement pRawResult, const FrRawElement &pRawA) {
+    Fr_rawMMul1(pRawResult, pRawA, 1);
+}
+
+int Fr_rawIsEq(const FrRawElement pRawA, const FrRawElement pRawB) {
+    return Fr_rawCmp(pRawA, pRawB) == 0;
+}
+
+int Fr_rawIsZero(const FrRawElement pRawB) {
+    for (int i=0; i<Fr_N64; i++) {
+        if (pRawB[i] != 0) return 0;
+    }
+    return 1;
+}
+
+
+// Operations on the elements, which are short, long in normal form or long in
+// Montgomery form
+
+static bool Fr_isLong(PFrElement pE) {
+    return pE->type & Fr_LONG;
+}
+
+static bool Fr_isMontgomery(PFrElement pE) {
+    return (pE->type & Fr_LONGMONTGOMERY) == Fr_LONGMONTGOMERY;
+}
+
+static void Fr_setShort(PFrElement r, int32_t value) {
+    r->shortVal = value;
+    r->type = Fr_SHORT;
+}
+
+void Fr_copy(PFrElement r, PFrElement a) {
+    *r = *a;
+}
+
+void Fr_copyn(PFrElement r, PFrElement a, int n) {
+    for (int i=0; i<n; i++) r[i] = a[i];
+}
+
+void Fr_toNormal(PFrElement r, PFrElement a) {
+    if (Fr_isMontgomery(a)) {
+        Fr_rawFromMontgomery(r->longVal, a->longVal);
+        r->type = Fr_LONG;
+    } else {
+        *r = *a;
+    }
+}
+
+void Fr_toLongNormal(PFrElement r, PFrElement a) {
+    if (Fr_isLong(a)) {
+        Fr_toNormal(r, a);
+    } else {
+        Fr_rawFromShort(r->longVal, a->shortVal);
+        r->type = Fr_LONG;
+    }
+}
+
+void Fr_toMontgomery(PFrElement r, PFrElement a) {
+    if (Fr_isMontgomery(a)) {
+        *r = *a;
+    } else {
+        Fr_toLongNormal(r, a);
+        Fr_rawToMontgomery(r->longVal, r->longVal);
+        r->type = Fr_LONGMONTGOMERY;
+    }
+}
+
+int Fr_isTrue(PFrElement pE) {
+    return Fr_isLong(pE) ? !Fr_rawIsZero(pE->longVal) : pE->shortVal != 0;
+}
+
+// The elements greater than q / 2 represent negative numbers
+int Fr_toInt(PFrElement pE) {
+    FrElement tmp;
+    Fr_toLongNormal(&tmp, pE);
+    bool negative = Fr_rawCmp(tmp.longVal, Fr_rawHalf) > 0;
+    if (negative) Fr_rawSubBorrow(tmp.longVal, Fr_rawq, tmp.longVal);
+    for (int i=1; i<Fr_N64; i++) {
+        if (tmp.longVal[i] != 0) Fr_fail();
+    }
+    if (tmp.longVal[0] > 0x7FFFFFFF) Fr_fail();
+    return negative ? -(int)tmp.longVal[0] : (int)tmp.longVal[0];
+}
+
+void Fr_add(PFrElement r, PFrElement a, PFrElement b) {
+    if (Fr_isMontgomery(a) && Fr_isMontgomery(b)) {
+        Fr_rawAdd(r->longVal, a->longVal, b->longVal);
+        r->type = Fr_LONGMONTGOMERY;
+    } else {
+        FrElement na, nb;
+        Fr_toLongNormal(&na, a);
+        Fr_toLongNormal(&nb, b);
+        Fr_rawAdd(r->longVal, na.longVal, nb.longVal);
+        r->type = Fr_LONG;
+    }
+}
+
+void Fr_sub(PFrElement r, PFrElement a, PFrElement b) {
+    if (Fr_isMontgomery(a) && Fr_isMontgomery(b)) {
+        Fr_rawSub(r->longVal, a->longVal, b->longVal);
+        r->type = Fr_LONGMONTGOMERY;
+    } else {
+        FrElement na, nb;
+        Fr_toLongNormal(&na, a);
+        Fr_toLongNormal(&nb, b);
+        Fr_rawSub(r->longVal, na.longVal, nb.longVal);
+        r->type = Fr_LONG;
+    }
+}
+
+void Fr_neg(PFrElement r, PFrElement a) {
+    if (Fr_isMontgomery(a)) {
+        Fr_rawNeg(r->longVal, a->longVal);
+        r->type = Fr_LONGMONTGOMERY;
+    } else {
+        FrElement na;
+        Fr_toLongNormal(&na, a);
+        Fr_rawNeg(r->longVal, na.longVal);
+        r->type = Fr_LONG;
+    }
+}
+
+// The Montgomery product of an element in Montgomery form and another one in normal
+// form is their product in normal form
+void Fr_mul(PFrElement r, PFrElement a, PFrElement b) {
+    bool montgomeryA = Fr_isMontgomery(a);
+    bool montgomeryB = Fr_isMontgomery(b);
+    FrElement na, nb;
+    if (montgomeryA) {
+        na = *a;
+    } else {
+        Fr_toLongNormal(&na, a);
+    }
+    if (montgomeryB) {
+        nb = *b;
+    } else {
+        Fr_toLongNormal(&nb, b);
+    }
+    Fr_rawMMul(r->longVal, na.longVal, nb.longVal);
+    if (montgomeryA && montgomeryB) {
+        r->type = Fr_LONGMONTGOMERY;
+    } else if (montgomeryA || montgomeryB) {
+        r->type = Fr_LONG;
+    } else {
+        Fr_rawMMul(r->longVal, r->longVal, Fr_rawR3);
+        r->type = Fr_LONGMONTGOMERY;
+    }
+}
+
+void Fr_square(PFrElement r, PFrElement a) {
+    Fr_mul(r, a, a);
+}
+
+static bool Fr_isEqual(PFrElement a, PFrElement b) {
+    if (Fr_isMontgomery(a) && Fr_isMontgomery(b)) {
+        return Fr_rawIsEq(a->longVal, b->longVal);
+    }
+    FrElement na, nb;
+    Fr_toLongNormal(&na, a);
+    Fr_toLongNormal(&nb, b);
+    return Fr_rawIsEq(na.longVal, nb.longVal);
+}
+
+// Compares the elements as signed numbers
+static int Fr_compare(PFrElement a, PFrElement b) {
+    FrElement na, nb;
+    Fr_toLongNormal(&na, a);
+    Fr_toLongNormal(&nb, b);
+    bool negativeA = Fr_rawCmp(na.longVal, Fr_rawHalf) > 0;
+    bool negativeB = Fr_rawCmp(nb.longVal, Fr_rawHalf) > 0;
+    if (negativeA != negativeB) return negativeA ? -1 : 1;
+    return Fr_rawCmp(na.longVal, nb.longVal);
+}
+
+void Fr_eq(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_isEqual(a, b));
+}
+
+void Fr_neq(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, !Fr_isEqual(a, b));
+}
+
+void Fr_lt(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_compare(a, b) < 0);
+}
+
+void Fr_gt(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_compare(a, b) > 0);
+}
+
+void Fr_leq(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_compare(a, b) <= 0);
+}
+
+void Fr_geq(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_compare(a, b) >= 0);
+}
+
+void Fr_land(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_isTrue(a) && Fr_isTrue(b));
+}
+
+void Fr_lor(PFrElement r, PFrElement a, PFrElement b) {
+    Fr_setShort(r, Fr_isTrue(a) || Fr_isTrue(b));
+}
+
+void Fr_lnot(PFrElement r, PFrElement a) {
+    Fr_setShort(r, !Fr_isTrue(a));
+}
+
+void Fr_band(PFrElement r, PFrElement a, PFrElement b) {
+    FrElement na, nb;
+    Fr_toLongNormal(&na, a);
+    Fr_toLongNormal(&nb, b);
+    for (int i=0; i<Fr_N64; i++) r->longVal[i] = na.longVal[i] & nb.longVal[i];
+    Fr_rawAdjustBinResult(r->longVal);
+    r->type = Fr_LONG;
+}
+
+void Fr_bor(PFrElement r, PFrElement a, PFrElement b) {
+    FrElement na, nb;
+    Fr_toLongNormal(&na, a);
+    Fr_toLongNormal(&nb, b);
+    for (int i=0; i<Fr_N64; i++) r->longVal[i] = na.longVal[i] | nb.longVal[i];
+    Fr_rawAdjustBinResult(r->longVal);
+    r->type = Fr_LONG;
+}
+
+void Fr_bxor(PFrElement r, PFrElement a, PFrElement b) {
+    FrElement na, nb;
+    Fr_toLongNormal(&na, a);
+    Fr_toLongNormal(&nb, b);
+    for (int i=0; i<Fr_N64; i++) r->longVal[i] = na.longVal[i] ^ nb.longVal[i];
+    Fr_rawAdjustBinResult(r->longVal);
+    r->type = Fr_LONG;
+}
+
+void Fr_bnot(PFrElement r, PFrElement a) {
+    FrElement na;
+    Fr_toLongNormal(&na, a);
+    for (int i=0; i<Fr_N64; i++) r->longVal[i] = ~na.longVal[i];
+    Fr_rawAdjustBinResult(r->longVal);
+    r->type = Fr_LONG;
+}
+
+// Number of bits of a shift: the elements greater than q / 2 shift to the other side,
+// and Fr_nBits shifts out every bit
+static int Fr_shiftBits(PFrElement b) {
+    FrElement nb;
+    FrRawElement distance;
+    Fr_toLongNormal(&nb, b);
+    Fr_rawSubBorrow(distance, Fr_rawq, nb.longVal);
+    bool small = true;
+    bool smallDistance = true;
+    for (int i=1; i<Fr_N64; i++) {
+        small = small && nb.longVal[i] == 0;
+        smallDistance = smallDistance && distance[i] == 0;
+    }
+    if (small && nb.longVal[0] < (uint64_t)Fr_nBits) return nb.longVal[0];
+    if (smallDistance && distance[0] < (uint64_t)Fr_nBits) return -(int)distance[0];
+    return Fr_nBits;
+}
+
+static void Fr_shiftLeft(PFrElement r, PFrElement a, int n) {
+    FrElement na;
+    Fr_toLongNormal(&na, a);
+    Fr_rawShl(r->longVal, na.longVal, n);
+    Fr_rawAdjustBinResult(r->longVal);
+    r->type = Fr_LONG;
+}
+
+static void Fr_shiftRight(PFrElement r, PFrElement a, int n) {
+    FrElement na;
+    Fr_toLongNormal(&na, a);
+    Fr_rawShr(r->longVal, na.longVal, n);
+    r->type = Fr_LONG;
+}
+
+void Fr_shl(PFrElement r, PFrElement a, PFrElement b) {
+    int n = Fr_shiftBits(b);
+    if (n >= 0) {
+        Fr_shiftLeft(r, a, n);
+    } else {
+        Fr_shiftRight(r, a, -n);
+    }
+}
+
+void Fr_shr(PFrElement r, PFrElement a, PFrElement b) {
+    int n = Fr_shiftBits(b);
+    if (n >= 0) {
+        Fr_shiftRight(r, a, n);
+    } else {
+        Fr_shiftLeft(r, a, -n);
+    }
+}
+
+void Fr_toMpz(mpz_t r, PFrElement pE) {
+    FrElement tmp;
+    Fr_toNormal(&tmp, pE);
+    if (!(tmp.type & Fr_LONG)) {
+        mpz_set_si(r, tmp.shortVal);
+        if (tmp.shortVal<0) {
+            mpz_add(r, r, q);
+        }
+    } else {
+        mpz_import(r, Fr_N64, -1, 8, -1, 0, (const void *)tmp.longVal);
+    }
+}
+
+void Fr_fromMpz(PFrElement pE, mpz_t v) {
+    if (mpz_fits_sint_p(v)) {
+        pE->type = Fr_SHORT;
+        pE->shortVal = mpz_get_si(v);
+    } else {
+        pE->type = Fr_LONG;
+        for (int i=0; i<Fr_N64; i++) pE->longVal[i] = 0;
+        mpz_export((void *)(pE->longVal), NULL, -1, 8, -1, 0, v);
+    }
+}
+
+
+bool Fr_init() {
+    if (initialized) return false;
+    initialized = true;
+    mpz_init(q);
+    mpz_import(q, Fr_N64, -1, 8, -1, 0, (const void *)Fr_q.longVal);
+    mpz_init_set_ui(zero, 0);
+    mpz_init_set_ui(one, 1);
+    nBits = mpz_sizeinbase (q, 2);
+    mpz_init(mask);
+    mpz_mul_2exp(mask, one, nBits);
+    mpz_sub(mask, mask, one);
+    return true;
+}
+
+void Fr_str2element(PFrElement pE, char const *s, uint base) {
+    mpz_t mr;
+    mpz_init_set_str(mr, s, base);
+    mpz_fdiv_r(mr, mr, q);
+    Fr_fromMpz(pE, mr);
+    mpz_clear(mr);
+}
+
+char *Fr_element2str(PFrElement pE) {
+    FrElement tmp;
+    mpz_t r;
+    if (!(pE->type & Fr_LONG)) {
+        if (pE->shortVal>=0) {
+            char *r = new char[32];
+            sprintf(r, "%d", pE->shortVal);
+            return r;
+        } else {
+            mpz_init_set_si(r, pE->shortVal);
+            mpz_add(r, r, q);
+        }
+    } else {
+        Fr_toNormal(&tmp, pE);
+        mpz_init(r);
+        mpz_import(r, Fr_N64, -1, 8, -1, 0, (const void *)tmp.longVal);
+    }
+    char *res = mpz_get_str (0, 10, r);
+    mpz_clear(r);
+    return res;
+}
+
+void Fr_idiv(PFrElement r, PFrElement a, PFrElement b) {
+    mpz_t ma;
+    mpz_t mb;
+    mpz_t mr;
+    mpz_init(ma);
+    mpz_init(mb);
+    mpz_init(mr);
+
+    Fr_toMpz(ma, a);
+    // char *s1 = mpz_get_str (0, 10, ma);
+    // printf("s1 %s\n", s1);
+    Fr_toMpz(mb, b);
+    // char *s2 = mpz_get_str (0, 10, mb);
+    // printf("s2 %s\n", s2);
+    mpz_fdiv_q(mr, ma, mb);
+    // char *sr = mpz_get_str (0, 10, mr);
+    // printf("r %s\n", sr);
+    Fr_fromMpz(r, mr);
+
+    mpz_clear(ma);
+    mpz_clear(mb);
+    mpz_clear(mr);
+}
+
+void Fr_mod(PFrElement r, PFrElement a, PFrElement b) {
+    mpz_t ma;
+    mpz_t mb;
+    mpz_t mr;
+    mpz_init(ma);
+    mpz_init(mb);
+    mpz_init(mr);
+
+    Fr_toMpz(ma, a);
+    Fr_toMpz(mb, b);
+    mpz_fdiv_r(mr, ma, mb);
+    Fr_fromMpz(r, mr);
+
+    mpz_clear(ma);
+    mpz_clear(mb);
+    mpz_clear(mr);
+}
+
+void Fr_pow(PFrElement r, PFrElement a, PFrElement b) {
+    mpz_t ma;
+    mpz_t mb;
+    mpz_t mr;
+    mpz_init(ma);
+    mpz_init(mb);
+    mpz_init(mr);
+
+    Fr_toMpz(ma, a);
+    Fr_toMpz(mb, b);
+    mpz_powm(mr, ma, mb, q);
+    Fr_fromMpz(r, mr);
+
+    mpz_clear(ma);
+    mpz_clear(mb);
+    mpz_clear(mr);
+}
+
+void Fr_inv(PFrElement r, PFrElement a) {
+    mpz_t ma;
+    mpz_t mr;
+    mpz_init(ma);
+    mpz_init(mr);
+
+    Fr_toMpz(ma, a);
+    mpz_invert(mr, ma, q);
+    Fr_fromMpz(r, mr);
+    mpz_clear(ma);
+    mpz_clear(mr);
+}
+
+void Fr_div(PFrElement r, PFrElement a, PFrElement b) {
+    FrElement tmp;
+    Fr_inv(&tmp, b);
+    Fr_mul(r, a, &tmp);
+}
+
+void Fr_fail() {
+    assert(false);
+}
+
+
+RawFr::RawFr() {
+    Fr_init();
+    set(fZero, 0);
+    set(fOne, 1);
+    neg(fNegOne, fOne);
+}
+
+RawFr::~RawFr() {
+}
+
+void RawFr::fromString(Element &r, const std::string &s, uint32_t radix) {
+    mpz_t mr;
+    mpz_init_set_str(mr, s.c_str(), radix);
+    mpz_fdiv_r(mr, mr, q);
+    for (int i=0; i<Fr_N64; i++) r.v[i] = 0;
+    mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, mr);
+    Fr_rawToMontgomery(r.v,r.v);
+    mpz_clear(mr);
+}
+
+void RawFr::fromUI(Element &r, unsigned long int v) {
+    mpz_t mr;
+    mpz_init(mr);
+    mpz_set_ui(mr, v);
+    for (int i=0; i<Fr_N64; i++) r.v[i] = 0;
+    mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, mr);
+    Fr_rawToMontgomery(r.v,r.v);
+    mpz_clear(mr);
+}
+
+RawFr::Element RawFr::set(int value) {
+  Element r;
+  set(r, value);
+  return r;
+}
+
+void RawFr::set(Element &r, int value) {
+  mpz_t mr;
+  mpz_init(mr);
+  mpz_set_si(mr, value);
+  if (value < 0) {
+      mpz_add(mr, mr, q);
+  }
+
+  mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, mr);
+      
+  for (int i=0; i<Fr_N64; i++) r.v[i] = 0;
+  mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, mr);
+  Fr_rawToMontgomery(r.v,r.v);
+  mpz_clear(mr);
+}
+
+std::string RawFr::toString(const Element &a, uint32_t radix) {
+    Element tmp;
+    mpz_t r;
+    Fr_rawFromMontgomery(tmp.v, a.v);
+    mpz_init(r);
+    mpz_import(r, Fr_N64, -1, 8, -1, 0, (const void *)(tmp.v));
+    char *res = mpz_get_str (0, radix, r);
+    mpz_clear(r);
+    std::string resS(res);
+    free(res);
+    return resS;
+}
+
+void RawFr::inv(Element &r, const Element &a) {
+    mpz_t mr;
+    mpz_init(mr);
+    mpz_import(mr, Fr_N64, -1, 8, -1, 0, (const void *)(a.v));
+    mpz_invert(mr, mr, q);
+
+
+    for (int i=0; i<Fr_N64; i++) r.v[i] = 0;
+    mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, mr);
+
+    Fr_rawMMul(r.v, r.v,Fr_rawR3);
+    mpz_clear(mr);
+}
+
+void RawFr::div(Element &r, const Element &a, const Element &b) {
+    Element tmp;
+    inv(tmp, b);
+    mul(r, a, tmp);
+}
+
+#define BIT_IS_SET(s, p) (s[p>>3] & (1 << (p & 0x7)))
+void RawFr::exp(Element &r, const Element &base, uint8_t* scalar, unsigned int scalarSize) {
+    bool oneFound = false;
+    Element copyBase;
+    copy(copyBase, base);
+    for (int i=scalarSize*8-1; i>=0; i--) {
+        if (!oneFound) {
+            if ( !BIT_IS_SET(scalar, i) ) continue;
+            copy(r, copyBase);
+            oneFound = true;
+            continue;
+        }
+        square(r, r);
+        if ( BIT_IS_SET(scalar, i) ) {
+            mul(r, r, copyBase);
+        }
+    }
+    if (!oneFound) {
+        copy(r, fOne);
+    }
+}
+
+void RawFr::toMpz(mpz_t r, const Element &a) {
+    Element tmp;
+    Fr_rawFromMontgomery(tmp.v, a.v);
+    mpz_import(r, Fr_N64, -1, 8, -1, 0, (const void *)tmp.v);
+}
+
+void RawFr::fromMpz(Element &r, const mpz_t a) {
+    for (int i=0; i<Fr_N64; i++) r.v[i] = 0;
+    mpz_export((void *)(r.v), NULL, -1, 8, -1, 0, a);
+    Fr_rawToMontgomery(r.v, r.v);
+}
+
+int RawFr::toRprBE(const Element &element, uint8_t *data, int bytes)
+{
+    if (bytes < Fr_N64 * 8) {
+      return -(Fr_N64 * 8);
+    }
+
+    mpz_t r;
+    mpz_init(r);
+  
+    toMpz(r, element);
+    
+    mpz_export(data, NULL, 1, 8, 1, 0, r);
+  
+    return Fr_N64 * 8;
+}
+
+int RawFr::fromRprBE(Element &element, const uint8_t *data, int bytes)
+{
+    if (bytes < Fr_N64 * 8) {
+      return -(Fr_N64* 8);
+    }
+    mpz_t r;
+    mpz_init(r);
+
+    mpz_import(r, Fr_N64 * 8, 0, 1, 0, 0, data);
+    fromMpz(element, r);
+    return Fr_N64 * 8;
+}
+
+static bool init = Fr_init();
+
+RawFr RawFr::field;
+
@@ -9,7 +9,7 @@
 #include <sys/types.h> // typedef unsigned int uint;
 #endif // __APPLE__
 
-#define Fr_N64 4
+#define Fr_N64 {{n64}}
 #define Fr_SHORT 0x00000000
 #define Fr_LONG 0x80000000
 #define Fr_LONGMONTGOMERY 0xC0000000
@@ -84,7 +84,7 @@ class RawFr {
 
 public:
     const static int N64 = Fr_N64;
-    const static int MaxBits = 255;
+    const static int MaxBits = {{bits}};
 
 
     struct Element {
@@ -25,6 +25,8 @@ pub struct CProducer {
     pub template_instance_list: TemplateListParallel,
     pub message_list: MessageList,
     pub field_tracking: Vec<String>,
+    pub trace_flag: bool,
+    pub trace_signal_names: TraceSignalNames,
     pub major_version: usize,
     pub minor_version: usize,
     pub patch_version: usize,
@@ -120,6 +122,8 @@ impl Default for CProducer {
                 "31424553576487322".to_string(),
             ]
             .to_vec(),
+            trace_flag: false,
+            trace_signal_names: TraceSignalNames::new(),
             total_number_of_signals: 80,
             number_of_components: 4,
             size_of_component_tree: 3,
@@ -204,6 +208,10 @@ impl CProducer {
         self.size_32_bit
     }
 
+    pub fn get_trace_signal_names(&self) -> &TraceSignalNames {
+        &self.trace_signal_names
+    }
+
     pub fn get_string_table(&self) -> &Vec<String> {
         &self.string_table
     }
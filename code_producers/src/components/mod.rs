@@ -20,6 +20,8 @@ pub type SignalList = Vec<usize>;
 pub type InputOutputList = Vec<IODef>;
 pub type TemplateInstanceIOMap = BTreeMap<usize, InputOutputList>;
 pub type MessageList = Vec<String>;
+// Full names of the signals whose assignments are traced, by position in the signal memory
+pub type TraceSignalNames = BTreeMap<usize, String>;
 
 pub fn hasher(value: &str) -> u64 {
     use lz_fnv::FnvHasher;
@@ -0,0 +1,119 @@
+use num_bigint_dig::{BigInt, Sign};
+
+// Constants of the prime field that the C++ and WebAssembly field code needs. Elements
+// are stored in n64 words of 64 bits, and the Montgomery form of x is x * R mod p, where
+// R = 2^(64 * n64)
+pub struct FieldConstants {
+    prime: BigInt,
+    n64: usize,
+}
+
+impl FieldConstants {
+    pub fn new(prime: &BigInt) -> FieldConstants {
+        let constants = FieldConstants {
+            prime: prime.clone(),
+            n64: prime.bits().div_ceil(64),
+        };
+        assert!(
+            constants.get_words64(prime)[0] & 1 == 1,
+            "the prime must be odd"
+        );
+        constants
+    }
+
+    pub fn get_prime(&self) -> &BigInt {
+        &self.prime
+    }
+    pub fn get_bits(&self) -> usize {
+        self.prime.bits()
+    }
+    pub fn get_n64(&self) -> usize {
+        self.n64
+    }
+    pub fn get_n32(&self) -> usize {
+        2 * self.n64
+    }
+
+    fn r(&self) -> BigInt {
+        BigInt::from(1) << (64 * self.n64)
+    }
+
+    // R^2 mod p, which turns a number into its Montgomery form with a Montgomery product
+    pub fn get_r2(&self) -> BigInt {
+        (self.r() * self.r()) % &self.prime
+    }
+
+    // R^3 mod p, which turns the Montgomery product of two numbers in normal form into
+    // the Montgomery form of their product
+    pub fn get_r3(&self) -> BigInt {
+        (self.r() * self.r() * self.r()) % &self.prime
+    }
+
+    // The numbers above (p - 1) / 2 represent the negative ones
+    pub fn get_half(&self) -> BigInt {
+        (&self.prime - 1) / 2
+    }
+
+    // 2^bits - 1, where bits is the number of bits of p
+    pub fn get_mask(&self) -> BigInt {
+        (BigInt::from(1) << self.get_bits()) - 1
+    }
+
+    // -p^(-1) mod 2^64, computed with Newton's iteration
+    pub fn get_np64(&self) -> u64 {
+        let p0 = self.get_words64(&self.prime)[0];
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(p0.wrapping_mul(inverse)));
+        }
+        inverse.wrapping_neg()
+    }
+
+    // -p^(-1) mod 2^32
+    pub fn get_np32(&self) -> u32 {
+        self.get_np64() as u32
+    }
+
+    // Little endian words of 64 bits of a number in [0, 2^(64 * n64))
+    pub fn get_words64(&self, value: &BigInt) -> Vec<u64> {
+        let (sign, bytes) = value.to_bytes_le();
+        assert!(sign != Sign::Minus && bytes.len() <= 8 * self.n64);
+        let mut words = vec![0; self.n64];
+        for (i, byte) in bytes.iter().enumerate() {
+            words[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn montgomery_constants() {
+        let babybear = FieldConstants::new(&BigInt::from(2013265921u64));
+        assert_eq!(babybear.get_n32(), 2);
+        assert_eq!(babybear.get_bits(), 31);
+        assert_eq!(babybear.get_np64().wrapping_mul(2013265921), u64::MAX);
+        assert_eq!(babybear.get_r2(), (BigInt::from(1) << 128) % 2013265921);
+        let prime = BigInt::parse_bytes(
+            b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            16,
+        )
+        .unwrap();
+        let secp256k1 = FieldConstants::new(&prime);
+        assert_eq!(secp256k1.get_n64(), 4);
+        assert_eq!(secp256k1.get_words64(&prime)[3], u64::MAX);
+        assert_eq!(
+            secp256k1
+                .get_np64()
+                .wrapping_mul(secp256k1.get_words64(&prime)[0]),
+            u64::MAX
+        );
+        assert_eq!(
+            (secp256k1.get_r2() * secp256k1.get_r2()) % &prime,
+            (secp256k1.get_r3() * secp256k1.r()) % &prime
+        );
+    }
+}
@@ -2,5 +2,7 @@
 pub mod c_elements;
 #[allow(dead_code)]
 pub mod wasm_elements;
+pub mod rust_elements;
 
 pub mod components;
+pub mod field_constants;
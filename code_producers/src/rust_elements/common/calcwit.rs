@@ -0,0 +1,292 @@
+// Memory of the components and signals during the computation of the witness, like
+// Circom_CalcWit in the C++ witness calculator
+use crate::field::Fr;
+use std::collections::HashMap;
+
+pub type WitnessResult<T> = Result<T, String>;
+
+pub struct TemplateInfo {
+    pub name: &'static str,
+    pub number_of_inputs: usize,
+    pub number_of_components: usize,
+    pub run: fn(usize, &mut CalcWit) -> WitnessResult<()>,
+}
+
+pub struct IODef {
+    pub code: usize,
+    pub offset: usize,
+    pub lengths: &'static [usize],
+}
+
+pub struct CircuitInfo {
+    pub constants: &'static [&'static str],
+    pub templates: &'static [TemplateInfo],
+    // inputs and outputs of the templates whose subcomponents are accessed by signal code
+    pub io_map: &'static [(usize, &'static [IODef])],
+    pub main_template: usize,
+    pub main_signal_offset: usize,
+    // name, start and size of every input of the main component
+    pub main_inputs: &'static [(&'static str, usize, usize)],
+    pub witness_to_signal: &'static [usize],
+    pub total_number_of_signals: usize,
+    pub number_of_components: usize,
+    pub trace_signal_names: &'static [(usize, &'static str)],
+}
+
+// Created subcomponents, see CreateCmpBucket
+pub struct Subcomponents {
+    pub template_id: usize,
+    pub name: &'static str,
+    pub dimensions: &'static [usize],
+    // positions of the array that are created, all of them if None
+    pub positions: Option<&'static [usize]>,
+    pub number_of_cmp: usize,
+    pub signal_offset: usize,
+    pub signal_offset_jump: usize,
+    pub component_offset: usize,
+    pub component_offset_jump: usize,
+}
+
+// Whether the assigned input is the last one of the subcomponent, see StatusInput
+pub enum InputStatus {
+    Last,
+    NoLast,
+    Unknown,
+}
+
+#[derive(Default)]
+pub struct Component {
+    pub template_id: usize,
+    pub signal_start: usize,
+    pub input_counter: usize,
+    pub name: String,
+    pub id_father: usize,
+    pub subcomponents: Vec<usize>,
+}
+
+pub struct CalcWit {
+    circuit: &'static CircuitInfo,
+    pub constants: Vec<Fr>,
+    pub signal_values: Vec<Fr>,
+    pub components: Vec<Component>,
+}
+
+impl CalcWit {
+    pub fn new(circuit: &'static CircuitInfo) -> CalcWit {
+        let constants = circuit
+            .constants
+            .iter()
+            .map(|constant| constant.parse().unwrap())
+            .collect();
+        CalcWit {
+            circuit,
+            constants,
+            signal_values: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn calculate_witness(
+        &mut self,
+        inputs: &HashMap<String, Vec<Fr>>,
+    ) -> WitnessResult<Vec<Fr>> {
+        let circuit = self.circuit;
+        self.signal_values = vec![Fr::zero(); circuit.total_number_of_signals];
+        self.signal_values[0] = Fr::one();
+        self.components = Vec::new();
+        self.components
+            .resize_with(circuit.number_of_components, Default::default);
+        self.set_main_inputs(inputs)?;
+
+        let main = &circuit.templates[circuit.main_template];
+        self.create_component(
+            circuit.main_template,
+            circuit.main_signal_offset,
+            0,
+            "main".to_string(),
+            0,
+        )?;
+        if main.number_of_inputs > 0 {
+            self.run_template(0)?;
+        }
+        Ok(circuit
+            .witness_to_signal
+            .iter()
+            .map(|signal| self.signal_values[*signal].clone())
+            .collect())
+    }
+
+    fn set_main_inputs(&mut self, inputs: &HashMap<String, Vec<Fr>>) -> WitnessResult<()> {
+        let input_list = self.circuit.main_inputs;
+        for name in inputs.keys() {
+            if !input_list.iter().any(|(input, _, _)| input == name) {
+                return Err(format!("Signal not found: {}", name));
+            }
+        }
+        for (name, start, size) in input_list {
+            let values = inputs
+                .get(*name)
+                .ok_or_else(|| format!("Not all inputs have been set. Missing signal {}", name))?;
+            if values.len() < *size {
+                return Err(format!("Error loading signal {}: Not enough values", name));
+            }
+            if values.len() > *size {
+                return Err(format!("Error loading signal {}: Too many values", name));
+            }
+            self.signal_values[*start..start + size].clone_from_slice(values);
+        }
+        Ok(())
+    }
+
+    fn create_component(
+        &mut self,
+        template_id: usize,
+        signal_start: usize,
+        cmp_index: usize,
+        name: String,
+        id_father: usize,
+    ) -> WitnessResult<()> {
+        let template = &self.circuit.templates[template_id];
+        if cmp_index >= self.components.len() {
+            self.components.resize_with(cmp_index + 1, Default::default);
+        }
+        self.components[cmp_index] = Component {
+            template_id,
+            signal_start,
+            input_counter: template.number_of_inputs,
+            name,
+            id_father,
+            subcomponents: vec![0; template.number_of_components],
+        };
+        if template.number_of_inputs == 0 {
+            self.run_template(cmp_index)?;
+        }
+        Ok(())
+    }
+
+    pub fn create_subcomponents(
+        &mut self,
+        ctx_index: usize,
+        signal_start: usize,
+        first: usize,
+        info: &Subcomponents,
+    ) -> WitnessResult<()> {
+        let all: Vec<usize>;
+        let positions = match info.positions {
+            Some(positions) => positions,
+            None => {
+                all = (0..info.number_of_cmp).collect();
+                &all
+            }
+        };
+        let mut cmp_index = info.component_offset + ctx_index + 1;
+        let mut signal_offset = signal_start + info.signal_offset;
+        for position in positions {
+            let name = if info.number_of_cmp > 1 {
+                format!(
+                    "{}{}",
+                    info.name,
+                    position_as_string(info.dimensions, *position)
+                )
+            } else {
+                info.name.to_string()
+            };
+            self.components[ctx_index].subcomponents[first + position] = cmp_index;
+            self.create_component(info.template_id, signal_offset, cmp_index, name, ctx_index)?;
+            signal_offset += info.signal_offset_jump;
+            cmp_index += info.component_offset_jump;
+        }
+        Ok(())
+    }
+
+    pub fn run_template(&mut self, cmp_index: usize) -> WitnessResult<()> {
+        let template = &self.circuit.templates[self.components[cmp_index].template_id];
+        (template.run)(cmp_index, self)
+    }
+
+    pub fn subcomponent(&self, ctx_index: usize, index: usize) -> usize {
+        self.components[ctx_index].subcomponents[index]
+    }
+
+    // Position in the subcomponent of the signal with the given code and indexes
+    pub fn mapped_signal(&self, cmp_index: usize, signal_code: usize, indexes: &[usize]) -> usize {
+        let template_id = self.components[cmp_index].template_id;
+        let (_, io_list) = self
+            .circuit
+            .io_map
+            .iter()
+            .find(|(id, _)| *id == template_id)
+            .expect("the template is not in the io map");
+        let def = io_list
+            .iter()
+            .find(|def| def.code == signal_code)
+            .expect("signal code not found in the io map");
+        let mut map_index = 0;
+        for (i, index) in indexes.iter().enumerate() {
+            map_index = if i == 0 {
+                *index
+            } else {
+                map_index * def.lengths[i] + index
+            };
+        }
+        def.offset + map_index
+    }
+
+    // Updates the counter of inputs of the subcomponent and runs it if all of them are set
+    pub fn set_inputs(
+        &mut self,
+        cmp_index: usize,
+        size: usize,
+        status: InputStatus,
+    ) -> WitnessResult<()> {
+        let counter = &mut self.components[cmp_index].input_counter;
+        *counter = counter.saturating_sub(size);
+        let run = match status {
+            InputStatus::Last => true,
+            InputStatus::NoLast => false,
+            InputStatus::Unknown => *counter == 0,
+        };
+        if run {
+            self.run_template(cmp_index)?;
+        }
+        Ok(())
+    }
+
+    pub fn trace_signals(&self, start: usize, size: usize) {
+        let names = self.circuit.trace_signal_names;
+        for signal in start..start + size {
+            if let Ok(i) = names.binary_search_by_key(&signal, |(signal, _)| *signal) {
+                println!("[trace] {} = {}", names[i].1, self.signal_values[signal]);
+            }
+        }
+    }
+
+    pub fn get_trace(&self, ctx_index: usize) -> String {
+        let component = &self.components[ctx_index];
+        if ctx_index == 0 {
+            component.name.clone()
+        } else {
+            format!("{}.{}", self.get_trace(component.id_father), component.name)
+        }
+    }
+
+    pub fn error(&self, message: &str, name: &str, line: usize, ctx_index: usize) -> String {
+        format!(
+            "{} in template/function {} line {}. Followed trace of components: {}",
+            message,
+            name,
+            line,
+            self.get_trace(ctx_index)
+        )
+    }
+}
+
+// Same naming as generate_position_array in calcwit.cpp
+fn position_as_string(dimensions: &[usize], mut index: usize) -> String {
+    let mut positions = String::new();
+    for dimension in dimensions.iter().rev() {
+        positions = format!("[{}]{}", index % dimension, positions);
+        index /= dimension;
+    }
+    positions
+}
@@ -0,0 +1,238 @@
+// Elements of the prime field of the circuit and the operators of circom over them, with the
+// same semantics as in the C++ and WebAssembly witness calculators
+use num_bigint::{BigInt, ModInverse};
+use num_traits::{One, ToPrimitive, Zero};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+const PRIME: &str = "{{prime}}";
+
+pub fn prime() -> &'static BigInt {
+    static VALUE: OnceLock<BigInt> = OnceLock::new();
+    VALUE.get_or_init(|| BigInt::parse_bytes(PRIME.as_bytes(), 10).unwrap())
+}
+
+// An element of the field, always in the range [0, prime)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fr(BigInt);
+
+impl Fr {
+    pub fn zero() -> Fr {
+        Fr(BigInt::zero())
+    }
+
+    pub fn one() -> Fr {
+        Fr(BigInt::one())
+    }
+
+    pub fn from_bigint(value: &BigInt) -> Fr {
+        Fr(modulus(value, prime()))
+    }
+
+    pub fn to_bigint(&self) -> &BigInt {
+        &self.0
+    }
+
+    pub fn from_address(address: usize) -> Fr {
+        Fr::from_bigint(&BigInt::from(address))
+    }
+
+    // Value of the element as a position in memory, like Fr_toInt
+    pub fn to_address(&self) -> usize {
+        self.0.to_usize().unwrap_or(0)
+    }
+
+    pub fn is_true(&self) -> bool {
+        !self.0.is_zero()
+    }
+}
+
+impl From<u64> for Fr {
+    fn from(value: u64) -> Fr {
+        Fr::from_bigint(&BigInt::from(value))
+    }
+}
+
+// Parses a decimal number, possibly negative, and reduces it modulo the prime
+impl FromStr for Fr {
+    type Err = String;
+    fn from_str(value: &str) -> Result<Fr, String> {
+        BigInt::parse_bytes(value.trim().as_bytes(), 10)
+            .map(|value| Fr::from_bigint(&value))
+            .ok_or_else(|| format!("{} is not a decimal number", value))
+    }
+}
+
+impl fmt::Display for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub const DIVISION_BY_ZERO: &str = "Division by zero";
+pub const SHIFT_OVERFLOW: &str = "Overflow in shift operation";
+
+fn modulus(a: &BigInt, b: &BigInt) -> BigInt {
+    ((a % b) + b) % b
+}
+
+fn reduce(value: BigInt) -> Fr {
+    Fr(modulus(&value, prime()))
+}
+
+fn from_bool(value: bool) -> Fr {
+    if value {
+        Fr::one()
+    } else {
+        Fr::zero()
+    }
+}
+
+// The elements greater than prime / 2 represent negative numbers in comparisons
+fn signed(a: &Fr) -> BigInt {
+    let half = prime() / BigInt::from(2);
+    if a.0 > half {
+        &a.0 - prime()
+    } else {
+        a.0.clone()
+    }
+}
+
+// 2^b - 1, where b is the number of bits of the prime
+fn mask() -> BigInt {
+    let bits = prime().to_radix_le(2).1.len();
+    num_traits::pow(BigInt::from(2), bits) - 1
+}
+
+pub fn add(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 + &b.0)
+}
+
+pub fn sub(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 - &b.0)
+}
+
+pub fn mul(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 * &b.0)
+}
+
+pub fn div(a: &Fr, b: &Fr) -> Result<Fr, &'static str> {
+    let inverse = b.0.clone().mod_inverse(prime()).ok_or(DIVISION_BY_ZERO)?;
+    Ok(reduce(&a.0 * inverse))
+}
+
+pub fn pow(a: &Fr, b: &Fr) -> Fr {
+    Fr(a.0.modpow(&b.0, prime()))
+}
+
+pub fn idiv(a: &Fr, b: &Fr) -> Result<Fr, &'static str> {
+    if b.0.is_zero() {
+        Err(DIVISION_BY_ZERO)
+    } else {
+        Ok(Fr(&a.0 / &b.0))
+    }
+}
+
+pub fn modulo(a: &Fr, b: &Fr) -> Result<Fr, &'static str> {
+    if b.0.is_zero() {
+        Err(DIVISION_BY_ZERO)
+    } else {
+        Ok(Fr(&a.0 % &b.0))
+    }
+}
+
+pub fn shl(a: &Fr, b: &Fr) -> Result<Fr, &'static str> {
+    let half = prime() / BigInt::from(2);
+    if b.0 <= half {
+        let shift = b.0.to_usize().ok_or(SHIFT_OVERFLOW)?;
+        Ok(reduce(
+            (&a.0 * num_traits::pow(BigInt::from(2), shift)) & mask(),
+        ))
+    } else {
+        shr(a, &Fr(prime() - &b.0))
+    }
+}
+
+pub fn shr(a: &Fr, b: &Fr) -> Result<Fr, &'static str> {
+    let half = prime() / BigInt::from(2);
+    if b.0 <= half {
+        let shift = b.0.to_usize().ok_or(SHIFT_OVERFLOW)?;
+        Ok(Fr(&a.0 / num_traits::pow(BigInt::from(2), shift)))
+    } else {
+        shl(a, &Fr(prime() - &b.0))
+    }
+}
+
+pub fn lt(a: &Fr, b: &Fr) -> Fr {
+    from_bool(signed(a) < signed(b))
+}
+
+pub fn gt(a: &Fr, b: &Fr) -> Fr {
+    from_bool(signed(a) > signed(b))
+}
+
+pub fn leq(a: &Fr, b: &Fr) -> Fr {
+    from_bool(signed(a) <= signed(b))
+}
+
+pub fn geq(a: &Fr, b: &Fr) -> Fr {
+    from_bool(signed(a) >= signed(b))
+}
+
+pub fn eq(a: &Fr, b: &Fr) -> Fr {
+    from_bool(a == b)
+}
+
+// Compares the first n elements of both lists
+pub fn eq_n(a: &[Fr], b: &[Fr], n: usize) -> Fr {
+    from_bool((0..n).all(|i| a.get(i) == b.get(i)))
+}
+
+pub fn neq(a: &Fr, b: &Fr) -> Fr {
+    from_bool(a != b)
+}
+
+pub fn lor(a: &Fr, b: &Fr) -> Fr {
+    from_bool(a.is_true() || b.is_true())
+}
+
+pub fn land(a: &Fr, b: &Fr) -> Fr {
+    from_bool(a.is_true() && b.is_true())
+}
+
+pub fn lnot(a: &Fr) -> Fr {
+    from_bool(!a.is_true())
+}
+
+pub fn bor(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 | &b.0)
+}
+
+pub fn band(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 & &b.0)
+}
+
+pub fn bxor(a: &Fr, b: &Fr) -> Fr {
+    reduce(&a.0 ^ &b.0)
+}
+
+pub fn neg(a: &Fr) -> Fr {
+    reduce(-&a.0)
+}
+
+// Complement of the bits of the prime, as in the C++ and WebAssembly witness calculators
+pub fn bnot(a: &Fr) -> Fr {
+    reduce(mask() ^ &a.0)
+}
+
+// Copies the first n values in the memory, starting at position start
+pub fn copy_n(memory: &mut [Fr], start: usize, values: Vec<Fr>, n: usize) {
+    for (i, value) in values.into_iter().take(n).enumerate() {
+        memory[start + i] = value;
+    }
+}
+
+pub fn first(values: Vec<Fr>) -> Fr {
+    values.into_iter().next().unwrap_or_default()
+}
@@ -0,0 +1,100 @@
+pub mod rust_code_generator;
+
+pub use crate::components::*;
+
+// Type of the Rust expression that computes the value of an instruction
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum RustValue {
+    Address,
+    Field,
+    Fields,
+}
+
+pub type RustInstruction = String;
+pub struct RustProducer {
+    pub main_header: String,
+    pub main_signal_offset: usize,
+    pub total_number_of_signals: usize,
+    pub number_of_components: usize,
+    pub prime: String,
+    pub prime_str: String,
+    pub main_input_list: InputList,
+    pub witness_to_signal_list: SignalList,
+    pub io_map: TemplateInstanceIOMap,
+    pub field_tracking: Vec<String>,
+    pub trace_flag: bool,
+    pub trace_signal_names: TraceSignalNames,
+    pub major_version: usize,
+    pub minor_version: usize,
+    pub patch_version: usize,
+    string_table: Vec<String>,
+}
+
+impl Default for RustProducer {
+    fn default() -> Self {
+        RustProducer {
+            main_header: "Main_0".to_string(),
+            main_signal_offset: 1,
+            total_number_of_signals: 1,
+            number_of_components: 1,
+            prime: "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+                .to_string(),
+            prime_str: "bn128".to_string(),
+            main_input_list: InputList::new(),
+            witness_to_signal_list: SignalList::new(),
+            io_map: TemplateInstanceIOMap::new(),
+            field_tracking: Vec::new(),
+            trace_flag: false,
+            trace_signal_names: TraceSignalNames::new(),
+            major_version: 0,
+            minor_version: 0,
+            patch_version: 0,
+            string_table: Vec::new(),
+        }
+    }
+}
+
+impl RustProducer {
+    pub fn get_main_header(&self) -> &str {
+        &self.main_header
+    }
+    pub fn get_main_signal_offset(&self) -> usize {
+        self.main_signal_offset
+    }
+    pub fn get_prime(&self) -> &str {
+        &self.prime
+    }
+    pub fn get_main_input_list(&self) -> &InputList {
+        &self.main_input_list
+    }
+    pub fn get_witness_to_signal_list(&self) -> &SignalList {
+        &self.witness_to_signal_list
+    }
+    pub fn get_total_number_of_signals(&self) -> usize {
+        self.total_number_of_signals
+    }
+    pub fn get_number_of_components(&self) -> usize {
+        self.number_of_components
+    }
+    pub fn get_io_map(&self) -> &TemplateInstanceIOMap {
+        &self.io_map
+    }
+    pub fn get_field_constant_list(&self) -> &Vec<String> {
+        &self.field_tracking
+    }
+    pub fn get_trace_signal_names(&self) -> &TraceSignalNames {
+        &self.trace_signal_names
+    }
+    pub fn get_version(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.major_version, self.minor_version, self.patch_version
+        )
+    }
+    pub fn get_string_table(&self) -> &Vec<String> {
+        &self.string_table
+    }
+    pub fn set_string_table(&mut self, string_table: Vec<String>) {
+        self.string_table = string_table;
+    }
+}
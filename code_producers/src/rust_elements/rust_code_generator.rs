@@ -0,0 +1,340 @@
+use super::*;
+use serde_json::json;
+use std::io::prelude::*;
+
+// Names used in the generated code
+pub const CALC_WIT: &str = "ctx";
+pub const CTX_INDEX: &str = "ctx_index";
+pub const SIGNAL_START: &str = "signal_start";
+pub const SIGNAL_VALUES: &str = "ctx.signal_values";
+pub const LVAR: &str = "lvar";
+pub const LVAR_CALL: &str = "lvarcall";
+pub const CMP_INDEX: &str = "cmp";
+pub const DEST_INDEX: &str = "pos";
+pub const VALUE: &str = "value";
+pub const NAME: &str = "NAME";
+pub const RESULT_TYPE: &str = "WitnessResult";
+
+// Circom allows $ in the identifiers
+fn rust_identifier(header: &str) -> String {
+    header.replace('$', "__")
+}
+
+pub fn template_function_name(header: &str) -> String {
+    format!("template_{}", rust_identifier(header))
+}
+
+pub fn function_name(header: &str) -> String {
+    format!("function_{}", rust_identifier(header))
+}
+
+// Name of the generated crate, which must be a valid identifier
+pub fn crate_name(run_name: &str) -> String {
+    let mut name: String = run_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name = format!("circuit_{}", name);
+    }
+    name
+}
+
+pub fn rust_string(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+pub fn constant(id: usize) -> String {
+    format!("{}.constants[{}].clone()", CALC_WIT, id)
+}
+
+pub fn subcomponent(cmp_address: String) -> String {
+    format!("{}.subcomponent({}, {})", CALC_WIT, CTX_INDEX, cmp_address)
+}
+
+pub fn subcomponent_signal_start(cmp_index: &str) -> String {
+    format!("{}.components[{}].signal_start", CALC_WIT, cmp_index)
+}
+
+pub fn mapped_signal(cmp_index: &str, signal_code: usize, indexes: Vec<String>) -> String {
+    format!(
+        "{}.mapped_signal({}, {}, &[{}])",
+        CALC_WIT,
+        cmp_index,
+        signal_code,
+        indexes.join(", ")
+    )
+}
+
+pub fn runtime_error(message: &str, line: usize) -> String {
+    format!(
+        "{}.error({}, {}, {}, {})",
+        CALC_WIT, message, NAME, line, CTX_INDEX
+    )
+}
+
+// Calls a method on the value of the expression, which may be a block
+pub fn method_call(expression: String, method: &str) -> String {
+    if expression.starts_with('{') {
+        format!("({}).{}()", expression, method)
+    } else {
+        format!("{}.{}()", expression, method)
+    }
+}
+
+// Conversions of the expression of an instruction to the type expected by its user
+
+pub fn as_address(value: RustValue, expression: String) -> String {
+    match value {
+        RustValue::Address => expression,
+        RustValue::Field => method_call(expression, "to_address"),
+        RustValue::Fields => format!("field::first({}).to_address()", expression),
+    }
+}
+
+pub fn as_field(value: RustValue, expression: String) -> String {
+    match value {
+        RustValue::Address => format!("Fr::from_address({})", expression),
+        RustValue::Field => expression,
+        RustValue::Fields => format!("field::first({})", expression),
+    }
+}
+
+pub fn as_fields(value: RustValue, expression: String) -> String {
+    match value {
+        RustValue::Address => format!("vec![Fr::from_address({})]", expression),
+        RustValue::Field => format!("vec![{}]", expression),
+        RustValue::Fields => expression,
+    }
+}
+
+pub fn is_true(value: RustValue, expression: String) -> String {
+    method_call(as_field(value, expression), "is_true")
+}
+
+pub fn indent(instructions: Vec<String>) -> Vec<String> {
+    let mut indented = vec![];
+    for instruction in instructions {
+        for line in instruction.lines() {
+            indented.push(format!("    {}", line));
+        }
+    }
+    indented
+}
+
+// Wraps the statements and the final expression in a block expression
+pub fn block(mut instructions: Vec<String>, expression: String) -> String {
+    instructions.push(expression);
+    format!("{{ {} }}", instructions.join(" "))
+}
+
+pub fn build_call(function: &str, arguments: Vec<String>) -> String {
+    format!("{}({})", function, arguments.join(", "))
+}
+
+pub fn merge_code(instructions: Vec<String>) -> String {
+    let mut code = instructions.join("\n");
+    code.push('\n');
+    code
+}
+
+// Tables of the circuit
+
+pub fn generate_constants(producer: &RustProducer) -> Vec<RustInstruction> {
+    let constants = producer.get_field_constant_list();
+    let mut code = vec![format!("static CONSTANTS: [&str; {}] = [", constants.len())];
+    for constant in constants {
+        code.push(format!("    \"{}\",", constant));
+    }
+    code.push("];".to_string());
+    code
+}
+
+pub fn generate_io_map(producer: &RustProducer) -> Vec<RustInstruction> {
+    let io_map = producer.get_io_map();
+    let mut code = vec![format!(
+        "static IO_MAP: [(usize, &[IODef]); {}] = [",
+        io_map.len()
+    )];
+    for (template_id, io_list) in io_map {
+        code.push(format!("    ({}, &[", template_id));
+        for def in io_list {
+            let lengths: Vec<String> = def.lengths.iter().map(usize::to_string).collect();
+            code.push(format!(
+                "        IODef {{ code: {}, offset: {}, lengths: &[{}] }},",
+                def.code,
+                def.offset,
+                lengths.join(", ")
+            ));
+        }
+        code.push("    ]),".to_string());
+    }
+    code.push("];".to_string());
+    code
+}
+
+pub fn generate_main_inputs(producer: &RustProducer) -> Vec<RustInstruction> {
+    let inputs = producer.get_main_input_list();
+    let mut code = vec![format!(
+        "static MAIN_INPUTS: [(&str, usize, usize); {}] = [",
+        inputs.len()
+    )];
+    for (name, start, size) in inputs {
+        code.push(format!("    ({}, {}, {}),", rust_string(name), start, size));
+    }
+    code.push("];".to_string());
+    code
+}
+
+pub fn generate_witness_to_signal(producer: &RustProducer) -> Vec<RustInstruction> {
+    let signals: Vec<String> = producer
+        .get_witness_to_signal_list()
+        .iter()
+        .map(usize::to_string)
+        .collect();
+    vec![
+        format!("static WITNESS_TO_SIGNAL: [usize; {}] = [", signals.len()),
+        signals.join(", "),
+        "];".to_string(),
+    ]
+}
+
+pub fn generate_trace_signal_names(producer: &RustProducer) -> Vec<RustInstruction> {
+    let names = producer.get_trace_signal_names();
+    let mut code = vec![format!(
+        "static TRACE_SIGNAL_NAMES: [(usize, &str); {}] = [",
+        names.len()
+    )];
+    for (signal, name) in names {
+        code.push(format!("    ({}, {}),", signal, rust_string(name)));
+    }
+    code.push("];".to_string());
+    code
+}
+
+// Files of the crate
+
+pub fn generate_cargo_toml_file(
+    toml_file: &mut dyn Write,
+    run_name: &str,
+    producer: &RustProducer,
+) -> std::io::Result<()> {
+    let cargo_template: &str = include_str!("common/manifest.toml");
+    let template = handlebars::Handlebars::new();
+    let code = template
+        .render_template(
+            cargo_template,
+            &json!({
+                "name": crate_name(run_name),
+                "circuit": run_name,
+                "version": producer.get_version(),
+            }),
+        )
+        .expect("must render");
+    toml_file.write_all(code.as_bytes())?;
+    toml_file.flush()
+}
+
+pub fn generate_field_rs_file(
+    rs_file: &mut dyn Write,
+    producer: &RustProducer,
+) -> std::io::Result<()> {
+    let field_template: &str = include_str!("common/field.rs");
+    let template = handlebars::Handlebars::new();
+    let code = template
+        .render_template(field_template, &json!({ "prime": producer.get_prime() }))
+        .expect("must render");
+    rs_file.write_all(code.as_bytes())?;
+    rs_file.flush()
+}
+
+pub fn generate_calcwit_rs_file(rs_file: &mut dyn Write) -> std::io::Result<()> {
+    let code = include_str!("common/calcwit.rs");
+    rs_file.write_all(code.as_bytes())?;
+    rs_file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The contents written by one of the generators of the files of the crate
+    fn generated(generate: impl FnOnce(&mut dyn Write) -> std::io::Result<()>) -> String {
+        let mut contents = Vec::new();
+        generate(&mut contents).unwrap();
+        String::from_utf8(contents).unwrap()
+    }
+
+    #[test]
+    fn names_of_the_crate_and_the_functions() {
+        assert_eq!(crate_name("multiplier2"), "multiplier2");
+        assert_eq!(crate_name("My-Circuit.v2"), "my_circuit_v2");
+        assert_eq!(crate_name("2bits"), "circuit_2bits");
+        assert_eq!(template_function_name("Num2Bits_3"), "template_Num2Bits_3");
+        assert_eq!(function_name("f$1"), "function_f__1");
+    }
+
+    #[test]
+    fn files_of_the_crate() {
+        let producer = RustProducer {
+            prime: "2013265921".to_string(),
+            major_version: 2,
+            minor_version: 1,
+            patch_version: 6,
+            ..RustProducer::default()
+        };
+        let manifest = generated(|file| generate_cargo_toml_file(file, "My-Circuit", &producer));
+        assert!(manifest.contains("name = \"my_circuit\""));
+        assert!(manifest.contains("circuit My-Circuit, generated by circom 2.1.6"));
+        assert!(manifest.contains("num-bigint-dig"));
+        let field = generated(|file| generate_field_rs_file(file, &producer));
+        assert!(field.contains("const PRIME: &str = \"2013265921\";"));
+        assert!(!field.contains("{{"));
+        assert_eq!(
+            generated(generate_calcwit_rs_file),
+            include_str!("common/calcwit.rs")
+        );
+    }
+
+    #[test]
+    fn tables_of_the_circuit() {
+        let producer = RustProducer {
+            field_tracking: vec!["0".to_string(), "5".to_string()],
+            main_input_list: vec![("in".to_string(), 2, 3)],
+            witness_to_signal_list: vec![0, 1, 3],
+            trace_signal_names: TraceSignalNames::from([(4, "main.\"x\"".to_string())]),
+            ..RustProducer::default()
+        };
+        assert_eq!(
+            generate_constants(&producer),
+            vec![
+                "static CONSTANTS: [&str; 2] = [",
+                "    \"0\",",
+                "    \"5\",",
+                "];"
+            ]
+        );
+        assert_eq!(
+            generate_main_inputs(&producer),
+            vec![
+                "static MAIN_INPUTS: [(&str, usize, usize); 1] = [",
+                "    (\"in\", 2, 3),",
+                "];"
+            ]
+        );
+        assert_eq!(
+            generate_witness_to_signal(&producer),
+            vec!["static WITNESS_TO_SIGNAL: [usize; 3] = [", "0, 1, 3", "];"]
+        );
+        assert_eq!(
+            generate_trace_signal_names(&producer)[1],
+            "    (4, \"main.\\\"x\\\"\"),"
+        );
+    }
+}
@@ -1,15 +0,0 @@
-(data (i32.const 88) "\ff\00\00\00\00\00\00\00")
-(data (i32.const 128) "\01\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-(data (i32.const 608) "\01\00\00\00\ff\ff\ff\ff\fe[\fe\ff\02\a4\bdS\05\d8\a1\09\08\d893H}\9d)S\a7\eds")
-(data (i32.const 640) "\fe\ff\ff\ff\01\00\00\00\02H\03\00\fa\b7\84X\f5O\bc\ec\efO\8c\99o\05\c5\acY\b1$\18")
-(data (i32.const 672) "m\9c\f2\f3\90\e9\99\c9#\5c\92\87\cb\edl+\8f9Tr\96\14\d3\05\11\ffY\9f\d9\d9H\07")
-(data (i32.const 704) "\afs\9bC\07\18,\c6\90i\f0\8c\18\0d>\1b\18\f4\b5\c7q<\d1s\e93\db\c8\b9[*n")
-(data (i32.const 736) "\fe\ff\ff\ff\01\00\00\00\02H\03\00\fa\b7\84X\f5O\bc\ec\efO\8c\99o\05\c5\acY\b1$\18")
-(data (i32.const 768) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-(data (i32.const 800) "\00\00\00\80\ff\ff\ff\7f\ff-\ff\7f\01\d2\de\a9\02\ec\d0\04\04\ec\9c\19\a4\be\ce\94\a9\d3\f69")
-(data (i32.const 832) "\01\00\00\80\ff\ff\ff\7f\ff-\ff\7f\01\d2\de\a9\02\ec\d0\04\04\ec\9c\19\a4\be\ce\94\a9\d3\f69")
-(data (i32.const 864) "\f5\ff\ff\ff\0a\00\00\00\0b\0c\12\00\df\f3\d9f\c5\b7\0b\96\a7\b7\83\cc\e5\9d;6m\cf\c9\04")
-(data (i32.const 896) "\ff\ff\ff\ff\fe[\fe\ff\02\a4\bdS\05\d8\a1\09\08\d893H}\9d)S\a7\eds\00\00\00\00")
-(data (i32.const 928) "|\f4\17\0c\5cm\ab\9c\e5qK\fd=\e9\e1\1c\05\d5\1dG0\b2m\0dj;:t\90\e9\0e?")
-(data (i32.const 960) "\00\00\00\80\ff-\ff\7f\01\d2\de\a9\02\ec\d0\04\04\ec\9c\19\a4\be\ce\94\a9\d3\f69\00\00\00\00")
-(data (i32.const 1920) "\00\00\00\80\ff\ff\ff\7f\ff-\ff\7f\01\d2\de\a9\02\ec\d0\04\04\ec\9c\19\a4\be\ce\94\a9\d3\f69")
@@ -1,15 +0,0 @@
-(data (i32.const 88) "\fe\00\00\00\00\00\00\00")
-(data (i32.const 128) "\01\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-(data (i32.const 608) "\01\00\00\f0\93\f5\e1C\91p\b9yH\e83(]X\81\81\b6EP\b8)\a01\e1rNd0")
-(data (i32.const 640) "\fb\ff\ffO\1c4\96\ac)\cd`\9f\95v\fc6.Fyxo\a3nf/\df\07\9a\c1w\0a\0e")
-(data (i32.const 672) "\a7m!\aeE\e6\b8\1b\e3Y\5c\e3\b1:\feS\85\80\bbS=\83I\8c\a5DN\7f\b1\d0\16\02")
-(data (i32.const 704) "@\00\bf\b4\e1\d8\94^\b8\b6\fb\1c\be\9cH*\ed\cf\9f\a1d\c6<\89|e\cc\7fKY\f8\0c")
-(data (i32.const 736) "\fb\ff\ffO\1c4\96\ac)\cd`\9f\95v\fc6.Fyxo\a3nf/\df\07\9a\c1w\0a\0e")
-(data (i32.const 768) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-(data (i32.const 800) "\00\00\00\f8\c9\fa\f0\a1H\b8\dc<$\f4\19\94.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
-(data (i32.const 832) "\01\00\00\f8\c9\fa\f0\a1H\b8\dc<$\f4\19\94.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
-(data (i32.const 864) "\e6\ff\ff\9f\f9\0e\0d\1b?\91*\a3\a3h\ba\ea\89\06\dd\d8v\eb\d8G\c3\bb\f5 U\08\d0\15")
-(data (i32.const 896) "?Y\1f>\14\09\97\9b\87\84>\83\d2\85\15\18h[\04\85\9b\02\1a\13.\e7D\06\03\00\00\00")
-(data (i32.const 928) "\9c=\d1\80Usnc\d6\ffE$t\f3+\a2\d8\03\b2\1e\c0*EV\e7\f9c)\94\ef`\18")
-(data (i32.const 960) "\a0\ac\0f\1f\8a\84\cb\cdCB\9fA\e9\c2\0a\0c\b4-\82\c2M\01\8d\09\97s\22\83\01\00\00\00")
-(data (i32.const 1920) "\00\00\00\f8\c9\fa\f0\a1H\b8\dc<$\f4\19\94.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
\ No newline at end of file
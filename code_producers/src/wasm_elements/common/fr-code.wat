@@ -0,0 +1,1960 @@
+;; Operations of the prime field of the circuit. The raw numbers have {{n32}} words of 32 bits
+;; in little endian order, and the elements are {shortVal i32, type i32, raw number}, where
+;; the type is short (0), long (0x80000000) or long in Montgomery form (0xC0000000). The
+;; Montgomery form of x is x * R mod q, where R = 2^({{n32}} * 32). The operations may change
+;; the representation of their arguments, but never their value.
+
+(func $Fr_int_copy
+ (param $px i32)
+ (param $pr i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load
+        i64.store
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+)
+
+(func $Fr_int_zero
+ (param $pr i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        i64.const 0
+        i64.store
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+)
+
+(func $Fr_int_isZero
+ (param $px i32)
+ (result i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load
+        i64.const 0
+        i64.ne
+        if
+            i32.const 0
+            return
+        end
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    i32.const 1
+)
+
+(func $Fr_int_eq
+ (param $px i32)
+ (param $py i32)
+ (result i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load
+        get_local $py
+        get_local $i
+        i32.add
+        i64.load
+        i64.ne
+        if
+            i32.const 0
+            return
+        end
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    i32.const 1
+)
+
+(func $Fr_int_gt
+ (param $px i32)
+ (param $py i32)
+ (result i32)
+ (local $i i32)
+ (local $x i64)
+ (local $y i64)
+    i32.const {{n8}}
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.eqz
+        br_if 1
+        get_local $i
+        i32.const 8
+        i32.sub
+        set_local $i
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load
+        set_local $x
+        get_local $py
+        get_local $i
+        i32.add
+        i64.load
+        set_local $y
+        get_local $x
+        get_local $y
+        i64.gt_u
+        if
+            i32.const 1
+            return
+        end
+        get_local $x
+        get_local $y
+        i64.lt_u
+        if
+            i32.const 0
+            return
+        end
+        br 0
+    end
+    end
+    i32.const 0
+)
+
+(func $Fr_int_gte
+ (param $px i32)
+ (param $py i32)
+ (result i32)
+    get_local $py
+    get_local $px
+    call $Fr_int_gt
+    i32.eqz
+)
+
+;; Returns the carry
+(func $Fr_int_add
+ (param $px i32)
+ (param $py i32)
+ (param $pr i32)
+ (result i32)
+ (local $i i32)
+ (local $c i64)
+    i32.const 0
+    set_local $i
+    i64.const 0
+    set_local $c
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load32_u
+        get_local $py
+        get_local $i
+        i32.add
+        i64.load32_u
+        i64.add
+        get_local $c
+        i64.add
+        set_local $c
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $c
+        i64.store32
+        get_local $c
+        i64.const 32
+        i64.shr_u
+        set_local $c
+        get_local $i
+        i32.const 4
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $c
+    i32.wrap_i64
+)
+
+;; Returns the borrow
+(func $Fr_int_sub
+ (param $px i32)
+ (param $py i32)
+ (param $pr i32)
+ (result i32)
+ (local $i i32)
+ (local $d i64)
+ (local $b i64)
+    i32.const 0
+    set_local $i
+    i64.const 0
+    set_local $b
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load32_u
+        get_local $py
+        get_local $i
+        i32.add
+        i64.load32_u
+        i64.sub
+        get_local $b
+        i64.sub
+        set_local $d
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $d
+        i64.store32
+        get_local $d
+        i64.const 63
+        i64.shr_u
+        set_local $b
+        get_local $i
+        i32.const 4
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $b
+    i32.wrap_i64
+)
+
+;; Quotient and remainder of x / y, which are stored if their pointers are not 0
+(func $Fr_int_div
+ (param $px i32)
+ (param $py i32)
+ (param $pq i32)
+ (param $pr i32)
+ (local $i i32)
+ (local $j i32)
+ (local $c i32)
+ (local $w i32)
+    get_local $py
+    call $Fr_int_isZero
+    if
+        unreachable
+    end
+    i32.const {{quotient}}
+    call $Fr_int_zero
+    i32.const {{remainder}}
+    call $Fr_int_zero
+    i32.const {{bits}}
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.eqz
+        br_if 1
+        get_local $i
+        i32.const 1
+        i32.sub
+        set_local $i
+        ;; remainder = 2 * remainder + bit i of x
+        get_local $px
+        get_local $i
+        i32.const 5
+        i32.shr_u
+        i32.const 2
+        i32.shl
+        i32.add
+        i32.load
+        get_local $i
+        i32.const 31
+        i32.and
+        i32.shr_u
+        i32.const 1
+        i32.and
+        set_local $c
+        i32.const 0
+        set_local $j
+        block
+        loop
+            get_local $j
+            i32.const {{n8}}
+            i32.eq
+            br_if 1
+            i32.const {{remainder}}
+            get_local $j
+            i32.add
+            i32.load
+            set_local $w
+            i32.const {{remainder}}
+            get_local $j
+            i32.add
+            get_local $w
+            i32.const 1
+            i32.shl
+            get_local $c
+            i32.or
+            i32.store
+            get_local $w
+            i32.const 31
+            i32.shr_u
+            set_local $c
+            get_local $j
+            i32.const 4
+            i32.add
+            set_local $j
+            br 0
+        end
+        end
+        get_local $c
+        i32.const {{remainder}}
+        get_local $py
+        call $Fr_int_gte
+        i32.or
+        if
+            i32.const {{remainder}}
+            get_local $py
+            i32.const {{remainder}}
+            call $Fr_int_sub
+            drop
+            i32.const {{quotient}}
+            get_local $i
+            i32.const 5
+            i32.shr_u
+            i32.const 2
+            i32.shl
+            i32.add
+            set_local $w
+            get_local $w
+            get_local $w
+            i32.load
+            i32.const 1
+            get_local $i
+            i32.const 31
+            i32.and
+            i32.shl
+            i32.or
+            i32.store
+        end
+        br 0
+    end
+    end
+    get_local $pq
+    if
+        i32.const {{quotient}}
+        get_local $pq
+        call $Fr_int_copy
+    end
+    get_local $pr
+    if
+        i32.const {{remainder}}
+        get_local $pr
+        call $Fr_int_copy
+    end
+)
+
+;; x << n, keeping the lower {{n32}} words
+(func $Fr_rawShl
+ (param $px i32)
+ (param $n i32)
+ (param $pr i32)
+ (local $i i32)
+ (local $w i32)
+ (local $b i32)
+ (local $s i32)
+ (local $v i32)
+    get_local $n
+    i32.const 5
+    i32.shr_u
+    set_local $w
+    get_local $n
+    i32.const 31
+    i32.and
+    set_local $b
+    i32.const {{n32}}
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.eqz
+        br_if 1
+        get_local $i
+        i32.const 1
+        i32.sub
+        set_local $i
+        i32.const 0
+        set_local $v
+        get_local $i
+        get_local $w
+        i32.sub
+        set_local $s
+        get_local $s
+        i32.const 0
+        i32.ge_s
+        if
+            get_local $px
+            get_local $s
+            i32.const 2
+            i32.shl
+            i32.add
+            i32.load
+            get_local $b
+            i32.shl
+            set_local $v
+            get_local $b
+            i32.const 0
+            i32.ne
+            get_local $s
+            i32.const 0
+            i32.gt_s
+            i32.and
+            if
+                get_local $v
+                get_local $px
+                get_local $s
+                i32.const 2
+                i32.shl
+                i32.add
+                i32.const 4
+                i32.sub
+                i32.load
+                i32.const 32
+                get_local $b
+                i32.sub
+                i32.shr_u
+                i32.or
+                set_local $v
+            end
+        end
+        get_local $pr
+        get_local $i
+        i32.const 2
+        i32.shl
+        i32.add
+        get_local $v
+        i32.store
+        br 0
+    end
+    end
+)
+
+(func $Fr_rawShr
+ (param $px i32)
+ (param $n i32)
+ (param $pr i32)
+ (local $i i32)
+ (local $w i32)
+ (local $b i32)
+ (local $s i32)
+ (local $v i32)
+    get_local $n
+    i32.const 5
+    i32.shr_u
+    set_local $w
+    get_local $n
+    i32.const 31
+    i32.and
+    set_local $b
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n32}}
+        i32.eq
+        br_if 1
+        i32.const 0
+        set_local $v
+        get_local $i
+        get_local $w
+        i32.add
+        set_local $s
+        get_local $s
+        i32.const {{n32}}
+        i32.lt_u
+        if
+            get_local $px
+            get_local $s
+            i32.const 2
+            i32.shl
+            i32.add
+            i32.load
+            get_local $b
+            i32.shr_u
+            set_local $v
+            get_local $b
+            i32.const 0
+            i32.ne
+            get_local $s
+            i32.const {{n32m1}}
+            i32.lt_u
+            i32.and
+            if
+                get_local $v
+                get_local $px
+                get_local $s
+                i32.const 2
+                i32.shl
+                i32.add
+                i32.load offset=4
+                i32.const 32
+                get_local $b
+                i32.sub
+                i32.shl
+                i32.or
+                set_local $v
+            end
+        end
+        get_local $pr
+        get_local $i
+        i32.const 2
+        i32.shl
+        i32.add
+        get_local $v
+        i32.store
+        get_local $i
+        i32.const 1
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+)
+
+(func $Fr_F1m_add
+ (param $px i32)
+ (param $py i32)
+ (param $pr i32)
+    get_local $px
+    get_local $py
+    get_local $pr
+    call $Fr_int_add
+    get_local $pr
+    i32.const {{q}}
+    call $Fr_int_gte
+    i32.or
+    if
+        get_local $pr
+        i32.const {{q}}
+        get_local $pr
+        call $Fr_int_sub
+        drop
+    end
+)
+
+(func $Fr_F1m_sub
+ (param $px i32)
+ (param $py i32)
+ (param $pr i32)
+    get_local $px
+    get_local $py
+    get_local $pr
+    call $Fr_int_sub
+    if
+        get_local $pr
+        i32.const {{q}}
+        get_local $pr
+        call $Fr_int_add
+        drop
+    end
+)
+
+(func $Fr_F1m_neg
+ (param $px i32)
+ (param $pr i32)
+    get_local $px
+    call $Fr_int_isZero
+    if
+        get_local $pr
+        call $Fr_int_zero
+    else
+        i32.const {{q}}
+        get_local $px
+        get_local $pr
+        call $Fr_int_sub
+        drop
+    end
+)
+
+;; Montgomery product x * y / R mod q, with the CIOS method
+(func $Fr_F1m_mul
+ (param $px i32)
+ (param $py i32)
+ (param $pr i32)
+ (local $i i32)
+ (local $j i32)
+ (local $b i64)
+ (local $c i64)
+ (local $t i64)
+ (local $m i64)
+    i32.const {{mul}}
+    call $Fr_int_zero
+    i32.const {{mul}}
+    i64.const 0
+    i64.store offset={{n8}}
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $py
+        get_local $i
+        i32.add
+        i64.load32_u
+        set_local $b
+        ;; t = t + x * y[i]
+        i64.const 0
+        set_local $c
+        i32.const 0
+        set_local $j
+        block
+        loop
+            get_local $j
+            i32.const {{n8}}
+            i32.eq
+            br_if 1
+            i32.const {{mul}}
+            get_local $j
+            i32.add
+            i64.load32_u
+            get_local $px
+            get_local $j
+            i32.add
+            i64.load32_u
+            get_local $b
+            i64.mul
+            i64.add
+            get_local $c
+            i64.add
+            set_local $t
+            i32.const {{mul}}
+            get_local $j
+            i32.add
+            get_local $t
+            i64.store32
+            get_local $t
+            i64.const 32
+            i64.shr_u
+            set_local $c
+            get_local $j
+            i32.const 4
+            i32.add
+            set_local $j
+            br 0
+        end
+        end
+        i32.const {{mul}}
+        i64.load32_u offset={{n8}}
+        get_local $c
+        i64.add
+        set_local $t
+        i32.const {{mul}}
+        get_local $t
+        i64.store32 offset={{n8}}
+        i32.const {{mul}}
+        get_local $t
+        i64.const 32
+        i64.shr_u
+        i64.store32 offset={{n8p4}}
+        ;; t = (t + m * q) / 2^32, where m makes the lowest word zero
+        i32.const {{mul}}
+        i64.load32_u
+        i64.const {{np}}
+        i64.mul
+        i64.const 0xffffffff
+        i64.and
+        set_local $m
+        i32.const {{mul}}
+        i64.load32_u
+        get_local $m
+        i32.const {{q}}
+        i64.load32_u
+        i64.mul
+        i64.add
+        i64.const 32
+        i64.shr_u
+        set_local $c
+        i32.const 4
+        set_local $j
+        block
+        loop
+            get_local $j
+            i32.const {{n8}}
+            i32.eq
+            br_if 1
+            i32.const {{mul}}
+            get_local $j
+            i32.add
+            i64.load32_u
+            get_local $m
+            i32.const {{q}}
+            get_local $j
+            i32.add
+            i64.load32_u
+            i64.mul
+            i64.add
+            get_local $c
+            i64.add
+            set_local $t
+            i32.const {{mul}}
+            get_local $j
+            i32.add
+            i32.const 4
+            i32.sub
+            get_local $t
+            i64.store32
+            get_local $t
+            i64.const 32
+            i64.shr_u
+            set_local $c
+            get_local $j
+            i32.const 4
+            i32.add
+            set_local $j
+            br 0
+        end
+        end
+        i32.const {{mul}}
+        i64.load32_u offset={{n8}}
+        get_local $c
+        i64.add
+        set_local $t
+        i32.const {{mul}}
+        get_local $t
+        i64.store32 offset={{n8m4}}
+        i32.const {{mul}}
+        i32.const {{mul}}
+        i64.load32_u offset={{n8p4}}
+        get_local $t
+        i64.const 32
+        i64.shr_u
+        i64.add
+        i64.store32 offset={{n8}}
+        get_local $i
+        i32.const 4
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    i32.const {{mul}}
+    i32.load offset={{n8}}
+    i32.const {{mul}}
+    i32.const {{q}}
+    call $Fr_int_gte
+    i32.or
+    if
+        i32.const {{mul}}
+        i32.const {{q}}
+        i32.const {{mul}}
+        call $Fr_int_sub
+        drop
+    end
+    i32.const {{mul}}
+    get_local $pr
+    call $Fr_int_copy
+)
+
+(func $Fr_F1m_toMontgomery
+ (param $px i32)
+ (param $pr i32)
+    get_local $px
+    i32.const {{r2}}
+    get_local $pr
+    call $Fr_F1m_mul
+)
+
+(func $Fr_F1m_fromMontgomery
+ (param $px i32)
+ (param $pr i32)
+    get_local $px
+    i32.const {{one}}
+    get_local $pr
+    call $Fr_F1m_mul
+)
+
+;; base^e, where the base and the result are in Montgomery form
+(func $Fr_F1m_exp
+ (param $pbase i32)
+ (param $pe i32)
+ (param $pr i32)
+ (local $i i32)
+    get_local $pbase
+    i32.const {{exp_base}}
+    call $Fr_int_copy
+    get_local $pe
+    i32.const {{exp_e}}
+    call $Fr_int_copy
+    i32.const {{one}}
+    i32.const {{exp_acc}}
+    call $Fr_F1m_toMontgomery
+    i32.const {{bits}}
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.eqz
+        br_if 1
+        get_local $i
+        i32.const 1
+        i32.sub
+        set_local $i
+        i32.const {{exp_acc}}
+        i32.const {{exp_acc}}
+        i32.const {{exp_acc}}
+        call $Fr_F1m_mul
+        i32.const {{exp_e}}
+        get_local $i
+        i32.const 5
+        i32.shr_u
+        i32.const 2
+        i32.shl
+        i32.add
+        i32.load
+        get_local $i
+        i32.const 31
+        i32.and
+        i32.shr_u
+        i32.const 1
+        i32.and
+        if
+            i32.const {{exp_acc}}
+            i32.const {{exp_base}}
+            i32.const {{exp_acc}}
+            call $Fr_F1m_mul
+        end
+        br 0
+    end
+    end
+    i32.const {{exp_acc}}
+    get_local $pr
+    call $Fr_int_copy
+)
+
+;; Raw number in [0, q) of a short value in (-q, q)
+(func $Fr_rawCopyS2L
+ (param $pr i32)
+ (param $v i64)
+    get_local $pr
+    call $Fr_int_zero
+    get_local $v
+    i64.const 0
+    i64.lt_s
+    if
+        get_local $pr
+        i64.const 0
+        get_local $v
+        i64.sub
+        i64.store
+    else
+        get_local $pr
+        get_local $v
+        i64.store
+    end
+    get_local $pr
+    i32.const {{q}}
+    call $Fr_int_gte
+    if
+        get_local $pr
+        i32.const {{q}}
+        get_local $pr
+        call $Fr_int_sub
+        drop
+    end
+    get_local $v
+    i64.const 0
+    i64.lt_s
+    get_local $pr
+    call $Fr_int_isZero
+    i32.eqz
+    i32.and
+    if
+        i32.const {{q}}
+        get_local $pr
+        get_local $pr
+        call $Fr_int_sub
+        drop
+    end
+)
+
+(func $Fr_isLong
+ (param $pe i32)
+ (result i32)
+    get_local $pe
+    i32.load8_u offset=7
+    i32.const 128
+    i32.and
+)
+
+(func $Fr_isMontgomery
+ (param $pe i32)
+ (result i32)
+    get_local $pe
+    i32.load8_u offset=7
+    i32.const 192
+    i32.and
+    i32.const 192
+    i32.eq
+)
+
+(func $Fr_copy
+ (param $pr i32)
+ (param $pa i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8e}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $pa
+        get_local $i
+        i32.add
+        i64.load
+        i64.store
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+)
+
+(func $Fr_copyn
+ (param $pr i32)
+ (param $pa i32)
+ (param $n i32)
+    block
+    loop
+        get_local $n
+        i32.eqz
+        br_if 1
+        get_local $pr
+        get_local $pa
+        call $Fr_copy
+        get_local $pr
+        i32.const {{n8e}}
+        i32.add
+        set_local $pr
+        get_local $pa
+        i32.const {{n8e}}
+        i32.add
+        set_local $pa
+        get_local $n
+        i32.const 1
+        i32.sub
+        set_local $n
+        br 0
+    end
+    end
+)
+
+(func $Fr_toNormal
+ (param $pe i32)
+    get_local $pe
+    call $Fr_isMontgomery
+    if
+        get_local $pe
+        i32.const 8
+        i32.add
+        get_local $pe
+        i32.const 8
+        i32.add
+        call $Fr_F1m_fromMontgomery
+        get_local $pe
+        i32.const -2147483648
+        i32.store offset=4
+    end
+)
+
+(func $Fr_toLongNormal
+ (param $pe i32)
+    get_local $pe
+    call $Fr_isLong
+    if
+        get_local $pe
+        call $Fr_toNormal
+    else
+        get_local $pe
+        i32.const 8
+        i32.add
+        get_local $pe
+        i64.load32_s
+        call $Fr_rawCopyS2L
+        get_local $pe
+        i32.const -2147483648
+        i32.store offset=4
+    end
+)
+
+(func $Fr_toMontgomery
+ (param $pe i32)
+    get_local $pe
+    call $Fr_isMontgomery
+    i32.eqz
+    if
+        get_local $pe
+        call $Fr_toLongNormal
+        get_local $pe
+        i32.const 8
+        i32.add
+        get_local $pe
+        i32.const 8
+        i32.add
+        call $Fr_F1m_toMontgomery
+        get_local $pe
+        i32.const -1073741824
+        i32.store offset=4
+    end
+)
+
+(func $Fr_isTrue
+ (param $pe i32)
+ (result i32)
+    get_local $pe
+    call $Fr_isLong
+    if (result i32)
+        get_local $pe
+        i32.const 8
+        i32.add
+        call $Fr_int_isZero
+        i32.eqz
+    else
+        get_local $pe
+        i32.load
+        i32.const 0
+        i32.ne
+    end
+)
+
+;; The elements greater than q / 2 represent negative numbers
+(func $Fr_toInt
+ (param $pe i32)
+ (result i32)
+    get_local $pe
+    call $Fr_isLong
+    i32.eqz
+    if
+        get_local $pe
+        i32.load
+        return
+    end
+    get_local $pe
+    call $Fr_toNormal
+    get_local $pe
+    i32.const 8
+    i32.add
+    i32.const {{half}}
+    call $Fr_int_gt
+    if
+        i32.const {{q}}
+        get_local $pe
+        i32.const 8
+        i32.add
+        i32.const {{element}}
+        call $Fr_int_sub
+        drop
+        i32.const 0
+        i32.const {{element}}
+        i32.load
+        i32.sub
+        return
+    end
+    get_local $pe
+    i32.load offset=8
+)
+
+(func $Fr_add
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pa
+    call $Fr_isMontgomery
+    get_local $pb
+    call $Fr_isMontgomery
+    i32.and
+    if
+        get_local $pr
+        i32.const -1073741824
+        i32.store offset=4
+    else
+        get_local $pa
+        call $Fr_toLongNormal
+        get_local $pb
+        call $Fr_toLongNormal
+        get_local $pr
+        i32.const -2147483648
+        i32.store offset=4
+    end
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_add
+)
+
+(func $Fr_sub
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pa
+    call $Fr_isMontgomery
+    get_local $pb
+    call $Fr_isMontgomery
+    i32.and
+    if
+        get_local $pr
+        i32.const -1073741824
+        i32.store offset=4
+    else
+        get_local $pa
+        call $Fr_toLongNormal
+        get_local $pb
+        call $Fr_toLongNormal
+        get_local $pr
+        i32.const -2147483648
+        i32.store offset=4
+    end
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_sub
+)
+
+(func $Fr_neg
+ (param $pr i32)
+ (param $pa i32)
+    get_local $pa
+    call $Fr_isMontgomery
+    if
+        get_local $pr
+        i32.const -1073741824
+        i32.store offset=4
+    else
+        get_local $pa
+        call $Fr_toLongNormal
+        get_local $pr
+        i32.const -2147483648
+        i32.store offset=4
+    end
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_neg
+)
+
+;; The Montgomery product of an element in Montgomery form and another one in normal form
+;; is their product in normal form
+(func $Fr_mul
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $ma i32)
+ (local $mb i32)
+    get_local $pa
+    call $Fr_isMontgomery
+    set_local $ma
+    get_local $pb
+    call $Fr_isMontgomery
+    set_local $mb
+    get_local $ma
+    i32.eqz
+    if
+        get_local $pa
+        call $Fr_toLongNormal
+    end
+    get_local $mb
+    i32.eqz
+    if
+        get_local $pb
+        call $Fr_toLongNormal
+    end
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_mul
+    get_local $ma
+    get_local $mb
+    i32.and
+    if
+        get_local $pr
+        i32.const -1073741824
+        i32.store offset=4
+    else
+        get_local $ma
+        get_local $mb
+        i32.or
+        i32.eqz
+        if
+            get_local $pr
+            i32.const 8
+            i32.add
+            i32.const {{r2}}
+            get_local $pr
+            i32.const 8
+            i32.add
+            call $Fr_F1m_mul
+        end
+        get_local $pr
+        i32.const -2147483648
+        i32.store offset=4
+    end
+)
+
+(func $Fr_eqR
+ (param $pa i32)
+ (param $pb i32)
+ (result i32)
+    get_local $pa
+    call $Fr_isMontgomery
+    get_local $pb
+    call $Fr_isMontgomery
+    i32.and
+    i32.eqz
+    if
+        get_local $pa
+        call $Fr_toLongNormal
+        get_local $pb
+        call $Fr_toLongNormal
+    end
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    call $Fr_int_eq
+)
+
+;; Compares the elements as signed numbers
+(func $Fr_ltR
+ (param $pa i32)
+ (param $pb i32)
+ (result i32)
+ (local $na i32)
+ (local $nb i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    get_local $pa
+    i32.const 8
+    i32.add
+    i32.const {{half}}
+    call $Fr_int_gt
+    set_local $na
+    get_local $pb
+    i32.const 8
+    i32.add
+    i32.const {{half}}
+    call $Fr_int_gt
+    set_local $nb
+    get_local $na
+    get_local $nb
+    i32.ne
+    if
+        get_local $na
+        return
+    end
+    get_local $pb
+    i32.const 8
+    i32.add
+    get_local $pa
+    i32.const 8
+    i32.add
+    call $Fr_int_gt
+)
+
+(func $Fr_setShort
+ (param $pr i32)
+ (param $v i32)
+    get_local $pr
+    get_local $v
+    i32.store
+    get_local $pr
+    i32.const 0
+    i32.store offset=4
+)
+
+(func $Fr_eq
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    get_local $pb
+    call $Fr_eqR
+    call $Fr_setShort
+)
+
+(func $Fr_neq
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    get_local $pb
+    call $Fr_eqR
+    i32.eqz
+    call $Fr_setShort
+)
+
+(func $Fr_lt
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    get_local $pb
+    call $Fr_ltR
+    call $Fr_setShort
+)
+
+(func $Fr_gt
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pb
+    get_local $pa
+    call $Fr_ltR
+    call $Fr_setShort
+)
+
+(func $Fr_leq
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pb
+    get_local $pa
+    call $Fr_ltR
+    i32.eqz
+    call $Fr_setShort
+)
+
+(func $Fr_geq
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    get_local $pb
+    call $Fr_ltR
+    i32.eqz
+    call $Fr_setShort
+)
+
+(func $Fr_land
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    call $Fr_isTrue
+    get_local $pb
+    call $Fr_isTrue
+    i32.and
+    call $Fr_setShort
+)
+
+(func $Fr_lor
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pr
+    get_local $pa
+    call $Fr_isTrue
+    get_local $pb
+    call $Fr_isTrue
+    i32.or
+    call $Fr_setShort
+)
+
+(func $Fr_lnot
+ (param $pr i32)
+ (param $pa i32)
+    get_local $pr
+    get_local $pa
+    call $Fr_isTrue
+    i32.eqz
+    call $Fr_setShort
+)
+
+;; Reduces the result of a bitwise operation to the bits of q and then modulo q
+(func $Fr_adjustBinResult
+ (param $px i32)
+ (local $i i32)
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        get_local $px
+        get_local $i
+        i32.add
+        i64.load
+        i32.const {{mask}}
+        get_local $i
+        i32.add
+        i64.load
+        i64.and
+        i64.store
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $px
+    i32.const {{q}}
+    call $Fr_int_gte
+    if
+        get_local $px
+        i32.const {{q}}
+        get_local $px
+        call $Fr_int_sub
+        drop
+    end
+)
+
+(func $Fr_band
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $i i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $pa
+        get_local $i
+        i32.add
+        i64.load offset=8
+        get_local $pb
+        get_local $i
+        i32.add
+        i64.load offset=8
+        i64.and
+        i64.store offset=8
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_adjustBinResult
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_bor
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $i i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $pa
+        get_local $i
+        i32.add
+        i64.load offset=8
+        get_local $pb
+        get_local $i
+        i32.add
+        i64.load offset=8
+        i64.or
+        i64.store offset=8
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_adjustBinResult
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_bxor
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $i i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $pa
+        get_local $i
+        i32.add
+        i64.load offset=8
+        get_local $pb
+        get_local $i
+        i32.add
+        i64.load offset=8
+        i64.xor
+        i64.store offset=8
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_adjustBinResult
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_bnot
+ (param $pr i32)
+ (param $pa i32)
+ (local $i i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    i32.const 0
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $pr
+        get_local $i
+        i32.add
+        get_local $pa
+        get_local $i
+        i32.add
+        i64.load offset=8
+        i64.const -1
+        i64.xor
+        i64.store offset=8
+        get_local $i
+        i32.const 8
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_adjustBinResult
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+;; Whether the raw number is lower than the number of bits of q
+(func $Fr_isSmallShift
+ (param $px i32)
+ (result i32)
+ (local $i i32)
+    i32.const 4
+    set_local $i
+    block
+    loop
+        get_local $i
+        i32.const {{n8}}
+        i32.eq
+        br_if 1
+        get_local $px
+        get_local $i
+        i32.add
+        i32.load
+        if
+            i32.const 0
+            return
+        end
+        get_local $i
+        i32.const 4
+        i32.add
+        set_local $i
+        br 0
+    end
+    end
+    get_local $px
+    i32.load
+    i32.const {{bits}}
+    i32.lt_u
+)
+
+;; Number of bits of a shift: the elements greater than q / 2 shift to the other side,
+;; and {{bits}} shifts out every bit
+(func $Fr_shiftBits
+ (param $pb i32)
+ (result i32)
+    get_local $pb
+    call $Fr_toLongNormal
+    get_local $pb
+    i32.const 8
+    i32.add
+    call $Fr_isSmallShift
+    if
+        get_local $pb
+        i32.load offset=8
+        return
+    end
+    i32.const {{q}}
+    get_local $pb
+    i32.const 8
+    i32.add
+    i32.const {{shift}}
+    call $Fr_int_sub
+    drop
+    i32.const {{shift}}
+    call $Fr_isSmallShift
+    if
+        i32.const 0
+        i32.const {{shift}}
+        i32.load
+        i32.sub
+        return
+    end
+    i32.const {{bits}}
+)
+
+(func $Fr_shiftLeft
+ (param $pr i32)
+ (param $pa i32)
+ (param $n i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $n
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_rawShl
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_adjustBinResult
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_shiftRight
+ (param $pr i32)
+ (param $pa i32)
+ (param $n i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $n
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_rawShr
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_shl
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $n i32)
+    get_local $pb
+    call $Fr_shiftBits
+    set_local $n
+    get_local $n
+    i32.const 0
+    i32.ge_s
+    if
+        get_local $pr
+        get_local $pa
+        get_local $n
+        call $Fr_shiftLeft
+    else
+        get_local $pr
+        get_local $pa
+        i32.const 0
+        get_local $n
+        i32.sub
+        call $Fr_shiftRight
+    end
+)
+
+(func $Fr_shr
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+ (local $n i32)
+    get_local $pb
+    call $Fr_shiftBits
+    set_local $n
+    get_local $n
+    i32.const 0
+    i32.ge_s
+    if
+        get_local $pr
+        get_local $pa
+        get_local $n
+        call $Fr_shiftRight
+    else
+        get_local $pr
+        get_local $pa
+        i32.const 0
+        get_local $n
+        i32.sub
+        call $Fr_shiftLeft
+    end
+)
+
+(func $Fr_idiv
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    get_local $pr
+    i32.const 8
+    i32.add
+    i32.const 0
+    call $Fr_int_div
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_mod
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pa
+    call $Fr_toLongNormal
+    get_local $pb
+    call $Fr_toLongNormal
+    get_local $pa
+    i32.const 8
+    i32.add
+    get_local $pb
+    i32.const 8
+    i32.add
+    i32.const 0
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_int_div
+    get_local $pr
+    i32.const -2147483648
+    i32.store offset=4
+)
+
+(func $Fr_pow
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    get_local $pb
+    call $Fr_toLongNormal
+    get_local $pb
+    i32.const 8
+    i32.add
+    i32.const {{exp_e}}
+    call $Fr_int_copy
+    get_local $pa
+    call $Fr_toMontgomery
+    get_local $pa
+    i32.const 8
+    i32.add
+    i32.const {{exp_e}}
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_exp
+    get_local $pr
+    i32.const -1073741824
+    i32.store offset=4
+)
+
+;; a^(q - 2), which is 0 for a = 0
+(func $Fr_inv
+ (param $pr i32)
+ (param $pa i32)
+    get_local $pa
+    call $Fr_toMontgomery
+    get_local $pa
+    i32.const 8
+    i32.add
+    i32.const {{qm2}}
+    get_local $pr
+    i32.const 8
+    i32.add
+    call $Fr_F1m_exp
+    get_local $pr
+    i32.const -1073741824
+    i32.store offset=4
+)
+
+(func $Fr_div
+ (param $pr i32)
+ (param $pa i32)
+ (param $pb i32)
+    i32.const {{element}}
+    get_local $pb
+    call $Fr_inv
+    get_local $pr
+    get_local $pa
+    i32.const {{element}}
+    call $Fr_mul
+)
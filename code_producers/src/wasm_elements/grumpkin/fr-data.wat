@@ -1,15 +0,0 @@
-    (data (i32.const 88) "\fe\00\00\00\00\00\00\00")
-    (data (i32.const 128) "\01\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 608) "G\fd|\d8\16\8c <\8d\caqh\91j\81\97]X\81\81\b6EP\b8)\a01\e1rNd0")
-    (data (i32.const 640) "\9d\0d\8f\c5\8dC]\d3=\0b\c7\f5(\ebx\0a,Fyxo\a3nf/\df\07\9a\c1w\0a\0e")
-    (data (i32.const 672) "\89\fa\8aS[\fc,\f3\fb\01E\d4\11\19\e7\b5\f6\7fA\0a\ff\1e\abG\1f5\b8\caq\9f\d8\06")
-    (data (i32.const 704) "\df0\15\da\afm\cd\b1\b6=(\a7\e6\10\f2b\fb\0a\da\0a\0c\0b\7f\efD%Y-\90n\fd ")
-    (data (i32.const 736) "\9d\0d\8f\c5\8dC]\d3=\0b\c7\f5(\ebx\0a,Fyxo\a3nf/\df\07\9a\c1w\0a\0e")
-    (data (i32.const 768) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 800) "\a3~>l\0bF\10\9eF\e58\b4H\b5\c0\cb.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
-    (data (i32.const 832) "\a4~>l\0bF\10\9eF\e58\b4H\b5\c0\cb.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
-    (data (i32.const 864) "\d7(\adP\a9\ca\17z\b9!U\e1z\c1j\1f\84\d2kiN\eaK3\8e\9d\17\ceDg\1f*")
-    (data (i32.const 896) "\a3~>l\0bF\10\9eF\e58\b4H\b5\c0\cb.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
-    (data (i32.const 928) "\aa\ef\ed\12\89H\c3hO\bf\aarh\7f\08\8d1\12\08\09G\a2\e1Q\fa\c0)G\b1\d6Y\22")
-    (data (i32.const 960) "R?\1f\b6\05#\08O\a3r\1cZ\a4Z\e0e\17V`\a0m\11\14n\0ahL\b8\9c\13\19\0c")
-    (data (i32.const 1920) "\a3~>l\0bF\10\9eF\e58\b4H\b5\c0\cb.\ac\c0@\db\22(\dc\14\d0\98p9'2\18")
\ No newline at end of file
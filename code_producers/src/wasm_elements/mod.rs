@@ -6,7 +6,7 @@ type WasmInstruction = String;
 
 pub struct WASMProducer {
     pub main_signal_offset: usize,
-    pub fr_memory_size: usize, // depending of the prime; missing in build.rs
+    pub fr_memory_size: usize, // depending of the prime, see fr_memory_size
     pub size_32_bit: usize,
     pub size_32_shift: usize,
     pub number_of_main_outputs: usize,
@@ -25,6 +25,8 @@ pub struct WASMProducer {
     pub message_list: MessageList,
     pub field_tracking: Vec<String>,
     pub wat_flag: bool,
+    pub trace_flag: bool,
+    pub trace_signal_names: TraceSignalNames,
     pub major_version: usize,
     pub minor_version: usize,
     pub patch_version: usize,
@@ -52,6 +54,7 @@ pub struct WASMProducer {
     store_aux_1_tag: String,
     store_aux_2_tag: String,
     copy_counter_tag: String,
+    trace_signal_tag: String,
     create_loop_sub_cmp_tag: String,
     create_loop_offset_tag: String,
     create_loop_counter_tag: String,
@@ -71,7 +74,7 @@ impl Default for WASMProducer {
             prime: "21888242871839275222246405745257275088548364400416034343698204186575808495617"
                 .to_string(),
             prime_str: "bn128".to_string(),
-            fr_memory_size: 1948,
+            fr_memory_size: wasm_code_generator::fr_memory_size(8),
             size_32_bit: 8,
             size_32_shift: 5,
             number_of_main_outputs: 0, //2,
@@ -87,6 +90,8 @@ impl Default for WASMProducer {
             template_instance_list: [].to_vec(),
             field_tracking: [].to_vec(),
             wat_flag: true,
+            trace_flag: false,
+            trace_signal_names: TraceSignalNames::new(),
             major_version: 0,
             minor_version: 0,
             patch_version: 0,
@@ -114,6 +119,7 @@ impl Default for WASMProducer {
             store_aux_1_tag: "$storeaux1".to_string(),
             store_aux_2_tag: "$storeaux2".to_string(),
             copy_counter_tag: "$copycounter".to_string(),
+            trace_signal_tag: "$tracesignal".to_string(),
             create_loop_sub_cmp_tag: "$createloopsubcmp".to_string(),
             create_loop_offset_tag: "$createloopoffset".to_string(),
             create_loop_counter_tag: "$createloopcounter".to_string(),
@@ -321,9 +327,23 @@ impl WASMProducer {
         self.get_message_list_start() + self.size_of_message_in_bytes * self.message_list.len()
     }
 
-    pub fn get_constant_numbers_start(&self) -> usize {
+    // When tracing, for each signal the position of its name or 0 if it is not traced
+    pub fn get_trace_signal_list_start(&self) -> usize {
         self.get_string_list_start() + self.size_of_message_in_bytes * self.string_table.len()
     }
+    pub fn get_trace_name_list_start(&self) -> usize {
+        let size = if self.trace_flag {
+            4 * self.total_number_of_signals
+        } else {
+            0
+        };
+        self.get_trace_signal_list_start() + size
+    }
+
+    pub fn get_constant_numbers_start(&self) -> usize {
+        self.get_trace_name_list_start()
+            + self.size_of_message_in_bytes * self.trace_signal_names.len()
+    }
 
     pub fn get_var_stack_memory_start(&self) -> usize {
         self.get_constant_numbers_start() + (self.size_32_bit + 2) * 4 * self.field_tracking.len()
@@ -391,6 +411,9 @@ impl WASMProducer {
     pub fn get_copy_counter_tag(&self) -> &str {
         &self.copy_counter_tag
     }
+    pub fn get_trace_signal_tag(&self) -> &str {
+        &self.trace_signal_tag
+    }
     pub fn get_create_loop_sub_cmp_tag(&self) -> &str {
         &self.create_loop_sub_cmp_tag
     }
@@ -407,6 +430,10 @@ impl WASMProducer {
         self.wat_flag
     }
 
+    pub fn get_trace_signal_names(&self) -> &TraceSignalNames {
+        &self.trace_signal_names
+    }
+
     pub fn get_string_table(&self) -> &Vec<String> {
         &self.string_table
     }
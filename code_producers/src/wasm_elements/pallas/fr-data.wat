@@ -1,15 +0,0 @@
-    (data (i32.const 88) "\ff\00\00\00\00\00\00\00")
-    (data (i32.const 128) "\01\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 608) "\01\00\00\00\ed0-\99\1b\f9L\09\fc\98F\22\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00@")
-    (data (i32.const 640) "\fd\ff\ff\ff8mx4\ad\14\19\e4\0b5,\99\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 672) "\0f\00\00\00\b3\ecx\8c\e7\e0\0d\8b\bd\0d\d3\d7\18]\c9\c3\9b\a9\97w\14\b7\9c{\afAm\09")
-    (data (i32.const 704) "\f9\10\9e:\99\a5\85\f1\d1\b1\c5j;\8f\a6\f6,\d4?5\14\10\8d\df\10\99--\22\09\e3*")
-    (data (i32.const 736) "\fd\ff\ff\ff8mx4\ad\14\19\e4\0b5,\99\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 768) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 800) "\00\00\00\80v\98\96\cc\8d|\a6\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
-    (data (i32.const 832) "\01\00\00\80v\98\96\cc\8d|\a6\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
-    (data (i32.const 864) "\ed\ff\ff\ffh^\a5\a1\f3\82IOK\a5\c2t\fd\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 896) "\ed0-\99\1b\f9L\09\fc\98F\22\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00@\00\00\00\00")
-    (data (i32.const 928) "\f0\db\d6\baI\b8\8d\a2\df9\b5\d3\03\cd\83\90\8eD\c8\9d\ca\b9\a6\fb\da\c6\89{t(\c9>")
-    (data (i32.const 960) "w\98\96\cc\8d|\a6\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 \00\00\00\00")
-    (data (i32.const 1920) "\00\00\00\80v\98\96\cc\8d|\a6\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
\ No newline at end of file
@@ -1,15 +0,0 @@
-    (data (i32.const 88) "\ff\00\00\00\00\00\00\00")
-    (data (i32.const 128) "\01\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 608) "\01\00\00\00!\ebF\8c\dd\a8\94\09\fc\98F\22\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00@")
-    (data (i32.const 640) "\fd\ff\ff\ff\9c>+[g\05B\e3\0b5,\99\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 672) "\0f\00\00\00\ffx\96\fc\e3\16\1a\89=C\bbg\90\f5\cc\04\10#\ae\7f\a9\da\cf|\afAm\09")
-    (data (i32.const 704) "L\ae\9d$\1cB\8b\00&\13\a4\dbP\da;\e1c\cb\15\8e\cb\ce\fe\88\c8\92gn\a0\97\dd\07")
-    (data (i32.const 736) "\fd\ff\ff\ff\9c>+[g\05B\e3\0b5,\99\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 768) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00")
-    (data (i32.const 800) "\00\00\00\80\90u#\c6nT\ca\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
-    (data (i32.const 832) "\01\00\00\80\90u#\c6nT\ca\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
-    (data (i32.const 864) "\ed\ff\ff\ff\8c\8c\bc\96\8ew\f7IK\a5\c2t\fd\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff?")
-    (data (i32.const 896) "!\ebF\8c\dd\a8\94\09\fc\98F\22\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00@\00\00\00\00")
-    (data (i32.const 928) "\deB\99\8cBw\80!\94\04\b6!\89WI\cc\e2\be\ef\b2'].\acV\b0-\7f\89\fay\0b")
-    (data (i32.const 960) "\91u#\c6nT\ca\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 \00\00\00\00")
-    (data (i32.const 1920) "\00\00\00\80\90u#\c6nT\ca\04~L#\11\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00 ")
\ No newline at end of file
@@ -1,8 +1,8 @@
 use super::*;
+use crate::field_constants::FieldConstants;
 use num_bigint_dig::BigInt;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
 
 pub fn wasm_hexa(nbytes: usize, num: &BigInt) -> String {
     let inbytes = num.to_str_radix(16);
@@ -639,6 +639,22 @@ pub fn generate_data_list(producer: &WASMProducer) -> Vec<WasmInstruction> {
             ));
         }
     }
+    let names = producer.get_trace_name_list_start();
+    for (i, (signal, name)) in producer.get_trace_signal_names().iter().enumerate() {
+        let position = names + i * producer.get_size_of_message_in_bytes();
+        wdata.push(format!(
+            "(data (i32.const {}) \"{}\")",
+            producer.get_trace_signal_list_start() + 4 * signal,
+            wasm_hexa(4, &BigInt::from(position))
+        ));
+        let message = format!("[trace] {} =", name);
+        let length = std::cmp::min(message.len(), producer.get_size_of_message_in_bytes() - 1);
+        wdata.push(format!(
+            "(data (i32.const {}) \"{}\\00\")",
+            position,
+            &message[..length]
+        ));
+    }
     wdata.push(format!(
         "(data (i32.const {}) \"{}\")",
         producer.get_constant_numbers_start(),
@@ -1280,6 +1296,69 @@ pub fn get_message_char_generator(producer: &WASMProducer) -> Vec<WasmInstructio
     instructions
 }
 
+// Prints the name and the value of the traced signals among the given ones, called after
+// each assignment when the circuit is compiled with --trace
+pub fn trace_signals_generator(producer: &WASMProducer) -> Vec<WasmInstruction> {
+    let mut instructions = vec![];
+    let header = "(func $traceSignals (type $_t_i32i32)".to_string();
+    instructions.push(header);
+    instructions.push(" (param $signal i32)".to_string()); //position of the first signal in memory
+    instructions.push(" (param $size i32)".to_string());
+    instructions.push(" (local $name i32)".to_string());
+    let fr_size = producer.get_size_32_bits_in_memory() * 4;
+    instructions.push(add_block());
+    instructions.push(add_loop());
+    instructions.push(get_local("$size"));
+    instructions.push(eqz32());
+    instructions.push(br_if("1"));
+    instructions.push(get_local("$signal"));
+    instructions.push(set_constant(
+        &producer.get_signal_memory_start().to_string(),
+    ));
+    instructions.push(sub32());
+    instructions.push(set_constant(&fr_size.to_string()));
+    instructions.push(div32_u());
+    instructions.push(set_constant("4"));
+    instructions.push(mul32());
+    instructions.push(load32(Some(
+        &producer.get_trace_signal_list_start().to_string(),
+    ))); // position of the name of the signal
+    instructions.push(tee_local("$name"));
+    instructions.push(add_if());
+    instructions.push(get_local("$name"));
+    instructions.push(call("$buildLogMessage"));
+    instructions.push(call("$writeBufferMessage"));
+    instructions.push(get_local("$signal"));
+    instructions.push(call("$copyFr2SharedRWMemory"));
+    instructions.push(call("$showSharedRWMemory"));
+    // add nl, like in the log bucket
+    instructions.push(set_constant(
+        &producer.get_message_buffer_start().to_string(),
+    ));
+    instructions.push(set_constant("0x0000000a"));
+    instructions.push(store32(None));
+    instructions.push(set_constant(
+        &producer.get_message_buffer_counter_position().to_string(),
+    ));
+    instructions.push(set_constant("0"));
+    instructions.push(store32(None));
+    instructions.push(call("$writeBufferMessage"));
+    instructions.push(add_end());
+    instructions.push(get_local("$signal"));
+    instructions.push(set_constant(&fr_size.to_string()));
+    instructions.push(add32());
+    instructions.push(set_local("$signal"));
+    instructions.push(get_local("$size"));
+    instructions.push(set_constant("1"));
+    instructions.push(sub32());
+    instructions.push(set_local("$size"));
+    instructions.push(br("0"));
+    instructions.push(add_end());
+    instructions.push(add_end());
+    instructions.push(")".to_string());
+    instructions
+}
+
 pub fn build_log_message_generator(producer: &WASMProducer) -> Vec<WasmInstruction> {
     let mut instructions = vec![];
     let header = "(func $buildLogMessage (type $_t_i32)".to_string();
@@ -1644,54 +1723,90 @@ fn get_file_instructions(name: &str) -> Vec<WasmInstruction> {
     instructions
 }
 
-pub fn fr_types(prime: &String) -> Vec<WasmInstruction> {
+// Buffers of the field code, which are placed after the stack pointer, in
+// [8, 4 + fr_memory_size). Each one of them holds a raw number of size_32_bit words,
+// except the last two, which are 8 bytes longer
+const FR_BUFFERS: [&str; 14] = [
+    "q",
+    "r2",
+    "one",
+    "half",
+    "mask",
+    "qm2",
+    "shift",
+    "quotient",
+    "remainder",
+    "exp_acc",
+    "exp_base",
+    "exp_e",
+    "mul",
+    "element",
+];
+
+fn fr_buffer_start(size_32_bit: usize, index: usize) -> usize {
+    let n8 = 4 * size_32_bit;
+    8 + index * n8 + index.saturating_sub(12) * 8
+}
+
+pub fn fr_memory_size(size_32_bit: usize) -> usize {
+    fr_buffer_start(size_32_bit, FR_BUFFERS.len()) - 4
+}
+
+fn fr_buffer(size_32_bit: usize, name: &str) -> usize {
+    let index = FR_BUFFERS
+        .iter()
+        .position(|buffer| *buffer == name)
+        .unwrap();
+    fr_buffer_start(size_32_bit, index)
+}
+
+// Constants of the field code, stored in their buffers
+pub fn fr_data(producer: &WASMProducer) -> Vec<WasmInstruction> {
+    let prime = producer.get_prime().parse::<BigInt>().unwrap();
+    let field = FieldConstants::new(&prime);
+    let n8 = 4 * producer.size_32_bit;
+    let constants = [
+        ("q", prime.clone()),
+        ("r2", field.get_r2()),
+        ("one", BigInt::from(1)),
+        ("half", field.get_half()),
+        ("mask", field.get_mask()),
+        ("qm2", &prime - 2),
+    ];
     let mut instructions = vec![];
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr-types.wat"),
-        "bls12381" => include_str!("bls12381/fr-types.wat"),
-        "goldilocks" => include_str!("goldilocks/fr-types.wat"),
-        "grumpkin" => include_str!("grumpkin/fr-types.wat"),
-        "pallas" => include_str!("pallas/fr-types.wat"),
-        "vesta" => include_str!("vesta/fr-types.wat"),
-        _ => unreachable!(),
-    };
-    for line in file.lines() {
-        instructions.push(line.to_string());
+    for (name, value) in constants {
+        instructions.push(format!(
+            "(data (i32.const {}) \"{}\")",
+            fr_buffer(producer.size_32_bit, name),
+            wasm_hexa(n8, &value)
+        ));
     }
     instructions
 }
 
-pub fn fr_data(prime: &String) -> Vec<WasmInstruction> {
-    let mut instructions = vec![];
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr-data.wat"),
-        "bls12381" => include_str!("bls12381/fr-data.wat"),
-        "goldilocks" => include_str!("goldilocks/fr-data.wat"),
-        "grumpkin" => include_str!("grumpkin/fr-data.wat"),
-        "pallas" => include_str!("pallas/fr-data.wat"),
-        "vesta" => include_str!("vesta/fr-data.wat"),
-        _ => unreachable!(),
-    };
-    for line in file.lines() {
-        instructions.push(line.to_string());
+pub fn fr_code(producer: &WASMProducer) -> Vec<WasmInstruction> {
+    let prime = producer.get_prime().parse::<BigInt>().unwrap();
+    let field = FieldConstants::new(&prime);
+    let n32 = producer.size_32_bit;
+    let n8 = 4 * n32;
+    let mut data = serde_json::Map::new();
+    for buffer in FR_BUFFERS {
+        data.insert(buffer.to_string(), fr_buffer(n32, buffer).into());
     }
-    instructions
-}
-pub fn fr_code(prime: &String) -> Vec<WasmInstruction> {
-    let mut instructions = vec![];
-    let file = match prime.as_ref() {
-        "bn128" => include_str!("bn128/fr-code.wat"),
-        "bls12381" => include_str!("bls12381/fr-code.wat"),
-        "goldilocks" => include_str!("goldilocks/fr-code.wat"),
-        "grumpkin" => include_str!("grumpkin/fr-code.wat"),
-        "pallas" => include_str!("pallas/fr-code.wat"),
-        "vesta" => include_str!("vesta/fr-code.wat"),
-        _ => unreachable!(),
-    };
-    for line in file.lines() {
-        instructions.push(line.to_string());
-    }
-    instructions
+    data.insert("n32".to_string(), n32.into());
+    data.insert("n32m1".to_string(), (n32 - 1).into());
+    data.insert("n8".to_string(), n8.into());
+    data.insert("n8m4".to_string(), (n8 - 4).into());
+    data.insert("n8p4".to_string(), (n8 + 4).into());
+    data.insert("n8e".to_string(), (n8 + 8).into());
+    data.insert("bits".to_string(), field.get_bits().into());
+    data.insert("np".to_string(), field.get_np32().into());
+    let fr_code_template: &str = include_str!("common/fr-code.wat");
+    let template = handlebars::Handlebars::new();
+    let code = template
+        .render_template(fr_code_template, &data)
+        .expect("must render");
+    code.lines().map(|line| line.to_string()).collect()
 }
 
 /*
@@ -1713,13 +1828,7 @@ pub fn generate_utils_js_file(js_folder: &PathBuf) -> std::io::Result<()> {
 }
  */
 
-pub fn generate_generate_witness_js_file(js_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = js_folder.clone();
-    file_path.push("generate_witness");
-    file_path.set_extension("js");
-    let file_name = file_path.to_str().unwrap();
-    let mut js_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_generate_witness_js_file(js_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
     let file = include_str!("common/generate_witness.js");
     for line in file.lines() {
@@ -1730,13 +1839,7 @@ pub fn generate_generate_witness_js_file(js_folder: &PathBuf) -> std::io::Result
     Ok(())
 }
 
-pub fn generate_witness_calculator_js_file(js_folder: &PathBuf) -> std::io::Result<()> {
-    use std::io::BufWriter;
-    let mut file_path = js_folder.clone();
-    file_path.push("witness_calculator");
-    file_path.set_extension("js");
-    let file_name = file_path.to_str().unwrap();
-    let mut js_file = BufWriter::new(File::create(file_name).unwrap());
+pub fn generate_witness_calculator_js_file(js_file: &mut dyn Write) -> std::io::Result<()> {
     let mut code = "".to_string();
     let file = include_str!("common/witness_calculator.js");
     for line in file.lines() {
@@ -6,8 +6,11 @@ use crate::intermediate_representation::translate;
 use crate::intermediate_representation::translate::{
     CodeInfo, FieldTracker, ParallelClusters, TemplateDB,
 };
+use crate::ir_processing::OptimizationLevel;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
+use num_bigint_dig::BigInt;
 use program_structure::file_definition::FileLibrary;
 use std::collections::{BTreeMap, HashMap};
 
@@ -99,6 +102,8 @@ fn build_template_instances(
             components: template.components,
             template_database: &c_info.template_database,
             string_table,
+            prime: &c_info.prime,
+            optimization_level: c_info.optimization_level,
             signals_to_tags: template.signals_to_tags,
         };
         let mut template_info = TemplateCodeInfo {
@@ -163,6 +168,8 @@ fn build_function_instances(
             component_to_parallel: HashMap::with_capacity(0),
             template_database: &c_info.template_database,
             string_table,
+            prime: &c_info.prime,
+            optimization_level: c_info.optimization_level,
             signals_to_tags: BTreeMap::new(),
         };
         let mut function_info = FunctionCodeInfo {
@@ -193,6 +200,7 @@ fn initialize_wasm_producer(
     version: &str,
 ) -> WASMProducer {
     use program_structure::utils::constants::UsefulConstants;
+    use code_producers::wasm_elements::wasm_code_generator::fr_memory_size;
     let initial_node = vcp.get_main_id();
     let prime = UsefulConstants::new(&vcp.prime).get_p().clone();
     let mut producer = WASMProducer::default();
@@ -201,21 +209,12 @@ fn initialize_wasm_producer(
     producer.main_signal_offset = 1;
     producer.prime = prime.to_str_radix(10);
     producer.prime_str = vcp.prime.clone();
-    producer.fr_memory_size = match vcp.prime.as_str() {
-        "goldilocks" => 412,
-        "bn128" => 1948,
-        "bls12381" => 1948,
-        "grumpkin" => 1948,
-        "pallas" => 1948,
-        "vesta" => 1948,
-        _ => unreachable!(),
-    };
-    //producer.fr_memory_size = 412 if goldilocks and 1948 for bn128 and bls12381
     // for each created component we store three u32, for each son we store a u32 in its father
     producer.size_of_component_tree =
         stats.all_created_components * 3 + stats.all_needed_subcomponents_indexes;
     producer.total_number_of_signals = stats.all_signals + 1;
-    producer.size_32_bit = prime.bits() / 32 + if prime.bits() % 32 != 0 { 1 } else { 0 };
+    // the field code works with words of 64 bits
+    producer.size_32_bit = 2 * prime.bits().div_ceil(64);
     producer.size_32_shift = 0;
     let mut pow = 1;
     while pow < producer.size_32_bit {
@@ -223,6 +222,7 @@ fn initialize_wasm_producer(
         producer.size_32_shift += 1;
     }
     producer.size_32_shift += 2;
+    producer.fr_memory_size = fr_memory_size(producer.size_32_bit);
     producer.number_of_components = stats.all_created_components;
     producer.witness_to_signal_list = vcp.get_witness_list().clone();
     producer.signals_in_witness = producer.witness_to_signal_list.len();
@@ -254,7 +254,8 @@ fn initialize_c_producer(vcp: &VCP, database: &TemplateDB, version: &str) -> CPr
     producer.size_of_component_tree =
         stats.all_created_components * 3 + stats.all_needed_subcomponents_indexes;
     producer.total_number_of_signals = stats.all_signals + 1;
-    producer.size_32_bit = prime.bits() / 32 + if prime.bits() % 32 != 0 { 1 } else { 0 };
+    // the field code works with words of 64 bits
+    producer.size_32_bit = 2 * prime.bits().div_ceil(64);
     producer.size_32_shift = 0;
     let mut pow = 1;
     while pow < producer.size_32_bit {
@@ -279,6 +280,30 @@ fn initialize_c_producer(vcp: &VCP, database: &TemplateDB, version: &str) -> CPr
     producer
 }
 
+fn initialize_rust_producer(vcp: &VCP, database: &TemplateDB, version: &str) -> RustProducer {
+    use program_structure::utils::constants::UsefulConstants;
+    let initial_node = vcp.get_main_id();
+    let prime = UsefulConstants::new(&vcp.prime).get_p().clone();
+    let mut producer = RustProducer::default();
+    let stats = vcp.get_stats();
+    producer.main_header = vcp.get_main_instance().unwrap().template_header.clone();
+    producer.main_signal_offset = 1;
+    producer.prime = prime.to_str_radix(10);
+    producer.prime_str = vcp.prime.clone();
+    producer.total_number_of_signals = stats.all_signals + 1;
+    producer.number_of_components = stats.all_created_components;
+    producer.witness_to_signal_list = vcp.get_witness_list().clone();
+    producer.main_input_list = main_input_list(&vcp.templates[initial_node]);
+    producer.io_map = build_io_map(vcp, database);
+    producer.field_tracking.clear();
+    (
+        producer.major_version,
+        producer.minor_version,
+        producer.patch_version,
+    ) = get_number_version(version);
+    producer
+}
+
 fn main_input_list(main: &TemplateInstance) -> InputList {
     use program_structure::ast::SignalType::*;
     let mut input_list = vec![];
@@ -359,6 +384,70 @@ fn write_main_inputs_log(vcp: &VCP) {
     }
 }
 
+// The component is the one of the filter or one of its subcomponents
+fn is_traced(path: &str, filter: &str) -> bool {
+    path == filter
+        || (path.starts_with(filter) && matches!(path.as_bytes()[filter.len()], b'.' | b'['))
+}
+
+// The positions of the elements of an array, [0][0], [0][1], ..., in the order of the signals
+fn generate_positions(lengths: &[usize]) -> Vec<String> {
+    let mut positions = vec![String::new()];
+    for length in lengths {
+        let mut extended = Vec::with_capacity(positions.len() * length);
+        for position in &positions {
+            for i in 0..*length {
+                extended.push(format!("{}[{}]", position, i));
+            }
+        }
+        positions = extended;
+    }
+    positions
+}
+
+// Full names of the signals whose assignments are traced, that is, the signals of the
+// components whose path is the given filter or starts with it
+fn trace_signal_names(vcp: &VCP, filter: &str) -> TraceSignalNames {
+    fn collect(
+        vcp: &VCP,
+        instance: &TemplateInstance,
+        signal_start: usize,
+        path: &str,
+        filter: &str,
+        names: &mut TraceSignalNames,
+    ) {
+        if is_traced(path, filter) {
+            for signal in &instance.signals {
+                let positions = generate_positions(&signal.lengths);
+                for (k, position) in positions.iter().enumerate() {
+                    let name = format!("{}.{}{}", path, signal.name, position);
+                    names.insert(signal_start + signal.local_id + k, name);
+                }
+            }
+        }
+        for trigger in &instance.triggers {
+            let mut sub_path = format!("{}.{}", path, trigger.component_name);
+            for index in &trigger.indexed_with {
+                sub_path.push_str(&format!("[{}]", index));
+            }
+            let sub_instance = &vcp.templates[trigger.template_id];
+            collect(
+                vcp,
+                sub_instance,
+                signal_start + trigger.offset,
+                &sub_path,
+                filter,
+                names,
+            );
+        }
+    }
+
+    let mut names = TraceSignalNames::new();
+    let main = vcp.get_main_instance().unwrap();
+    collect(vcp, main, 1, "main", filter, &mut names);
+    names
+}
+
 fn get_number_version(version: &str) -> (usize, usize, usize) {
     use std::str::FromStr;
     let version_splitted: Vec<&str> = version.split('.').collect();
@@ -373,10 +462,13 @@ struct CircuitInfo {
     file_library: FileLibrary,
     functions: HashMap<String, Vec<usize>>,
     template_database: TemplateDB,
+    prime: BigInt,
+    optimization_level: OptimizationLevel,
 }
 
 pub fn build_circuit(vcp: VCP, flag: CompilationFlags, version: &str) -> Circuit {
     use crate::ir_processing::set_arena_size_in_calls;
+    use program_structure::utils::constants::UsefulConstants;
     if flag.main_inputs_log {
         write_main_inputs_log(&vcp);
     }
@@ -385,12 +477,24 @@ pub fn build_circuit(vcp: VCP, flag: CompilationFlags, version: &str) -> Circuit
     circuit.wasm_producer =
         initialize_wasm_producer(&vcp, &template_database, flag.wat_flag, version);
     circuit.c_producer = initialize_c_producer(&vcp, &template_database, version);
+    circuit.rust_producer = initialize_rust_producer(&vcp, &template_database, version);
+    if let Some(filter) = &flag.trace {
+        let names = trace_signal_names(&vcp, filter);
+        circuit.wasm_producer.trace_flag = true;
+        circuit.wasm_producer.trace_signal_names = names.clone();
+        circuit.c_producer.trace_flag = true;
+        circuit.c_producer.trace_signal_names = names.clone();
+        circuit.rust_producer.trace_flag = true;
+        circuit.rust_producer.trace_signal_names = names;
+    }
 
     let field_tracker = FieldTracker::new();
     let circuit_info = CircuitInfo {
         template_database,
+        prime: UsefulConstants::new(&vcp.prime).get_p().clone(),
         file_library: vcp.file_library,
         functions: vcp.quick_knowledge,
+        optimization_level: flag.ir_optimization,
     };
 
     let (field_tracker, string_table) =
@@ -407,11 +511,17 @@ pub fn build_circuit(vcp: VCP, flag: CompilationFlags, version: &str) -> Circuit
     circuit
         .wasm_producer
         .set_string_table(table_usize_to_string.clone());
-    circuit.c_producer.set_string_table(table_usize_to_string);
+    circuit
+        .c_producer
+        .set_string_table(table_usize_to_string.clone());
+    circuit
+        .rust_producer
+        .set_string_table(table_usize_to_string);
     for i in 0..field_tracker.next_id() {
         let constant = field_tracker.get_constant(i).unwrap().clone();
         circuit.wasm_producer.field_tracking.push(constant.clone());
-        circuit.c_producer.field_tracking.push(constant);
+        circuit.c_producer.field_tracking.push(constant.clone());
+        circuit.rust_producer.field_tracking.push(constant);
     }
     for fun in &mut circuit.functions {
         set_arena_size_in_calls(&mut fun.body, &function_to_arena_size);
@@ -432,3 +542,29 @@ pub fn create_table_usize_to_string(string_table: HashMap<String, usize>) -> Vec
     }
     table_usize_to_string
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traced_components() {
+        assert!(is_traced("main.sq[1]", "main.sq[1]"));
+        assert!(is_traced("main.sq[1].inner", "main.sq[1]"));
+        assert!(is_traced("main.sq[1][0]", "main.sq[1]"));
+        assert!(is_traced("main.sq[1]", "main"));
+        assert!(!is_traced("main.sq[10]", "main.sq[1]"));
+        assert!(!is_traced("main.square", "main.sq"));
+        assert!(!is_traced("main", "main.sq"));
+    }
+
+    #[test]
+    fn positions_of_the_signals() {
+        assert_eq!(generate_positions(&[]), vec![""]);
+        assert_eq!(
+            generate_positions(&[2, 3]),
+            vec!["[0][0]", "[0][1]", "[0][2]", "[1][0]", "[1][1]", "[1][2]"]
+        );
+        assert!(generate_positions(&[2, 0]).is_empty());
+    }
+}
@@ -2,20 +2,26 @@ use super::function::{FunctionCode, FunctionCodeInfo};
 use super::template::{TemplateCode, TemplateCodeInfo};
 use super::types::*;
 use crate::hir::very_concrete_program::VCP;
+use crate::ir_processing::OptimizationLevel;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 pub struct CompilationFlags {
     pub main_inputs_log: bool,
     pub wat_flag: bool,
+    pub trace: Option<String>,
+    pub ir_optimization: OptimizationLevel,
 }
 
 #[derive(Default)]
 pub struct Circuit {
     pub wasm_producer: WASMProducer,
     pub c_producer: CProducer,
+    pub rust_producer: RustProducer,
     pub templates: Vec<TemplateCode>,
     pub functions: Vec<FunctionCode>,
 }
@@ -32,15 +38,12 @@ impl WriteWasm for Circuit {
         code_aux = generate_memory_def_list(producer);
         code.append(&mut code_aux);
 
-        code_aux = fr_types(&producer.prime_str);
-        code.append(&mut code_aux);
-
         code_aux = generate_types_list();
         code.append(&mut code_aux);
         code_aux = generate_exports_list();
         code.append(&mut code_aux);
 
-        code_aux = fr_code(&producer.prime_str);
+        code_aux = fr_code(producer);
         code.append(&mut code_aux);
 
         code_aux = desp_io_subcomponent_generator(producer);
@@ -100,6 +103,11 @@ impl WriteWasm for Circuit {
         code_aux = build_log_message_generator(producer);
         code.append(&mut code_aux);
 
+        if producer.trace_flag {
+            code_aux = trace_signals_generator(producer);
+            code.append(&mut code_aux);
+        }
+
         // Actual code from the program
 
         for f in &self.functions {
@@ -113,7 +121,7 @@ impl WriteWasm for Circuit {
         code_aux = generate_table_of_template_runs(producer);
         code.append(&mut code_aux);
 
-        code_aux = fr_data(&producer.prime_str);
+        code_aux = fr_data(producer);
         code.append(&mut code_aux);
 
         code_aux = generate_data_list(producer);
@@ -139,11 +147,6 @@ impl WriteWasm for Circuit {
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
 
-        code_aux = fr_types(&producer.prime_str);
-        code = merge_code(code_aux);
-        writer.write_all(code.as_bytes()).map_err(|_| {})?;
-        //writer.flush().map_err(|_| {})?;
-
         code_aux = generate_types_list();
         code = merge_code(code_aux);
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
@@ -154,7 +157,7 @@ impl WriteWasm for Circuit {
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
 
-        code_aux = fr_code(&producer.prime_str);
+        code_aux = fr_code(producer);
         code = merge_code(code_aux);
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
@@ -254,6 +257,12 @@ impl WriteWasm for Circuit {
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
 
+        if producer.trace_flag {
+            code_aux = trace_signals_generator(producer);
+            code = merge_code(code_aux);
+            writer.write_all(code.as_bytes()).map_err(|_| {})?;
+        }
+
         // Actual code from the program
 
         for f in &self.functions {
@@ -271,7 +280,7 @@ impl WriteWasm for Circuit {
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
 
-        code_aux = fr_data(&producer.prime_str);
+        code_aux = fr_data(producer);
         code = merge_code(code_aux);
         writer.write_all(code.as_bytes()).map_err(|_| {})?;
         //writer.flush().map_err(|_| {})?;
@@ -357,6 +366,10 @@ impl WriteC for Circuit {
         ));
         //code.append(&mut generate_message_list_def(producer, producer.get_message_list()));
 
+        // Names of the traced signals
+        let mut trace_code = generate_function_get_trace_signal_name(producer);
+        code.append(&mut trace_code);
+
         // Functions to release the memory
         let mut release_component_code = generate_function_release_memory_component();
         code.append(&mut release_component_code);
@@ -483,6 +496,10 @@ impl WriteC for Circuit {
         ));
         //code.append(&mut generate_message_list_def(producer, producer.get_message_list()));
 
+        // Names of the traced signals
+        let mut trace_code = generate_function_get_trace_signal_name(producer);
+        code.append(&mut trace_code);
+
         // Functions to release the memory
         let mut release_component_code = generate_function_release_memory_component();
         code.append(&mut release_component_code);
@@ -552,6 +569,111 @@ impl WriteC for Circuit {
     }
 }
 
+impl WriteRust for Circuit {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let mut code = vec![];
+        // Prologue
+        code.push(format!(
+            "// Witness calculator of the circuit generated by circom {}",
+            producer.get_version()
+        ));
+        code.push(
+            "#![allow(non_snake_case, dead_code, unused_mut, unused_variables, unused_parens, unreachable_code, clippy::all)]"
+                .to_string(),
+        );
+        code.push("extern crate num_bigint_dig as num_bigint;".to_string());
+        code.push("mod calcwit;".to_string());
+        code.push("pub mod field;".to_string());
+        code.push("use calcwit::*;".to_string());
+        code.push("pub use calcwit::WitnessResult;".to_string());
+        code.push("pub use field::Fr;".to_string());
+        code.push("use std::collections::HashMap;".to_string());
+        code.push("".to_string());
+
+        // Tables of the circuit
+        code.append(&mut generate_constants(producer));
+        code.append(&mut generate_io_map(producer));
+        code.append(&mut generate_main_inputs(producer));
+        code.append(&mut generate_witness_to_signal(producer));
+        code.append(&mut generate_trace_signal_names(producer));
+        code.push(format!(
+            "static TEMPLATES: [TemplateInfo; {}] = [",
+            self.templates.len()
+        ));
+        for t in &self.templates {
+            code.push(format!(
+                "    TemplateInfo {{ name: {}, number_of_inputs: {}, number_of_components: {}, run: {} }},",
+                rust_string(&t.name),
+                t.number_of_inputs,
+                t.number_of_components,
+                template_function_name(&t.header)
+            ));
+        }
+        code.push("];".to_string());
+        let main_template = self
+            .templates
+            .iter()
+            .position(|t| t.header == producer.get_main_header())
+            .unwrap_or(0);
+        code.push("static CIRCUIT: CircuitInfo = CircuitInfo {".to_string());
+        code.push("    constants: &CONSTANTS,".to_string());
+        code.push("    templates: &TEMPLATES,".to_string());
+        code.push("    io_map: &IO_MAP,".to_string());
+        code.push(format!("    main_template: {},", main_template));
+        code.push(format!(
+            "    main_signal_offset: {},",
+            producer.get_main_signal_offset()
+        ));
+        code.push("    main_inputs: &MAIN_INPUTS,".to_string());
+        code.push("    witness_to_signal: &WITNESS_TO_SIGNAL,".to_string());
+        code.push(format!(
+            "    total_number_of_signals: {},",
+            producer.get_total_number_of_signals()
+        ));
+        code.push(format!(
+            "    number_of_components: {},",
+            producer.get_number_of_components()
+        ));
+        code.push("    trace_signal_names: &TRACE_SIGNAL_NAMES,".to_string());
+        code.push("};".to_string());
+        code.push("".to_string());
+
+        // Entry points
+        code.push(
+            "// Computes the witness from the values of the inputs of the main component"
+                .to_string(),
+        );
+        code.push(
+            "pub fn calculate_witness(inputs: &HashMap<String, Vec<Fr>>) -> Vec<Fr> {".to_string(),
+        );
+        code.push("    match try_calculate_witness(inputs) {".to_string());
+        code.push("        Ok(witness) => witness,".to_string());
+        code.push("        Err(message) => panic!(\"{}\", message),".to_string());
+        code.push("    }".to_string());
+        code.push("}".to_string());
+        code.push("".to_string());
+        code.push(format!(
+            "pub fn try_calculate_witness(inputs: &HashMap<String, Vec<Fr>>) -> {}<Vec<Fr>> {{",
+            RESULT_TYPE
+        ));
+        code.push("    CalcWit::new(&CIRCUIT).calculate_witness(inputs)".to_string());
+        code.push("}".to_string());
+
+        for f in &self.functions {
+            let (mut function_code, _) = f.produce_rust(producer);
+            code.push("".to_string());
+            code.append(&mut function_code);
+        }
+        for t in &self.templates {
+            let (mut template_code, _) = t.produce_rust(producer);
+            code.push("".to_string());
+            code.append(&mut template_code);
+        }
+        (code, "".to_string())
+    }
+}
+
 impl Circuit {
     pub fn build(vcp: VCP, flags: CompilationFlags, version: &str) -> Self {
         use super::build::build_circuit;
@@ -575,11 +697,36 @@ impl Circuit {
     pub fn get_template(&self, id: ID) -> &TemplateCodeInfo {
         self.templates[id].as_ref()
     }
-    pub fn produce_ir_string_for_template(&self, id: ID) -> String {
-        self.templates[id].to_string()
-    }
-    pub fn produce_ir_string_for_function(&self, id: ID) -> String {
-        self.functions[id].to_string()
+    // The files of the C++ folder with the code that does not depend on the templates and
+    // functions of the circuit, by name
+    fn c_support_files(&self, run_name: &str) -> std::io::Result<BTreeMap<String, Vec<u8>>> {
+        let mut main_cpp = Vec::new();
+        c_code_generator::generate_main_cpp_file(&mut main_cpp)?;
+        let mut circom_hpp = Vec::new();
+        c_code_generator::generate_circom_hpp_file(&mut circom_hpp)?;
+        let mut fr_hpp = Vec::new();
+        c_code_generator::generate_fr_hpp_file(&mut fr_hpp, &self.c_producer)?;
+        let mut calcwit_hpp = Vec::new();
+        c_code_generator::generate_calcwit_hpp_file(&mut calcwit_hpp)?;
+        let mut fr_cpp = Vec::new();
+        c_code_generator::generate_fr_cpp_file(&mut fr_cpp, &self.c_producer)?;
+        let mut calcwit_cpp = Vec::new();
+        c_code_generator::generate_calcwit_cpp_file(&mut calcwit_cpp)?;
+        let mut makefile = Vec::new();
+        c_code_generator::generate_make_file(&mut makefile, run_name, &self.c_producer)?;
+        let files = vec![
+            ("main.cpp", main_cpp),
+            ("circom.hpp", circom_hpp),
+            ("fr.hpp", fr_hpp),
+            ("calcwit.hpp", calcwit_hpp),
+            ("fr.cpp", fr_cpp),
+            ("calcwit.cpp", calcwit_cpp),
+            ("Makefile", makefile),
+        ];
+        Ok(files
+            .into_iter()
+            .map(|(name, contents)| (name.to_string(), contents))
+            .collect())
     }
     pub fn produce_c<W: Write>(
         &self,
@@ -589,22 +736,36 @@ impl Circuit {
         c_dat: &mut W,
     ) -> Result<(), ()> {
         use std::path::Path;
-        let c_folder_path = Path::new(c_folder.clone()).to_path_buf();
-        c_code_generator::generate_main_cpp_file(&c_folder_path).map_err(|_err| {})?;
-        c_code_generator::generate_circom_hpp_file(&c_folder_path).map_err(|_err| {})?;
-        c_code_generator::generate_fr_hpp_file(&c_folder_path, &self.c_producer.prime_str)
-            .map_err(|_err| {})?;
-        c_code_generator::generate_calcwit_hpp_file(&c_folder_path).map_err(|_err| {})?;
-        c_code_generator::generate_fr_cpp_file(&c_folder_path, &self.c_producer.prime_str)
-            .map_err(|_err| {})?;
-        c_code_generator::generate_calcwit_cpp_file(&c_folder_path).map_err(|_err| {})?;
-        c_code_generator::generate_fr_asm_file(&c_folder_path, &self.c_producer.prime_str)
-            .map_err(|_err| {})?;
-        c_code_generator::generate_make_file(&c_folder_path, run_name, &self.c_producer)
-            .map_err(|_err| {})?;
+        for (name, contents) in self.c_support_files(run_name).map_err(|_err| {})? {
+            std::fs::write(Path::new(c_folder).join(name), contents).map_err(|_err| {})?;
+        }
         c_code_generator::generate_dat_file(c_dat, &self.c_producer).map_err(|_err| {})?;
         self.write_c(c_circuit, &self.c_producer)
     }
+    // Every file of the C++ folder produced in memory, by name
+    pub fn produce_c_files(&self, run_name: &str) -> Result<BTreeMap<String, Vec<u8>>, ()> {
+        let mut files = self.c_support_files(run_name).map_err(|_err| {})?;
+        let mut c_dat = Vec::new();
+        c_code_generator::generate_dat_file(&mut c_dat, &self.c_producer).map_err(|_err| {})?;
+        let mut c_circuit = Vec::new();
+        self.write_c(&mut c_circuit, &self.c_producer)?;
+        files.insert(format!("{}.dat", run_name), c_dat);
+        files.insert(format!("{}.cpp", run_name), c_circuit);
+        Ok(files)
+    }
+    // The JavaScript files of the wasm folder, by name
+    pub fn produce_js_files(&self) -> Result<BTreeMap<String, Vec<u8>>, ()> {
+        let mut generate_witness = Vec::new();
+        wasm_code_generator::generate_generate_witness_js_file(&mut generate_witness)
+            .map_err(|_err| {})?;
+        let mut witness_calculator = Vec::new();
+        wasm_code_generator::generate_witness_calculator_js_file(&mut witness_calculator)
+            .map_err(|_err| {})?;
+        let mut files = BTreeMap::new();
+        files.insert("generate_witness.js".to_string(), generate_witness);
+        files.insert("witness_calculator.js".to_string(), witness_calculator);
+        Ok(files)
+    }
     pub fn produce_wasm<W: Write>(
         &self,
         js_folder: &str,
@@ -612,11 +773,48 @@ impl Circuit {
         writer: &mut W,
     ) -> Result<(), ()> {
         use std::path::Path;
-        let js_folder_path = Path::new(js_folder.clone()).to_path_buf();
-        wasm_code_generator::generate_generate_witness_js_file(&js_folder_path)
-            .map_err(|_err| {})?;
-        wasm_code_generator::generate_witness_calculator_js_file(&js_folder_path)
-            .map_err(|_err| {})?;
+        for (name, contents) in self.produce_js_files()? {
+            std::fs::write(Path::new(js_folder).join(name), contents).map_err(|_err| {})?;
+        }
         self.write_wasm(writer, &self.wasm_producer)
     }
+    // The wat code of the circuit produced in memory
+    pub fn produce_wat(&self) -> Result<String, ()> {
+        let mut wat = Vec::new();
+        self.write_wasm(&mut wat, &self.wasm_producer)?;
+        String::from_utf8(wat).map_err(|_err| {})
+    }
+    fn rust_support_files(&self, run_name: &str) -> std::io::Result<BTreeMap<String, Vec<u8>>> {
+        let mut cargo_toml = Vec::new();
+        rust_code_generator::generate_cargo_toml_file(
+            &mut cargo_toml,
+            run_name,
+            &self.rust_producer,
+        )?;
+        let mut field_rs = Vec::new();
+        rust_code_generator::generate_field_rs_file(&mut field_rs, &self.rust_producer)?;
+        let mut calcwit_rs = Vec::new();
+        rust_code_generator::generate_calcwit_rs_file(&mut calcwit_rs)?;
+        let files = vec![
+            ("Cargo.toml", cargo_toml),
+            ("src/field.rs", field_rs),
+            ("src/calcwit.rs", calcwit_rs),
+        ];
+        Ok(files
+            .into_iter()
+            .map(|(name, contents)| (name.to_string(), contents))
+            .collect())
+    }
+    pub fn produce_rust<W: Write>(
+        &self,
+        rust_folder: &str,
+        run_name: &str,
+        writer: &mut W,
+    ) -> Result<(), ()> {
+        use std::path::Path;
+        for (name, contents) in self.rust_support_files(run_name).map_err(|_err| {})? {
+            std::fs::write(Path::new(rust_folder).join(name), contents).map_err(|_err| {})?;
+        }
+        self.write_rust(writer, &self.rust_producer)
+    }
 }
@@ -3,6 +3,7 @@ use crate::hir::very_concrete_program::Param;
 use crate::intermediate_representation::InstructionList;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 //use std::io::Write;
 
@@ -119,6 +120,38 @@ impl WriteC for FunctionCodeInfo {
     }
 }
 
+impl WriteRust for FunctionCodeInfo {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let mut body = vec![];
+        body.push(format!(
+            "const {}: &str = {};",
+            NAME,
+            rust_string(&self.name)
+        ));
+        body.push(format!(
+            "if {}.len() < {} {{ {}.resize({}, Fr::zero()); }}",
+            LVAR, self.max_number_of_vars, LVAR, self.max_number_of_vars
+        ));
+        for t in &self.body {
+            let (mut instructions_body, _) = t.produce_rust(producer);
+            body.append(&mut instructions_body);
+        }
+        body.push("Ok(Vec::new())".to_string());
+        let mut function = vec![format!(
+            "fn {}({}: &mut CalcWit, {}: usize, mut {}: Vec<Fr>) -> {}<Vec<Fr>> {{",
+            function_name(&self.header),
+            CALC_WIT,
+            CTX_INDEX,
+            LVAR,
+            RESULT_TYPE
+        )];
+        function.append(&mut indent(body));
+        function.push("}".to_string());
+        (function, "".to_string())
+    }
+}
+
 impl FunctionCodeInfo {
     pub fn wrap(self) -> FunctionCode {
         FunctionCode::new(self)
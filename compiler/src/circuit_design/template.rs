@@ -1,6 +1,7 @@
 use crate::intermediate_representation::InstructionList;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 type TemplateID = usize;
@@ -112,6 +113,9 @@ impl WriteWasm for TemplateCodeInfo {
             producer.get_create_loop_counter_tag()
         ));
         instructions.push(format!(" (local {} i32)", producer.get_merror_tag()));
+        if producer.trace_flag {
+            instructions.push(format!(" (local {} i32)", producer.get_trace_signal_tag()));
+        }
         let local_info_size_u32 = producer.get_local_info_size_u32(); // in the future we can add some info like pointer to run father or text father
                                                                       //set lvar (start of auxiliar memory for vars)
         instructions.push(set_constant("0"));
@@ -174,6 +178,41 @@ impl WriteC for TemplateCodeInfo {
     }
 }
 
+impl WriteRust for TemplateCodeInfo {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let mut run_body = vec![];
+        run_body.push(format!(
+            "const {}: &str = {};",
+            NAME,
+            rust_string(&self.name)
+        ));
+        run_body.push(format!(
+            "let {} = {}.components[{}].signal_start;",
+            SIGNAL_START, CALC_WIT, CTX_INDEX
+        ));
+        run_body.push(format!(
+            "let mut {} = vec![Fr::zero(); {}];",
+            LVAR, self.var_stack_depth
+        ));
+        for t in &self.body {
+            let (mut instructions_body, _) = t.produce_rust(producer);
+            run_body.append(&mut instructions_body);
+        }
+        run_body.push("Ok(())".to_string());
+        let mut run_fun = vec![format!(
+            "fn {}({}: usize, {}: &mut CalcWit) -> {}<()> {{",
+            template_function_name(&self.header),
+            CTX_INDEX,
+            CALC_WIT,
+            RESULT_TYPE
+        )];
+        run_fun.append(&mut indent(run_body));
+        run_fun.push("}".to_string());
+        (run_fun, "".to_string())
+    }
+}
+
 impl TemplateCodeInfo {
     fn produce_c_parallel_case(&self, producer: &CProducer, parallel: bool) -> Vec<String> {
         use c_code_generator::*;
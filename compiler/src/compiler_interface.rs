@@ -1,23 +1,25 @@
 pub use crate::circuit_design::circuit::{Circuit, CompilationFlags};
 pub use crate::hir::very_concrete_program::VCP;
+pub use crate::ir_processing::OptimizationLevel;
+use crate::ir_text;
 use std::fs::File;
 use std::io::BufWriter;
 
 pub struct Config {
-    pub debug_output: bool,
     pub produce_input_log: bool,
     pub wat_flag: bool,
+    pub trace: Option<String>,
+    pub ir_optimization: OptimizationLevel,
 }
 
 pub fn run_compiler(vcp: VCP, config: Config, version: &str) -> Result<Circuit, ()> {
     let flags = CompilationFlags {
         main_inputs_log: config.produce_input_log,
         wat_flag: config.wat_flag,
+        trace: config.trace,
+        ir_optimization: config.ir_optimization,
     };
     let circuit = Circuit::build(vcp, flags, version);
-    if config.debug_output {
-        produce_debug_output(&circuit)?;
-    }
     Ok(circuit)
 }
 
@@ -56,29 +58,38 @@ pub fn write_c(
     circuit.produce_c(c_folder, c_run_name, &mut c_file, &mut dat_file)
 }
 
-fn produce_debug_output(circuit: &Circuit) -> Result<(), ()> {
-    use std::io::Write;
+pub fn write_rust(
+    circuit: &Circuit,
+    rust_folder: &str,
+    rust_run_name: &str,
+    rust_file: &str,
+) -> Result<(), ()> {
     use std::path::Path;
-    let path = "ir_log".to_string();
-    if Path::new(&path).is_dir() {
-        std::fs::remove_dir_all(&path).map_err(|_err| {})?;
+    if Path::new(rust_folder).is_dir() {
+        std::fs::remove_dir_all(rust_folder).map_err(|_err| {})?;
     }
-    std::fs::create_dir(&path).map_err(|_err| {})?;
-    for id in 0..circuit.templates.len() {
-        let file = format!("ir_log/template_{}.txt", id);
-        let file_signals = File::create(file).map_err(|_err| {})?;
-        let mut writer = BufWriter::new(file_signals);
-        let body = circuit.produce_ir_string_for_template(id);
-        writer.write_all(body.as_bytes()).map_err(|_err| {})?;
-        writer.flush().map_err(|_err| {})?;
-    }
-    for id in 0..circuit.functions.len() {
-        let file = format!("ir_log/function_{}.txt", id);
-        let file_signals = File::create(file).map_err(|_err| {})?;
-        let mut writer = BufWriter::new(file_signals);
-        let body = circuit.produce_ir_string_for_function(id);
-        writer.write_all(body.as_bytes()).map_err(|_err| {})?;
-        writer.flush().map_err(|_err| {})?;
-    }
-    Result::Ok(())
+    std::fs::create_dir(rust_folder).map_err(|_err| {})?;
+    std::fs::create_dir(Path::new(rust_folder).join("src")).map_err(|_err| {})?;
+    let rust_file = File::create(rust_file).map_err(|_err| {})?;
+    let mut rust_file = BufWriter::new(rust_file);
+    circuit.produce_rust(rust_folder, rust_run_name, &mut rust_file)
+}
+
+// Writes the code of the circuit in the textual IR format
+pub fn write_ir(circuit: &Circuit, file: &str) -> Result<(), ()> {
+    std::fs::write(file, ir_text::write_code(circuit)).map_err(|_err| {})
+}
+
+// Replaces the code of the circuit by the one of a file written by write_ir, returning the
+// description of the error otherwise
+pub fn read_ir(circuit: &mut Circuit, file: &str) -> Result<(), String> {
+    let text = std::fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+    ir_text::read_code(&text, circuit).map_err(|err| format!("{}: {}", file, err))
+}
+
+// Builds the circuit of a file written by write_ir, which can only be run by the witness
+// calculator of the witness crate
+pub fn read_ir_circuit(file: &str) -> Result<Circuit, String> {
+    let text = std::fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+    ir_text::read_circuit(&text).map_err(|err| format!("{}: {}", file, err))
 }
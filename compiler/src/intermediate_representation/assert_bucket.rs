@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -86,3 +87,19 @@ impl WriteC for AssertBucket {
         (assert_c, "".to_string())
     }
 }
+
+impl WriteRust for AssertBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let (_, value) = self.evaluate.produce_rust(producer);
+        let assertion = vec![
+            format!("if !{} {{", is_true(self.evaluate.rust_value(), value)),
+            format!(
+                "    return Err({});",
+                runtime_error(&rust_string("Failed assert"), self.line)
+            ),
+            "}".to_string(),
+        ];
+        (assertion, "".to_string())
+    }
+}
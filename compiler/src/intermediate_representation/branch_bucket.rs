@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -119,3 +120,28 @@ impl WriteC for BranchBucket {
         (c_branch, "".to_string())
     }
 }
+
+impl WriteRust for BranchBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let (_, cond) = self.cond.produce_rust(producer);
+        let mut if_body = Vec::new();
+        for instr in &self.if_branch {
+            let (mut instr_code, _) = instr.produce_rust(producer);
+            if_body.append(&mut instr_code);
+        }
+        let mut else_body = Vec::new();
+        for instr in &self.else_branch {
+            let (mut instr_code, _) = instr.produce_rust(producer);
+            else_body.append(&mut instr_code);
+        }
+        let mut conditional = vec![format!("if {} {{", is_true(self.cond.rust_value(), cond))];
+        conditional.append(&mut indent(if_body));
+        if !else_body.is_empty() {
+            conditional.push("} else {".to_string());
+            conditional.append(&mut indent(else_body));
+        }
+        conditional.push("}".to_string());
+        (conditional, "".to_string())
+    }
+}
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -286,6 +287,14 @@ impl WriteWasm for CallBucket {
                         }
                     }
                 }
+                let trace = producer.trace_flag
+                    && matches!(
+                        data.dest_address_type,
+                        AddressType::Signal | AddressType::SubcmpSignal { .. }
+                    );
+                if trace {
+                    instructions.push(tee_local(producer.get_trace_signal_tag()));
+                }
                 instructions.push(set_constant(&data.context.size.to_string()));
                 instructions.push(call(&format!("${}", self.symbol)));
                 instructions.push(tee_local(producer.get_merror_tag()));
@@ -297,6 +306,11 @@ impl WriteWasm for CallBucket {
                 instructions.push(get_local(producer.get_merror_tag()));
                 instructions.push(add_return());
                 instructions.push(add_end());
+                if trace {
+                    instructions.push(get_local(producer.get_trace_signal_tag()));
+                    instructions.push(set_constant(&data.context.size.to_string()));
+                    instructions.push(call("$traceSignals"));
+                }
                 match &data.dest_address_type {
                     AddressType::SubcmpSignal { .. } => {
                         // if subcomponent input check if run needed
@@ -534,12 +548,28 @@ impl WriteC for CallBucket {
                         )
                     }
                 };
+                let trace_signals = format!(
+                    "{}->traceSignals({} - {}->{}, {});",
+                    CIRCOM_CALC_WIT,
+                    result_ref,
+                    CIRCOM_CALC_WIT,
+                    SIGNAL_VALUES,
+                    data.context.size
+                );
                 call_arguments.push(result_ref);
                 call_arguments.push(data.context.size.to_string());
                 prologue.push(format!(
                     "{};",
                     build_call(self.symbol.clone(), call_arguments)
                 ));
+                if producer.trace_flag
+                    && matches!(
+                        data.dest_address_type,
+                        AddressType::Signal | AddressType::SubcmpSignal { .. }
+                    )
+                {
+                    prologue.push(trace_signals);
+                }
                 if let LocationRule::Mapped { indexes, .. } = &data.dest {
                     if !indexes.is_empty() {
                         prologue.push("}".to_string());
@@ -811,3 +841,61 @@ impl WriteC for CallBucket {
         (prologue, result)
     }
 }
+
+impl WriteRust for CallBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use super::store_bucket::produce_rust_store;
+        use rust_code_generator::*;
+        let mut prologue = vec![];
+        // create lvar parameter
+        prologue.push(format!(
+            "let mut {} = vec![Fr::zero(); {}];",
+            LVAR_CALL, self.arena_size
+        ));
+        // copying parameters
+        let mut count = 0;
+        for (p, argument_type) in self.arguments.iter().zip(&self.argument_types) {
+            let (_, src) = p.produce_rust(producer);
+            if argument_type.size > 1 {
+                prologue.push(format!(
+                    "field::copy_n(&mut {}, {}, {}, {});",
+                    LVAR_CALL,
+                    count,
+                    as_fields(p.rust_value(), src),
+                    argument_type.size
+                ));
+            } else {
+                prologue.push(format!(
+                    "{}[{}] = {};",
+                    LVAR_CALL,
+                    count,
+                    as_field(p.rust_value(), src)
+                ));
+            }
+            count += argument_type.size;
+        }
+        let call_arguments = vec![
+            CALC_WIT.to_string(),
+            CTX_INDEX.to_string(),
+            LVAR_CALL.to_string(),
+        ];
+        let call = format!(
+            "{}?",
+            build_call(&function_name(&self.symbol), call_arguments)
+        );
+        let result = block(prologue, call);
+        match &self.return_info {
+            ReturnType::Intermediate { .. } => (vec![], format!("field::first({})", result)),
+            ReturnType::Final(data) => {
+                let store = produce_rust_store(
+                    &data.dest_address_type,
+                    &data.dest,
+                    data.context.size,
+                    (RustValue::Fields, result),
+                    producer,
+                );
+                (store, "".to_string())
+            }
+        }
+    }
+}
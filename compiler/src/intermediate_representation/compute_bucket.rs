@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -402,3 +403,95 @@ impl WriteC for ComputeBucket {
         (compute_c, result)
     }
 }
+
+impl WriteRust for ComputeBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        fn get_field_op(op_type: OperatorType) -> &'static str {
+            match op_type {
+                OperatorType::Add => "field::add",
+                OperatorType::Div => "field::div",
+                OperatorType::Mul => "field::mul",
+                OperatorType::Sub => "field::sub",
+                OperatorType::Pow => "field::pow",
+                OperatorType::IntDiv => "field::idiv",
+                OperatorType::Mod => "field::modulo",
+                OperatorType::ShiftL => "field::shl",
+                OperatorType::ShiftR => "field::shr",
+                OperatorType::LesserEq => "field::leq",
+                OperatorType::GreaterEq => "field::geq",
+                OperatorType::Lesser => "field::lt",
+                OperatorType::Greater => "field::gt",
+                OperatorType::Eq(_) => "field::eq",
+                OperatorType::NotEq => "field::neq",
+                OperatorType::BoolOr => "field::lor",
+                OperatorType::BoolAnd => "field::land",
+                OperatorType::BitOr => "field::bor",
+                OperatorType::BitAnd => "field::band",
+                OperatorType::BitXor => "field::bxor",
+                OperatorType::PrefixSub => "field::neg",
+                OperatorType::BoolNot => "field::lnot",
+                OperatorType::Complement => "field::bnot",
+                _ => unreachable!(),
+            }
+        }
+
+        let mut operands = vec![];
+        for instr in &self.stack {
+            let (_, operand) = instr.produce_rust(producer);
+            operands.push((instr.rust_value(), operand));
+        }
+        let result = match &self.op {
+            OperatorType::AddAddress | OperatorType::MulAddress => {
+                let symbol = if self.op == OperatorType::AddAddress {
+                    "+"
+                } else {
+                    "*"
+                };
+                let mut operands = operands.into_iter().map(|(t, e)| as_address(t, e));
+                let left = operands.next().unwrap();
+                let right = operands.next().unwrap();
+                format!("({} {} {})", left, symbol, right)
+            }
+            OperatorType::ToAddress => {
+                let (value_type, operand) = operands.pop().unwrap();
+                as_address(value_type, operand)
+            }
+            OperatorType::Eq(n) if *n > 1 => {
+                let arguments: Vec<String> = operands
+                    .into_iter()
+                    .map(|(t, e)| format!("&{}", as_fields(t, e)))
+                    .collect();
+                format!("field::eq_n({}, {}, {})", arguments[0], arguments[1], n)
+            }
+            _ => {
+                let arguments: Vec<String> = operands
+                    .into_iter()
+                    .map(|(t, e)| format!("&{}", as_field(t, e)))
+                    .collect();
+                let call = build_call(get_field_op(self.op), arguments);
+                match self.op {
+                    OperatorType::Div
+                    | OperatorType::IntDiv
+                    | OperatorType::Mod
+                    | OperatorType::ShiftL
+                    | OperatorType::ShiftR => format!(
+                        "{}.map_err(|err| {})?",
+                        call,
+                        runtime_error("err", self.line)
+                    ),
+                    _ => call,
+                }
+            }
+        };
+        (vec![], result)
+    }
+
+    fn rust_value(&self) -> RustValue {
+        if self.op.is_address_op() {
+            RustValue::Address
+        } else {
+            RustValue::Field
+        }
+    }
+}
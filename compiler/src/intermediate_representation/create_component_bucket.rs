@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -335,3 +336,48 @@ impl WriteC for CreateCmpBucket {
         (instructions, "".to_string())
     }
 }
+
+impl WriteRust for CreateCmpBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let complete_array: bool = self.defined_positions.len() == self.number_of_cmp;
+        let (_, scmp_idx) = self.sub_cmp_id.produce_rust(producer);
+        let positions = if complete_array {
+            "None".to_string()
+        } else {
+            let positions: Vec<String> = self
+                .defined_positions
+                .iter()
+                .map(|(x, _y)| x.to_string())
+                .collect();
+            format!("Some(&[{}])", positions.join(", "))
+        };
+        let dimensions: Vec<String> = self.dimensions.iter().map(usize::to_string).collect();
+        let instructions = vec![
+            "{".to_string(),
+            format!(
+                "    let first = {};",
+                as_address(self.sub_cmp_id.rust_value(), scmp_idx)
+            ),
+            format!(
+                "    {}.create_subcomponents({}, {}, first, &Subcomponents {{",
+                CALC_WIT, CTX_INDEX, SIGNAL_START
+            ),
+            format!("        template_id: {},", self.template_id),
+            format!("        name: {},", rust_string(&self.name_subcomponent)),
+            format!("        dimensions: &[{}],", dimensions.join(", ")),
+            format!("        positions: {},", positions),
+            format!("        number_of_cmp: {},", self.number_of_cmp),
+            format!("        signal_offset: {},", self.signal_offset),
+            format!("        signal_offset_jump: {},", self.signal_offset_jump),
+            format!("        component_offset: {},", self.component_offset),
+            format!(
+                "        component_offset_jump: {},",
+                self.component_offset_jump
+            ),
+            "    })?;".to_string(),
+            "}".to_string(),
+        ];
+        (instructions, "".to_string())
+    }
+}
@@ -16,6 +16,7 @@ pub use super::value_bucket::ValueBucket;
 
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 pub trait IntoInstruction {
@@ -144,6 +145,42 @@ impl WriteC for Instruction {
     }
 }
 
+impl WriteRust for Instruction {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use Instruction::*;
+        match self {
+            Value(v) => v.produce_rust(producer),
+            Load(v) => v.produce_rust(producer),
+            Store(v) => v.produce_rust(producer),
+            Compute(v) => v.produce_rust(producer),
+            Call(v) => v.produce_rust(producer),
+            Branch(v) => v.produce_rust(producer),
+            Return(v) => v.produce_rust(producer),
+            Loop(v) => v.produce_rust(producer),
+            Assert(v) => v.produce_rust(producer),
+            CreateCmp(v) => v.produce_rust(producer),
+            Log(v) => v.produce_rust(producer),
+        }
+    }
+
+    fn rust_value(&self) -> RustValue {
+        use Instruction::*;
+        match self {
+            Value(v) => v.rust_value(),
+            Load(v) => v.rust_value(),
+            Store(v) => v.rust_value(),
+            Compute(v) => v.rust_value(),
+            Call(v) => v.rust_value(),
+            Branch(v) => v.rust_value(),
+            Return(v) => v.rust_value(),
+            Loop(v) => v.rust_value(),
+            Assert(v) => v.rust_value(),
+            CreateCmp(v) => v.rust_value(),
+            Log(v) => v.rust_value(),
+        }
+    }
+}
+
 impl ToString for Instruction {
     fn to_string(&self) -> String {
         use Instruction::*;
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -345,3 +346,44 @@ impl WriteC for LoadBucket {
         (prologue, access)
     }
 }
+
+impl WriteRust for LoadBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use super::location_rule::produce_rust_location;
+        use rust_code_generator::*;
+        let (mut prologue, memory, position) =
+            produce_rust_location(&self.address_type, &self.src, producer);
+        let is_constant = matches!(
+            &self.src,
+            LocationRule::Indexed { location, .. } if matches!(**location, Instruction::Value(_))
+        );
+        // the position is computed first, it may call functions that use the memory
+        let position = if prologue.is_empty() && is_constant {
+            position
+        } else {
+            prologue.push(format!("let {} = {};", DEST_INDEX, position));
+            DEST_INDEX.to_string()
+        };
+        let value = if self.context.size > 1 {
+            format!(
+                "{}[{}..{} + {}].to_vec()",
+                memory, position, position, self.context.size
+            )
+        } else {
+            format!("{}[{}].clone()", memory, position)
+        };
+        if prologue.is_empty() {
+            (vec![], value)
+        } else {
+            (vec![], block(prologue, value))
+        }
+    }
+
+    fn rust_value(&self) -> RustValue {
+        if self.context.size > 1 {
+            RustValue::Fields
+        } else {
+            RustValue::Field
+        }
+    }
+}
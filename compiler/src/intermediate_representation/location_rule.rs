@@ -1,4 +1,6 @@
 use super::ir_interface::*;
+use crate::translating_traits::*;
+use code_producers::rust_elements::*;
 
 #[derive(Clone)]
 pub enum LocationRule {
@@ -37,3 +39,48 @@ impl ToString for LocationRule {
         }
     }
 }
+
+// Returns the rust statements that select the subcomponent of the address, the memory that
+// contains the location and the expression of its position in that memory
+pub fn produce_rust_location(
+    address_type: &AddressType,
+    location: &LocationRule,
+    producer: &RustProducer,
+) -> (Vec<String>, String, String) {
+    use rust_code_generator::*;
+    let mut prologue = vec![];
+    if let AddressType::SubcmpSignal { cmp_address, .. } = address_type {
+        let (_, cmp_index) = cmp_address.produce_rust(producer);
+        let cmp_index = as_address(cmp_address.rust_value(), cmp_index);
+        prologue.push(format!("let {} = {};", CMP_INDEX, subcomponent(cmp_index)));
+    }
+    let index = match location {
+        LocationRule::Indexed { location, .. } => {
+            let (_, index) = location.produce_rust(producer);
+            as_address(location.rust_value(), index)
+        }
+        LocationRule::Mapped {
+            signal_code,
+            indexes,
+        } => {
+            let mut map_indexes = vec![];
+            for index in indexes {
+                let (_, map_index) = index.produce_rust(producer);
+                map_indexes.push(as_address(index.rust_value(), map_index));
+            }
+            mapped_signal(CMP_INDEX, *signal_code, map_indexes)
+        }
+    };
+    let (memory, position) = match address_type {
+        AddressType::Variable => (LVAR.to_string(), index),
+        AddressType::Signal => (
+            SIGNAL_VALUES.to_string(),
+            format!("{} + {}", SIGNAL_START, index),
+        ),
+        AddressType::SubcmpSignal { .. } => (
+            SIGNAL_VALUES.to_string(),
+            format!("{} + {}", subcomponent_signal_start(CMP_INDEX), index),
+        ),
+    };
+    (prologue, memory, position)
+}
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -156,3 +157,30 @@ impl WriteC for LogBucket {
         (log_c, "".to_string())
     }
 }
+
+impl WriteRust for LogBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let mut arguments = vec![];
+        for logarg in &self.argsprint {
+            if let LogBucketArg::LogExp(exp) = logarg {
+                let (_, value) = exp.produce_rust(producer);
+                arguments.push(method_call(as_field(exp.rust_value(), value), "to_string"));
+            } else if let LogBucketArg::LogStr(string_id) = logarg {
+                let string_value = &producer.get_string_table()[*string_id];
+                arguments.push(format!("String::from({})", rust_string(string_value)));
+            } else {
+                unreachable!();
+            }
+        }
+        let log_rust = if arguments.is_empty() {
+            "println!();".to_string()
+        } else {
+            format!(
+                "println!(\"{{}}\", [{}].join(\" \"));",
+                arguments.join(", ")
+            )
+        };
+        (vec![log_rust], "".to_string())
+    }
+}
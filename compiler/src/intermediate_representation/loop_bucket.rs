@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -94,3 +95,22 @@ impl WriteC for LoopBucket {
         (loop_c, "".to_string())
     }
 }
+
+impl WriteRust for LoopBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let (_, continue_code) = self.continue_condition.produce_rust(producer);
+        let mut body = vec![];
+        for instr in &self.body {
+            let (mut instr_code, _) = instr.produce_rust(producer);
+            body.append(&mut instr_code);
+        }
+        let mut loop_rust = vec![format!(
+            "while {} {{",
+            is_true(self.continue_condition.rust_value(), continue_code)
+        )];
+        loop_rust.append(&mut indent(body));
+        loop_rust.push("}".to_string());
+        (loop_rust, "".to_string())
+    }
+}
@@ -15,4 +15,7 @@ mod value_bucket;
 
 pub mod ir_interface;
 pub mod translate;
+pub mod visitor;
+#[cfg(test)]
+pub mod test_code;
 pub use ir_interface::{Instruction, InstructionList, InstructionPointer};
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -124,3 +125,12 @@ impl WriteC for ReturnBucket {
         (instructions, "".to_string())
     }
 }
+
+impl WriteRust for ReturnBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        let (_, src) = self.value.produce_rust(producer);
+        let value = as_fields(self.value.rust_value(), src);
+        (vec![format!("return Ok({});", value)], "".to_string())
+    }
+}
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -183,6 +184,14 @@ impl WriteWasm for StoreBucket {
                 }
             }
         }
+        let trace = producer.trace_flag
+            && matches!(
+                self.dest_address_type,
+                AddressType::Signal | AddressType::SubcmpSignal { .. }
+            );
+        if trace {
+            instructions.push(tee_local(producer.get_trace_signal_tag()));
+        }
         if producer.needs_comments() {
             instructions.push(";; getting src".to_string());
         }
@@ -222,6 +231,11 @@ impl WriteWasm for StoreBucket {
             instructions.push(add_end());
             instructions.push(add_end());
         }
+        if trace {
+            instructions.push(get_local(producer.get_trace_signal_tag()));
+            instructions.push(set_constant(&self.context.size.to_string()));
+            instructions.push(call("$traceSignals"));
+        }
         match &self.dest_address_type {
             AddressType::SubcmpSignal { .. } => {
                 // if subcomponent input check if run needed
@@ -419,12 +433,28 @@ impl WriteC for StoreBucket {
         prologue.append(&mut src_prologue);
         prologue.push("// end load src".to_string());
         std::mem::drop(src_prologue);
+        let trace = producer.trace_flag
+            && matches!(
+                self.dest_address_type,
+                AddressType::Signal | AddressType::SubcmpSignal { .. }
+            );
+        let trace_signals = format!(
+            "{}->traceSignals({} - {}->{}, {});",
+            CIRCOM_CALC_WIT,
+            aux_dest,
+            CIRCOM_CALC_WIT,
+            SIGNAL_VALUES,
+            self.context.size
+        );
         if self.context.size > 1 {
             let copy_arguments = vec![aux_dest, src, self.context.size.to_string()];
             prologue.push(format!(
                 "{};",
                 build_call("Fr_copyn".to_string(), copy_arguments)
             ));
+            if trace {
+                prologue.push(trace_signals.clone());
+            }
             if let AddressType::Signal = &self.dest_address_type {
                 if parallel.unwrap() && self.dest_is_output {
                     prologue.push("{".to_string());
@@ -467,6 +497,9 @@ impl WriteC for StoreBucket {
                 "{};",
                 build_call("Fr_copy".to_string(), copy_arguments)
             ));
+            if trace {
+                prologue.push(trace_signals);
+            }
             if let AddressType::Signal = &self.dest_address_type {
                 if parallel.unwrap() && self.dest_is_output {
                     prologue.push(format!(
@@ -694,3 +727,138 @@ impl WriteC for StoreBucket {
         (prologue, "".to_string())
     }
 }
+
+// Rust statements that store the value, whose type is given, in the destination, shared with
+// the calls whose result is stored directly
+pub fn produce_rust_store(
+    dest_address_type: &AddressType,
+    dest: &LocationRule,
+    size: usize,
+    value: (RustValue, String),
+    producer: &RustProducer,
+) -> Vec<String> {
+    use super::location_rule::produce_rust_location;
+    use rust_code_generator::*;
+    let (value_type, value) = value;
+    let mut instructions = vec![];
+    // the value is computed first, it may call functions that use the memory
+    if size > 1 {
+        instructions.push(format!("let {} = {};", VALUE, as_fields(value_type, value)));
+    } else {
+        instructions.push(format!("let {} = {};", VALUE, as_field(value_type, value)));
+    }
+    let (mut prologue, memory, position) = produce_rust_location(dest_address_type, dest, producer);
+    instructions.append(&mut prologue);
+    instructions.push(format!("let {} = {};", DEST_INDEX, position));
+    if size > 1 {
+        instructions.push(format!(
+            "field::copy_n(&mut {}, {}, {}, {});",
+            memory, DEST_INDEX, VALUE, size
+        ));
+    } else {
+        instructions.push(format!("{}[{}] = {};", memory, DEST_INDEX, VALUE));
+    }
+    if producer.trace_flag && !matches!(dest_address_type, AddressType::Variable) {
+        instructions.push(format!(
+            "{}.trace_signals({}, {});",
+            CALC_WIT, DEST_INDEX, size
+        ));
+    }
+    if let AddressType::SubcmpSignal {
+        input_information: InputInformation::Input { status },
+        ..
+    } = dest_address_type
+    {
+        let status = match status {
+            StatusInput::Last => "Last",
+            StatusInput::NoLast => "NoLast",
+            StatusInput::Unknown => "Unknown",
+        };
+        instructions.push(format!(
+            "{}.set_inputs({}, {}, InputStatus::{})?;",
+            CALC_WIT, CMP_INDEX, size, status
+        ));
+    }
+    let mut store = vec!["{".to_string()];
+    store.append(&mut indent(instructions));
+    store.push("}".to_string());
+    store
+}
+
+impl WriteRust for StoreBucket {
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String) {
+        if self.context.size == 0 {
+            return (vec![], "".to_string());
+        }
+        let (_, src) = self.src.produce_rust(producer);
+        let store = produce_rust_store(
+            &self.dest_address_type,
+            &self.dest,
+            self.context.size,
+            (self.src.rust_value(), src),
+            producer,
+        );
+        (store, "".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(value: usize) -> InstructionPointer {
+        ValueBucket {
+            line: 0,
+            message_id: 0,
+            parse_as: ValueType::U32,
+            op_aux_no: 0,
+            value,
+        }
+        .allocate()
+    }
+
+    fn store(dest_address_type: AddressType) -> StoreBucket {
+        StoreBucket {
+            line: 0,
+            message_id: 0,
+            context: InstrContext { size: 1 },
+            dest_is_output: false,
+            dest_address_type,
+            dest: LocationRule::Indexed {
+                location: value(1),
+                template_header: None,
+            },
+            src: value(0),
+        }
+    }
+
+    #[test]
+    fn traced_stores_of_signals() {
+        for trace_flag in [false, true] {
+            let mut c_producer = CProducer::default();
+            let mut wasm_producer = WASMProducer::default();
+            let mut rust_producer = RustProducer::default();
+            c_producer.trace_flag = trace_flag;
+            wasm_producer.trace_flag = trace_flag;
+            rust_producer.trace_flag = trace_flag;
+            for (dest_address_type, is_signal) in
+                [(AddressType::Signal, true), (AddressType::Variable, false)]
+            {
+                let bucket = store(dest_address_type);
+                let (c, _) = bucket.produce_c(&c_producer, Some(false));
+                let wasm = bucket.produce_wasm(&wasm_producer);
+                let (rust, _) = bucket.produce_rust(&rust_producer);
+                let traced = trace_flag && is_signal;
+                assert_eq!(c.iter().any(|line| line.contains("traceSignals(")), traced);
+                assert_eq!(
+                    wasm.iter().any(|line| line.contains("$traceSignals")),
+                    traced
+                );
+                assert_eq!(
+                    rust.iter().any(|line| line.contains("trace_signals(")),
+                    traced
+                );
+            }
+        }
+    }
+}
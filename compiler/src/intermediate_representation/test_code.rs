@@ -0,0 +1,196 @@
+// Builders of small instruction lists for the tests of the passes over the IR. The instructions
+// have line 0 and message id 0, the positions are constant unless a location is given.
+use super::ir_interface::*;
+use super::translate::FieldTracker;
+use num_bigint_dig::BigInt;
+
+// The field of the tests and its constants
+pub struct TestField {
+    pub prime: BigInt,
+    pub field_tracker: FieldTracker,
+}
+
+impl Default for TestField {
+    fn default() -> TestField {
+        TestField {
+            prime: BigInt::from(65537),
+            field_tracker: FieldTracker::new(),
+        }
+    }
+}
+
+impl TestField {
+    pub fn field(&mut self, value: usize) -> InstructionPointer {
+        let value = self.field_tracker.insert(value.to_string());
+        value_bucket(ValueType::BigInt, value)
+    }
+
+    // The value of a field constant, None for any other instruction
+    pub fn value_of(&self, instruction: &Instruction) -> Option<usize> {
+        match instruction {
+            Instruction::Value(ValueBucket {
+                parse_as: ValueType::BigInt,
+                value,
+                ..
+            }) => self.field_tracker.get_constant(*value)?.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+fn value_bucket(parse_as: ValueType, value: usize) -> InstructionPointer {
+    ValueBucket {
+        line: 0,
+        message_id: 0,
+        parse_as,
+        op_aux_no: 0,
+        value,
+    }
+    .allocate()
+}
+
+fn indexed(location: InstructionPointer) -> LocationRule {
+    LocationRule::Indexed {
+        location,
+        template_header: None,
+    }
+}
+
+pub fn address(value: usize) -> InstructionPointer {
+    value_bucket(ValueType::U32, value)
+}
+
+pub fn load(address_type: AddressType, location: InstructionPointer) -> InstructionPointer {
+    LoadBucket {
+        line: 0,
+        message_id: 0,
+        address_type,
+        src: indexed(location),
+        context: InstrContext { size: 1 },
+    }
+    .allocate()
+}
+
+pub fn load_var(index: usize) -> InstructionPointer {
+    load(AddressType::Variable, address(index))
+}
+
+pub fn load_signal(index: usize) -> InstructionPointer {
+    load(AddressType::Signal, address(index))
+}
+
+pub fn store(
+    dest_address_type: AddressType,
+    location: InstructionPointer,
+    src: InstructionPointer,
+) -> InstructionPointer {
+    StoreBucket {
+        line: 0,
+        message_id: 0,
+        context: InstrContext { size: 1 },
+        dest_is_output: false,
+        dest_address_type,
+        dest: indexed(location),
+        src,
+    }
+    .allocate()
+}
+
+pub fn store_var(index: usize, src: InstructionPointer) -> InstructionPointer {
+    store(AddressType::Variable, address(index), src)
+}
+
+pub fn store_signal(index: usize, src: InstructionPointer) -> InstructionPointer {
+    store(AddressType::Signal, address(index), src)
+}
+
+pub fn compute(op: OperatorType, stack: Vec<InstructionPointer>) -> InstructionPointer {
+    ComputeBucket {
+        line: 0,
+        message_id: 0,
+        op,
+        op_aux_no: 0,
+        stack,
+    }
+    .allocate()
+}
+
+pub fn branch(
+    cond: InstructionPointer,
+    if_branch: InstructionList,
+    else_branch: InstructionList,
+) -> InstructionPointer {
+    BranchBucket {
+        line: 0,
+        message_id: 0,
+        cond,
+        if_branch,
+        else_branch,
+    }
+    .allocate()
+}
+
+pub fn while_loop(
+    continue_condition: InstructionPointer,
+    body: InstructionList,
+) -> InstructionPointer {
+    LoopBucket {
+        line: 0,
+        message_id: 0,
+        continue_condition,
+        body,
+    }
+    .allocate()
+}
+
+// A call whose result is used as an intermediate value
+pub fn call(symbol: &str, arguments: InstructionList) -> InstructionPointer {
+    CallBucket {
+        line: 0,
+        message_id: 0,
+        symbol: symbol.to_string(),
+        argument_types: arguments.iter().map(|_| InstrContext { size: 1 }).collect(),
+        arguments,
+        arena_size: 0,
+        return_info: ReturnType::Intermediate { op_aux_no: 0 },
+    }
+    .allocate()
+}
+
+pub fn ret(value: InstructionPointer) -> InstructionPointer {
+    ReturnBucket {
+        line: 0,
+        message_id: 0,
+        with_size: 1,
+        value,
+    }
+    .allocate()
+}
+
+// The position of a store or a load with a constant location
+pub fn position(instruction: &Instruction) -> Option<usize> {
+    let location = match instruction {
+        Instruction::Store(bucket) => &bucket.dest,
+        Instruction::Load(bucket) => &bucket.src,
+        _ => return None,
+    };
+    match location {
+        LocationRule::Indexed { location, .. } => match **location {
+            Instruction::Value(ValueBucket {
+                parse_as: ValueType::U32,
+                value,
+                ..
+            }) => Some(value),
+            _ => None,
+        },
+        LocationRule::Mapped { .. } => None,
+    }
+}
+
+// The value that a store writes
+pub fn src(instruction: &Instruction) -> &Instruction {
+    match instruction {
+        Instruction::Store(bucket) => &bucket.src,
+        _ => panic!("expected a store"),
+    }
+}
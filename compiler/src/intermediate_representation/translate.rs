@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::hir::very_concrete_program::*;
 use crate::intermediate_representation::log_bucket::LogBucketArg;
+use crate::ir_processing::OptimizationLevel;
 use constant_tracking::ConstantTracker;
 use num_bigint_dig::BigInt;
 use program_structure::ast::*;
@@ -1553,6 +1554,8 @@ pub struct CodeInfo<'a> {
     pub component_to_parallel: HashMap<String, ParallelClusters>,
     pub string_table: HashMap<String, usize>,
     pub signals_to_tags: BTreeMap<String, TagInfo>,
+    pub prime: &'a BigInt,
+    pub optimization_level: OptimizationLevel,
 }
 
 pub struct CodeOutput {
@@ -1594,6 +1597,12 @@ pub fn translate_code(body: Statement, code_info: CodeInfo) -> CodeOutput {
     ir_processing::build_inputs_info(&mut state.code);
 
     let mut code = ir_processing::reduce_intermediate_operations(state.code);
+    code = ir_processing::optimize(
+        code,
+        code_info.optimization_level,
+        code_info.prime,
+        &mut state.field_tracker,
+    );
     let expression_depth = ir_processing::build_auxiliary_stack(&mut code);
 
     CodeOutput {
@@ -1,6 +1,7 @@
 use super::ir_interface::*;
 use crate::translating_traits::*;
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 
 #[derive(Clone)]
@@ -85,3 +86,20 @@ impl WriteC for ValueBucket {
         }
     }
 }
+
+impl WriteRust for ValueBucket {
+    fn produce_rust(&self, _producer: &RustProducer) -> (Vec<String>, String) {
+        use rust_code_generator::*;
+        match self.parse_as {
+            ValueType::U32 => (vec![], self.value.to_string()),
+            ValueType::BigInt => (vec![], constant(self.value)),
+        }
+    }
+
+    fn rust_value(&self) -> RustValue {
+        match self.parse_as {
+            ValueType::U32 => RustValue::Address,
+            ValueType::BigInt => RustValue::Field,
+        }
+    }
+}
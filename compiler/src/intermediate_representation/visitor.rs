@@ -0,0 +1,452 @@
+// Traversals of the instructions. Every visit_ method walks the nested instructions by
+// default, so a pass only overrides the buckets it is interested in and calls the
+// corresponding walk_ function when it also wants to reach the instructions inside them.
+use super::ir_interface::*;
+
+pub trait Visitor<'ir> {
+    fn visit_instruction_list(&mut self, list: &'ir [InstructionPointer]) {
+        walk_instruction_list(self, list)
+    }
+    fn visit_instruction(&mut self, instruction: &'ir Instruction) {
+        walk_instruction(self, instruction)
+    }
+    fn visit_value_bucket(&mut self, _bucket: &'ir ValueBucket) {}
+    fn visit_load_bucket(&mut self, bucket: &'ir LoadBucket) {
+        walk_load_bucket(self, bucket)
+    }
+    fn visit_store_bucket(&mut self, bucket: &'ir StoreBucket) {
+        walk_store_bucket(self, bucket)
+    }
+    fn visit_compute_bucket(&mut self, bucket: &'ir ComputeBucket) {
+        walk_compute_bucket(self, bucket)
+    }
+    fn visit_call_bucket(&mut self, bucket: &'ir CallBucket) {
+        walk_call_bucket(self, bucket)
+    }
+    fn visit_branch_bucket(&mut self, bucket: &'ir BranchBucket) {
+        walk_branch_bucket(self, bucket)
+    }
+    fn visit_return_bucket(&mut self, bucket: &'ir ReturnBucket) {
+        walk_return_bucket(self, bucket)
+    }
+    fn visit_assert_bucket(&mut self, bucket: &'ir AssertBucket) {
+        walk_assert_bucket(self, bucket)
+    }
+    fn visit_log_bucket(&mut self, bucket: &'ir LogBucket) {
+        walk_log_bucket(self, bucket)
+    }
+    fn visit_loop_bucket(&mut self, bucket: &'ir LoopBucket) {
+        walk_loop_bucket(self, bucket)
+    }
+    fn visit_create_cmp_bucket(&mut self, bucket: &'ir CreateCmpBucket) {
+        walk_create_cmp_bucket(self, bucket)
+    }
+    fn visit_address_type(&mut self, address_type: &'ir AddressType) {
+        walk_address_type(self, address_type)
+    }
+    fn visit_location_rule(&mut self, location: &'ir LocationRule) {
+        walk_location_rule(self, location)
+    }
+}
+
+pub fn walk_instruction_list<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    list: &'ir [InstructionPointer],
+) {
+    for instruction in list {
+        visitor.visit_instruction(instruction);
+    }
+}
+
+pub fn walk_instruction<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    instruction: &'ir Instruction,
+) {
+    use Instruction::*;
+    match instruction {
+        Value(bucket) => visitor.visit_value_bucket(bucket),
+        Load(bucket) => visitor.visit_load_bucket(bucket),
+        Store(bucket) => visitor.visit_store_bucket(bucket),
+        Compute(bucket) => visitor.visit_compute_bucket(bucket),
+        Call(bucket) => visitor.visit_call_bucket(bucket),
+        Branch(bucket) => visitor.visit_branch_bucket(bucket),
+        Return(bucket) => visitor.visit_return_bucket(bucket),
+        Assert(bucket) => visitor.visit_assert_bucket(bucket),
+        Log(bucket) => visitor.visit_log_bucket(bucket),
+        Loop(bucket) => visitor.visit_loop_bucket(bucket),
+        CreateCmp(bucket) => visitor.visit_create_cmp_bucket(bucket),
+    }
+}
+
+pub fn walk_load_bucket<'ir, V: Visitor<'ir> + ?Sized>(visitor: &mut V, bucket: &'ir LoadBucket) {
+    visitor.visit_address_type(&bucket.address_type);
+    visitor.visit_location_rule(&bucket.src);
+}
+
+pub fn walk_store_bucket<'ir, V: Visitor<'ir> + ?Sized>(visitor: &mut V, bucket: &'ir StoreBucket) {
+    visitor.visit_address_type(&bucket.dest_address_type);
+    visitor.visit_location_rule(&bucket.dest);
+    visitor.visit_instruction(&bucket.src);
+}
+
+pub fn walk_compute_bucket<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    bucket: &'ir ComputeBucket,
+) {
+    visitor.visit_instruction_list(&bucket.stack);
+}
+
+pub fn walk_call_bucket<'ir, V: Visitor<'ir> + ?Sized>(visitor: &mut V, bucket: &'ir CallBucket) {
+    visitor.visit_instruction_list(&bucket.arguments);
+    if let ReturnType::Final(data) = &bucket.return_info {
+        visitor.visit_address_type(&data.dest_address_type);
+        visitor.visit_location_rule(&data.dest);
+    }
+}
+
+pub fn walk_branch_bucket<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    bucket: &'ir BranchBucket,
+) {
+    visitor.visit_instruction(&bucket.cond);
+    visitor.visit_instruction_list(&bucket.if_branch);
+    visitor.visit_instruction_list(&bucket.else_branch);
+}
+
+pub fn walk_return_bucket<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    bucket: &'ir ReturnBucket,
+) {
+    visitor.visit_instruction(&bucket.value);
+}
+
+pub fn walk_assert_bucket<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    bucket: &'ir AssertBucket,
+) {
+    visitor.visit_instruction(&bucket.evaluate);
+}
+
+pub fn walk_log_bucket<'ir, V: Visitor<'ir> + ?Sized>(visitor: &mut V, bucket: &'ir LogBucket) {
+    for argument in &bucket.argsprint {
+        if let LogBucketArg::LogExp(expression) = argument {
+            visitor.visit_instruction(expression);
+        }
+    }
+}
+
+pub fn walk_loop_bucket<'ir, V: Visitor<'ir> + ?Sized>(visitor: &mut V, bucket: &'ir LoopBucket) {
+    visitor.visit_instruction(&bucket.continue_condition);
+    visitor.visit_instruction_list(&bucket.body);
+}
+
+pub fn walk_create_cmp_bucket<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    bucket: &'ir CreateCmpBucket,
+) {
+    visitor.visit_instruction(&bucket.sub_cmp_id);
+}
+
+pub fn walk_address_type<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    address_type: &'ir AddressType,
+) {
+    if let AddressType::SubcmpSignal { cmp_address, .. } = address_type {
+        visitor.visit_instruction(cmp_address);
+    }
+}
+
+pub fn walk_location_rule<'ir, V: Visitor<'ir> + ?Sized>(
+    visitor: &mut V,
+    location: &'ir LocationRule,
+) {
+    match location {
+        LocationRule::Indexed { location, .. } => visitor.visit_instruction(location),
+        LocationRule::Mapped { indexes, .. } => visitor.visit_instruction_list(indexes),
+    }
+}
+
+// Same traversal as Visitor, but the instructions can be modified or replaced
+pub trait VisitorMut {
+    fn visit_instruction_list_mut(&mut self, list: &mut InstructionList) {
+        walk_instruction_list_mut(self, list)
+    }
+    fn visit_instruction_mut(&mut self, instruction: &mut Instruction) {
+        walk_instruction_mut(self, instruction)
+    }
+    fn visit_value_bucket_mut(&mut self, _bucket: &mut ValueBucket) {}
+    fn visit_load_bucket_mut(&mut self, bucket: &mut LoadBucket) {
+        walk_load_bucket_mut(self, bucket)
+    }
+    fn visit_store_bucket_mut(&mut self, bucket: &mut StoreBucket) {
+        walk_store_bucket_mut(self, bucket)
+    }
+    fn visit_compute_bucket_mut(&mut self, bucket: &mut ComputeBucket) {
+        walk_compute_bucket_mut(self, bucket)
+    }
+    fn visit_call_bucket_mut(&mut self, bucket: &mut CallBucket) {
+        walk_call_bucket_mut(self, bucket)
+    }
+    fn visit_branch_bucket_mut(&mut self, bucket: &mut BranchBucket) {
+        walk_branch_bucket_mut(self, bucket)
+    }
+    fn visit_return_bucket_mut(&mut self, bucket: &mut ReturnBucket) {
+        walk_return_bucket_mut(self, bucket)
+    }
+    fn visit_assert_bucket_mut(&mut self, bucket: &mut AssertBucket) {
+        walk_assert_bucket_mut(self, bucket)
+    }
+    fn visit_log_bucket_mut(&mut self, bucket: &mut LogBucket) {
+        walk_log_bucket_mut(self, bucket)
+    }
+    fn visit_loop_bucket_mut(&mut self, bucket: &mut LoopBucket) {
+        walk_loop_bucket_mut(self, bucket)
+    }
+    fn visit_create_cmp_bucket_mut(&mut self, bucket: &mut CreateCmpBucket) {
+        walk_create_cmp_bucket_mut(self, bucket)
+    }
+    fn visit_address_type_mut(&mut self, address_type: &mut AddressType) {
+        walk_address_type_mut(self, address_type)
+    }
+    fn visit_location_rule_mut(&mut self, location: &mut LocationRule) {
+        walk_location_rule_mut(self, location)
+    }
+}
+
+pub fn walk_instruction_list_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    list: &mut InstructionList,
+) {
+    for instruction in list {
+        visitor.visit_instruction_mut(instruction);
+    }
+}
+
+pub fn walk_instruction_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    instruction: &mut Instruction,
+) {
+    use Instruction::*;
+    match instruction {
+        Value(bucket) => visitor.visit_value_bucket_mut(bucket),
+        Load(bucket) => visitor.visit_load_bucket_mut(bucket),
+        Store(bucket) => visitor.visit_store_bucket_mut(bucket),
+        Compute(bucket) => visitor.visit_compute_bucket_mut(bucket),
+        Call(bucket) => visitor.visit_call_bucket_mut(bucket),
+        Branch(bucket) => visitor.visit_branch_bucket_mut(bucket),
+        Return(bucket) => visitor.visit_return_bucket_mut(bucket),
+        Assert(bucket) => visitor.visit_assert_bucket_mut(bucket),
+        Log(bucket) => visitor.visit_log_bucket_mut(bucket),
+        Loop(bucket) => visitor.visit_loop_bucket_mut(bucket),
+        CreateCmp(bucket) => visitor.visit_create_cmp_bucket_mut(bucket),
+    }
+}
+
+pub fn walk_load_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut LoadBucket) {
+    visitor.visit_address_type_mut(&mut bucket.address_type);
+    visitor.visit_location_rule_mut(&mut bucket.src);
+}
+
+pub fn walk_store_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut StoreBucket) {
+    visitor.visit_address_type_mut(&mut bucket.dest_address_type);
+    visitor.visit_location_rule_mut(&mut bucket.dest);
+    visitor.visit_instruction_mut(&mut bucket.src);
+}
+
+pub fn walk_compute_bucket_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    bucket: &mut ComputeBucket,
+) {
+    visitor.visit_instruction_list_mut(&mut bucket.stack);
+}
+
+pub fn walk_call_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut CallBucket) {
+    visitor.visit_instruction_list_mut(&mut bucket.arguments);
+    if let ReturnType::Final(data) = &mut bucket.return_info {
+        visitor.visit_address_type_mut(&mut data.dest_address_type);
+        visitor.visit_location_rule_mut(&mut data.dest);
+    }
+}
+
+pub fn walk_branch_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut BranchBucket) {
+    visitor.visit_instruction_mut(&mut bucket.cond);
+    visitor.visit_instruction_list_mut(&mut bucket.if_branch);
+    visitor.visit_instruction_list_mut(&mut bucket.else_branch);
+}
+
+pub fn walk_return_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut ReturnBucket) {
+    visitor.visit_instruction_mut(&mut bucket.value);
+}
+
+pub fn walk_assert_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut AssertBucket) {
+    visitor.visit_instruction_mut(&mut bucket.evaluate);
+}
+
+pub fn walk_log_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut LogBucket) {
+    for argument in &mut bucket.argsprint {
+        if let LogBucketArg::LogExp(expression) = argument {
+            visitor.visit_instruction_mut(expression);
+        }
+    }
+}
+
+pub fn walk_loop_bucket_mut<V: VisitorMut + ?Sized>(visitor: &mut V, bucket: &mut LoopBucket) {
+    visitor.visit_instruction_mut(&mut bucket.continue_condition);
+    visitor.visit_instruction_list_mut(&mut bucket.body);
+}
+
+pub fn walk_create_cmp_bucket_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    bucket: &mut CreateCmpBucket,
+) {
+    visitor.visit_instruction_mut(&mut bucket.sub_cmp_id);
+}
+
+pub fn walk_address_type_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    address_type: &mut AddressType,
+) {
+    if let AddressType::SubcmpSignal { cmp_address, .. } = address_type {
+        visitor.visit_instruction_mut(cmp_address);
+    }
+}
+
+pub fn walk_location_rule_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    location: &mut LocationRule,
+) {
+    match location {
+        LocationRule::Indexed { location, .. } => visitor.visit_instruction_mut(location),
+        LocationRule::Mapped { indexes, .. } => visitor.visit_instruction_list_mut(indexes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::test_code::*;
+    use OperatorType::*;
+
+    // Positions of the variables that are read, in the order of the traversal
+    #[derive(Default)]
+    struct VariableReads {
+        skip_loops: bool,
+        positions: Vec<usize>,
+    }
+
+    impl<'ir> Visitor<'ir> for VariableReads {
+        fn visit_load_bucket(&mut self, bucket: &'ir LoadBucket) {
+            if let AddressType::Variable = bucket.address_type {
+                self.positions.extend(position_of(&bucket.src));
+            }
+            walk_load_bucket(self, bucket);
+        }
+        fn visit_loop_bucket(&mut self, bucket: &'ir LoopBucket) {
+            if !self.skip_loops {
+                walk_loop_bucket(self, bucket);
+            }
+        }
+    }
+
+    // Moves the variables with a constant position by offset
+    struct ShiftVariables {
+        offset: usize,
+    }
+
+    impl ShiftVariables {
+        fn shift(&self, location: &mut LocationRule) {
+            if let Some(index) = position_of(location) {
+                *location = LocationRule::Indexed {
+                    location: address(index + self.offset),
+                    template_header: None,
+                };
+            }
+        }
+    }
+
+    impl VisitorMut for ShiftVariables {
+        fn visit_load_bucket_mut(&mut self, bucket: &mut LoadBucket) {
+            walk_load_bucket_mut(self, bucket);
+            if let AddressType::Variable = bucket.address_type {
+                self.shift(&mut bucket.src);
+            }
+        }
+        fn visit_store_bucket_mut(&mut self, bucket: &mut StoreBucket) {
+            walk_store_bucket_mut(self, bucket);
+            if let AddressType::Variable = bucket.dest_address_type {
+                self.shift(&mut bucket.dest);
+            }
+        }
+    }
+
+    fn position_of(location: &LocationRule) -> Option<usize> {
+        match location {
+            LocationRule::Indexed { location, .. } => match **location {
+                Instruction::Value(ValueBucket {
+                    parse_as: ValueType::U32,
+                    value,
+                    ..
+                }) => Some(value),
+                _ => None,
+            },
+            LocationRule::Mapped { .. } => None,
+        }
+    }
+
+    // if (signal 0) {
+    //     while (var 0 < 3) { signal[var 3] = f(var 1); var 4 = 0; }
+    // } else {
+    //     return var 2;
+    // }
+    fn nested_code(test: &mut TestField) -> InstructionList {
+        vec![branch(
+            load_signal(0),
+            vec![while_loop(
+                compute(Lesser, vec![load_var(0), test.field(3)]),
+                vec![
+                    store(
+                        AddressType::Signal,
+                        compute(ToAddress, vec![load_var(3)]),
+                        call("f", vec![load_var(1)]),
+                    ),
+                    store_var(4, test.field(0)),
+                ],
+            )],
+            vec![ret(load_var(2))],
+        )]
+    }
+
+    fn variable_reads(code: &InstructionList, skip_loops: bool) -> Vec<usize> {
+        let mut reads = VariableReads {
+            skip_loops,
+            ..Default::default()
+        };
+        reads.visit_instruction_list(code);
+        reads.positions
+    }
+
+    #[test]
+    fn visitor_walks_the_nested_instructions() {
+        let mut test = TestField::default();
+        let code = nested_code(&mut test);
+        assert_eq!(variable_reads(&code, false), vec![0, 3, 1, 2]);
+        // the instructions inside a bucket are only visited if the bucket is walked
+        assert_eq!(variable_reads(&code, true), vec![2]);
+    }
+
+    #[test]
+    fn visitor_mut_rewrites_the_nested_instructions() {
+        let mut test = TestField::default();
+        let mut code = nested_code(&mut test);
+        ShiftVariables { offset: 10 }.visit_instruction_list_mut(&mut code);
+        assert_eq!(variable_reads(&code, false), vec![10, 13, 11, 12]);
+        let store = match &*code[0] {
+            Instruction::Branch(branch) => match &*branch.if_branch[0] {
+                Instruction::Loop(bucket) => &bucket.body[1],
+                _ => panic!("expected a loop"),
+            },
+            _ => panic!("expected a branch"),
+        };
+        assert_eq!(position(store), Some(14));
+    }
+}
@@ -0,0 +1,240 @@
+use super::fold_constants::constant_location;
+use crate::intermediate_representation::ir_interface::*;
+use crate::intermediate_representation::visitor::{walk_load_bucket, walk_store_bucket, Visitor};
+use std::collections::HashSet;
+
+// Variables that may be read after a point of the code
+#[derive(Clone, Default)]
+pub struct LiveVars {
+    all: bool,
+    positions: HashSet<usize>,
+}
+
+impl LiveVars {
+    fn is_live(&self, index: usize, size: usize) -> bool {
+        self.all || (index..index + size).any(|position| self.positions.contains(&position))
+    }
+
+    fn kill(&mut self, index: usize, size: usize) {
+        for position in index..index + size {
+            self.positions.remove(&position);
+        }
+    }
+
+    fn join(&mut self, other: LiveVars) {
+        self.all |= other.all;
+        self.positions.extend(other.positions);
+    }
+}
+
+// Removes the stores to variables that are never read afterwards. The list is traversed
+// backwards and live contains the variables read after it.
+pub fn eliminate_list(list: InstructionList, live: &mut LiveVars) -> InstructionList {
+    let mut kept = Vec::with_capacity(list.len());
+    for instruction in list.into_iter().rev() {
+        if let Some(instruction) = eliminate_instruction(*instruction, live) {
+            kept.push(instruction.allocate());
+        }
+    }
+    kept.reverse();
+    kept
+}
+
+fn eliminate_instruction(instruction: Instruction, live: &mut LiveVars) -> Option<Instruction> {
+    use Instruction::*;
+    match instruction {
+        Store(bucket) => {
+            if let (AddressType::Variable, Some(index)) =
+                (&bucket.dest_address_type, constant_location(&bucket.dest))
+            {
+                if !live.is_live(index, bucket.context.size) && !may_fail(&bucket.src) {
+                    return None;
+                }
+                live.kill(index, bucket.context.size);
+            }
+            walk_store_bucket(live, &bucket);
+            Some(bucket.into_instruction())
+        }
+        Branch(mut bucket) => {
+            let mut live_else = live.clone();
+            bucket.if_branch = eliminate_list(bucket.if_branch, live);
+            bucket.else_branch = eliminate_list(bucket.else_branch, &mut live_else);
+            live.join(live_else);
+            live.visit_instruction(&bucket.cond);
+            Some(bucket.into_instruction())
+        }
+        Loop(mut bucket) => {
+            // the values read anywhere in the loop may be read in the next iteration
+            live.visit_loop_bucket(&bucket);
+            bucket.body = eliminate_list(bucket.body, &mut live.clone());
+            Some(bucket.into_instruction())
+        }
+        Return(bucket) => {
+            *live = LiveVars::default();
+            live.visit_instruction(&bucket.value);
+            Some(bucket.into_instruction())
+        }
+        instruction => {
+            live.visit_instruction(&instruction);
+            Some(instruction)
+        }
+    }
+}
+
+// The stores of values whose computation can stop the witness generation are kept
+fn may_fail(instruction: &Instruction) -> bool {
+    use Instruction::*;
+    use OperatorType::*;
+    match instruction {
+        Call(_) => true,
+        Compute(bucket) => {
+            matches!(bucket.op, Div | IntDiv | Mod | ShiftL | ShiftR)
+                || bucket.stack.iter().any(|operand| may_fail(operand))
+        }
+        Load(bucket) => match &bucket.src {
+            LocationRule::Indexed { location, .. } => may_fail(location),
+            LocationRule::Mapped { indexes, .. } => indexes.iter().any(|index| may_fail(index)),
+        },
+        _ => false,
+    }
+}
+
+// Marks as live the variables read by the visited instructions
+impl<'ir> Visitor<'ir> for LiveVars {
+    fn visit_load_bucket(&mut self, bucket: &'ir LoadBucket) {
+        if let AddressType::Variable = bucket.address_type {
+            match constant_location(&bucket.src) {
+                Some(index) => self.positions.extend(index..index + bucket.context.size),
+                None => self.all = true,
+            }
+        }
+        walk_load_bucket(self, bucket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::test_code::*;
+    use OperatorType::*;
+
+    fn eliminate(list: InstructionList) -> InstructionList {
+        eliminate_list(list, &mut LiveVars::default())
+    }
+
+    fn positions(list: &InstructionList) -> Vec<Option<usize>> {
+        list.iter()
+            .map(|instruction| position(instruction))
+            .collect()
+    }
+
+    #[test]
+    fn overwritten_and_unread_stores_are_removed() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(1)),
+            store_var(1, test.field(2)),
+            store_var(0, test.field(3)),
+            store_signal(0, load_var(0)),
+            store_var(2, load_var(1)),
+        ];
+        let kept = eliminate(list);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(test.value_of(src(&kept[0])), Some(3));
+        assert!(matches!(&*kept[1], Instruction::Store(bucket)
+            if matches!(bucket.dest_address_type, AddressType::Signal)));
+    }
+
+    #[test]
+    fn stores_that_may_fail_are_kept() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, compute(Div, vec![test.field(1), load_signal(0)])),
+            store_var(1, compute(IntDiv, vec![test.field(1), load_signal(0)])),
+            store_var(2, compute(Add, vec![test.field(1), call("f", vec![])])),
+            store_var(
+                3,
+                load(
+                    AddressType::Variable,
+                    compute(
+                        ToAddress,
+                        vec![compute(Mod, vec![load_signal(0), load_signal(1)])],
+                    ),
+                ),
+            ),
+            store_var(4, compute(Mul, vec![test.field(1), load_signal(0)])),
+            store_signal(0, test.field(1)),
+        ];
+        let kept = eliminate(list);
+        assert_eq!(
+            positions(&kept),
+            vec![Some(0), Some(1), Some(2), Some(3), Some(0)]
+        );
+    }
+
+    #[test]
+    fn loads_from_unknown_positions_read_every_variable() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(1)),
+            store_var(1, test.field(2)),
+            store_signal(
+                0,
+                load(
+                    AddressType::Variable,
+                    compute(ToAddress, vec![load_signal(1)]),
+                ),
+            ),
+        ];
+        assert_eq!(eliminate(list).len(), 3);
+    }
+
+    #[test]
+    fn stores_read_in_a_branch_or_a_later_iteration_are_kept() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(1)),
+            store_var(1, test.field(1)),
+            branch(
+                load_signal(0),
+                vec![store_signal(1, load_var(0))],
+                vec![store_var(2, test.field(1))],
+            ),
+            while_loop(
+                compute(Lesser, vec![load_var(1), test.field(3)]),
+                vec![
+                    store_signal(2, load_var(3)),
+                    store_var(3, load_var(1)),
+                    store_var(1, compute(Add, vec![load_var(1), test.field(1)])),
+                    store_var(4, test.field(1)),
+                ],
+            ),
+        ];
+        let kept = eliminate(list);
+        assert_eq!(kept.len(), 4);
+        match &*kept[2] {
+            Instruction::Branch(bucket) => assert!(bucket.else_branch.is_empty()),
+            _ => panic!("expected a branch"),
+        }
+        match &*kept[3] {
+            Instruction::Loop(bucket) => {
+                assert_eq!(positions(&bucket.body), vec![Some(2), Some(3), Some(1)])
+            }
+            _ => panic!("expected a loop"),
+        }
+    }
+
+    #[test]
+    fn only_the_returned_variables_are_read_after_a_return() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(1)),
+            store_var(1, test.field(2)),
+            branch(load_var(2), vec![ret(load_var(1))], vec![]),
+            store_var(0, test.field(3)),
+            ret(load_var(0)),
+        ];
+        let kept = eliminate(list);
+        assert_eq!(positions(&kept), vec![Some(1), None, Some(0), None]);
+    }
+}
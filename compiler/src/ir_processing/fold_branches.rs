@@ -0,0 +1,115 @@
+use super::fold_constants::Folding;
+use crate::intermediate_representation::ir_interface::*;
+
+// Replaces the branches with a known condition by the code of the branch that is taken and
+// removes the loops whose condition is known to be false
+pub fn fold_list(list: InstructionList, folding: &Folding) -> InstructionList {
+    let mut folded = InstructionList::with_capacity(list.len());
+    for instruction in list {
+        match *instruction {
+            Instruction::Branch(mut bucket) => match folding.condition(&bucket.cond) {
+                Some(true) => folded.append(&mut fold_list(bucket.if_branch, folding)),
+                Some(false) => folded.append(&mut fold_list(bucket.else_branch, folding)),
+                None => {
+                    bucket.if_branch = fold_list(bucket.if_branch, folding);
+                    bucket.else_branch = fold_list(bucket.else_branch, folding);
+                    folded.push(bucket.allocate());
+                }
+            },
+            Instruction::Loop(mut bucket) => {
+                if folding.condition(&bucket.continue_condition) != Some(false) {
+                    bucket.body = fold_list(bucket.body, folding);
+                    folded.push(bucket.allocate());
+                }
+            }
+            instruction => folded.push(instruction.allocate()),
+        }
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::test_code::*;
+
+    fn fold(test: &mut TestField, list: InstructionList) -> InstructionList {
+        let folding = Folding {
+            prime: &test.prime,
+            field_tracker: &mut test.field_tracker,
+            unroll_loops: false,
+        };
+        fold_list(list, &folding)
+    }
+
+    #[test]
+    fn branches_with_known_conditions_are_replaced() {
+        let mut test = TestField::default();
+        let list = vec![
+            branch(
+                test.field(1),
+                vec![
+                    store_signal(0, load_signal(1)),
+                    store_signal(1, load_signal(1)),
+                ],
+                vec![store_signal(2, load_signal(1))],
+            ),
+            branch(
+                address(0),
+                vec![store_signal(3, load_signal(1))],
+                vec![branch(
+                    test.field(0),
+                    vec![store_signal(4, load_signal(1))],
+                    vec![store_signal(5, load_signal(1))],
+                )],
+            ),
+        ];
+        let folded = fold(&mut test, list);
+        let positions: Vec<_> = folded.iter().map(|store| position(store)).collect();
+        assert_eq!(positions, vec![Some(0), Some(1), Some(5)]);
+    }
+
+    #[test]
+    fn branches_with_unknown_conditions_are_kept() {
+        let mut test = TestField::default();
+        let list = vec![branch(
+            load_signal(0),
+            vec![branch(
+                test.field(2),
+                vec![store_signal(1, load_signal(0))],
+                vec![],
+            )],
+            vec![],
+        )];
+        let folded = fold(&mut test, list);
+        match &*folded[0] {
+            Instruction::Branch(bucket) => {
+                assert_eq!(bucket.if_branch.len(), 1);
+                assert_eq!(position(&bucket.if_branch[0]), Some(1));
+            }
+            _ => panic!("expected a branch"),
+        }
+    }
+
+    #[test]
+    fn loops_that_never_run_are_removed() {
+        let mut test = TestField::default();
+        let list = vec![
+            while_loop(test.field(0), vec![store_signal(0, load_signal(1))]),
+            while_loop(
+                load_var(0),
+                vec![branch(
+                    test.field(0),
+                    vec![],
+                    vec![store_signal(1, load_signal(1))],
+                )],
+            ),
+        ];
+        let folded = fold(&mut test, list);
+        assert_eq!(folded.len(), 1);
+        match &*folded[0] {
+            Instruction::Loop(bucket) => assert_eq!(position(&bucket.body[0]), Some(1)),
+            _ => panic!("expected a loop"),
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use super::unroll_loops;
+use crate::intermediate_representation::ir_interface::*;
+use crate::intermediate_representation::translate::FieldTracker;
+use circom_algebra::modular_arithmetic;
+use num_bigint_dig::BigInt;
+use num_traits::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+
+// Values of the variables known at a point of the code, by position in the var memory
+pub type KnownVars = HashMap<usize, BigInt>;
+
+pub enum Constant {
+    Field(BigInt),
+    Address(usize),
+}
+
+pub struct Folding<'a> {
+    pub prime: &'a BigInt,
+    pub field_tracker: &'a mut FieldTracker,
+    pub unroll_loops: bool,
+}
+
+impl<'a> Folding<'a> {
+    pub fn constant(&self, instruction: &Instruction) -> Option<Constant> {
+        if let Instruction::Value(bucket) = instruction {
+            match bucket.parse_as {
+                ValueType::U32 => Some(Constant::Address(bucket.value)),
+                ValueType::BigInt => {
+                    let value = self.field_tracker.get_constant(bucket.value)?;
+                    BigInt::parse_bytes(value.as_bytes(), 10).map(Constant::Field)
+                }
+            }
+        } else {
+            None
+        }
+    }
+
+    // The value of a condition if it is known
+    pub fn condition(&self, instruction: &Instruction) -> Option<bool> {
+        match self.constant(instruction)? {
+            Constant::Field(value) => Some(modular_arithmetic::as_bool(&value, self.prime)),
+            Constant::Address(value) => Some(value != 0),
+        }
+    }
+
+    fn field_value(&mut self, line: usize, message_id: usize, value: &BigInt) -> Instruction {
+        let value = self.field_tracker.insert(value.to_str_radix(10));
+        ValueBucket {
+            line,
+            message_id,
+            parse_as: ValueType::BigInt,
+            op_aux_no: 0,
+            value,
+        }
+        .into_instruction()
+    }
+
+    fn address_value(line: usize, message_id: usize, value: usize) -> Instruction {
+        ValueBucket {
+            line,
+            message_id,
+            parse_as: ValueType::U32,
+            op_aux_no: 0,
+            value,
+        }
+        .into_instruction()
+    }
+
+    pub fn fold_list(&mut self, list: InstructionList, known: &mut KnownVars) -> InstructionList {
+        let mut folded = InstructionList::with_capacity(list.len());
+        for instruction in list {
+            match *instruction {
+                Instruction::Loop(bucket) if self.unroll_loops => {
+                    match unroll_loops::unroll_loop(&bucket, known, self) {
+                        Some(mut unrolled) => folded.append(&mut unrolled),
+                        None => folded.push(self.fold_loop(bucket, known).allocate()),
+                    }
+                }
+                instruction => folded.push(self.fold_statement(instruction, known).allocate()),
+            }
+        }
+        folded
+    }
+
+    fn fold_statement(&mut self, instruction: Instruction, known: &mut KnownVars) -> Instruction {
+        use Instruction::*;
+        match instruction {
+            Store(bucket) => self.fold_store(bucket, known),
+            Call(bucket) => self.fold_call(bucket, known),
+            Branch(bucket) => self.fold_branch(bucket, known),
+            Loop(bucket) => self.fold_loop(bucket, known),
+            Return(mut bucket) => {
+                bucket.value = self.fold_expression(*bucket.value, known).allocate();
+                bucket.into_instruction()
+            }
+            Assert(mut bucket) => {
+                bucket.evaluate = self.fold_expression(*bucket.evaluate, known).allocate();
+                bucket.into_instruction()
+            }
+            Log(mut bucket) => {
+                let mut argsprint = Vec::with_capacity(bucket.argsprint.len());
+                for argument in bucket.argsprint {
+                    argsprint.push(match argument {
+                        LogBucketArg::LogExp(exp) => {
+                            LogBucketArg::LogExp(self.fold_expression(*exp, known).allocate())
+                        }
+                        LogBucketArg::LogStr(id) => LogBucketArg::LogStr(id),
+                    });
+                }
+                bucket.argsprint = argsprint;
+                bucket.into_instruction()
+            }
+            CreateCmp(mut bucket) => {
+                bucket.sub_cmp_id = self.fold_expression(*bucket.sub_cmp_id, known).allocate();
+                bucket.into_instruction()
+            }
+            instruction => self.fold_expression(instruction, known),
+        }
+    }
+
+    fn fold_store(&mut self, mut bucket: StoreBucket, known: &mut KnownVars) -> Instruction {
+        bucket.dest_address_type = self.fold_address_type(bucket.dest_address_type, known);
+        bucket.dest = self.fold_location(bucket.dest, known);
+        bucket.src = self.fold_expression(*bucket.src, known).allocate();
+        if let AddressType::Variable = bucket.dest_address_type {
+            match constant_location(&bucket.dest) {
+                Some(index) => {
+                    forget(known, index, bucket.context.size);
+                    if bucket.context.size == 1 {
+                        if let Some(Constant::Field(value)) = self.constant(&bucket.src) {
+                            known.insert(index, value);
+                        }
+                    }
+                }
+                None => known.clear(),
+            }
+        }
+        bucket.into_instruction()
+    }
+
+    fn fold_call(&mut self, mut bucket: CallBucket, known: &mut KnownVars) -> Instruction {
+        let mut arguments = InstructionList::with_capacity(bucket.arguments.len());
+        for argument in bucket.arguments {
+            arguments.push(self.fold_expression(*argument, known).allocate());
+        }
+        bucket.arguments = arguments;
+        if let ReturnType::Final(mut data) = bucket.return_info {
+            data.dest_address_type = self.fold_address_type(data.dest_address_type, known);
+            data.dest = self.fold_location(data.dest, known);
+            if let AddressType::Variable = data.dest_address_type {
+                match constant_location(&data.dest) {
+                    Some(index) => forget(known, index, data.context.size),
+                    None => known.clear(),
+                }
+            }
+            bucket.return_info = ReturnType::Final(data);
+        }
+        bucket.into_instruction()
+    }
+
+    fn fold_branch(&mut self, mut bucket: BranchBucket, known: &mut KnownVars) -> Instruction {
+        bucket.cond = self.fold_expression(*bucket.cond, known).allocate();
+        // the branch that is never taken is removed by fold_branches
+        match self.condition(&bucket.cond) {
+            Some(true) => bucket.if_branch = self.fold_list(bucket.if_branch, known),
+            Some(false) => bucket.else_branch = self.fold_list(bucket.else_branch, known),
+            None => {
+                let mut known_else = known.clone();
+                bucket.if_branch = self.fold_list(bucket.if_branch, known);
+                bucket.else_branch = self.fold_list(bucket.else_branch, &mut known_else);
+                known.retain(|index, value| known_else.get(index) == Some(value));
+            }
+        }
+        bucket.into_instruction()
+    }
+
+    pub fn fold_loop(&mut self, mut bucket: LoopBucket, known: &mut KnownVars) -> Instruction {
+        // only the variables that are not written in the loop keep their values in the body
+        match written_vars(&bucket.body) {
+            Some(written) => known.retain(|index, _| !written.contains(index)),
+            None => known.clear(),
+        }
+        bucket.continue_condition = self
+            .fold_expression(*bucket.continue_condition, known)
+            .allocate();
+        bucket.body = self.fold_list(bucket.body, &mut known.clone());
+        bucket.into_instruction()
+    }
+
+    pub fn fold_expression(&mut self, instruction: Instruction, known: &KnownVars) -> Instruction {
+        use Instruction::*;
+        match instruction {
+            Load(mut bucket) => {
+                bucket.address_type = self.fold_address_type(bucket.address_type, known);
+                bucket.src = self.fold_location(bucket.src, known);
+                if let (AddressType::Variable, Some(index)) =
+                    (&bucket.address_type, constant_location(&bucket.src))
+                {
+                    if bucket.context.size == 1 {
+                        if let Some(value) = known.get(&index) {
+                            let value = value.clone();
+                            return self.field_value(bucket.line, bucket.message_id, &value);
+                        }
+                    }
+                }
+                bucket.into_instruction()
+            }
+            Compute(mut bucket) => {
+                let mut stack = Vec::with_capacity(bucket.stack.len());
+                for operand in bucket.stack {
+                    stack.push(self.fold_expression(*operand, known).allocate());
+                }
+                bucket.stack = stack;
+                self.fold_compute(bucket)
+            }
+            Call(bucket) => self.fold_call(bucket, &mut known.clone()),
+            instruction => instruction,
+        }
+    }
+
+    fn fold_compute(&mut self, bucket: ComputeBucket) -> Instruction {
+        use OperatorType::*;
+        let mut operands = Vec::with_capacity(bucket.stack.len());
+        for operand in &bucket.stack {
+            match self.constant(operand) {
+                Some(constant) => operands.push(constant),
+                None => return bucket.into_instruction(),
+            }
+        }
+        let (line, message_id) = (bucket.line, bucket.message_id);
+        let field = self.prime;
+        let result = match (bucket.op, operands.as_slice()) {
+            (AddAddress, [Constant::Address(l), Constant::Address(r)]) => {
+                return Folding::address_value(line, message_id, l + r)
+            }
+            (MulAddress, [Constant::Address(l), Constant::Address(r)]) => {
+                return Folding::address_value(line, message_id, l * r)
+            }
+            (ToAddress, [Constant::Field(value)]) => match value.to_u32() {
+                Some(value) => return Folding::address_value(line, message_id, value as usize),
+                None => None,
+            },
+            (PrefixSub, [Constant::Field(value)]) => {
+                Some(modular_arithmetic::prefix_sub(value, field))
+            }
+            (BoolNot, [Constant::Field(value)]) => Some(modular_arithmetic::not(value, field)),
+            (op, [Constant::Field(left), Constant::Field(right)]) => match op {
+                Mul => Some(modular_arithmetic::mul(left, right, field)),
+                Div => modular_arithmetic::div(left, right, field).ok(),
+                Add => Some(modular_arithmetic::add(left, right, field)),
+                Sub => Some(modular_arithmetic::sub(left, right, field)),
+                Pow => Some(modular_arithmetic::pow(left, right, field)),
+                IntDiv => modular_arithmetic::idiv(left, right, field).ok(),
+                Mod => modular_arithmetic::mod_op(left, right, field).ok(),
+                ShiftL => modular_arithmetic::shift_l(left, right, field).ok(),
+                ShiftR => modular_arithmetic::shift_r(left, right, field).ok(),
+                LesserEq => Some(modular_arithmetic::lesser_eq(left, right, field)),
+                GreaterEq => Some(modular_arithmetic::greater_eq(left, right, field)),
+                Lesser => Some(modular_arithmetic::lesser(left, right, field)),
+                Greater => Some(modular_arithmetic::greater(left, right, field)),
+                Eq(1) => Some(modular_arithmetic::eq(left, right, field)),
+                NotEq => Some(modular_arithmetic::not_eq(left, right, field)),
+                BoolOr => Some(modular_arithmetic::bool_or(left, right, field)),
+                BoolAnd => Some(modular_arithmetic::bool_and(left, right, field)),
+                BitOr => Some(modular_arithmetic::bit_or(left, right, field)),
+                BitAnd => Some(modular_arithmetic::bit_and(left, right, field)),
+                BitXor => Some(modular_arithmetic::bit_xor(left, right, field)),
+                _ => None,
+            },
+            // the complement depends on the size of the field elements in the generated code,
+            // errors are left to be reported when the witness is computed
+            _ => None,
+        };
+        match result {
+            Some(value) => self.field_value(line, message_id, &value),
+            None => bucket.into_instruction(),
+        }
+    }
+
+    fn fold_address_type(&mut self, address_type: AddressType, known: &KnownVars) -> AddressType {
+        if let AddressType::SubcmpSignal {
+            cmp_address,
+            uniform_parallel_value,
+            is_output,
+            input_information,
+        } = address_type
+        {
+            AddressType::SubcmpSignal {
+                cmp_address: self.fold_expression(*cmp_address, known).allocate(),
+                uniform_parallel_value,
+                is_output,
+                input_information,
+            }
+        } else {
+            address_type
+        }
+    }
+
+    fn fold_location(&mut self, location: LocationRule, known: &KnownVars) -> LocationRule {
+        match location {
+            LocationRule::Indexed {
+                location,
+                template_header,
+            } => LocationRule::Indexed {
+                location: self.fold_expression(*location, known).allocate(),
+                template_header,
+            },
+            LocationRule::Mapped {
+                signal_code,
+                indexes,
+            } => {
+                let mut folded = Vec::with_capacity(indexes.len());
+                for index in indexes {
+                    folded.push(self.fold_expression(*index, known).allocate());
+                }
+                LocationRule::Mapped {
+                    signal_code,
+                    indexes: folded,
+                }
+            }
+        }
+    }
+}
+
+pub fn constant_location(location: &LocationRule) -> Option<usize> {
+    if let LocationRule::Indexed { location, .. } = location {
+        if let Instruction::Value(ValueBucket {
+            parse_as: ValueType::U32,
+            value,
+            ..
+        }) = **location
+        {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn forget(known: &mut KnownVars, index: usize, size: usize) {
+    for position in index..index + size {
+        known.remove(&position);
+    }
+}
+
+// Positions of the variables written in the code, None if some position is not known
+fn written_vars(list: &InstructionList) -> Option<HashSet<usize>> {
+    fn write(
+        written: &mut HashSet<usize>,
+        address_type: &AddressType,
+        location: &LocationRule,
+        size: usize,
+    ) -> Option<()> {
+        if let AddressType::Variable = address_type {
+            let index = constant_location(location)?;
+            written.extend(index..index + size);
+        }
+        Some(())
+    }
+
+    fn visit_list(list: &InstructionList, written: &mut HashSet<usize>) -> Option<()> {
+        for instruction in list {
+            match &**instruction {
+                Instruction::Store(bucket) => write(
+                    written,
+                    &bucket.dest_address_type,
+                    &bucket.dest,
+                    bucket.context.size,
+                )?,
+                Instruction::Call(CallBucket {
+                    return_info: ReturnType::Final(data),
+                    ..
+                }) => write(
+                    written,
+                    &data.dest_address_type,
+                    &data.dest,
+                    data.context.size,
+                )?,
+                Instruction::Branch(bucket) => {
+                    visit_list(&bucket.if_branch, written)?;
+                    visit_list(&bucket.else_branch, written)?;
+                }
+                Instruction::Loop(bucket) => visit_list(&bucket.body, written)?,
+                _ => {}
+            }
+        }
+        Some(())
+    }
+
+    let mut written = HashSet::new();
+    visit_list(list, &mut written)?;
+    Some(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::test_code::*;
+    use OperatorType::*;
+
+    fn fold(test: &mut TestField, list: InstructionList) -> InstructionList {
+        let mut folding = Folding {
+            prime: &test.prime,
+            field_tracker: &mut test.field_tracker,
+            unroll_loops: false,
+        };
+        folding.fold_list(list, &mut HashMap::new())
+    }
+
+    #[test]
+    fn known_variables_are_folded() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(3)),
+            store_var(1, compute(Mul, vec![load_var(0), test.field(65535)])),
+            store_signal(0, compute(Add, vec![load_var(1), load_var(0)])),
+            store_signal(1, compute(Add, vec![load_var(0), load_signal(0)])),
+            store_signal(2, compute(Div, vec![test.field(1), test.field(0)])),
+        ];
+        let folded = fold(&mut test, list);
+        assert_eq!(folded.len(), 5);
+        // 3 * -2 = -6 and -6 + 3 = -3 in the field of 65537
+        assert_eq!(test.value_of(src(&folded[1])), Some(65531));
+        assert_eq!(test.value_of(src(&folded[2])), Some(65534));
+        // the signals are not known and the division by zero is left to the witness calculator
+        match src(&folded[3]) {
+            Instruction::Compute(bucket) => {
+                assert_eq!(test.value_of(&bucket.stack[0]), Some(3));
+                assert!(matches!(*bucket.stack[1], Instruction::Load(_)));
+            }
+            _ => panic!("expected a compute"),
+        }
+        assert!(matches!(src(&folded[4]), Instruction::Compute(_)));
+    }
+
+    #[test]
+    fn stores_to_unknown_positions_forget_the_variables() {
+        let mut test = TestField::default();
+        let unknown = compute(ToAddress, vec![load_signal(0)]);
+        let list = vec![
+            store_var(0, test.field(3)),
+            store(AddressType::Variable, unknown, test.field(4)),
+            store_signal(1, load_var(0)),
+        ];
+        let folded = fold(&mut test, list);
+        assert_eq!(position(src(&folded[2])), Some(0));
+    }
+
+    #[test]
+    fn branches_keep_the_values_known_in_both_branches() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(1)),
+            store_var(1, test.field(1)),
+            store_var(2, test.field(1)),
+            branch(
+                load_signal(0),
+                vec![store_var(0, test.field(2)), store_var(2, test.field(5))],
+                vec![store_var(2, test.field(5))],
+            ),
+            store_signal(1, load_var(0)),
+            store_signal(2, load_var(1)),
+            store_signal(3, load_var(2)),
+        ];
+        let folded = fold(&mut test, list);
+        assert!(matches!(src(&folded[4]), Instruction::Load(_)));
+        assert_eq!(test.value_of(src(&folded[5])), Some(1));
+        assert_eq!(test.value_of(src(&folded[6])), Some(5));
+    }
+
+    #[test]
+    fn variables_written_in_loops_are_not_known() {
+        let mut test = TestField::default();
+        let list = vec![
+            store_var(0, test.field(0)),
+            store_var(1, test.field(5)),
+            while_loop(
+                compute(Lesser, vec![load_var(0), test.field(3)]),
+                vec![
+                    store_signal(0, compute(Add, vec![load_var(0), load_var(1)])),
+                    store_var(0, compute(Add, vec![load_var(0), test.field(1)])),
+                ],
+            ),
+            store_signal(1, load_var(1)),
+        ];
+        let folded = fold(&mut test, list);
+        let bucket = match &*folded[2] {
+            Instruction::Loop(bucket) => bucket,
+            _ => panic!("expected a loop"),
+        };
+        assert!(matches!(
+            *bucket.continue_condition,
+            Instruction::Compute(_)
+        ));
+        match src(&bucket.body[0]) {
+            Instruction::Compute(compute) => {
+                assert!(matches!(*compute.stack[0], Instruction::Load(_)));
+                assert_eq!(test.value_of(&compute.stack[1]), Some(5));
+            }
+            _ => panic!("expected a compute"),
+        }
+        assert_eq!(test.value_of(src(&folded[3])), Some(5));
+    }
+}
@@ -1,10 +1,72 @@
 mod build_inputs_info;
 mod build_stack;
+mod eliminate_dead_stores;
+mod fold_branches;
+mod fold_constants;
 mod reduce_stack;
 mod set_arena_size;
+mod unroll_loops;
 use crate::intermediate_representation::ir_interface::InstructionList;
+use crate::intermediate_representation::translate::FieldTracker;
+use crate::intermediate_representation::visitor::VisitorMut;
+use num_bigint_dig::BigInt;
 use std::collections::{HashMap, HashSet};
 
+// Optimization level of the code that computes the witness
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OptimizationLevel {
+    O0,
+    O1,
+    O2,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OptimizationPass {
+    // folds the operations over constants, replacing the variables with known values
+    FoldConstants,
+    // like FoldConstants, also unrolling the small loops with a known number of iterations
+    UnrollLoops,
+    FoldBranches,
+    EliminateDeadStores,
+}
+
+impl OptimizationLevel {
+    pub fn passes(&self) -> Vec<OptimizationPass> {
+        use OptimizationPass::*;
+        match self {
+            OptimizationLevel::O0 => vec![],
+            OptimizationLevel::O1 => vec![FoldConstants, FoldBranches, EliminateDeadStores],
+            OptimizationLevel::O2 => vec![UnrollLoops, FoldBranches, EliminateDeadStores],
+        }
+    }
+}
+
+// Runs the passes of the level in order. The constants created by the passes are added to
+// the field tracker.
+pub fn optimize(
+    mut code: InstructionList,
+    level: OptimizationLevel,
+    prime: &BigInt,
+    field_tracker: &mut FieldTracker,
+) -> InstructionList {
+    use OptimizationPass::*;
+    for pass in level.passes() {
+        let mut folding = fold_constants::Folding {
+            prime,
+            field_tracker,
+            unroll_loops: pass == UnrollLoops,
+        };
+        code = match pass {
+            FoldConstants | UnrollLoops => folding.fold_list(code, &mut HashMap::new()),
+            FoldBranches => fold_branches::fold_list(code, &folding),
+            EliminateDeadStores => {
+                eliminate_dead_stores::eliminate_list(code, &mut Default::default())
+            }
+        };
+    }
+    code
+}
+
 pub fn reduce_intermediate_operations(code: InstructionList) -> InstructionList {
     reduce_stack::reduce_list(code)
 }
@@ -17,7 +79,10 @@ pub fn set_arena_size_in_calls(
     code: &mut InstructionList,
     function_to_arena_size: &HashMap<String, usize>,
 ) {
-    set_arena_size::visit_list(code, function_to_arena_size);
+    let mut setter = set_arena_size::ArenaSizeSetter {
+        function_to_arena_size,
+    };
+    setter.visit_instruction_list_mut(code);
 }
 
 pub fn build_inputs_info(code: &mut InstructionList) {
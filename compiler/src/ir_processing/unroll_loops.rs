@@ -0,0 +1,173 @@
+use super::fold_constants::{Folding, KnownVars};
+use crate::intermediate_representation::ir_interface::*;
+
+// Loops are only unrolled if they do not run more times than this
+const MAX_ITERATIONS: usize = 32;
+// and if the unrolled code does not have more instructions than this
+const MAX_INSTRUCTIONS: usize = 1024;
+
+// Replaces the loop by the folded copies of its body when the condition is known in every
+// iteration. Returns None and leaves the known variables untouched otherwise.
+pub fn unroll_loop(
+    bucket: &LoopBucket,
+    known: &mut KnownVars,
+    folding: &mut Folding,
+) -> Option<InstructionList> {
+    if has_return(&bucket.body) {
+        return None;
+    }
+    let mut known_in_loop = known.clone();
+    let mut unrolled = InstructionList::new();
+    let mut iterations = 0;
+    loop {
+        let condition = (*bucket.continue_condition).clone();
+        let condition = folding.fold_expression(condition, &known_in_loop);
+        if !folding.condition(&condition)? {
+            break;
+        }
+        iterations += 1;
+        if iterations > MAX_ITERATIONS {
+            return None;
+        }
+        let mut body = folding.fold_list(bucket.body.clone(), &mut known_in_loop);
+        unrolled.append(&mut body);
+        if count_list(&unrolled) > MAX_INSTRUCTIONS {
+            return None;
+        }
+    }
+    *known = known_in_loop;
+    Some(unrolled)
+}
+
+fn has_return(list: &InstructionList) -> bool {
+    list.iter().any(|instruction| match &**instruction {
+        Instruction::Return(_) => true,
+        Instruction::Branch(bucket) => {
+            has_return(&bucket.if_branch) || has_return(&bucket.else_branch)
+        }
+        Instruction::Loop(bucket) => has_return(&bucket.body),
+        _ => false,
+    })
+}
+
+fn count_list(list: &InstructionList) -> usize {
+    list.iter()
+        .map(|instruction| count_instruction(instruction))
+        .sum()
+}
+
+fn count_instruction(instruction: &Instruction) -> usize {
+    use Instruction::*;
+    match instruction {
+        Branch(bucket) => 1 + count_list(&bucket.if_branch) + count_list(&bucket.else_branch),
+        Loop(bucket) => 1 + count_list(&bucket.body),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_representation::test_code::*;
+    use std::collections::HashMap;
+    use OperatorType::*;
+
+    fn unroll(test: &mut TestField, list: InstructionList) -> InstructionList {
+        let mut folding = Folding {
+            prime: &test.prime,
+            field_tracker: &mut test.field_tracker,
+            unroll_loops: true,
+        };
+        folding.fold_list(list, &mut HashMap::new())
+    }
+
+    // for (i = 0; i < iterations; i++) { body; signal[i] = i; }
+    fn counting_loop(
+        test: &mut TestField,
+        iterations: usize,
+        mut body: InstructionList,
+    ) -> InstructionList {
+        body.push(store(
+            AddressType::Signal,
+            compute(ToAddress, vec![load_var(0)]),
+            load_var(0),
+        ));
+        body.push(store_var(0, compute(Add, vec![load_var(0), test.field(1)])));
+        vec![
+            store_var(0, test.field(0)),
+            while_loop(
+                compute(Lesser, vec![load_var(0), test.field(iterations)]),
+                body,
+            ),
+            store_signal(16, load_var(0)),
+        ]
+    }
+
+    fn has_loop(list: &InstructionList) -> bool {
+        list.iter()
+            .any(|instruction| matches!(**instruction, Instruction::Loop(_)))
+    }
+
+    #[test]
+    fn loops_with_known_iterations_are_unrolled() {
+        let mut test = TestField::default();
+        let list = counting_loop(&mut test, 3, vec![]);
+        let unrolled = unroll(&mut test, list);
+        assert_eq!(unrolled.len(), 8);
+        assert!(!has_loop(&unrolled));
+        for i in 0..3 {
+            assert_eq!(position(&unrolled[1 + 2 * i]), Some(i));
+            assert_eq!(test.value_of(src(&unrolled[1 + 2 * i])), Some(i));
+        }
+        // the value of the counter after the loop is known
+        assert_eq!(test.value_of(src(&unrolled[7])), Some(3));
+    }
+
+    #[test]
+    fn loops_are_unrolled_up_to_max_iterations() {
+        let mut test = TestField::default();
+        let list = counting_loop(&mut test, MAX_ITERATIONS, vec![]);
+        let unrolled = unroll(&mut test, list);
+        assert!(!has_loop(&unrolled));
+        assert_eq!(unrolled.len(), 2 + 2 * MAX_ITERATIONS);
+
+        let list = counting_loop(&mut test, MAX_ITERATIONS + 1, vec![]);
+        let folded = unroll(&mut test, list);
+        assert!(has_loop(&folded));
+        // the counter is written in the loop
+        assert!(matches!(src(&folded[2]), Instruction::Load(_)));
+    }
+
+    #[test]
+    fn loops_are_unrolled_up_to_max_instructions() {
+        let mut test = TestField::default();
+        let iterations = 8;
+        let per_iteration = MAX_INSTRUCTIONS / iterations;
+        let body = |size| {
+            (0..size)
+                .map(|_| store_signal(20, load_signal(21)))
+                .collect()
+        };
+        let list = counting_loop(&mut test, iterations, body(per_iteration - 2));
+        assert!(!has_loop(&unroll(&mut test, list)));
+        let list = counting_loop(&mut test, iterations, body(per_iteration - 1));
+        assert!(has_loop(&unroll(&mut test, list)));
+    }
+
+    #[test]
+    fn loops_with_unknown_conditions_or_returns_are_kept() {
+        let mut test = TestField::default();
+        let list = vec![while_loop(
+            compute(Lesser, vec![load_var(0), load_signal(0)]),
+            vec![store_var(0, compute(Add, vec![load_var(0), test.field(1)]))],
+        )];
+        assert!(has_loop(&unroll(&mut test, list)));
+
+        let list = counting_loop(
+            &mut test,
+            3,
+            vec![branch(load_signal(0), vec![ret(load_var(0))], vec![])],
+        );
+        assert!(has_loop(&unroll(&mut test, list)));
+    }
+}
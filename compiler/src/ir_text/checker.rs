@@ -0,0 +1,305 @@
+use crate::circuit_design::function::FunctionCodeInfo;
+use crate::circuit_design::template::TemplateCodeInfo;
+use crate::intermediate_representation::ir_interface::*;
+use crate::intermediate_representation::visitor::{
+    walk_call_bucket, walk_create_cmp_bucket, walk_load_bucket, walk_store_bucket, Visitor,
+};
+use super::CircuitData;
+use std::collections::HashMap;
+
+// Checks of the code read from a file. The fields of the templates and the functions that
+// the rest of the generated code depends on must be the ones of the circuit, and the
+// addresses that are known before running the code must be inside the memory of the
+// template or the function. The other addresses are checked when the code runs.
+pub struct Code<'a> {
+    pub templates: &'a [TemplateCodeInfo],
+    pub functions: &'a [FunctionCodeInfo],
+}
+
+struct Memory {
+    vars: usize,
+    // None in the functions, which can not access signals
+    signals: Option<usize>,
+    components: usize,
+}
+
+struct AddressChecker<'a> {
+    code: &'a Code<'a>,
+    memory: Memory,
+    functions: HashMap<&'a str, &'a FunctionCodeInfo>,
+    error: Option<String>,
+}
+
+pub fn same_template_layout(old: &TemplateCodeInfo, template: &TemplateCodeInfo) -> Result<(), String> {
+    let fields = [
+        ("name", old.name == template.name),
+        ("is_parallel", old.is_parallel == template.is_parallel),
+        (
+            "is_parallel_component",
+            old.is_parallel_component == template.is_parallel_component,
+        ),
+        (
+            "is_not_parallel_component",
+            old.is_not_parallel_component == template.is_not_parallel_component,
+        ),
+        (
+            "has_parallel_sub_cmp",
+            old.has_parallel_sub_cmp == template.has_parallel_sub_cmp,
+        ),
+        ("inputs", old.number_of_inputs == template.number_of_inputs),
+        ("outputs", old.number_of_outputs == template.number_of_outputs),
+        (
+            "intermediates",
+            old.number_of_intermediates == template.number_of_intermediates,
+        ),
+        ("components", old.number_of_components == template.number_of_components),
+        ("signal_stack", old.signal_stack_depth == template.signal_stack_depth),
+    ];
+    check_fields(&template.header, &fields)
+}
+
+pub fn same_function_layout(old: &FunctionCodeInfo, function: &FunctionCodeInfo) -> Result<(), String> {
+    let same_params = old.params.len() == function.params.len()
+        && old
+            .params
+            .iter()
+            .zip(&function.params)
+            .all(|(old, new)| old.name == new.name && old.length == new.length);
+    let fields = [
+        ("name", old.name == function.name),
+        ("params", same_params),
+        ("returns", old.returns == function.returns),
+    ];
+    check_fields(&function.header, &fields)
+}
+
+pub fn check_template<'a>(code: &'a Code<'a>, template: &'a TemplateCodeInfo) -> Result<(), String> {
+    let memory = Memory {
+        vars: template.var_stack_depth,
+        signals: Some(number_of_signals(template)),
+        components: template.number_of_components,
+    };
+    check_body(code, memory, &template.header, &template.body)
+}
+
+pub fn check_function<'a>(code: &'a Code<'a>, function: &'a FunctionCodeInfo) -> Result<(), String> {
+    let params: usize = function.params.iter().map(|param| size(&param.length)).sum();
+    if params > function.max_number_of_vars {
+        return Result::Err(format!(
+            "{}: the parameters need {} variables, but vars is {}",
+            function.header, params, function.max_number_of_vars
+        ));
+    }
+    let memory = Memory {
+        vars: function.max_number_of_vars,
+        signals: None,
+        components: 0,
+    };
+    check_body(code, memory, &function.header, &function.body)
+}
+
+// The main component and its inputs must be inside the memory of the circuit
+pub fn check_circuit_data(data: &CircuitData, templates: &[TemplateCodeInfo]) -> Result<(), String> {
+    let main = match templates.iter().find(|template| template.header == data.main_header) {
+        Some(main) => main,
+        None => return Result::Err(format!("the main template {} is not in the IR", data.main_header)),
+    };
+    let fits = |start: usize, size: usize| matches!(start.checked_add(size), Some(end) if end <= data.number_of_signals);
+    if data.number_of_components == 0 || !fits(data.main_signal_offset, number_of_signals(main)) {
+        return Result::Err("the main component is not inside the memory of the circuit".to_string());
+    }
+    if let Some((name, _, _)) = data.main_inputs.iter().find(|(_, start, size)| !fits(*start, *size)) {
+        return Result::Err(format!("the input {} is not inside the memory of the circuit", name));
+    }
+    if data.witness.iter().any(|signal| *signal >= data.number_of_signals) {
+        return Result::Err("the witness has signals outside the memory of the circuit".to_string());
+    }
+    Result::Ok(())
+}
+
+fn check_fields(header: &str, fields: &[(&str, bool)]) -> Result<(), String> {
+    match fields.iter().find(|(_, same)| !same) {
+        Some((key, _)) => Result::Err(format!("{}: {} is not the one of the circuit", header, key)),
+        None => Result::Ok(()),
+    }
+}
+
+fn check_body<'a>(
+    code: &'a Code<'a>,
+    memory: Memory,
+    header: &str,
+    body: &'a [InstructionPointer],
+) -> Result<(), String> {
+    let mut checker = AddressChecker {
+        code,
+        memory,
+        functions: code
+            .functions
+            .iter()
+            .map(|function| (function.header.as_str(), function))
+            .collect(),
+        error: None,
+    };
+    checker.visit_instruction_list(body);
+    match checker.error {
+        Some(error) => Result::Err(format!("{}: {}", header, error)),
+        None => Result::Ok(()),
+    }
+}
+
+fn number_of_signals(template: &TemplateCodeInfo) -> usize {
+    template.number_of_inputs + template.number_of_outputs + template.number_of_intermediates
+}
+
+fn size(lengths: &[usize]) -> usize {
+    lengths.iter().product()
+}
+
+fn constant(instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::Value(value) if value.parse_as == ValueType::U32 => Some(value.value),
+        _ => None,
+    }
+}
+
+impl<'a> AddressChecker<'a> {
+    fn fail(&mut self, line: usize, message: String) {
+        if self.error.is_none() {
+            self.error = Some(format!("{} in line {}", message, line));
+        }
+    }
+
+    fn check_access(
+        &mut self,
+        address_type: &AddressType,
+        location: &LocationRule,
+        size: usize,
+        line: usize,
+    ) {
+        let (kind, limit) = match address_type {
+            AddressType::Variable => ("variable", Some(self.memory.vars)),
+            AddressType::Signal => match self.memory.signals {
+                Some(signals) => ("signal", Some(signals)),
+                None => return self.fail(line, "signal access in a function".to_string()),
+            },
+            AddressType::SubcmpSignal { cmp_address, .. } => {
+                let components = self.memory.components;
+                if let Some(cmp) = constant(cmp_address) {
+                    if cmp >= components {
+                        return self.fail(
+                            line,
+                            format!("component {} out of {} components", cmp, components),
+                        );
+                    }
+                }
+                // the signals of a subcomponent depend on the template created at runtime
+                ("subcomponent signal", None)
+            }
+        };
+        if let (LocationRule::Indexed { location, .. }, Some(limit)) = (location, limit) {
+            if let Some(index) = constant(location) {
+                if !matches!(index.checked_add(size), Some(end) if end <= limit) {
+                    self.fail(
+                        line,
+                        format!(
+                            "{} address {} of size {} out of {} {}s",
+                            kind, index, size, limit, kind
+                        ),
+                    );
+                }
+            }
+        } else if let (LocationRule::Mapped { .. }, Some(_)) = (location, limit) {
+            self.fail(line, format!("mapped {} address", kind));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for AddressChecker<'a> {
+    fn visit_load_bucket(&mut self, bucket: &'a LoadBucket) {
+        self.check_access(
+            &bucket.address_type,
+            &bucket.src,
+            bucket.context.size,
+            bucket.line,
+        );
+        walk_load_bucket(self, bucket)
+    }
+
+    fn visit_store_bucket(&mut self, bucket: &'a StoreBucket) {
+        self.check_access(
+            &bucket.dest_address_type,
+            &bucket.dest,
+            bucket.context.size,
+            bucket.line,
+        );
+        walk_store_bucket(self, bucket)
+    }
+
+    fn visit_call_bucket(&mut self, bucket: &'a CallBucket) {
+        let function = match self.functions.get(bucket.symbol.as_str()) {
+            Some(function) => *function,
+            None => return self.fail(bucket.line, format!("unknown function {}", bucket.symbol)),
+        };
+        let sizes: Vec<usize> = bucket.argument_types.iter().map(|arg| arg.size).collect();
+        let expected: Vec<usize> = function
+            .params
+            .iter()
+            .map(|param| size(&param.length))
+            .collect();
+        if bucket.arguments.len() != sizes.len() || sizes != expected {
+            return self.fail(
+                bucket.line,
+                format!(
+                    "the arguments of {} do not match its parameters",
+                    bucket.symbol
+                ),
+            );
+        }
+        if let ReturnType::Final(data) = &bucket.return_info {
+            if data.context.size != size(&function.returns) {
+                return self.fail(
+                    bucket.line,
+                    format!(
+                        "the size of the result of {} is not {}",
+                        bucket.symbol,
+                        size(&function.returns)
+                    ),
+                );
+            }
+            self.check_access(
+                &data.dest_address_type,
+                &data.dest,
+                data.context.size,
+                bucket.line,
+            );
+        }
+        walk_call_bucket(self, bucket)
+    }
+
+    fn visit_create_cmp_bucket(&mut self, bucket: &'a CreateCmpBucket) {
+        if bucket.template_id >= self.code.templates.len() {
+            return self.fail(
+                bucket.line,
+                format!("unknown template {}", bucket.template_id),
+            );
+        }
+        if self.memory.signals.is_none() {
+            return self.fail(bucket.line, "component created in a function".to_string());
+        }
+        if let Some(first) = constant(&bucket.sub_cmp_id) {
+            let components = self.memory.components;
+            if !matches!(first.checked_add(bucket.number_of_cmp), Some(end) if end <= components) {
+                return self.fail(
+                    bucket.line,
+                    format!(
+                        "components {} to {} out of {} components",
+                        first,
+                        first.saturating_add(bucket.number_of_cmp),
+                        components
+                    ),
+                );
+            }
+        }
+        walk_create_cmp_bucket(self, bucket)
+    }
+}
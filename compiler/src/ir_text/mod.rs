@@ -0,0 +1,553 @@
+// Textual format of the code of a circuit, written with --irout and read back with --irin.
+//
+// The file starts with (version 1), the prime of the circuit, (prime p), and the data of the
+// circuit, (circuit ...), with the main template, its signals and components, the inputs,
+// the signals of the witness and the traced names. Then it has a
+// (template ...) for every template and a (function ...) for every function of the circuit,
+// in the order of their ids. Every field is written as a list (key values...) and the fields
+// of a node always appear in the same order. The instructions are (value ...), (load ...),
+// (store ...), (compute ...), (call ...), (branch ...), (return ...), (assert ...), (log ...),
+// (loop ...) and (create_cmp ...), and all of them start with (at line message_id). The field
+// constants are written with their decimal value and the strings of the logs with their text,
+// so the ids of both tables do not appear in the file. A ; starts a comment until the end of
+// the line.
+//
+// The data that the compiler derives from the instructions is not written: the positions in
+// the stack of intermediate results, the depth of this stack and the arena size of the calls
+// are computed again when the file is read.
+mod checker;
+mod reader;
+mod sexpr;
+mod writer;
+
+use crate::circuit_design::circuit::Circuit;
+use crate::circuit_design::function::FunctionCodeInfo;
+use crate::circuit_design::template::TemplateCodeInfo;
+use crate::ir_processing;
+use checker::Code;
+use code_producers::c_elements::{CProducer, IODef, InputList, SignalList, TraceSignalNames};
+use num_bigint_dig::BigInt;
+use program_structure::constants::is_valid_prime;
+use reader::{error, Items, ReadResult, Tables};
+use sexpr::SExpr;
+use std::collections::{BTreeMap, HashMap};
+
+pub const IR_TEXT_VERSION: usize = 1;
+
+// Data of the circuit besides its code that the witness calculator needs, written in the
+// (circuit ...) node so that the witness can be computed from the file alone
+#[derive(PartialEq, Eq)]
+pub struct CircuitData {
+    pub main_header: String,
+    pub main_signal_offset: usize,
+    pub number_of_signals: usize,
+    pub number_of_components: usize,
+    pub main_inputs: InputList,
+    pub witness: SignalList,
+    // (code, offset, dimensions) of the inputs and outputs of every template
+    pub io_map: BTreeMap<usize, Vec<(usize, usize, Vec<usize>)>>,
+}
+
+impl CircuitData {
+    pub fn of(producer: &CProducer) -> CircuitData {
+        let io_map = producer
+            .get_io_map()
+            .iter()
+            .map(|(template_id, signals)| {
+                let signals = signals
+                    .iter()
+                    .map(|signal| (signal.code, signal.offset, signal.lengths.clone()))
+                    .collect();
+                (*template_id, signals)
+            })
+            .collect();
+        CircuitData {
+            main_header: producer.get_main_header().to_string(),
+            main_signal_offset: producer.get_main_signal_offset(),
+            number_of_signals: producer.get_total_number_of_signals(),
+            number_of_components: producer.get_number_of_components(),
+            main_inputs: producer.get_main_input_list().clone(),
+            witness: producer.get_witness_to_signal_list().clone(),
+            io_map,
+        }
+    }
+
+    fn set_in(self, producer: &mut CProducer) {
+        producer.main_header = self.main_header;
+        producer.main_signal_offset = self.main_signal_offset;
+        producer.total_number_of_signals = self.number_of_signals;
+        producer.number_of_components = self.number_of_components;
+        producer.number_of_main_inputs = self.main_inputs.iter().map(|(_, _, size)| size).sum();
+        producer.main_input_list = self.main_inputs;
+        producer.signals_in_witness = self.witness.len();
+        producer.witness_to_signal_list = self.witness;
+        producer.io_map = self
+            .io_map
+            .into_iter()
+            .map(|(template_id, signals)| {
+                let signals = signals
+                    .into_iter()
+                    .map(|(code, offset, lengths)| IODef {
+                        code,
+                        offset,
+                        lengths,
+                    })
+                    .collect();
+                (template_id, signals)
+            })
+            .collect();
+    }
+}
+
+pub fn write_code(circuit: &Circuit) -> String {
+    writer::write_circuit(circuit)
+}
+
+// Replaces the code of the circuit by the one of the text. The text must be written for the
+// same prime and circuit, and the templates and the functions must have the same headers and
+// layout as the ones of the circuit, since the rest of the generated code refers to them.
+pub fn read_code(text: &str, circuit: &mut Circuit) -> ReadResult<()> {
+    let trees = sexpr::parse(text)?;
+    let header = read_header(&trees)?;
+    if !matches!(header.prime, SExpr::Atom(atom, _) if *atom == circuit.c_producer.prime) {
+        return error(
+            header.prime,
+            &format!(
+                "the IR is written for another prime, the circuit uses {}",
+                circuit.c_producer.prime
+            ),
+        );
+    }
+    // the signals that are traced are the ones chosen when the circuit is compiled
+    if header.data != CircuitData::of(&circuit.c_producer) {
+        return error(
+            header.data_tree,
+            "the IR is written for another circuit, its signals or components are not the ones of the circuit",
+        );
+    }
+
+    let prime = BigInt::parse_bytes(circuit.c_producer.prime.as_bytes(), 10).unwrap();
+    let mut tables = Tables::new(
+        prime,
+        &circuit.c_producer.field_tracking,
+        circuit.c_producer.get_string_table(),
+    );
+    let (templates, functions) = read_templates_and_functions(header.code, &mut tables)?;
+    if templates.len() != circuit.templates.len() || functions.len() != circuit.functions.len() {
+        return Result::Err(format!(
+            "the IR has {} templates and {} functions, but the circuit has {} and {}",
+            templates.len(),
+            functions.len(),
+            circuit.templates.len(),
+            circuit.functions.len()
+        ));
+    }
+    for (old, (template, tree)) in circuit.templates.iter().zip(&templates) {
+        if old.id != template.id || old.header != template.header {
+            return error(tree, &format!("the circuit has no template {}", template.header));
+        }
+        checker::same_template_layout(old, template)?;
+    }
+    for (old, (function, tree)) in circuit.functions.iter().zip(&functions) {
+        if old.header != function.header {
+            return error(tree, &format!("the circuit has no function {}", function.header));
+        }
+        checker::same_function_layout(old, function)?;
+    }
+    let (templates, functions) = prepare_code(templates, functions)?;
+
+    for (id, template) in templates.into_iter().enumerate() {
+        *circuit.templates[id] = template;
+    }
+    for (id, function) in functions.into_iter().enumerate() {
+        *circuit.functions[id] = function;
+    }
+    let known_constants = circuit.c_producer.field_tracking.len();
+    for id in known_constants..tables.field_tracker.next_id() {
+        let constant = tables.field_tracker.get_constant(id).unwrap().clone();
+        circuit.wasm_producer.field_tracking.push(constant.clone());
+        circuit.c_producer.field_tracking.push(constant.clone());
+        circuit.rust_producer.field_tracking.push(constant);
+    }
+    circuit
+        .wasm_producer
+        .set_string_table(tables.string_table.clone());
+    circuit
+        .c_producer
+        .set_string_table(tables.string_table.clone());
+    circuit.rust_producer.set_string_table(tables.string_table);
+    Result::Ok(())
+}
+
+// Builds a circuit from the text alone. Only the witness calculator of the witness crate can
+// run it: the producers of the WebAssembly, C++ and Rust code do not have the data of the
+// circuit that the file does not contain.
+pub fn read_circuit(text: &str) -> ReadResult<Circuit> {
+    let trees = sexpr::parse(text)?;
+    let header = read_header(&trees)?;
+    let prime = match header.prime {
+        SExpr::Atom(atom, _) if is_valid_prime(atom) => BigInt::parse_bytes(atom.as_bytes(), 10),
+        _ => None,
+    };
+    let prime = match prime {
+        Some(prime) => prime,
+        None => return error(header.prime, "expected a prime in decimal"),
+    };
+    let mut tables = Tables::new(prime.clone(), &[], &[]);
+    let (templates, functions) = read_templates_and_functions(header.code, &mut tables)?;
+    for (id, (template, tree)) in templates.iter().enumerate() {
+        if template.id != id {
+            return error(tree, &format!("expected the template with id {}", id));
+        }
+    }
+    let (templates, functions) = prepare_code(templates, functions)?;
+    checker::check_circuit_data(&header.data, &templates)?;
+
+    let mut circuit = Circuit::default();
+    let producer = &mut circuit.c_producer;
+    producer.prime = prime.to_string();
+    producer.prime_str = prime.to_string();
+    header.data.set_in(producer);
+    producer.trace_flag = !header.trace_signal_names.is_empty();
+    producer.trace_signal_names = header.trace_signal_names;
+    producer.field_tracking = (0..tables.field_tracker.next_id())
+        .map(|id| tables.field_tracker.get_constant(id).unwrap().clone())
+        .collect();
+    producer.set_string_table(tables.string_table);
+    for template in templates {
+        circuit.add_template_code(template);
+    }
+    for function in functions {
+        circuit.add_function_code(function);
+    }
+    Result::Ok(circuit)
+}
+
+// The nodes before the templates and the functions
+struct Header<'a> {
+    prime: &'a SExpr,
+    data: CircuitData,
+    data_tree: &'a SExpr,
+    trace_signal_names: TraceSignalNames,
+    code: &'a [SExpr],
+}
+
+fn read_header(trees: &[SExpr]) -> ReadResult<Header<'_>> {
+    let version = match trees.first() {
+        Some(version) => version,
+        None => return Result::Err("empty IR file".to_string()),
+    };
+    let mut items = Items::new(version, "version")?;
+    let number = items.next("the version number")?;
+    if !matches!(number, SExpr::Atom(atom, _) if *atom == IR_TEXT_VERSION.to_string()) {
+        return error(number, "unsupported version of the IR format");
+    }
+    items.end()?;
+    let prime = match trees.get(1) {
+        Some(prime) => {
+            let mut items = Items::new(prime, "prime")?;
+            let value = items.next("the prime")?;
+            items.end()?;
+            value
+        }
+        None => return error(version, "missing (prime ...)"),
+    };
+    let data_tree = match trees.get(2) {
+        Some(data_tree) => data_tree,
+        None => return error(version, "missing (circuit ...)"),
+    };
+    let (data, trace_signal_names) = reader::read_circuit_data(data_tree)?;
+    Result::Ok(Header {
+        prime,
+        data,
+        data_tree,
+        trace_signal_names,
+        code: &trees[3..],
+    })
+}
+
+// The code read from the file, with the tree of every template and function
+type ReadCode<'a> = (Vec<(TemplateCodeInfo, &'a SExpr)>, Vec<(FunctionCodeInfo, &'a SExpr)>);
+
+fn read_templates_and_functions<'a>(
+    trees: &'a [SExpr],
+    tables: &mut Tables,
+) -> ReadResult<ReadCode<'a>> {
+    let mut templates = Vec::new();
+    let mut functions = Vec::new();
+    for tree in trees {
+        match reader::head(tree) {
+            Some("template") => templates.push((reader::read_template(tree, tables)?, tree)),
+            Some("function") => functions.push((reader::read_function(tree, tables)?, tree)),
+            _ => return error(tree, "expected (template ...) or (function ...)"),
+        }
+    }
+    Result::Ok((templates, functions))
+}
+
+// Checks the addresses of the code and computes the data derived from the instructions
+fn prepare_code(
+    templates: Vec<(TemplateCodeInfo, &SExpr)>,
+    functions: Vec<(FunctionCodeInfo, &SExpr)>,
+) -> ReadResult<(Vec<TemplateCodeInfo>, Vec<FunctionCodeInfo>)> {
+    let mut templates: Vec<_> = templates.into_iter().map(|(template, _)| template).collect();
+    let mut functions: Vec<_> = functions.into_iter().map(|(function, _)| function).collect();
+    let code = Code {
+        templates: &templates,
+        functions: &functions,
+    };
+    for template in &templates {
+        checker::check_template(&code, template)?;
+    }
+    for function in &functions {
+        checker::check_function(&code, function)?;
+    }
+
+    let function_to_arena_size: HashMap<String, usize> = functions
+        .iter()
+        .map(|function| (function.header.clone(), function.max_number_of_vars))
+        .collect();
+    for template in &mut templates {
+        template.expression_stack_depth = ir_processing::build_auxiliary_stack(&mut template.body);
+        ir_processing::set_arena_size_in_calls(&mut template.body, &function_to_arena_size);
+    }
+    for function in &mut functions {
+        function.max_number_of_ops_in_expression =
+            ir_processing::build_auxiliary_stack(&mut function.body);
+        ir_processing::set_arena_size_in_calls(&mut function.body, &function_to_arena_size);
+    }
+    Result::Ok((templates, functions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_design::function::FunctionCodeInfo;
+    use crate::circuit_design::template::TemplateCodeInfo;
+    use crate::hir::very_concrete_program::Param;
+    use crate::intermediate_representation::Instruction;
+
+    const SAMPLE: &str = r#"
+        (version 1)
+        (prime 21888242871839275222246405745257275088548364400416034343698204186575808495617)
+        (circuit (main "Main_0") (main_signal_offset 1) (signals 6) (components 2)
+          (main_inputs ("a" 2 1) ("b" 3 1)) (witness 0 1 2 3 4 5) (io_map (0 (0 1) (1 0)))
+          (trace_names))
+        (template (id 0) (header "Inner_1") (name "Inner") (is_parallel false)
+          (is_parallel_component false) (is_not_parallel_component false)
+          (has_parallel_sub_cmp false) (inputs 1) (outputs 1) (intermediates 0) (components 0)
+          (vars 0) (signal_stack 0)
+          (body
+            (store (at 3 0) (size 1) (output true) (address signal)
+              (dest (indexed (location (value (at 3 0) (u32 0))) (header none)))
+              (src (call (at 3 0) (symbol "double") (argument_sizes 1)
+                (arguments
+                  (load (at 3 0) (size 1) (address signal)
+                    (src (indexed (location (value (at 3 0) (u32 1))) (header none)))))
+                (return intermediate))))))
+        (template (id 1) (header "Main_0") (name "Main") (is_parallel false)
+          (is_parallel_component false) (is_not_parallel_component false)
+          (has_parallel_sub_cmp false) (inputs 2) (outputs 1) (intermediates 0) (components 1)
+          (vars 2) (signal_stack 0)
+          (body
+            (store (at 5 0) (size 1) (output false) (address variable)
+              (dest (indexed (location (value (at 5 0) (u32 1))) (header none)))
+              (src (value (at 5 0) (field -1))))
+            (create_cmp (at 6 0) (template_id 0) (cmp_unique_id 0) (symbol "Inner_1")
+              (sub_cmp_id (value (at 6 0) (u32 0))) (name "c") (defined_positions (0 false))
+              (mixed_not_uniform_parallel false) (uniform_parallel none) (dimensions)
+              (signal_offset 3) (signal_offset_jump 2) (component_offset 1)
+              (component_offset_jump 1) (number_of_cmp 1) (has_inputs true))
+            (loop (at 7 0)
+              (cond (compute (at 7 0) (op lesser)
+                (load (at 7 0) (size 1) (address variable)
+                  (src (indexed (location (value (at 7 0) (u32 1))) (header none))))
+                (value (at 7 0) (field 3))))
+              (body
+                (branch (at 8 0) (cond (value (at 8 0) (field 1)))
+                  (then (log (at 8 0) (args (str "i = ") (exp (value (at 8 0) (u32 2))))))
+                  (else))))
+            (store (at 9 0) (size 1) (output false)
+              (address (subcmp (cmp (value (at 9 0) (u32 0))) (uniform_parallel none)
+                (output false) (input last)))
+              (dest (mapped (signal_code 0) (indexes)))
+              (src (load (at 9 0) (size 1) (address signal)
+                (src (indexed (location (value (at 9 0) (u32 1))) (header none))))))
+            (assert (at 10 0)
+              (evaluate (compute (at 10 0) (op (eq 1))
+                (load (at 10 0) (size 1) (address signal)
+                  (src (indexed (location (value (at 10 0) (u32 0))) (header none))))
+                (value (at 10 0) (field 0)))))))
+        (function (header "double") (name "double") (params ("x")) (returns) (vars 1)
+          (body
+            (return (at 2 0) (size 1)
+              (value (compute (at 2 0) (op mul) (value (at 2 0) (field 2))
+                (load (at 2 0) (size 1) (address variable)
+                  (src (indexed (location (value (at 2 0) (u32 0))) (header none)))))))))
+    "#;
+
+    // The templates and the functions of SAMPLE without code
+    fn circuit_without_code() -> Circuit {
+        let mut circuit = Circuit::default();
+        let data = CircuitData {
+            main_header: "Main_0".to_string(),
+            main_signal_offset: 1,
+            number_of_signals: 6,
+            number_of_components: 2,
+            main_inputs: vec![("a".to_string(), 2, 1), ("b".to_string(), 3, 1)],
+            witness: (0..6).collect(),
+            io_map: BTreeMap::from([(0, vec![(0, 1, Vec::new()), (1, 0, Vec::new())])]),
+        };
+        data.set_in(&mut circuit.c_producer);
+        circuit.add_template_code(TemplateCodeInfo {
+            id: 0,
+            header: "Inner_1".to_string(),
+            name: "Inner".to_string(),
+            number_of_inputs: 1,
+            number_of_outputs: 1,
+            ..Default::default()
+        });
+        circuit.add_template_code(TemplateCodeInfo {
+            id: 1,
+            header: "Main_0".to_string(),
+            name: "Main".to_string(),
+            number_of_inputs: 2,
+            number_of_outputs: 1,
+            number_of_components: 1,
+            ..Default::default()
+        });
+        circuit.add_function_code(FunctionCodeInfo {
+            header: "double".to_string(),
+            name: "double".to_string(),
+            params: vec![Param {
+                name: "x".to_string(),
+                length: Vec::new(),
+            }],
+            ..Default::default()
+        });
+        circuit
+    }
+
+    fn read(text: &str) -> ReadResult<Circuit> {
+        let mut circuit = circuit_without_code();
+        read_code(text, &mut circuit)?;
+        Result::Ok(circuit)
+    }
+
+    #[test]
+    fn written_code_is_read_back() {
+        let circuit = read(SAMPLE).unwrap();
+        let text = write_code(&circuit);
+        assert_eq!(write_code(&read(&text).unwrap()), text);
+
+        let main = circuit.get_template(1);
+        assert_eq!(main.body.len(), 5);
+        assert_eq!(main.var_stack_depth, 2);
+        // the negative constant is read modulo the prime
+        assert!(text.contains(
+            "(field 21888242871839275222246405745257275088548364400416034343698204186575808495616)"
+        ));
+        assert!(text.contains("(str \"i = \")"));
+        // the data derived from the instructions is computed again
+        match &*circuit.get_template(0).body[0] {
+            Instruction::Store(store) => match &*store.src {
+                Instruction::Call(call) => assert_eq!(call.arena_size, 1),
+                _ => panic!("expected a call"),
+            },
+            _ => panic!("expected a store"),
+        }
+        assert!(main.expression_stack_depth > 0);
+    }
+
+    #[test]
+    fn circuit_is_read_from_the_text_alone() {
+        let text = write_code(&read(SAMPLE).unwrap());
+        let circuit = read_circuit(&text).unwrap();
+        assert_eq!(write_code(&circuit), text);
+        assert_eq!(circuit.c_producer.get_main_header(), "Main_0");
+        assert_eq!(circuit.c_producer.get_main_input_list()[1], ("b".to_string(), 3, 1));
+
+        let cases = [
+            ("(prime 2188", "(prime 2288", "expected a prime in decimal"),
+            ("(main \"Main_0\")", "(main \"Other_0\")", "template Other_0 is not in the IR"),
+            ("(\"b\" 3 1)", "(\"b\" 6 1)", "input b is not inside the memory"),
+            ("(witness 0 1", "(witness 6 1", "the witness has signals outside"),
+            ("(template (id 1)", "(template (id 2)", "expected the template with id 1"),
+        ];
+        for (from, to, message) in cases {
+            assert!(SAMPLE.contains(from), "{}", from);
+            match read_circuit(&SAMPLE.replacen(from, to, 1)) {
+                Result::Err(error) => assert!(error.contains(message), "{}: {}", message, error),
+                Result::Ok(_) => panic!("{} was accepted", to),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_code_is_rejected() {
+        assert_eq!(read("").err().unwrap(), "empty IR file");
+        let bn128 =
+            "(prime 21888242871839275222246405745257275088548364400416034343698204186575808495617)";
+        let goldilocks = "(prime 18446744069414584321)";
+        let cases = [
+            ("(version 1)", "(version 2)", "unsupported version"),
+            ("(returns)", "(returns", "unclosed parenthesis"),
+            ("(assert", "(assart", "unknown instruction assart"),
+            (
+                "(field 3)",
+                "(field x)",
+                "line 36: expected a decimal number",
+            ),
+            (bn128, goldilocks, "written for another prime"),
+            (
+                "(inputs 2)",
+                "(inputs 3)",
+                "Main_0: inputs is not the one of the circuit",
+            ),
+            (
+                "(params (\"x\"))",
+                "(params (\"y\"))",
+                "double: params is not the one",
+            ),
+            (
+                "(vars 2)",
+                "(vars 1)",
+                "Main_0: variable address 1 of size 1 out of 1",
+            ),
+            (
+                "(u32 1)))",
+                "(u32 3)))",
+                "Inner_1: signal address 3 of size 1 out of 2",
+            ),
+            (
+                "(symbol \"double\")",
+                "(symbol \"triple\")",
+                "unknown function triple",
+            ),
+            (
+                "(argument_sizes 1)",
+                "(argument_sizes 2)",
+                "arguments of double do not match",
+            ),
+            ("(template_id 0)", "(template_id 2)", "unknown template 2"),
+            (
+                "(components 1)",
+                "(components 0)",
+                "components is not the one",
+            ),
+            (
+                "(cmp (value (at 9 0) (u32 0)))",
+                "(cmp (value (at 9 0) (u32 1)))",
+                "component 1 out",
+            ),
+        ];
+        for (from, to, message) in cases {
+            assert!(SAMPLE.contains(from), "{}", from);
+            let text = SAMPLE.replacen(from, to, 1);
+            match read(&text) {
+                Result::Err(error) => assert!(error.contains(message), "{}: {}", message, error),
+                Result::Ok(_) => panic!("{} was accepted", to),
+            }
+        }
+    }
+}
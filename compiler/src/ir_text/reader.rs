@@ -0,0 +1,630 @@
+use super::sexpr::SExpr;
+use super::CircuitData;
+use crate::circuit_design::function::FunctionCodeInfo;
+use crate::circuit_design::template::TemplateCodeInfo;
+use crate::hir::very_concrete_program::Param;
+use crate::intermediate_representation::ir_interface::*;
+use crate::intermediate_representation::translate::FieldTracker;
+use code_producers::components::TraceSignalNames;
+use num_bigint_dig::BigInt;
+use std::collections::{BTreeMap, HashMap};
+
+pub type ReadResult<T> = Result<T, String>;
+
+pub fn error<T>(expr: &SExpr, message: &str) -> ReadResult<T> {
+    Result::Err(format!("line {}: {}", expr.line(), message))
+}
+
+// Constants and strings of the circuit. The ones found in the text that are not in the
+// circuit yet are added at the end.
+pub struct Tables {
+    pub prime: BigInt,
+    pub field_tracker: FieldTracker,
+    pub string_table: Vec<String>,
+    string_ids: HashMap<String, usize>,
+}
+
+impl Tables {
+    pub fn new(prime: BigInt, field_tracking: &[String], string_table: &[String]) -> Tables {
+        let mut field_tracker = FieldTracker::new();
+        for constant in field_tracking {
+            field_tracker.insert(constant.clone());
+        }
+        let string_ids = string_table
+            .iter()
+            .enumerate()
+            .map(|(id, string)| (string.clone(), id))
+            .collect();
+        Tables {
+            prime,
+            field_tracker,
+            string_table: string_table.to_vec(),
+            string_ids,
+        }
+    }
+
+    fn field_constant(&mut self, expr: &SExpr) -> ReadResult<usize> {
+        let value = match expr {
+            SExpr::Atom(atom, _) => BigInt::parse_bytes(atom.as_bytes(), 10),
+            _ => None,
+        };
+        match value {
+            Some(value) => {
+                let value = ((value % &self.prime) + &self.prime) % &self.prime;
+                Result::Ok(self.field_tracker.insert(value.to_string()))
+            }
+            None => error(expr, "expected a decimal number"),
+        }
+    }
+
+    fn string_id(&mut self, string: String) -> usize {
+        if let Some(id) = self.string_ids.get(&string) {
+            return *id;
+        }
+        let id = self.string_table.len();
+        self.string_table.push(string.clone());
+        self.string_ids.insert(string, id);
+        id
+    }
+}
+
+// The items of a list that starts with a given atom, read in order
+pub struct Items<'a> {
+    list: &'a SExpr,
+    items: std::slice::Iter<'a, SExpr>,
+}
+
+pub fn head(expr: &SExpr) -> Option<&str> {
+    match expr {
+        SExpr::List(items, _) => match items.first() {
+            Some(SExpr::Atom(atom, _)) => Some(atom),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl<'a> Items<'a> {
+    pub fn new(expr: &'a SExpr, key: &str) -> ReadResult<Items<'a>> {
+        match expr {
+            SExpr::List(items, _) if head(expr) == Some(key) => Result::Ok(Items {
+                list: expr,
+                items: items[1..].iter(),
+            }),
+            _ => error(expr, &format!("expected ({} ...)", key)),
+        }
+    }
+
+    pub fn next(&mut self, what: &str) -> ReadResult<&'a SExpr> {
+        match self.items.next() {
+            Some(item) => Result::Ok(item),
+            None => error(self.list, &format!("missing {}", what)),
+        }
+    }
+
+    pub fn end(mut self) -> ReadResult<()> {
+        match self.items.next() {
+            Some(item) => error(item, "unexpected element"),
+            None => Result::Ok(()),
+        }
+    }
+
+    pub fn field(&mut self, key: &str) -> ReadResult<Items<'a>> {
+        let item = self.next(&format!("({} ...)", key))?;
+        Items::new(item, key)
+    }
+
+    // The value of a field with a single element
+    fn single(&mut self, key: &str) -> ReadResult<&'a SExpr> {
+        let mut field = self.field(key)?;
+        let value = field.next(&format!("the value of {}", key))?;
+        field.end()?;
+        Result::Ok(value)
+    }
+
+    fn usize_field(&mut self, key: &str) -> ReadResult<usize> {
+        read_usize(self.single(key)?)
+    }
+
+    fn bool_field(&mut self, key: &str) -> ReadResult<bool> {
+        read_bool(self.single(key)?)
+    }
+
+    fn option_field(&mut self, key: &str) -> ReadResult<Option<bool>> {
+        let value = self.single(key)?;
+        match value {
+            SExpr::Atom(atom, _) if atom == "none" => Result::Ok(None),
+            _ => Result::Ok(Some(read_bool(value)?)),
+        }
+    }
+
+    fn string_field(&mut self, key: &str) -> ReadResult<String> {
+        read_string(self.single(key)?)
+    }
+
+    fn usize_list_field(&mut self, key: &str) -> ReadResult<Vec<usize>> {
+        self.field(key)?.items.map(read_usize).collect()
+    }
+
+    fn instruction_field(
+        &mut self,
+        key: &str,
+        tables: &mut Tables,
+    ) -> ReadResult<InstructionPointer> {
+        read_instruction(self.single(key)?, tables)
+    }
+
+    fn list_field(&mut self, key: &str, tables: &mut Tables) -> ReadResult<InstructionList> {
+        read_list(self.field(key)?, tables)
+    }
+}
+
+fn read_usize(expr: &SExpr) -> ReadResult<usize> {
+    match expr {
+        SExpr::Atom(atom, _) => match atom.parse::<usize>() {
+            Ok(value) => Result::Ok(value),
+            Err(_) => error(expr, "expected a natural number"),
+        },
+        _ => error(expr, "expected a natural number"),
+    }
+}
+
+fn read_bool(expr: &SExpr) -> ReadResult<bool> {
+    match expr {
+        SExpr::Atom(atom, _) if atom == "true" => Result::Ok(true),
+        SExpr::Atom(atom, _) if atom == "false" => Result::Ok(false),
+        _ => error(expr, "expected true or false"),
+    }
+}
+
+fn read_string(expr: &SExpr) -> ReadResult<String> {
+    match expr {
+        SExpr::Str(string, _) => Result::Ok(string.clone()),
+        _ => error(expr, "expected a string"),
+    }
+}
+
+fn read_at(items: &mut Items) -> ReadResult<(usize, usize)> {
+    let mut at = items.field("at")?;
+    let line = read_usize(at.next("the line")?)?;
+    let message_id = read_usize(at.next("the message id")?)?;
+    at.end()?;
+    Result::Ok((line, message_id))
+}
+
+fn read_list(items: Items, tables: &mut Tables) -> ReadResult<InstructionList> {
+    let mut list = InstructionList::new();
+    for item in items.items {
+        list.push(read_instruction(item, tables)?);
+    }
+    Result::Ok(list)
+}
+
+pub fn read_circuit_data(expr: &SExpr) -> ReadResult<(CircuitData, TraceSignalNames)> {
+    let mut items = Items::new(expr, "circuit")?;
+    let main_header = items.string_field("main")?;
+    let main_signal_offset = items.usize_field("main_signal_offset")?;
+    let number_of_signals = items.usize_field("signals")?;
+    let number_of_components = items.usize_field("components")?;
+    let mut main_inputs = Vec::new();
+    for input in items.field("main_inputs")?.items {
+        match input {
+            SExpr::List(values, _) if values.len() == 3 => main_inputs.push((
+                read_string(&values[0])?,
+                read_usize(&values[1])?,
+                read_usize(&values[2])?,
+            )),
+            _ => return error(input, "expected (\"name\" start size)"),
+        }
+    }
+    let witness = items.usize_list_field("witness")?;
+    let mut io_map = BTreeMap::new();
+    for template in items.field("io_map")?.items {
+        let (template_id, signals) = match template {
+            SExpr::List(values, _) if !values.is_empty() => (read_usize(&values[0])?, &values[1..]),
+            _ => return error(template, "expected (template_id (code offset dimensions...)...)"),
+        };
+        let mut io_list = Vec::new();
+        for signal in signals {
+            match signal {
+                SExpr::List(values, _) if values.len() >= 2 => {
+                    let lengths: ReadResult<Vec<usize>> = values[2..].iter().map(read_usize).collect();
+                    io_list.push((read_usize(&values[0])?, read_usize(&values[1])?, lengths?));
+                }
+                _ => return error(signal, "expected (code offset dimensions...)"),
+            }
+        }
+        io_map.insert(template_id, io_list);
+    }
+    let mut trace_signal_names = TraceSignalNames::new();
+    for name in items.field("trace_names")?.items {
+        match name {
+            SExpr::List(values, _) if values.len() == 2 => {
+                trace_signal_names.insert(read_usize(&values[0])?, read_string(&values[1])?);
+            }
+            _ => return error(name, "expected (signal \"name\")"),
+        }
+    }
+    items.end()?;
+    let data = CircuitData {
+        main_header,
+        main_signal_offset,
+        number_of_signals,
+        number_of_components,
+        main_inputs,
+        witness,
+        io_map,
+    };
+    Result::Ok((data, trace_signal_names))
+}
+
+pub fn read_template(expr: &SExpr, tables: &mut Tables) -> ReadResult<TemplateCodeInfo> {
+    let mut items = Items::new(expr, "template")?;
+    let template = TemplateCodeInfo {
+        id: items.usize_field("id")?,
+        header: items.string_field("header")?,
+        name: items.string_field("name")?,
+        is_parallel: items.bool_field("is_parallel")?,
+        is_parallel_component: items.bool_field("is_parallel_component")?,
+        is_not_parallel_component: items.bool_field("is_not_parallel_component")?,
+        has_parallel_sub_cmp: items.bool_field("has_parallel_sub_cmp")?,
+        number_of_inputs: items.usize_field("inputs")?,
+        number_of_outputs: items.usize_field("outputs")?,
+        number_of_intermediates: items.usize_field("intermediates")?,
+        number_of_components: items.usize_field("components")?,
+        var_stack_depth: items.usize_field("vars")?,
+        signal_stack_depth: items.usize_field("signal_stack")?,
+        body: items.list_field("body", tables)?,
+        expression_stack_depth: 0,
+    };
+    items.end()?;
+    Result::Ok(template)
+}
+
+pub fn read_function(expr: &SExpr, tables: &mut Tables) -> ReadResult<FunctionCodeInfo> {
+    let mut items = Items::new(expr, "function")?;
+    let header = items.string_field("header")?;
+    let name = items.string_field("name")?;
+    let mut params = Vec::new();
+    for param in items.field("params")?.items {
+        let (name, length) = match param {
+            SExpr::List(values, _) if !values.is_empty() => {
+                let length: ReadResult<Vec<usize>> = values[1..].iter().map(read_usize).collect();
+                (read_string(&values[0])?, length?)
+            }
+            _ => return error(param, "expected (\"name\" dimensions...)"),
+        };
+        params.push(Param { name, length });
+    }
+    let function = FunctionCodeInfo {
+        header,
+        name,
+        params,
+        returns: items.usize_list_field("returns")?,
+        max_number_of_vars: items.usize_field("vars")?,
+        body: items.list_field("body", tables)?,
+        max_number_of_ops_in_expression: 0,
+    };
+    items.end()?;
+    Result::Ok(function)
+}
+
+pub fn read_instruction(expr: &SExpr, tables: &mut Tables) -> ReadResult<InstructionPointer> {
+    let kind = match head(expr) {
+        Some(kind) => kind,
+        None => return error(expr, "expected an instruction"),
+    };
+    let mut items = Items::new(expr, kind)?;
+    let (line, message_id) = read_at(&mut items)?;
+    let instruction = match kind {
+        "value" => {
+            let value = items.next("the value")?;
+            let (parse_as, value) = match head(value) {
+                Some("field") => {
+                    let mut field = Items::new(value, "field")?;
+                    let id = tables.field_constant(field.next("the constant")?)?;
+                    field.end()?;
+                    (ValueType::BigInt, id)
+                }
+                Some("u32") => {
+                    let mut field = Items::new(value, "u32")?;
+                    let value = read_usize(field.next("the value")?)?;
+                    field.end()?;
+                    (ValueType::U32, value)
+                }
+                _ => return error(value, "expected (field ...) or (u32 ...)"),
+            };
+            ValueBucket {
+                line,
+                message_id,
+                parse_as,
+                op_aux_no: 0,
+                value,
+            }
+            .allocate()
+        }
+        "load" => LoadBucket {
+            line,
+            message_id,
+            context: InstrContext {
+                size: items.usize_field("size")?,
+            },
+            address_type: read_address_type(&mut items, tables)?,
+            src: read_location(&mut items, "src", tables)?,
+        }
+        .allocate(),
+        "store" => StoreBucket {
+            line,
+            message_id,
+            context: InstrContext {
+                size: items.usize_field("size")?,
+            },
+            dest_is_output: items.bool_field("output")?,
+            dest_address_type: read_address_type(&mut items, tables)?,
+            dest: read_location(&mut items, "dest", tables)?,
+            src: items.instruction_field("src", tables)?,
+        }
+        .allocate(),
+        "compute" => {
+            let op = read_operator(items.single("op")?)?;
+            let mut stack = Vec::new();
+            for operand in items.items.by_ref() {
+                stack.push(read_instruction(operand, tables)?);
+            }
+            ComputeBucket {
+                line,
+                message_id,
+                op,
+                op_aux_no: 0,
+                stack,
+            }
+            .allocate()
+        }
+        "call" => {
+            let symbol = items.string_field("symbol")?;
+            let argument_types = items
+                .usize_list_field("argument_sizes")?
+                .into_iter()
+                .map(|size| InstrContext { size })
+                .collect();
+            let arguments = items.list_field("arguments", tables)?;
+            let return_info = items.single("return")?;
+            let return_info = match (return_info, head(return_info)) {
+                (SExpr::Atom(atom, _), _) if atom == "intermediate" => {
+                    ReturnType::Intermediate { op_aux_no: 0 }
+                }
+                (_, Some("final")) => {
+                    let mut data = Items::new(return_info, "final")?;
+                    let final_data = FinalData {
+                        context: InstrContext {
+                            size: data.usize_field("size")?,
+                        },
+                        dest_is_output: data.bool_field("output")?,
+                        dest_address_type: read_address_type(&mut data, tables)?,
+                        dest: read_location(&mut data, "dest", tables)?,
+                    };
+                    data.end()?;
+                    ReturnType::Final(final_data)
+                }
+                _ => return error(return_info, "expected intermediate or (final ...)"),
+            };
+            CallBucket {
+                line,
+                message_id,
+                symbol,
+                argument_types,
+                arguments,
+                arena_size: 0,
+                return_info,
+            }
+            .allocate()
+        }
+        "branch" => BranchBucket {
+            line,
+            message_id,
+            cond: items.instruction_field("cond", tables)?,
+            if_branch: items.list_field("then", tables)?,
+            else_branch: items.list_field("else", tables)?,
+        }
+        .allocate(),
+        "return" => ReturnBucket {
+            line,
+            message_id,
+            with_size: items.usize_field("size")?,
+            value: items.instruction_field("value", tables)?,
+        }
+        .allocate(),
+        "assert" => AssertBucket {
+            line,
+            message_id,
+            evaluate: items.instruction_field("evaluate", tables)?,
+        }
+        .allocate(),
+        "log" => {
+            let mut argsprint = Vec::new();
+            for argument in items.field("args")?.items {
+                match head(argument) {
+                    Some("exp") => {
+                        let mut exp = Items::new(argument, "exp")?;
+                        let instruction = read_instruction(exp.next("the expression")?, tables)?;
+                        exp.end()?;
+                        argsprint.push(LogBucketArg::LogExp(instruction));
+                    }
+                    Some("str") => {
+                        let mut string = Items::new(argument, "str")?;
+                        let id = tables.string_id(read_string(string.next("the string")?)?);
+                        string.end()?;
+                        argsprint.push(LogBucketArg::LogStr(id));
+                    }
+                    _ => return error(argument, "expected (exp ...) or (str ...)"),
+                }
+            }
+            LogBucket {
+                line,
+                message_id,
+                argsprint,
+            }
+            .allocate()
+        }
+        "loop" => LoopBucket {
+            line,
+            message_id,
+            continue_condition: items.instruction_field("cond", tables)?,
+            body: items.list_field("body", tables)?,
+        }
+        .allocate(),
+        "create_cmp" => {
+            let template_id = items.usize_field("template_id")?;
+            let cmp_unique_id = items.usize_field("cmp_unique_id")?;
+            let symbol = items.string_field("symbol")?;
+            let sub_cmp_id = items.instruction_field("sub_cmp_id", tables)?;
+            let name_subcomponent = items.string_field("name")?;
+            let mut defined_positions = Vec::new();
+            for position in items.field("defined_positions")?.items {
+                match position {
+                    SExpr::List(values, _) if values.len() == 2 => {
+                        defined_positions.push((read_usize(&values[0])?, read_bool(&values[1])?))
+                    }
+                    _ => return error(position, "expected (position parallel)"),
+                }
+            }
+            CreateCmpBucket {
+                line,
+                message_id,
+                template_id,
+                cmp_unique_id,
+                symbol,
+                sub_cmp_id,
+                name_subcomponent,
+                defined_positions,
+                is_part_mixed_array_not_uniform_parallel: items
+                    .bool_field("mixed_not_uniform_parallel")?,
+                uniform_parallel: items.option_field("uniform_parallel")?,
+                dimensions: items.usize_list_field("dimensions")?,
+                signal_offset: items.usize_field("signal_offset")?,
+                signal_offset_jump: items.usize_field("signal_offset_jump")?,
+                component_offset: items.usize_field("component_offset")?,
+                component_offset_jump: items.usize_field("component_offset_jump")?,
+                number_of_cmp: items.usize_field("number_of_cmp")?,
+                has_inputs: items.bool_field("has_inputs")?,
+            }
+            .allocate()
+        }
+        _ => return error(expr, &format!("unknown instruction {}", kind)),
+    };
+    items.end()?;
+    Result::Ok(instruction)
+}
+
+fn read_address_type(items: &mut Items, tables: &mut Tables) -> ReadResult<AddressType> {
+    let address = items.single("address")?;
+    match address {
+        SExpr::Atom(atom, _) if atom == "variable" => Result::Ok(AddressType::Variable),
+        SExpr::Atom(atom, _) if atom == "signal" => Result::Ok(AddressType::Signal),
+        _ if head(address) == Some("subcmp") => {
+            let mut subcmp = Items::new(address, "subcmp")?;
+            let cmp_address = subcmp.instruction_field("cmp", tables)?;
+            let uniform_parallel_value = subcmp.option_field("uniform_parallel")?;
+            let is_output = subcmp.bool_field("output")?;
+            let input = subcmp.single("input")?;
+            let input_information = match input {
+                SExpr::Atom(atom, _) => match atom.as_str() {
+                    "no" => InputInformation::NoInput,
+                    "last" => InputInformation::Input {
+                        status: StatusInput::Last,
+                    },
+                    "no_last" => InputInformation::Input {
+                        status: StatusInput::NoLast,
+                    },
+                    "unknown" => InputInformation::Input {
+                        status: StatusInput::Unknown,
+                    },
+                    _ => return error(input, "expected no, last, no_last or unknown"),
+                },
+                _ => return error(input, "expected no, last, no_last or unknown"),
+            };
+            subcmp.end()?;
+            Result::Ok(AddressType::SubcmpSignal {
+                cmp_address,
+                uniform_parallel_value,
+                is_output,
+                input_information,
+            })
+        }
+        _ => error(address, "expected variable, signal or (subcmp ...)"),
+    }
+}
+
+fn read_location(items: &mut Items, key: &str, tables: &mut Tables) -> ReadResult<LocationRule> {
+    let location = items.single(key)?;
+    match head(location) {
+        Some("indexed") => {
+            let mut indexed = Items::new(location, "indexed")?;
+            let index = indexed.instruction_field("location", tables)?;
+            let header = indexed.single("header")?;
+            let template_header = match header {
+                SExpr::Atom(atom, _) if atom == "none" => None,
+                _ => Some(read_string(header)?),
+            };
+            indexed.end()?;
+            Result::Ok(LocationRule::Indexed {
+                location: index,
+                template_header,
+            })
+        }
+        Some("mapped") => {
+            let mut mapped = Items::new(location, "mapped")?;
+            let signal_code = mapped.usize_field("signal_code")?;
+            let indexes = mapped.list_field("indexes", tables)?;
+            mapped.end()?;
+            Result::Ok(LocationRule::Mapped {
+                signal_code,
+                indexes,
+            })
+        }
+        _ => error(location, "expected (indexed ...) or (mapped ...)"),
+    }
+}
+
+fn read_operator(expr: &SExpr) -> ReadResult<OperatorType> {
+    use OperatorType::*;
+    if head(expr) == Some("eq") {
+        let mut eq = Items::new(expr, "eq")?;
+        let size = read_usize(eq.next("the size")?)?;
+        eq.end()?;
+        return Result::Ok(Eq(size));
+    }
+    let name = match expr {
+        SExpr::Atom(atom, _) => atom.as_str(),
+        _ => return error(expr, "expected an operator"),
+    };
+    let op = match name {
+        "mul" => Mul,
+        "div" => Div,
+        "add" => Add,
+        "sub" => Sub,
+        "pow" => Pow,
+        "int_div" => IntDiv,
+        "mod" => Mod,
+        "shift_l" => ShiftL,
+        "shift_r" => ShiftR,
+        "lesser_eq" => LesserEq,
+        "greater_eq" => GreaterEq,
+        "lesser" => Lesser,
+        "greater" => Greater,
+        "not_eq" => NotEq,
+        "bool_or" => BoolOr,
+        "bool_and" => BoolAnd,
+        "bit_or" => BitOr,
+        "bit_and" => BitAnd,
+        "bit_xor" => BitXor,
+        "prefix_sub" => PrefixSub,
+        "bool_not" => BoolNot,
+        "complement" => Complement,
+        "to_address" => ToAddress,
+        "mul_address" => MulAddress,
+        "add_address" => AddAddress,
+        _ => return error(expr, &format!("unknown operator {}", name)),
+    };
+    Result::Ok(op)
+}
@@ -0,0 +1,207 @@
+// Trees of the textual IR: atoms, quoted strings and parenthesized lists. The parsed nodes
+// keep their line in the text to report the errors, the written ones have line 0.
+pub enum SExpr {
+    Atom(String, usize),
+    Str(String, usize),
+    List(Vec<SExpr>, usize),
+}
+
+// Lists are written in a single line when they fit in this width
+const MAX_WIDTH: usize = 100;
+const INDENT: usize = 2;
+
+impl SExpr {
+    pub fn atom<T: ToString>(value: T) -> SExpr {
+        SExpr::Atom(value.to_string(), 0)
+    }
+
+    pub fn string(value: &str) -> SExpr {
+        SExpr::Str(value.to_string(), 0)
+    }
+
+    pub fn list(items: Vec<SExpr>) -> SExpr {
+        SExpr::List(items, 0)
+    }
+
+    // A list starting with the atom head
+    pub fn node(head: &str, mut items: Vec<SExpr>) -> SExpr {
+        items.insert(0, SExpr::atom(head));
+        SExpr::list(items)
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            SExpr::Atom(_, line) | SExpr::Str(_, line) | SExpr::List(_, line) => *line,
+        }
+    }
+
+    pub fn write(&self, out: &mut String, indent: usize) {
+        if let (SExpr::List(items, _), None) = (self, self.width(MAX_WIDTH.saturating_sub(indent)))
+        {
+            // the atoms at the start of the list stay in the first line
+            let in_first_line = items
+                .iter()
+                .take_while(|item| matches!(item, SExpr::Atom(..) | SExpr::Str(..)))
+                .count()
+                .max(1);
+            let first: Vec<String> = items[..in_first_line].iter().map(SExpr::flat).collect();
+            out.push('(');
+            out.push_str(&first.join(" "));
+            for item in &items[in_first_line..] {
+                out.push('\n');
+                out.push_str(&" ".repeat(indent + INDENT));
+                item.write(out, indent + INDENT);
+            }
+            out.push(')');
+        } else {
+            out.push_str(&self.flat());
+        }
+    }
+
+    // Width of the expression written in a single line, None if it is greater than limit
+    fn width(&self, limit: usize) -> Option<usize> {
+        let width = match self {
+            SExpr::Atom(atom, _) => atom.chars().count(),
+            SExpr::Str(string, _) => quote(string).chars().count(),
+            SExpr::List(items, _) => {
+                let mut width = 1 + items.len().max(1);
+                for item in items {
+                    width += item.width(limit.checked_sub(width)?)?;
+                }
+                width
+            }
+        };
+        if width <= limit {
+            Some(width)
+        } else {
+            None
+        }
+    }
+
+    fn flat(&self) -> String {
+        match self {
+            SExpr::Atom(atom, _) => atom.clone(),
+            SExpr::Str(string, _) => quote(string),
+            SExpr::List(items, _) => {
+                let items: Vec<String> = items.iter().map(SExpr::flat).collect();
+                format!("({})", items.join(" "))
+            }
+        }
+    }
+}
+
+fn quote(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Parses all the trees of the text. A ; starts a comment that lasts until the end of the line.
+pub fn parse(text: &str) -> Result<Vec<SExpr>, String> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+    let mut trees = Vec::new();
+    loop {
+        parser.skip_blanks();
+        if parser.chars.peek().is_none() {
+            return Result::Ok(trees);
+        }
+        trees.push(parser.expression()?);
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == ';' {
+                while !matches!(self.next(), Some('\n') | None) {}
+            } else if c.is_whitespace() {
+                self.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Result::Err(format!("line {}: {}", self.line, message))
+    }
+
+    fn expression(&mut self) -> Result<SExpr, String> {
+        let line = self.line;
+        match self.chars.peek() {
+            Some('(') => {
+                self.next();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_blanks();
+                    match self.chars.peek() {
+                        Some(')') => {
+                            self.next();
+                            return Result::Ok(SExpr::List(items, line));
+                        }
+                        Some(_) => items.push(self.expression()?),
+                        None => return self.error("unclosed parenthesis"),
+                    }
+                }
+            }
+            Some(')') => self.error("unexpected )"),
+            Some('"') => {
+                self.next();
+                let mut string = String::new();
+                loop {
+                    match self.next() {
+                        Some('"') => return Result::Ok(SExpr::Str(string, line)),
+                        Some('\\') => match self.next() {
+                            Some('"') => string.push('"'),
+                            Some('\\') => string.push('\\'),
+                            Some('n') => string.push('\n'),
+                            Some('r') => string.push('\r'),
+                            Some('t') => string.push('\t'),
+                            _ => return self.error("invalid escape sequence"),
+                        },
+                        Some(c) => string.push(c),
+                        None => return self.error("unclosed string"),
+                    }
+                }
+            }
+            _ => {
+                let mut atom = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' || c == ';' {
+                        break;
+                    }
+                    atom.push(c);
+                    self.next();
+                }
+                Result::Ok(SExpr::Atom(atom, line))
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use super::sexpr::SExpr;
+use super::{CircuitData, IR_TEXT_VERSION};
+use crate::circuit_design::circuit::Circuit;
+use crate::circuit_design::function::FunctionCodeInfo;
+use crate::circuit_design::template::TemplateCodeInfo;
+use crate::intermediate_representation::ir_interface::*;
+use code_producers::components::TraceSignalNames;
+
+// Constants and strings of the circuit that are referenced by index in the instructions
+pub struct Tables<'a> {
+    pub field_tracking: &'a [String],
+    pub string_table: &'a [String],
+}
+
+pub fn write_circuit(circuit: &Circuit) -> String {
+    let tables = Tables {
+        field_tracking: &circuit.c_producer.field_tracking,
+        string_table: circuit.c_producer.get_string_table(),
+    };
+    let mut out = format!(";; circom IR, version {}\n", IR_TEXT_VERSION);
+    SExpr::node("version", vec![SExpr::atom(IR_TEXT_VERSION)]).write(&mut out, 0);
+    out.push('\n');
+    field("prime", &circuit.c_producer.prime).write(&mut out, 0);
+    out.push('\n');
+    let data = CircuitData::of(&circuit.c_producer);
+    write_circuit_data(&data, circuit.c_producer.get_trace_signal_names()).write(&mut out, 0);
+    out.push('\n');
+    for template in &circuit.templates {
+        out.push('\n');
+        write_template(template, &tables).write(&mut out, 0);
+        out.push('\n');
+    }
+    for function in &circuit.functions {
+        out.push('\n');
+        write_function(function, &tables).write(&mut out, 0);
+        out.push('\n');
+    }
+    out
+}
+
+pub fn write_circuit_data(data: &CircuitData, trace_signal_names: &TraceSignalNames) -> SExpr {
+    let main_inputs = data
+        .main_inputs
+        .iter()
+        .map(|(name, start, size)| {
+            SExpr::list(vec![SExpr::string(name), SExpr::atom(start), SExpr::atom(size)])
+        })
+        .collect();
+    let io_map = data
+        .io_map
+        .iter()
+        .map(|(template_id, signals)| {
+            let mut items = vec![SExpr::atom(template_id)];
+            for (code, offset, lengths) in signals {
+                let mut signal = vec![SExpr::atom(code), SExpr::atom(offset)];
+                signal.extend(lengths.iter().map(SExpr::atom));
+                items.push(SExpr::list(signal));
+            }
+            SExpr::list(items)
+        })
+        .collect();
+    let trace_names = trace_signal_names
+        .iter()
+        .map(|(signal, name)| SExpr::list(vec![SExpr::atom(signal), SExpr::string(name)]))
+        .collect();
+    SExpr::node(
+        "circuit",
+        vec![
+            string_field("main", &data.main_header),
+            field("main_signal_offset", data.main_signal_offset),
+            field("signals", data.number_of_signals),
+            field("components", data.number_of_components),
+            SExpr::node("main_inputs", main_inputs),
+            SExpr::node("witness", data.witness.iter().map(SExpr::atom).collect()),
+            SExpr::node("io_map", io_map),
+            SExpr::node("trace_names", trace_names),
+        ],
+    )
+}
+
+fn field<T: ToString>(key: &str, value: T) -> SExpr {
+    SExpr::node(key, vec![SExpr::atom(value)])
+}
+
+fn string_field(key: &str, value: &str) -> SExpr {
+    SExpr::node(key, vec![SExpr::string(value)])
+}
+
+fn option_field(key: &str, value: Option<bool>) -> SExpr {
+    match value {
+        Some(value) => field(key, value),
+        None => field(key, "none"),
+    }
+}
+
+fn at(line: usize, message_id: usize) -> SExpr {
+    SExpr::node("at", vec![SExpr::atom(line), SExpr::atom(message_id)])
+}
+
+fn write_list(list: &[InstructionPointer], tables: &Tables) -> Vec<SExpr> {
+    list.iter()
+        .map(|instruction| write_instruction(instruction, tables))
+        .collect()
+}
+
+fn list_field(key: &str, list: &[InstructionPointer], tables: &Tables) -> SExpr {
+    SExpr::node(key, write_list(list, tables))
+}
+
+fn instruction_field(key: &str, instruction: &Instruction, tables: &Tables) -> SExpr {
+    SExpr::node(key, vec![write_instruction(instruction, tables)])
+}
+
+pub fn write_template(template: &TemplateCodeInfo, tables: &Tables) -> SExpr {
+    SExpr::node(
+        "template",
+        vec![
+            field("id", template.id),
+            string_field("header", &template.header),
+            string_field("name", &template.name),
+            field("is_parallel", template.is_parallel),
+            field("is_parallel_component", template.is_parallel_component),
+            field(
+                "is_not_parallel_component",
+                template.is_not_parallel_component,
+            ),
+            field("has_parallel_sub_cmp", template.has_parallel_sub_cmp),
+            field("inputs", template.number_of_inputs),
+            field("outputs", template.number_of_outputs),
+            field("intermediates", template.number_of_intermediates),
+            field("components", template.number_of_components),
+            field("vars", template.var_stack_depth),
+            field("signal_stack", template.signal_stack_depth),
+            list_field("body", &template.body, tables),
+        ],
+    )
+}
+
+pub fn write_function(function: &FunctionCodeInfo, tables: &Tables) -> SExpr {
+    let params = function
+        .params
+        .iter()
+        .map(|param| {
+            let mut items = vec![SExpr::string(&param.name)];
+            items.extend(param.length.iter().map(SExpr::atom));
+            SExpr::list(items)
+        })
+        .collect();
+    SExpr::node(
+        "function",
+        vec![
+            string_field("header", &function.header),
+            string_field("name", &function.name),
+            SExpr::node("params", params),
+            SExpr::node(
+                "returns",
+                function.returns.iter().map(SExpr::atom).collect(),
+            ),
+            field("vars", function.max_number_of_vars),
+            list_field("body", &function.body, tables),
+        ],
+    )
+}
+
+pub fn write_instruction(instruction: &Instruction, tables: &Tables) -> SExpr {
+    use Instruction::*;
+    match instruction {
+        Value(bucket) => {
+            let value = match bucket.parse_as {
+                ValueType::BigInt => field("field", &tables.field_tracking[bucket.value]),
+                ValueType::U32 => field("u32", bucket.value),
+            };
+            SExpr::node("value", vec![at(bucket.line, bucket.message_id), value])
+        }
+        Load(bucket) => SExpr::node(
+            "load",
+            vec![
+                at(bucket.line, bucket.message_id),
+                field("size", bucket.context.size),
+                write_address_type(&bucket.address_type, tables),
+                write_location("src", &bucket.src, tables),
+            ],
+        ),
+        Store(bucket) => SExpr::node(
+            "store",
+            vec![
+                at(bucket.line, bucket.message_id),
+                field("size", bucket.context.size),
+                field("output", bucket.dest_is_output),
+                write_address_type(&bucket.dest_address_type, tables),
+                write_location("dest", &bucket.dest, tables),
+                instruction_field("src", &bucket.src, tables),
+            ],
+        ),
+        Compute(bucket) => {
+            let mut items = vec![
+                at(bucket.line, bucket.message_id),
+                SExpr::node("op", vec![write_operator(&bucket.op)]),
+            ];
+            items.extend(write_list(&bucket.stack, tables));
+            SExpr::node("compute", items)
+        }
+        Call(bucket) => {
+            let return_info = match &bucket.return_info {
+                ReturnType::Intermediate { .. } => SExpr::atom("intermediate"),
+                ReturnType::Final(data) => SExpr::node(
+                    "final",
+                    vec![
+                        field("size", data.context.size),
+                        field("output", data.dest_is_output),
+                        write_address_type(&data.dest_address_type, tables),
+                        write_location("dest", &data.dest, tables),
+                    ],
+                ),
+            };
+            let sizes = bucket
+                .argument_types
+                .iter()
+                .map(|context| SExpr::atom(context.size));
+            SExpr::node(
+                "call",
+                vec![
+                    at(bucket.line, bucket.message_id),
+                    string_field("symbol", &bucket.symbol),
+                    SExpr::node("argument_sizes", sizes.collect()),
+                    list_field("arguments", &bucket.arguments, tables),
+                    SExpr::node("return", vec![return_info]),
+                ],
+            )
+        }
+        Branch(bucket) => SExpr::node(
+            "branch",
+            vec![
+                at(bucket.line, bucket.message_id),
+                instruction_field("cond", &bucket.cond, tables),
+                list_field("then", &bucket.if_branch, tables),
+                list_field("else", &bucket.else_branch, tables),
+            ],
+        ),
+        Return(bucket) => SExpr::node(
+            "return",
+            vec![
+                at(bucket.line, bucket.message_id),
+                field("size", bucket.with_size),
+                instruction_field("value", &bucket.value, tables),
+            ],
+        ),
+        Assert(bucket) => SExpr::node(
+            "assert",
+            vec![
+                at(bucket.line, bucket.message_id),
+                instruction_field("evaluate", &bucket.evaluate, tables),
+            ],
+        ),
+        Log(bucket) => {
+            let args = bucket
+                .argsprint
+                .iter()
+                .map(|argument| match argument {
+                    LogBucketArg::LogExp(expression) => {
+                        instruction_field("exp", expression, tables)
+                    }
+                    LogBucketArg::LogStr(id) => string_field("str", &tables.string_table[*id]),
+                })
+                .collect();
+            SExpr::node(
+                "log",
+                vec![
+                    at(bucket.line, bucket.message_id),
+                    SExpr::node("args", args),
+                ],
+            )
+        }
+        Loop(bucket) => SExpr::node(
+            "loop",
+            vec![
+                at(bucket.line, bucket.message_id),
+                instruction_field("cond", &bucket.continue_condition, tables),
+                list_field("body", &bucket.body, tables),
+            ],
+        ),
+        CreateCmp(bucket) => {
+            let positions = bucket
+                .defined_positions
+                .iter()
+                .map(|(position, parallel)| {
+                    SExpr::list(vec![SExpr::atom(position), SExpr::atom(parallel)])
+                })
+                .collect();
+            SExpr::node(
+                "create_cmp",
+                vec![
+                    at(bucket.line, bucket.message_id),
+                    field("template_id", bucket.template_id),
+                    field("cmp_unique_id", bucket.cmp_unique_id),
+                    string_field("symbol", &bucket.symbol),
+                    instruction_field("sub_cmp_id", &bucket.sub_cmp_id, tables),
+                    string_field("name", &bucket.name_subcomponent),
+                    SExpr::node("defined_positions", positions),
+                    field(
+                        "mixed_not_uniform_parallel",
+                        bucket.is_part_mixed_array_not_uniform_parallel,
+                    ),
+                    option_field("uniform_parallel", bucket.uniform_parallel),
+                    SExpr::node(
+                        "dimensions",
+                        bucket.dimensions.iter().map(SExpr::atom).collect(),
+                    ),
+                    field("signal_offset", bucket.signal_offset),
+                    field("signal_offset_jump", bucket.signal_offset_jump),
+                    field("component_offset", bucket.component_offset),
+                    field("component_offset_jump", bucket.component_offset_jump),
+                    field("number_of_cmp", bucket.number_of_cmp),
+                    field("has_inputs", bucket.has_inputs),
+                ],
+            )
+        }
+    }
+}
+
+fn write_address_type(address_type: &AddressType, tables: &Tables) -> SExpr {
+    let address = match address_type {
+        AddressType::Variable => SExpr::atom("variable"),
+        AddressType::Signal => SExpr::atom("signal"),
+        AddressType::SubcmpSignal {
+            cmp_address,
+            uniform_parallel_value,
+            is_output,
+            input_information,
+        } => {
+            let input = match input_information {
+                InputInformation::NoInput => "no",
+                InputInformation::Input { status } => match status {
+                    StatusInput::Last => "last",
+                    StatusInput::NoLast => "no_last",
+                    StatusInput::Unknown => "unknown",
+                },
+            };
+            SExpr::node(
+                "subcmp",
+                vec![
+                    instruction_field("cmp", cmp_address, tables),
+                    option_field("uniform_parallel", *uniform_parallel_value),
+                    field("output", is_output),
+                    field("input", input),
+                ],
+            )
+        }
+    };
+    SExpr::node("address", vec![address])
+}
+
+fn write_location(key: &str, location: &LocationRule, tables: &Tables) -> SExpr {
+    let location = match location {
+        LocationRule::Indexed {
+            location,
+            template_header,
+        } => {
+            let header = match template_header {
+                Some(header) => string_field("header", header),
+                None => field("header", "none"),
+            };
+            SExpr::node(
+                "indexed",
+                vec![instruction_field("location", location, tables), header],
+            )
+        }
+        LocationRule::Mapped {
+            signal_code,
+            indexes,
+        } => SExpr::node(
+            "mapped",
+            vec![
+                field("signal_code", signal_code),
+                list_field("indexes", indexes, tables),
+            ],
+        ),
+    };
+    SExpr::node(key, vec![location])
+}
+
+pub fn write_operator(op: &OperatorType) -> SExpr {
+    use OperatorType::*;
+    let name = match op {
+        Eq(n) => return SExpr::node("eq", vec![SExpr::atom(n)]),
+        Mul => "mul",
+        Div => "div",
+        Add => "add",
+        Sub => "sub",
+        Pow => "pow",
+        IntDiv => "int_div",
+        Mod => "mod",
+        ShiftL => "shift_l",
+        ShiftR => "shift_r",
+        LesserEq => "lesser_eq",
+        GreaterEq => "greater_eq",
+        Lesser => "lesser",
+        Greater => "greater",
+        NotEq => "not_eq",
+        BoolOr => "bool_or",
+        BoolAnd => "bool_and",
+        BitOr => "bit_or",
+        BitAnd => "bit_and",
+        BitXor => "bit_xor",
+        PrefixSub => "prefix_sub",
+        BoolNot => "bool_not",
+        Complement => "complement",
+        ToAddress => "to_address",
+        MulAddress => "mul_address",
+        AddAddress => "add_address",
+    };
+    SExpr::atom(name)
+}
@@ -1,7 +1,8 @@
 #[allow(dead_code)]
-mod circuit_design;
-mod intermediate_representation;
+pub mod circuit_design;
+pub mod intermediate_representation;
 mod ir_processing;
+pub mod ir_text;
 pub extern crate num_bigint_dig as num_bigint;
 pub extern crate num_traits;
 
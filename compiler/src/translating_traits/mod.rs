@@ -1,4 +1,5 @@
 use code_producers::c_elements::*;
+use code_producers::rust_elements::*;
 use code_producers::wasm_elements::*;
 use std::io::Write;
 
@@ -27,3 +28,22 @@ pub trait WriteWasm {
         writer.flush().map_err(|_| {})
     }
 }
+
+pub trait WriteRust {
+    /*
+        returns (x, y) where:
+            x: rust statements produced.
+            y: if the statements in x compute some value, the expression of that value.
+    */
+    fn produce_rust(&self, producer: &RustProducer) -> (Vec<String>, String);
+    // type of the expression returned by produce_rust
+    fn rust_value(&self) -> RustValue {
+        RustValue::Field
+    }
+    fn write_rust<T: Write>(&self, writer: &mut T, producer: &RustProducer) -> Result<(), ()> {
+        let (rust_instructions, _) = self.produce_rust(producer);
+        let code = rust_code_generator::merge_code(rust_instructions);
+        writer.write_all(code.as_bytes()).map_err(|_| {})?;
+        writer.flush().map_err(|_| {})
+    }
+}
@@ -14,15 +14,19 @@ use super::environment_utils::{
 
 use program_structure::constants::UsefulConstants;
 
-use super::execution_data::analysis::Analysis;
+use super::execution_data::analysis::{Analysis, AnalysisSummary};
+use super::execution_data::type_definitions::ParameterContext;
 use super::execution_data::{ExecutedProgram, ExecutedTemplate, NodePointer, PreExecutedTemplate};
+use super::instance_pool::{Fragment, Instance, InstancePool};
 use super::{
     ast::*, ArithmeticError, FileID, ProgramArchive, Report, ReportCode, ReportCollection,
 };
+use super::template_cache::TemplateCache;
 use crate::FlagsExecution;
 use circom_algebra::num_bigint::BigInt;
 use std::collections::{BTreeMap, HashMap};
 use std::mem;
+use std::sync::Arc;
 type AExpr = ArithmeticExpressionGen<String>;
 type AnonymousComponentsInfo = BTreeMap<String, (Meta, Vec<Expression>)>;
 
@@ -43,6 +47,10 @@ struct RuntimeInformation {
     pub environment: ExecutionEnvironment,
     pub exec_program: ExecutedProgram,
     pub anonymous_components: AnonymousComponentsInfo,
+    pub cache: Option<TemplateCache>,
+    pub pool: Option<Arc<InstancePool>>,
+    // Only for the threads executing the instances of the pool
+    pub summaries: Option<Vec<AnalysisSummary>>,
 }
 impl RuntimeInformation {
     pub fn new(current_file: FileID, id_max: usize, prime: &String) -> RuntimeInformation {
@@ -57,6 +65,9 @@ impl RuntimeInformation {
             environment: ExecutionEnvironment::new(),
             exec_program: ExecutedProgram::new(prime),
             anonymous_components: AnonymousComponentsInfo::new(),
+            cache: None,
+            pool: None,
+            summaries: None,
         }
     }
 }
@@ -94,28 +105,62 @@ enum ExecutionWarning {
     CanBeQuadraticConstraintMultiple(Vec<String>),
 }
 
+const WORKER_STACK_SIZE: usize = 64 * 1024 * 1024;
+
 pub fn constraint_execution(
     program_archive: &ProgramArchive,
     flags: FlagsExecution,
     prime: &String,
+    cache: Option<TemplateCache>,
+    threads: usize,
 ) -> Result<(ExecutedProgram, ReportCollection), ReportCollection> {
     let main_file_id = program_archive.get_file_id_main();
     let mut runtime_information =
         RuntimeInformation::new(*main_file_id, program_archive.id_max, prime);
-    use Expression::Call;
-
+    runtime_information.cache = cache;
     runtime_information.public_inputs = program_archive.get_public_inputs_main_component().clone();
 
-    let folded_value_result = if let Call { id, args, .. } = &program_archive.get_main_expression()
-    {
+    let folded_value_result = if threads > 1 {
+        let pool = Arc::new(InstancePool::new());
+        runtime_information.pool = Some(Arc::clone(&pool));
+        std::thread::scope(|scope| {
+            for _ in 1..threads {
+                let _ = std::thread::Builder::new()
+                    .stack_size(WORKER_STACK_SIZE)
+                    .spawn_scoped(scope, || {
+                        execute_pool_tasks(&pool, program_archive, flags, prime)
+                    });
+            }
+            let result = execute_main(program_archive, &mut runtime_information, flags);
+            pool.finish();
+            result
+        })
+    } else {
+        execute_main(program_archive, &mut runtime_information, flags)
+    };
+
+    match folded_value_result {
+        Result::Err(_) => Result::Err(runtime_information.runtime_errors),
+        Result::Ok(folded_value) => {
+            debug_assert!(FoldedValue::valid_node_pointer(&folded_value));
+            Result::Ok((
+                runtime_information.exec_program,
+                runtime_information.runtime_errors,
+            ))
+        }
+    }
+}
+
+fn execute_main(
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation,
+    flags: FlagsExecution,
+) -> Result<FoldedValue, ()> {
+    use Expression::Call;
+    if let Call { id, args, .. } = &program_archive.get_main_expression() {
         let mut arg_values = Vec::new();
         for arg_expression in args.iter() {
-            let f_arg = execute_expression(
-                arg_expression,
-                program_archive,
-                &mut runtime_information,
-                flags,
-            );
+            let f_arg = execute_expression(arg_expression, program_archive, runtime, flags);
             arg_values.push(safe_unwrap_to_arithmetic_slice(f_arg.unwrap(), line!()));
             // improve
         }
@@ -124,25 +169,61 @@ pub fn constraint_execution(
             arg_values,
             BTreeMap::new(),
             program_archive,
-            &mut runtime_information,
+            runtime,
             flags,
         )
     } else {
         unreachable!("The main expression should be a call.");
-    };
+    }
+}
 
-    match folded_value_result {
-        Result::Err(_) => Result::Err(runtime_information.runtime_errors),
-        Result::Ok(folded_value) => {
-            debug_assert!(FoldedValue::valid_node_pointer(&folded_value));
-            Result::Ok((
-                runtime_information.exec_program,
-                runtime_information.runtime_errors,
-            ))
-        }
+// Executes the instances queued in the pool until the program is executed
+fn execute_pool_tasks(
+    pool: &Arc<InstancePool>,
+    program_archive: &ProgramArchive,
+    flags: FlagsExecution,
+    prime: &String,
+) {
+    while let Some((index, instance)) = pool.next_task() {
+        let outcome = execute_instance(&instance, pool, program_archive, flags, prime);
+        pool.complete(index, outcome);
     }
 }
 
+// Executes the instance with a runtime of its own and collects the nodes it adds
+fn execute_instance(
+    instance: &Instance,
+    pool: &Arc<InstancePool>,
+    program_archive: &ProgramArchive,
+    flags: FlagsExecution,
+    prime: &String,
+) -> Option<Arc<Fragment>> {
+    let template = program_archive.get_template_data(&instance.name);
+    let mut runtime =
+        RuntimeInformation::new(template.get_file_id(), program_archive.id_max, prime);
+    runtime.pool = Some(Arc::clone(pool));
+    runtime.summaries = Some(Vec::new());
+    let arguments = template
+        .get_name_of_params()
+        .iter()
+        .map(|name| instance.parameters[name].clone())
+        .collect();
+    let result = execute_template_call_complete(
+        &instance.name,
+        arguments,
+        instance.tags.clone(),
+        program_archive,
+        &mut runtime,
+        flags,
+    );
+    if result.is_err() || !runtime.runtime_errors.is_empty() {
+        return Option::None;
+    }
+    let summaries = runtime.summaries.unwrap();
+    let fragment = runtime.exec_program.model.into_iter().zip(summaries).collect();
+    Option::Some(Arc::new(fragment))
+}
+
 pub fn execute_constant_expression(
     expression: &Expression,
     program_archive: &ProgramArchive,
@@ -1288,6 +1369,30 @@ fn perform_assign(
                     &mut runtime.runtime_errors,
                     &runtime.call_trace,
                 )?;
+                // Without tags in the inputs the instance is already known and other threads
+                // can execute it while its inputs are assigned
+                if let Option::Some(pool) = &runtime.pool {
+                    if component.inputs_tags.values().all(TagInfo::is_empty) {
+                        let prenode = runtime.exec_program.get_prenode(prenode_pointer).unwrap();
+                        let instance = Instance {
+                            name: prenode.template_name().clone(),
+                            parameters: instance_parameters(
+                                program_archive,
+                                prenode.template_name(),
+                                prenode.parameter_instances(),
+                            ),
+                            tags: component.inputs_tags.clone(),
+                        };
+                        let existent_node = runtime.exec_program.identify_node(
+                            &instance.name,
+                            &instance.parameters,
+                            &instance.tags,
+                        );
+                        if existent_node.is_none() {
+                            pool.submit(instance);
+                        }
+                    }
+                }
                 if component.is_ready_initialize() {
                     // calls to execute and initialize the component
                     let pretemplate_info = runtime
@@ -1989,9 +2094,39 @@ fn execute_template_call(
     let existent_node = runtime
         .exec_program
         .identify_node(id, &args_to_values, &tag_values);
-    let node_pointer = if let Option::Some(pointer) = existent_node {
+    let cache_key = match &mut runtime.cache {
+        Some(cache) if existent_node.is_none() => {
+            cache.key(program_archive, id, &args_to_values, &tag_values, &is_main)
+        }
+        _ => None,
+    };
+    let cached_node = match (&mut runtime.cache, &cache_key) {
+        (Some(cache), Some(key)) => cache.load(program_archive, key, &mut runtime.exec_program),
+        _ => None,
+    };
+    let pooled_node = match (&runtime.pool, existent_node.or(cached_node)) {
+        // The instance of a task is executed by the thread running the task
+        (Some(pool), None) if runtime.call_trace.len() > 1 => {
+            let instance = Instance {
+                name: id.to_string(),
+                parameters: args_to_values.clone(),
+                tags: tag_values.clone(),
+            };
+            let pool = Arc::clone(pool);
+            let prime = runtime.exec_program.prime.clone();
+            pool.obtain(instance, |instance| {
+                execute_instance(instance, &pool, program_archive, flags, &prime)
+            })
+            .map(|fragment| add_fragment(&fragment, program_archive, runtime))
+        }
+        _ => None,
+    };
+    let node_pointer = if let Option::Some(pointer) = existent_node.or(cached_node).or(pooled_node)
+    {
         pointer
     } else {
+        let reports_before = runtime.runtime_errors.len();
+        let public = is_main.clone();
         let analysis =
             std::mem::replace(&mut runtime.analysis, Analysis::new(program_archive.id_max));
         let code = program_archive.get_template_data(id).get_body().clone();
@@ -2031,8 +2166,9 @@ fn execute_template_call(
 
         let new_node = node_wrap.unwrap();
         let analysis = std::mem::replace(&mut runtime.analysis, analysis);
-        
-        runtime.exec_program.add_node_to_scheme(new_node, analysis)
+        // Instances that produced reports are executed again to show them
+        let cache_key = cache_key.filter(|_| runtime.runtime_errors.len() == reports_before);
+        add_executed_node(new_node, &public, analysis, cache_key, program_archive, runtime)
     };
     Result::Ok(FoldedValue {
         node_pointer: Option::Some(node_pointer),
@@ -2041,6 +2177,81 @@ fn execute_template_call(
     })
 }
 
+// Adds an executed instance to the program. The threads executing the instances of the
+// pool keep the code as it is, it is cleaned when their nodes are added to the program.
+fn add_executed_node(
+    node: ExecutedTemplate,
+    public: &[String],
+    analysis: Analysis,
+    cache_key: Option<String>,
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation,
+) -> NodePointer {
+    if let Option::Some(summaries) = &mut runtime.summaries {
+        let summary = AnalysisSummary::new(&analysis);
+        let pointer = runtime.exec_program.insert_node(node);
+        if pointer == summaries.len() {
+            summaries.push(summary);
+        }
+        return pointer;
+    }
+    let stored = match (&mut runtime.cache, &cache_key) {
+        (Some(cache), Some(key)) => cache.store(program_archive, key, &node, public, &analysis),
+        _ => false,
+    };
+    let pointer = runtime.exec_program.add_node_to_scheme(node, analysis);
+    if let (true, Some(cache), Some(key)) = (stored, &mut runtime.cache, &cache_key) {
+        cache.register(pointer, key);
+    }
+    pointer
+}
+
+// Adds the nodes of the fragment that are not in the program yet and returns the
+// instance executed
+fn add_fragment(
+    fragment: &Fragment,
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation,
+) -> NodePointer {
+    let mut pointers = Vec::with_capacity(fragment.len());
+    for (node, summary) in fragment {
+        let existent_node = runtime.exec_program.identify_node(
+            node.template_name(),
+            node.parameter_instances(),
+            node.tag_instances(),
+        );
+        let pointer = if let Option::Some(pointer) = existent_node {
+            pointer
+        } else {
+            let mut node = node.clone();
+            node.remap_subcomponents(&pointers);
+            let cache_key = match &mut runtime.cache {
+                Some(cache) => cache.key(
+                    program_archive,
+                    node.template_name(),
+                    node.parameter_instances(),
+                    node.tag_instances(),
+                    &[],
+                ),
+                None => None,
+            };
+            let analysis = summary.to_analysis(program_archive.id_max);
+            add_executed_node(node, &[], analysis, cache_key, program_archive, runtime)
+        };
+        pointers.push(pointer);
+    }
+    *pointers.last().unwrap()
+}
+
+fn instance_parameters(
+    program_archive: &ProgramArchive,
+    id: &str,
+    values: &[AExpressionSlice],
+) -> ParameterContext {
+    let names = program_archive.get_template_data(id).get_name_of_params();
+    names.iter().cloned().zip(values.iter().cloned()).collect()
+}
+
 fn preexecute_template_call(
     id: &str,
     parameter_values: &[AExpressionSlice],
@@ -1,5 +1,6 @@
 use circom_algebra::algebra;
 use num_bigint::BigInt;
+use std::ops::Range;
 
 type AExpr = algebra::ArithmeticExpression<String>;
 
@@ -38,6 +39,41 @@ impl Analysis {
             _ => None,
         }
     }
+
+    pub fn reached_in(analysis: &Analysis, ids: Range<usize>) -> Vec<usize> {
+        ids.filter(|id| analysis.reached[*id]).collect()
+    }
+
+    pub fn computed_in(analysis: &Analysis, ids: Range<usize>) -> Vec<(usize, BigInt)> {
+        ids.filter_map(|id| Analysis::read_computed(analysis, id).map(|value| (id, value)))
+            .collect()
+    }
+}
+
+// The part of an analysis that is not the default, to move it to another thread
+pub struct AnalysisSummary {
+    reached: Vec<usize>,
+    computed: Vec<(usize, BigInt)>,
+}
+impl AnalysisSummary {
+    pub fn new(analysis: &Analysis) -> AnalysisSummary {
+        let ids = 0..analysis.reached.len();
+        AnalysisSummary {
+            reached: Analysis::reached_in(analysis, ids.clone()),
+            computed: Analysis::computed_in(analysis, ids),
+        }
+    }
+
+    pub fn to_analysis(&self, id_max: usize) -> Analysis {
+        let mut analysis = Analysis::new(id_max);
+        for id in &self.reached {
+            Analysis::reached(&mut analysis, *id);
+        }
+        for (id, value) in &self.computed {
+            analysis.computed_values[*id] = ValuePOS::Val(value.clone());
+        }
+        analysis
+    }
 }
 
 #[derive(Clone)]
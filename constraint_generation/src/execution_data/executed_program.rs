@@ -86,7 +86,11 @@ impl ExecutedProgram {
         // Clean code
         apply_unused(&mut node.code, &analysis, &self.prime);
         apply_computed(&mut node.code, &analysis);
-        // Insert template
+        self.insert_node(node)
+    }
+
+    // Inserts a node without cleaning its code
+    pub fn insert_node(&mut self, node: ExecutedTemplate) -> NodePointer {
         let possible_index = self.identify_node(
             node.template_name(),
             node.parameter_instances(),
@@ -144,6 +148,9 @@ impl ExecutedProgram {
         if flags.inspect {
             let mut w = dag.constraint_analysis()?;
             warnings.append(&mut w);
+            for output in dag.underconstrained_outputs() {
+                warnings.push(output.to_report());
+            }
         }
 
         let dag_stats = produce_dags_stats(&dag);
@@ -8,6 +8,7 @@ use num_bigint::BigInt;
 use program_structure::ast::{SignalType, Statement};
 use std::collections::{HashMap, HashSet};
 
+#[derive(Clone)]
 struct Connexion {
     full_name: String,
     inspect: SubComponentData,
@@ -57,6 +58,7 @@ impl PreExecutedTemplate {
     }
 }
 
+#[derive(Clone)]
 pub struct ExecutedTemplate {
     pub code: Statement,
     pub template_name: String,
@@ -215,6 +217,20 @@ impl ExecutedTemplate {
         &self.tag_instances
     }
 
+    pub fn subcomponents(&self) -> Vec<(&String, &SubComponentData)> {
+        self.connexions
+            .iter()
+            .map(|cnn| (&cnn.full_name, &cnn.inspect))
+            .collect()
+    }
+
+    // The subcomponents point to positions of the given list of nodes
+    pub fn remap_subcomponents(&mut self, nodes: &[NodePointer]) {
+        for cnn in &mut self.connexions {
+            cnn.inspect.goes_to = nodes[cnn.inspect.goes_to];
+        }
+    }
+
     pub fn inputs(&self) -> &SignalCollector {
         &self.inputs
     }
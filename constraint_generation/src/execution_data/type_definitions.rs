@@ -11,6 +11,7 @@ pub type TagInfo = BTreeMap<String, Option<BigInt>>;
 // From name to dimensions
 pub type SignalCollector = Vec<(String, Vec<usize>)>;
 pub type ComponentCollector = Vec<(String, Vec<usize>)>;
+#[derive(Clone)]
 pub struct SubComponentData {
     pub name: String,
     pub is_parallel: bool,
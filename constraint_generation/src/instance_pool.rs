@@ -0,0 +1,241 @@
+use super::execution_data::analysis::AnalysisSummary;
+use super::execution_data::type_definitions::{ParameterContext, TagContext};
+use super::execution_data::ExecutedTemplate;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+
+// Nodes added to the program while executing an instance, in the order a sequential
+// execution adds them. The subcomponents point to positions of the list and the instance
+// is the last node.
+pub type Fragment = Vec<(ExecutedTemplate, AnalysisSummary)>;
+
+#[derive(Clone)]
+pub struct Instance {
+    pub name: String,
+    pub parameters: ParameterContext,
+    pub tags: TagContext,
+}
+
+impl Instance {
+    fn is_equal(&self, other: &Instance) -> bool {
+        self.name == other.name && self.parameters == other.parameters && self.tags == other.tags
+    }
+}
+
+// None when the execution failed or produced reports, then it is repeated by the
+// thread that needs the instance so that the reports are the ones of a sequential run
+type Outcome = Option<Arc<Fragment>>;
+
+enum TaskState {
+    Pending,
+    Running,
+    Done(Outcome),
+}
+
+struct Task {
+    instance: Instance,
+    state: TaskState,
+}
+
+#[derive(Default)]
+struct PoolState {
+    tasks: Vec<Task>,
+    tasks_of_template: HashMap<String, Vec<usize>>,
+    queue: VecDeque<usize>,
+    finished: bool,
+}
+
+// Template instances that are executed by the worker threads before the thread
+// executing the program needs them
+#[derive(Default)]
+pub struct InstancePool {
+    state: Mutex<PoolState>,
+    changed: Condvar,
+}
+
+impl InstancePool {
+    pub fn new() -> InstancePool {
+        InstancePool::default()
+    }
+
+    fn position(state: &PoolState, instance: &Instance) -> Option<usize> {
+        let tasks = state.tasks_of_template.get(&instance.name)?;
+        tasks
+            .iter()
+            .copied()
+            .find(|index| state.tasks[*index].instance.is_equal(instance))
+    }
+
+    fn add(state: &mut PoolState, instance: Instance) -> usize {
+        let index = state.tasks.len();
+        state
+            .tasks_of_template
+            .entry(instance.name.clone())
+            .or_default()
+            .push(index);
+        state.tasks.push(Task {
+            instance,
+            state: TaskState::Pending,
+        });
+        index
+    }
+
+    // Queues the instance unless it is already known
+    pub fn submit(&self, instance: Instance) {
+        let mut state = self.state.lock().unwrap();
+        if InstancePool::position(&state, &instance).is_none() {
+            let index = InstancePool::add(&mut state, instance);
+            state.queue.push_back(index);
+            self.changed.notify_all();
+        }
+    }
+
+    // Returns the result of the instance. If no thread has started it, it is executed
+    // with the given function in the calling thread.
+    pub fn obtain<F>(&self, instance: Instance, execute: F) -> Outcome
+    where
+        F: FnOnce(&Instance) -> Outcome,
+    {
+        let mut state = self.state.lock().unwrap();
+        let index = match InstancePool::position(&state, &instance) {
+            Some(index) => index,
+            None => InstancePool::add(&mut state, instance),
+        };
+        loop {
+            match &state.tasks[index].state {
+                TaskState::Done(outcome) => return outcome.clone(),
+                TaskState::Running => state = self.changed.wait(state).unwrap(),
+                TaskState::Pending => break,
+            }
+        }
+        state.tasks[index].state = TaskState::Running;
+        let instance = state.tasks[index].instance.clone();
+        drop(state);
+        let outcome = execute(&instance);
+        self.complete(index, outcome.clone());
+        outcome
+    }
+
+    // Takes the next queued instance that nobody has started, waiting for one until
+    // finish is called
+    pub fn next_task(&self) -> Option<(usize, Instance)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.finished {
+                return None;
+            }
+            while let Some(index) = state.queue.pop_front() {
+                if let TaskState::Pending = state.tasks[index].state {
+                    state.tasks[index].state = TaskState::Running;
+                    return Some((index, state.tasks[index].instance.clone()));
+                }
+            }
+            state = self.changed.wait(state).unwrap();
+        }
+    }
+
+    pub fn complete(&self, index: usize, outcome: Outcome) {
+        let mut state = self.state.lock().unwrap();
+        state.tasks[index].state = TaskState::Done(outcome);
+        self.changed.notify_all();
+    }
+
+    pub fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.finished = true;
+        self.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_circuits::{generate, r1cs, TestDir, SQUARE};
+
+    fn instance(name: &str) -> Instance {
+        Instance {
+            name: name.to_string(),
+            parameters: ParameterContext::new(),
+            tags: TagContext::new(),
+        }
+    }
+
+    fn fragment() -> Outcome {
+        Some(Arc::new(Fragment::new()))
+    }
+
+    fn not_executed(_: &Instance) -> Outcome {
+        panic!("the instance is executed twice")
+    }
+
+    #[test]
+    fn instances_are_executed_once() {
+        let pool = InstancePool::new();
+        let first = pool.obtain(instance("A"), |_| fragment()).unwrap();
+        let second = pool.obtain(instance("A"), not_executed).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // the submitted instances are given once to the workers
+        pool.submit(instance("A"));
+        pool.submit(instance("B"));
+        pool.submit(instance("B"));
+        let (index, task) = pool.next_task().unwrap();
+        assert_eq!(task.name, "B");
+        pool.finish();
+        assert!(pool.next_task().is_none());
+        pool.complete(index, None);
+        // the thread that needs a failed instance executes it again by itself
+        assert!(pool.obtain(instance("B"), not_executed).is_none());
+    }
+
+    #[test]
+    fn wait_for_the_instance_of_a_worker() {
+        let pool = Arc::new(InstancePool::new());
+        pool.submit(instance("A"));
+        let (index, _) = pool.next_task().unwrap();
+        let worker = {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                pool.complete(index, fragment());
+            })
+        };
+        assert!(pool.obtain(instance("A"), not_executed).is_some());
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn execute_instances_in_parallel() {
+        const MAIN: &str = "pragma circom 2.0.0;
+include \"square.circom\";
+template Power(n) {
+    signal input in;
+    signal output out;
+    component s[n];
+    for (var i = 0; i < n; i++) {
+        s[i] = Square();
+        s[i].in <== i == 0 ? in : s[i - 1].out;
+    }
+    out <== s[n - 1].out;
+}
+template Main() {
+    signal input in[4];
+    signal output out[4];
+    component p[4];
+    for (var i = 0; i < 4; i++) {
+        p[i] = Power(i + 1);
+    }
+    for (var i = 0; i < 4; i++) {
+        p[i].in <== in[i];
+        out[i] <== p[i].out;
+    }
+}
+component main = Main();
+";
+        let output = TestDir::new("parallel");
+        let sequential = generate(MAIN, SQUARE, None, 1);
+        let parallel = generate(MAIN, SQUARE, None, 4);
+        assert_eq!(sequential.template_instances, parallel.template_instances);
+        assert_eq!(r1cs(&sequential, &output), r1cs(&parallel, &output));
+    }
+}
@@ -5,6 +5,10 @@ mod compute_constants;
 mod environment_utils;
 mod execute;
 mod execution_data;
+mod instance_pool;
+mod template_cache;
+#[cfg(test)]
+mod test_circuits;
 
 use ansi_term::Colour;
 use circom_algebra::algebra::{ArithmeticError, ArithmeticExpression};
@@ -19,7 +23,9 @@ use program_structure::error_code::ReportCode;
 use program_structure::error_definition::{Report, ReportCollection};
 use program_structure::file_definition::FileID;
 use program_structure::program_archive::ProgramArchive;
+use std::path::PathBuf;
 use std::rc::Rc;
+use template_cache::TemplateCache;
 
 pub struct BuildConfig {
     pub no_rounds: usize,
@@ -31,6 +37,8 @@ pub struct BuildConfig {
     pub flag_old_heuristics: bool,
     pub inspect_constraints: bool,
     pub prime: String,
+    pub cache_dir: Option<PathBuf>,
+    pub threads: usize,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -43,26 +51,81 @@ pub type ConstraintWriter = Box<dyn ConstraintExporter>;
 type BuildResponse = Result<(ConstraintWriter, VCP), ()>;
 pub fn build_circuit(program: ProgramArchive, config: BuildConfig) -> BuildResponse {
     let files = program.file_library.clone();
+    match generate_circuit(program, config) {
+        Result::Ok(circuit) => {
+            let success = Colour::Green.paint("template instances");
+            println!("{}: {}", success, circuit.template_instances);
+            Report::print_reports(&circuit.warnings, &files);
+            Result::Ok((circuit.constraints, circuit.vcp))
+        }
+        Result::Err(errors) => {
+            Report::print_reports(&errors, &files);
+            Result::Err(())
+        }
+    }
+}
+
+pub struct GeneratedCircuit {
+    pub constraints: ConstraintWriter,
+    pub vcp: VCP,
+    pub template_instances: usize,
+    pub warnings: ReportCollection,
+}
+
+// Same as build_circuit, but the reports are returned to the caller instead of printed
+pub fn generate_circuit(
+    program: ProgramArchive,
+    config: BuildConfig,
+) -> Result<GeneratedCircuit, ReportCollection> {
     let flags = FlagsExecution {
         verbose: config.flag_verbose,
         inspect: config.inspect_constraints,
     };
-    let (exe, warnings) = instantiation(&program, flags, &config.prime).map_err(|r| {
-        Report::print_reports(&r, &files);
-    })?;
-    Report::print_reports(&warnings, &files);
-    let (mut dag, mut vcp, warnings) = export(exe, program, flags).map_err(|r| {
-        Report::print_reports(&r, &files);
-    })?;
+    let (exe, mut warnings) = instantiation(&program, flags, &config)?;
+    let template_instances = exe.number_of_nodes();
+    let (mut dag, mut vcp, mut inspect_warnings) = match export(exe, program, flags) {
+        Result::Ok(exported) => exported,
+        Result::Err(mut errors) => {
+            warnings.append(&mut errors);
+            return Result::Err(warnings);
+        }
+    };
     if config.inspect_constraints {
-        Report::print_reports(&warnings, &files);
+        warnings.append(&mut inspect_warnings);
     }
-    if config.flag_f {
+    let constraints: ConstraintWriter = if config.flag_f {
         sync_dag_and_vcp(&mut vcp, &mut dag);
-        Result::Ok((Box::new(dag), vcp))
+        Box::new(dag)
     } else {
-        let list = simplification_process(&mut vcp, dag, &config);
-        Result::Ok((Box::new(list), vcp))
+        Box::new(simplification_process(&mut vcp, dag, &config))
+    };
+    Result::Ok(GeneratedCircuit {
+        constraints,
+        vcp,
+        template_instances,
+        warnings,
+    })
+}
+
+// Executes the program and returns the constraint DAG before any simplification
+pub fn generate_dag(
+    program: ProgramArchive,
+    config: BuildConfig,
+) -> Result<(DAG, ReportCollection), ReportCollection> {
+    let flags = FlagsExecution {
+        verbose: config.flag_verbose,
+        inspect: config.inspect_constraints,
+    };
+    let (exe, mut warnings) = instantiation(&program, flags, &config)?;
+    match export(exe, program, flags) {
+        Result::Ok((dag, _vcp, mut inspect_warnings)) => {
+            warnings.append(&mut inspect_warnings);
+            Result::Ok((dag, warnings))
+        }
+        Result::Err(mut errors) => {
+            warnings.append(&mut errors);
+            Result::Err(warnings)
+        }
     }
 }
 
@@ -70,23 +133,21 @@ type InstantiationResponse = Result<(ExecutedProgram, ReportCollection), ReportC
 fn instantiation(
     program: &ProgramArchive,
     flags: FlagsExecution,
-    prime: &String,
+    config: &BuildConfig,
 ) -> InstantiationResponse {
-    let execution_result = execute::constraint_execution(program, flags, prime);
-    match execution_result {
-        Ok((program_exe, warnings)) => {
-            let no_nodes = program_exe.number_of_nodes();
-            let success = Colour::Green.paint("template instances");
-            let nodes_created = format!("{}: {}", success, no_nodes);
-            println!("{}", &nodes_created);
-            InstantiationResponse::Ok((program_exe, warnings))
+    // The logs of the templates are only printed when they are executed
+    let cache = match &config.cache_dir {
+        Some(directory) if !flags.verbose => {
+            Some(TemplateCache::new(directory, &config.prime, flags))
         }
-        Err(reports) => InstantiationResponse::Err(reports),
-    }
+        _ => None,
+    };
+    // The logs of the templates would be printed in any order
+    let threads = if flags.verbose { 1 } else { config.threads };
+    execute::constraint_execution(program, flags, &config.prime, cache, threads)
 }
 
 fn export(exe: ExecutedProgram, program: ProgramArchive, flags: FlagsExecution) -> ExportResult {
-    
     exe.export(program, flags)
 }
 
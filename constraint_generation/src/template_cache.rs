@@ -0,0 +1,785 @@
+use super::environment_utils::slice_types::{AExpressionSlice, ArithmeticExpression, TagInfo};
+use super::execution_data::analysis::Analysis;
+use super::execution_data::type_definitions::{
+    Constraint, ParameterContext, SignalCollector, SubComponentData, TagContext,
+};
+use super::execution_data::{ExecutedProgram, ExecutedTemplate, NodePointer};
+use crate::FlagsExecution;
+use json::JsonValue;
+use num_bigint::BigInt;
+use program_structure::ast::{Access, Expression, LogArgument, Meta, Statement, VariableType};
+use program_structure::program_archive::ProgramArchive;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Hash of the structure of a body, leaving out the locations, together with the names of
+// the templates and functions it calls and the range of element ids it uses
+struct Fingerprint {
+    hasher: DefaultHasher,
+    calls: BTreeSet<String>,
+    last_id: usize,
+}
+
+impl Fingerprint {
+    fn new() -> Fingerprint {
+        Fingerprint {
+            hasher: DefaultHasher::new(),
+            calls: BTreeSet::new(),
+            last_id: 0,
+        }
+    }
+
+    fn write<T: Hash + ?Sized>(&mut self, value: &T) {
+        value.hash(&mut self.hasher);
+    }
+
+    fn meta(&mut self, meta: &Meta) {
+        self.last_id = usize::max(self.last_id, meta.elem_id);
+    }
+
+    fn statements(&mut self, stmts: &[Statement]) {
+        self.write(&stmts.len());
+        for stmt in stmts {
+            self.statement(stmt);
+        }
+    }
+
+    fn expressions(&mut self, exprs: &[Expression]) {
+        self.write(&exprs.len());
+        for expr in exprs {
+            self.expression(expr);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        self.meta(stmt.get_meta());
+        match stmt {
+            IfThenElse {
+                cond,
+                if_case,
+                else_case,
+                ..
+            } => {
+                self.write(&0u8);
+                self.expression(cond);
+                self.statement(if_case);
+                self.write(&else_case.is_some());
+                if let Some(else_case) = else_case {
+                    self.statement(else_case);
+                }
+            }
+            While { cond, stmt, .. } => {
+                self.write(&1u8);
+                self.expression(cond);
+                self.statement(stmt);
+            }
+            Return { value, .. } => {
+                self.write(&2u8);
+                self.expression(value);
+            }
+            InitializationBlock {
+                xtype,
+                initializations,
+                ..
+            } => {
+                self.write(&3u8);
+                self.variable_type(xtype);
+                self.statements(initializations);
+            }
+            Declaration {
+                xtype,
+                name,
+                dimensions,
+                is_constant,
+                ..
+            } => {
+                self.write(&4u8);
+                self.variable_type(xtype);
+                self.write(name);
+                self.expressions(dimensions);
+                self.write(is_constant);
+            }
+            Substitution {
+                var,
+                access,
+                op,
+                rhe,
+                ..
+            } => {
+                self.write(&5u8);
+                self.write(var);
+                self.accesses(access);
+                self.write(&(*op as u8));
+                self.expression(rhe);
+            }
+            MultSubstitution { lhe, op, rhe, .. } => {
+                self.write(&6u8);
+                self.expression(lhe);
+                self.write(&(*op as u8));
+                self.expression(rhe);
+            }
+            UnderscoreSubstitution { op, rhe, .. } => {
+                self.write(&7u8);
+                self.write(&(*op as u8));
+                self.expression(rhe);
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                self.write(&8u8);
+                self.expression(lhe);
+                self.expression(rhe);
+            }
+            LogCall { args, .. } => {
+                self.write(&9u8);
+                self.write(&args.len());
+                for arg in args {
+                    match arg {
+                        LogArgument::LogStr(value) => self.write(value),
+                        LogArgument::LogExp(expr) => self.expression(expr),
+                    }
+                }
+            }
+            Block { stmts, .. } => {
+                self.write(&10u8);
+                self.statements(stmts);
+            }
+            Assert { arg, .. } => {
+                self.write(&11u8);
+                self.expression(arg);
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        use Expression::*;
+        self.meta(expr.get_meta());
+        match expr {
+            InfixOp {
+                lhe, infix_op, rhe, ..
+            } => {
+                self.write(&0u8);
+                self.expression(lhe);
+                self.write(&(*infix_op as u8));
+                self.expression(rhe);
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                self.write(&1u8);
+                self.write(&(*prefix_op as u8));
+                self.expression(rhe);
+            }
+            InlineSwitchOp {
+                cond,
+                if_true,
+                if_false,
+                ..
+            } => {
+                self.write(&2u8);
+                self.expression(cond);
+                self.expression(if_true);
+                self.expression(if_false);
+            }
+            ParallelOp { rhe, .. } => {
+                self.write(&3u8);
+                self.expression(rhe);
+            }
+            Variable { name, access, .. } => {
+                self.write(&4u8);
+                self.write(name);
+                self.accesses(access);
+            }
+            Number(_, value) => {
+                self.write(&5u8);
+                self.write(value);
+            }
+            Call { id, args, .. } => {
+                self.write(&6u8);
+                self.write(id);
+                self.expressions(args);
+                self.calls.insert(id.clone());
+            }
+            AnonymousComp {
+                id,
+                is_parallel,
+                params,
+                signals,
+                names,
+                ..
+            } => {
+                self.write(&7u8);
+                self.write(id);
+                self.write(is_parallel);
+                self.expressions(params);
+                self.expressions(signals);
+                let names: Vec<_> = names
+                    .iter()
+                    .flatten()
+                    .map(|(op, name)| (*op as u8, name))
+                    .collect();
+                self.write(&names);
+                self.calls.insert(id.clone());
+            }
+            ArrayInLine { values, .. } => {
+                self.write(&8u8);
+                self.expressions(values);
+            }
+            Tuple { values, .. } => {
+                self.write(&9u8);
+                self.expressions(values);
+            }
+            UniformArray {
+                value, dimension, ..
+            } => {
+                self.write(&10u8);
+                self.expression(value);
+                self.expression(dimension);
+            }
+        }
+    }
+
+    fn accesses(&mut self, accesses: &[Access]) {
+        self.write(&accesses.len());
+        for access in accesses {
+            match access {
+                Access::ComponentAccess(name) => self.write(name),
+                Access::ArrayAccess(index) => self.expression(index),
+            }
+        }
+    }
+
+    fn variable_type(&mut self, xtype: &VariableType) {
+        use VariableType::*;
+        match xtype {
+            Var => self.write(&0u8),
+            Signal(signal_type, tags) => {
+                self.write(&1u8);
+                self.write(&(*signal_type as u8));
+                self.write(tags);
+            }
+            Bus(bus, signal_type, tags) => {
+                self.write(&2u8);
+                self.write(&bus.name);
+                self.write(&(*signal_type as u8));
+                self.write(tags);
+            }
+            Component => self.write(&3u8),
+            AnonymousComponent => self.write(&4u8),
+        }
+    }
+}
+
+struct DefinitionInfo {
+    hash: u64,
+    calls: BTreeSet<String>,
+    first_id: usize,
+    last_id: usize,
+}
+
+// Instance read from the cache. The connexions go to positions of the children.
+struct CachedInstance {
+    key: String,
+    file: String,
+    node: ExecutedTemplate,
+    connexions: Vec<(String, SubComponentData)>,
+    children: Vec<CachedInstance>,
+    reached: Vec<usize>,
+    computed: Vec<(usize, BigInt)>,
+}
+
+// On disk cache of the executed template instances. An instance is stored in a file named
+// after the hash of its key: the compiler version, the prime, the structure of the
+// template and of everything it calls, its parameters and the tags of its inputs.
+pub struct TemplateCache {
+    directory: PathBuf,
+    settings: String,
+    definitions: HashMap<String, DefinitionInfo>,
+    source_hashes: HashMap<String, u64>,
+    node_files: HashMap<NodePointer, String>,
+}
+
+impl TemplateCache {
+    pub fn new(directory: &Path, prime: &str, flags: FlagsExecution) -> TemplateCache {
+        TemplateCache {
+            directory: directory.to_path_buf(),
+            settings: format!("circom {} {} inspect={}", VERSION, prime, flags.inspect),
+            definitions: HashMap::new(),
+            source_hashes: HashMap::new(),
+            node_files: HashMap::new(),
+        }
+    }
+
+    fn definition(&mut self, program: &ProgramArchive, name: &str) -> Option<&DefinitionInfo> {
+        if !self.definitions.contains_key(name) {
+            let mut fingerprint = Fingerprint::new();
+            let body = if program.contains_template(name) {
+                let data = program.get_template_data(name);
+                fingerprint.write("template");
+                fingerprint.write(data.get_name_of_params());
+                fingerprint.write(&data.is_parallel());
+                fingerprint.write(&data.is_custom_gate());
+                data.get_body()
+            } else if program.contains_function(name) {
+                let data = program.get_function_data(name);
+                fingerprint.write("function");
+                fingerprint.write(data.get_name_of_params());
+                data.get_body()
+            } else {
+                return None;
+            };
+            fingerprint.statement(body);
+            let info = DefinitionInfo {
+                hash: fingerprint.hasher.finish(),
+                calls: fingerprint.calls,
+                first_id: body.get_meta().elem_id,
+                last_id: fingerprint.last_id,
+            };
+            self.definitions.insert(name.to_string(), info);
+        }
+        self.definitions.get(name)
+    }
+
+    // Combines the hashes of the definition and of every definition it may call
+    fn source_hash(&mut self, program: &ProgramArchive, name: &str) -> u64 {
+        if let Some(hash) = self.source_hashes.get(name) {
+            return *hash;
+        }
+        let mut reached = BTreeSet::new();
+        let mut pending = vec![name.to_string()];
+        while let Some(current) = pending.pop() {
+            if reached.contains(&current) {
+                continue;
+            }
+            if let Some(info) = self.definition(program, &current) {
+                pending.extend(info.calls.iter().cloned());
+                reached.insert(current);
+            }
+        }
+        let mut hasher = DefaultHasher::new();
+        for current in &reached {
+            current.hash(&mut hasher);
+            self.definitions[current].hash.hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+        self.source_hashes.insert(name.to_string(), hash);
+        hash
+    }
+
+    // None if some parameter is not a number
+    pub fn key(
+        &mut self,
+        program: &ProgramArchive,
+        name: &str,
+        parameters: &ParameterContext,
+        tags: &TagContext,
+        public: &[String],
+    ) -> Option<String> {
+        let hash = self.source_hash(program, name);
+        let mut key = format!("{} {} {:016x}", self.settings, name, hash);
+        for (parameter, value) in parameters {
+            let (route, values) = value.clone().destruct();
+            let values = numbers(values)?;
+            key.push_str(&format!(" {}{:?}=", parameter, route));
+            key.push_str(&values.join(","));
+        }
+        for (signal, signal_tags) in tags {
+            for (tag, value) in signal_tags {
+                match value {
+                    Some(value) => key.push_str(&format!(" {}.{}={}", signal, tag, value)),
+                    None => key.push_str(&format!(" {}.{}=null", signal, tag)),
+                }
+            }
+        }
+        if !public.is_empty() {
+            key.push_str(&format!(" public={}", public.join(",")));
+        }
+        Some(key)
+    }
+
+    fn file_name(key: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        format!("{:016x}.json", hasher.finish())
+    }
+
+    // Writes the instance unless one of its subcomponents is not in the cache
+    pub fn store(
+        &mut self,
+        program: &ProgramArchive,
+        key: &str,
+        node: &ExecutedTemplate,
+        public: &[String],
+        analysis: &Analysis,
+    ) -> bool {
+        let mut children: Vec<NodePointer> = Vec::new();
+        let mut connexions = JsonValue::new_array();
+        for (full_name, data) in node.subcomponents() {
+            let child = match children.iter().position(|c| *c == data.goes_to) {
+                Some(position) => position,
+                None if self.node_files.contains_key(&data.goes_to) => {
+                    children.push(data.goes_to);
+                    children.len() - 1
+                }
+                None => return false,
+            };
+            let _ = connexions.push(json::object! {
+                "full_name" => full_name.as_str(),
+                "name" => data.name.as_str(),
+                "is_parallel" => data.is_parallel,
+                "indexed_with" => data.indexed_with.clone(),
+                "goes_to" => child,
+            });
+        }
+        let children: Vec<_> = children
+            .iter()
+            .map(|child| self.node_files[child].clone())
+            .collect();
+        let info = self.definition(program, node.template_name()).unwrap();
+        let ids = info.first_id..info.last_id + 1;
+        let first_id = info.first_id;
+        let reached: Vec<_> = Analysis::reached_in(analysis, ids.clone())
+            .into_iter()
+            .map(|id| id - first_id)
+            .collect();
+        let mut computed = JsonValue::new_array();
+        for (id, value) in Analysis::computed_in(analysis, ids) {
+            let _ = computed.push(json::array![id - first_id, value.to_string()]);
+        }
+        let mut entry = json::object! {
+            "key" => key,
+            "template_name" => node.template_name.as_str(),
+            "report_name" => node.report_name.as_str(),
+            "public" => public.to_vec(),
+            "parameters" => parameters_to_json(&node.parameter_instances),
+            "tags" => tags_to_json(&node.tag_instances),
+            "signal_to_tags" => tags_to_json(&node.signal_to_tags),
+            "inputs" => collector_to_json(&node.inputs),
+            "outputs" => collector_to_json(&node.outputs),
+            "intermediates" => collector_to_json(&node.intermediates),
+            "ordered_signals" => node.ordered_signals.clone(),
+            "components" => collector_to_json(&node.components),
+            "number_of_components" => node.number_of_components,
+            "is_parallel" => node.is_parallel,
+            "has_parallel_sub_cmp" => node.has_parallel_sub_cmp,
+            "is_custom_gate" => node.is_custom_gate,
+            "underscored_signals" => node.underscored_signals.clone(),
+            "children" => children,
+            "reached" => reached,
+            "computed" => computed,
+        };
+        entry["connexions"] = connexions;
+        let mut constraints = JsonValue::new_array();
+        for constraint in &node.constraints {
+            let _ = constraints.push(json::array![
+                linear_to_json(constraint.a()),
+                linear_to_json(constraint.b()),
+                linear_to_json(constraint.c())
+            ]);
+        }
+        entry["constraints"] = constraints;
+        let file = TemplateCache::file_name(key);
+        let path = self.directory.join(&file);
+        let temporary = self.directory.join(format!("{}.tmp", file));
+        std::fs::write(&temporary, entry.dump()).is_ok() && std::fs::rename(temporary, path).is_ok()
+    }
+
+    // Remembers the file of an instance of the program, its parents can be stored now
+    pub fn register(&mut self, node_pointer: NodePointer, key: &str) {
+        self.node_files
+            .insert(node_pointer, TemplateCache::file_name(key));
+    }
+
+    // Adds the instance and the subcomponents it needs to the program if all of them are
+    // in the cache and still valid
+    pub fn load(
+        &mut self,
+        program: &ProgramArchive,
+        key: &str,
+        exec_program: &mut ExecutedProgram,
+    ) -> Option<NodePointer> {
+        let instance = self.read(program, &TemplateCache::file_name(key))?;
+        if instance.key != key {
+            return None;
+        }
+        Some(self.insert(program, instance, exec_program))
+    }
+
+    fn read(&mut self, program: &ProgramArchive, file: &str) -> Option<CachedInstance> {
+        let contents = std::fs::read_to_string(self.directory.join(file)).ok()?;
+        let entry = json::parse(&contents).ok()?;
+        let name = entry["template_name"].as_str()?;
+        if !program.contains_template(name) {
+            return None;
+        }
+        let public = strings(&entry["public"])?;
+        let parameters = parameters_from_json(&entry["parameters"])?;
+        let tags = tags_from_json(&entry["tags"])?;
+        let key = self.key(program, name, &parameters, &tags, &public)?;
+        if entry["key"].as_str() != Some(key.as_str()) {
+            return None;
+        }
+        let data = program.get_template_data(name);
+        let mut node = ExecutedTemplate::new(
+            public,
+            name.to_string(),
+            entry["report_name"].as_str()?.to_string(),
+            parameters,
+            tags,
+            data.get_body().clone(),
+            entry["is_parallel"].as_bool()?,
+            entry["is_custom_gate"].as_bool()?,
+        );
+        node.signal_to_tags = tags_from_json(&entry["signal_to_tags"])?;
+        node.inputs = collector_from_json(&entry["inputs"])?;
+        node.outputs = collector_from_json(&entry["outputs"])?;
+        node.intermediates = collector_from_json(&entry["intermediates"])?;
+        node.ordered_signals = strings(&entry["ordered_signals"])?;
+        node.components = collector_from_json(&entry["components"])?;
+        node.number_of_components = entry["number_of_components"].as_usize()?;
+        node.has_parallel_sub_cmp = entry["has_parallel_sub_cmp"].as_bool()?;
+        node.underscored_signals = strings(&entry["underscored_signals"])?;
+        for constraint in entry["constraints"].members() {
+            node.constraints.push(Constraint::new(
+                linear_from_json(&constraint[0])?,
+                linear_from_json(&constraint[1])?,
+                linear_from_json(&constraint[2])?,
+            ));
+        }
+        let mut connexions = Vec::new();
+        for connexion in entry["connexions"].members() {
+            let data = SubComponentData {
+                name: connexion["name"].as_str()?.to_string(),
+                is_parallel: connexion["is_parallel"].as_bool()?,
+                indexed_with: usizes(&connexion["indexed_with"])?,
+                goes_to: connexion["goes_to"].as_usize()?,
+            };
+            connexions.push((connexion["full_name"].as_str()?.to_string(), data));
+        }
+        let mut children = Vec::new();
+        for child in entry["children"].members() {
+            children.push(self.read(program, child.as_str()?)?);
+        }
+        if connexions
+            .iter()
+            .any(|(_, data)| data.goes_to >= children.len())
+        {
+            return None;
+        }
+        let mut computed = Vec::new();
+        for value in entry["computed"].members() {
+            computed.push((value[0].as_usize()?, value[1].as_str()?.parse().ok()?));
+        }
+        Some(CachedInstance {
+            key,
+            file: file.to_string(),
+            node,
+            connexions,
+            children,
+            reached: usizes(&entry["reached"])?,
+            computed,
+        })
+    }
+
+    fn insert(
+        &mut self,
+        program: &ProgramArchive,
+        instance: CachedInstance,
+        exec_program: &mut ExecutedProgram,
+    ) -> NodePointer {
+        let mut children = Vec::new();
+        for child in instance.children {
+            let existent = exec_program.identify_node(
+                child.node.template_name(),
+                child.node.parameter_instances(),
+                child.node.tag_instances(),
+            );
+            let pointer = match existent {
+                Some(pointer) => pointer,
+                None => self.insert(program, child, exec_program),
+            };
+            children.push(pointer);
+        }
+        let mut node = instance.node;
+        for (full_name, mut data) in instance.connexions {
+            data.goes_to = children[data.goes_to];
+            node.add_arrow(full_name, data);
+        }
+        let first_id = self
+            .definition(program, node.template_name())
+            .unwrap()
+            .first_id;
+        let mut analysis = Analysis::new(program.id_max);
+        for id in instance.reached {
+            Analysis::reached(&mut analysis, first_id + id);
+        }
+        for (id, value) in instance.computed {
+            Analysis::computed(
+                &mut analysis,
+                first_id + id,
+                ArithmeticExpression::Number { value },
+            );
+        }
+        let node_pointer = exec_program.add_node_to_scheme(node, analysis);
+        self.node_files.insert(node_pointer, instance.file);
+        node_pointer
+    }
+}
+
+fn numbers(values: Vec<ArithmeticExpression<String>>) -> Option<Vec<String>> {
+    values
+        .into_iter()
+        .map(|value| match value {
+            ArithmeticExpression::Number { value } => Some(value.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn strings(value: &JsonValue) -> Option<Vec<String>> {
+    value
+        .members()
+        .map(|member| member.as_str().map(str::to_string))
+        .collect()
+}
+
+fn usizes(value: &JsonValue) -> Option<Vec<usize>> {
+    value.members().map(JsonValue::as_usize).collect()
+}
+
+fn parameters_to_json(parameters: &ParameterContext) -> JsonValue {
+    let mut object = JsonValue::new_object();
+    for (name, slice) in parameters {
+        let inserts = AExpressionSlice::get_number_of_inserts(slice);
+        let (route, values) = slice.clone().destruct();
+        object[name.as_str()] = json::object! {
+            "route" => route,
+            "values" => numbers(values).unwrap_or_default(),
+            "inserts" => inserts,
+        };
+    }
+    object
+}
+
+fn parameters_from_json(value: &JsonValue) -> Option<ParameterContext> {
+    let mut parameters = ParameterContext::new();
+    for (name, slice) in value.entries() {
+        let mut values = Vec::new();
+        for number in slice["values"].members() {
+            let value: BigInt = number.as_str()?.parse().ok()?;
+            values.push(ArithmeticExpression::Number { value });
+        }
+        let slice = AExpressionSlice::new_array_with_inserts(
+            usizes(&slice["route"])?,
+            values,
+            slice["inserts"].as_usize()?,
+        );
+        parameters.insert(name.to_string(), slice);
+    }
+    Some(parameters)
+}
+
+fn tags_to_json(tags: &TagContext) -> JsonValue {
+    let mut object = JsonValue::new_object();
+    for (signal, signal_tags) in tags {
+        let mut values = JsonValue::new_object();
+        for (tag, value) in signal_tags {
+            values[tag.as_str()] = match value {
+                Some(value) => value.to_string().into(),
+                None => JsonValue::Null,
+            };
+        }
+        object[signal.as_str()] = values;
+    }
+    object
+}
+
+fn tags_from_json(value: &JsonValue) -> Option<TagContext> {
+    let mut tags = TagContext::new();
+    for (signal, values) in value.entries() {
+        let mut signal_tags = TagInfo::new();
+        for (tag, value) in values.entries() {
+            let value = if value.is_null() {
+                None
+            } else {
+                Some(value.as_str()?.parse().ok()?)
+            };
+            signal_tags.insert(tag.to_string(), value);
+        }
+        tags.insert(signal.to_string(), signal_tags);
+    }
+    Some(tags)
+}
+
+fn collector_to_json(collector: &SignalCollector) -> JsonValue {
+    let mut array = JsonValue::new_array();
+    for (name, dimensions) in collector {
+        let _ = array.push(json::array![name.as_str(), dimensions.clone()]);
+    }
+    array
+}
+
+fn collector_from_json(value: &JsonValue) -> Option<SignalCollector> {
+    value
+        .members()
+        .map(|member| Some((member[0].as_str()?.to_string(), usizes(&member[1])?)))
+        .collect()
+}
+
+fn linear_to_json(linear: &HashMap<String, BigInt>) -> JsonValue {
+    let mut object = JsonValue::new_object();
+    for (signal, value) in linear {
+        object[signal.as_str()] = value.to_string().into();
+    }
+    object
+}
+
+fn linear_from_json(value: &JsonValue) -> Option<HashMap<String, BigInt>> {
+    let mut linear = HashMap::new();
+    for (signal, value) in value.entries() {
+        linear.insert(signal.to_string(), value.as_str()?.parse().ok()?);
+    }
+    Some(linear)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_circuits::{generate, r1cs, TestDir, SQUARE};
+
+    const MAIN: &str = "pragma circom 2.0.0;
+include \"square.circom\";
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c;
+    component s = Square();
+    s.in <== a * b;
+    c <== s.out;
+}
+component main {public [a]} = Main();
+";
+
+    #[test]
+    fn reuse_cached_template_instances() {
+        let cache = TestDir::new("cache");
+        let output = TestDir::new("cache_output");
+        let first = generate(MAIN, SQUARE, Some(&cache.0), 1);
+        assert_eq!(cache.files(), 2);
+        let second = generate(MAIN, SQUARE, Some(&cache.0), 1);
+        assert_eq!(cache.files(), 2);
+        assert_eq!(r1cs(&first, &output), r1cs(&second, &output));
+
+        // the instance of Square is reused when only Main changes
+        let main = MAIN.replace("c <== s.out;", "c <== s.out + a;");
+        let cached = generate(&main, SQUARE, Some(&cache.0), 1);
+        assert_eq!(cache.files(), 3);
+        let executed = generate(&main, SQUARE, None, 1);
+        assert_eq!(r1cs(&cached, &output), r1cs(&executed, &output));
+
+        // a change in Square changes the key of the instances that use it
+        let square = SQUARE.replace("in * in;", "in * in + 1;");
+        generate(MAIN, &square, Some(&cache.0), 1);
+        assert_eq!(cache.files(), 5);
+    }
+}
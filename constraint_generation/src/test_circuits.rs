@@ -0,0 +1,81 @@
+// Compilation of the circuits of the tests of the cache and of the parallel execution
+use super::{generate_circuit, BuildConfig, GeneratedCircuit};
+use parser::source_provider::MemoryProvider;
+use std::path::{Path, PathBuf};
+
+pub const SQUARE: &str = "pragma circom 2.0.0;
+template Square() {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}
+";
+
+// A directory of the test, removed when the test ends
+pub struct TestDir(pub PathBuf);
+
+impl TestDir {
+    pub fn new(test: &str) -> TestDir {
+        let dir = std::env::temp_dir().join(format!(
+            "constraint_generation_{}_{}",
+            test,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+
+    pub fn files(&self) -> usize {
+        std::fs::read_dir(&self.0).unwrap().count()
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// The main file includes square.circom
+pub fn generate(
+    main: &str,
+    square: &str,
+    cache_dir: Option<&Path>,
+    threads: usize,
+) -> GeneratedCircuit {
+    let mut provider = MemoryProvider::new();
+    provider.add_file("main.circom", main.to_string());
+    provider.add_file("square.circom", square.to_string());
+    let (mut program, _) =
+        parser::run_parser_with_provider("main.circom".to_string(), "2.1.6", Vec::new(), &provider)
+            .ok()
+            .unwrap();
+    type_analysis::check_types::check_types(&mut program)
+        .ok()
+        .unwrap();
+    let config = BuildConfig {
+        no_rounds: usize::MAX,
+        flag_json_sub: false,
+        flag_s: false,
+        flag_f: false,
+        flag_p: false,
+        flag_verbose: false,
+        flag_old_heuristics: false,
+        inspect_constraints: false,
+        prime: "bn128".to_string(),
+        cache_dir: cache_dir.map(Path::to_path_buf),
+        threads,
+    };
+    generate_circuit(program, config).ok().unwrap()
+}
+
+// The bytes of the r1cs file of the circuit
+pub fn r1cs(circuit: &GeneratedCircuit, dir: &TestDir) -> Vec<u8> {
+    let file = dir.0.join("circuit.r1cs");
+    let file = file.to_str().unwrap();
+    circuit.constraints.r1cs(file, false).unwrap();
+    let bytes = std::fs::read(file).unwrap();
+    std::fs::remove_file(file).unwrap();
+    bytes
+}
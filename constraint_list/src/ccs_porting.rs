@@ -0,0 +1,56 @@
+use super::{ConstraintList, EncodingIterator, IteratorSignal, SignalMap, C};
+use constraint_writers::ccs_writer::{CCSEncoding, CCSHeader, CCSSignal, CCSWriter};
+use std::io::{Seek, Write};
+
+// Writes the simplified constraints as a circom constraint system file (ccs)
+pub fn port_ccs<W: Write + Seek>(
+    list: &ConstraintList,
+    output: W,
+    encoding: CCSEncoding,
+) -> Result<W, ()> {
+    let header = CCSHeader {
+        field: list.field.clone(),
+        wires: ConstraintList::no_wires(list),
+        public_outputs: list.no_public_outputs,
+        public_inputs: list.no_public_inputs,
+        private_inputs: list.no_private_inputs,
+    };
+    let mut ccs = CCSWriter::from_writer(output, encoding, &header)?;
+    for c_id in list.constraints.get_ids() {
+        let c = list.constraints.read_constraint(c_id).unwrap();
+        let c = C::apply_correspondence(&c, &list.signal_map);
+        ccs.write_constraint(c.a(), c.b(), c.c())?;
+    }
+    let iter = EncodingIterator::new(&list.dag_encoding);
+    signal_iteration(iter, &list.signal_map, &mut ccs)?;
+    CCSWriter::finish_writing(ccs)
+}
+
+fn signal_iteration<W: Write + Seek>(
+    mut iter: EncodingIterator,
+    map: &SignalMap,
+    ccs: &mut CCSWriter<W>,
+) -> Result<(), ()> {
+    let (signals, _) = EncodingIterator::take(&mut iter);
+
+    for signal in signals {
+        let signal = IteratorSignal::new(signal, map);
+        let ccs_signal = CCSSignal {
+            label: signal.original,
+            wire: if signal.witness == map.len() {
+                None
+            } else {
+                Some(signal.witness)
+            },
+            component: iter.node_id,
+            name: signal.name.clone(),
+        };
+        ccs.write_signal(&ccs_signal)?;
+    }
+
+    for edge in EncodingIterator::edges(&iter) {
+        let next = EncodingIterator::next(&iter, edge);
+        signal_iteration(next, map, ccs)?;
+    }
+    Ok(())
+}
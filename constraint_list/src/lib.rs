@@ -2,13 +2,17 @@ use std::collections::{HashMap, HashSet, LinkedList};
 
 use circom_algebra::constraint_storage::ConstraintStorage;
 use circom_algebra::num_bigint::BigInt;
+use constraint_writers::ccs_writer::CCSEncoding;
 use constraint_writers::debug_writer::DebugWriter;
-use constraint_writers::ConstraintExporter;
+use constraint_writers::{create_file, ConstraintExporter};
+use std::io::Cursor;
 
+mod ccs_porting;
 mod constraint_simplification;
 mod json_porting;
 mod non_linear_simplification;
 mod non_linear_utils;
+mod plonk_porting;
 mod r1cs_porting;
 mod state_utils;
 mod sym_porting;
@@ -171,7 +175,15 @@ pub struct ConstraintList {
 
 impl ConstraintExporter for ConstraintList {
     fn r1cs(&self, out: &str, custom_gates: bool) -> Result<(), ()> {
-        r1cs_porting::port_r1cs(self, out, custom_gates)
+        r1cs_porting::port_r1cs(self, create_file(out)?, custom_gates).map(|_| ())
+    }
+
+    fn plonk(&self, out: &str, custom_gates: bool) -> Result<(), ()> {
+        plonk_porting::port_plonk(self, create_file(out)?, custom_gates).map(|_| ())
+    }
+
+    fn ccs(&self, out: &str, encoding: CCSEncoding) -> Result<(), ()> {
+        ccs_porting::port_ccs(self, create_file(out)?, encoding).map(|_| ())
     }
 
     fn json_constraints(&self, writer: &DebugWriter) -> Result<(), ()> {
@@ -179,7 +191,23 @@ impl ConstraintExporter for ConstraintList {
     }
 
     fn sym(&self, out: &str) -> Result<(), ()> {
-        sym_porting::port_sym(self, out)
+        sym_porting::port_sym(self, create_file(out)?).map(|_| ())
+    }
+
+    fn r1cs_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()> {
+        r1cs_porting::port_r1cs(self, Cursor::new(Vec::new()), custom_gates).map(Cursor::into_inner)
+    }
+
+    fn plonk_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()> {
+        plonk_porting::port_plonk(self, Vec::new(), custom_gates)
+    }
+
+    fn ccs_bytes(&self, encoding: CCSEncoding) -> Result<Vec<u8>, ()> {
+        ccs_porting::port_ccs(self, Cursor::new(Vec::new()), encoding).map(Cursor::into_inner)
+    }
+
+    fn sym_bytes(&self) -> Result<Vec<u8>, ()> {
+        sym_porting::port_sym(self, Vec::new())
     }
 }
 
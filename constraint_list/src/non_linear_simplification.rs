@@ -1,11 +1,457 @@
-use circom_algebra::constraint_storage::ConstraintStorage;
+use super::C;
+use circom_algebra::constraint_storage::{ConstraintID, ConstraintStorage};
+use circom_algebra::modular_arithmetic;
 use circom_algebra::num_bigint::BigInt;
-use std::collections::{HashSet, LinkedList};
+use circom_algebra::num_traits::Zero;
+use std::collections::{BTreeMap, HashMap, HashSet, LinkedList};
+use std::hash::Hash;
+use std::ops::Bound::{Excluded, Unbounded};
+use std::sync::Arc;
 
+// A monomial is the product of two signals, the constant signal 0 stands for 1. In this
+// way (0, 0) is the constant term, (0, s) the term of s and (s, t) with s <= t the term of
+// s * t. The order of the tuples puts every quadratic monomial after the linear ones.
+type Monomial = (usize, usize);
+// The constraint A * B - C = 0 written as the sum of its monomials
+type Polynomial = BTreeMap<Monomial, BigInt>;
+
+fn monomial(s: usize, t: usize) -> Monomial {
+    if s <= t {
+        (s, t)
+    } else {
+        (t, s)
+    }
+}
+
+fn is_quadratic(monomial: &Monomial) -> bool {
+    monomial.0 != 0
+}
+
+fn add_term(polynomial: &mut Polynomial, key: Monomial, value: &BigInt, field: &BigInt) {
+    let prev = polynomial.remove(&key).unwrap_or_else(BigInt::zero);
+    let next = modular_arithmetic::add(&prev, value, field);
+    if !next.is_zero() {
+        polynomial.insert(key, next);
+    }
+}
+
+// The products are added up first and each sum is reduced once, since reducing every product
+// takes most of the time of the pass
+fn expand(constraint: &C, field: &BigInt) -> Polynomial {
+    let mut sums: HashMap<Monomial, BigInt> = HashMap::new();
+    for (s, a) in constraint.a() {
+        for (t, b) in constraint.b() {
+            *sums.entry(monomial(*s, *t)).or_insert_with(BigInt::zero) += a * b;
+        }
+    }
+    for (s, c) in constraint.c() {
+        *sums.entry(monomial(0, *s)).or_insert_with(BigInt::zero) -= c;
+    }
+    sums.into_iter()
+        .map(|(key, sum)| (key, ((sum % field) + field) % field))
+        .filter(|(_, value)| !value.is_zero())
+        .collect()
+}
+
+// An element with a key that no other element has can not be part of a linear combination
+// that adds up to zero, and removing it may leave other keys alone. Returns which elements
+// remain after removing those, usually a small part of the input.
+fn dependent<K: Copy + Eq + Hash>(elements: &[Vec<K>]) -> Vec<bool> {
+    // The number of elements that have each key and the sum of their indexes, which is the
+    // index of the last one when a single element is left
+    let mut holders: HashMap<K, (usize, usize)> = HashMap::new();
+    for (index, keys) in elements.iter().enumerate() {
+        for key in keys {
+            let holder = holders.entry(*key).or_insert((0, 0));
+            holder.0 += 1;
+            holder.1 += index;
+        }
+    }
+    let mut pending: Vec<usize> = holders
+        .values()
+        .filter(|(count, _)| *count == 1)
+        .map(|(_, index)| *index)
+        .collect();
+    let mut remaining = vec![true; elements.len()];
+    while let Some(index) = pending.pop() {
+        if !remaining[index] {
+            continue;
+        }
+        remaining[index] = false;
+        for key in &elements[index] {
+            let holder = holders.get_mut(key).unwrap();
+            holder.0 -= 1;
+            holder.1 -= index;
+            if holder.0 == 1 {
+                pending.push(holder.1);
+            }
+        }
+    }
+    remaining
+}
+
+// Groups the constraints that share quadratic monomials. The quadratic part of a linear
+// combination of constraints from different groups can not cancel.
+fn build_clusters(
+    polynomials: Vec<(ConstraintID, Polynomial)>,
+) -> Vec<Vec<(ConstraintID, Polynomial)>> {
+    fn find(parent: &mut [usize], org: usize) -> usize {
+        let mut current = org;
+        while parent[current] != current {
+            parent[current] = parent[parent[current]];
+            current = parent[current];
+        }
+        current
+    }
+
+    let mut parent: Vec<usize> = (0..polynomials.len()).collect();
+    let mut monomial_to_polynomial: HashMap<Monomial, usize> = HashMap::new();
+    for (index, (_, polynomial)) in polynomials.iter().enumerate() {
+        for key in polynomial.keys().filter(|key| is_quadratic(key)) {
+            if let Some(prev) = monomial_to_polynomial.insert(*key, index) {
+                let root_prev = find(&mut parent, prev);
+                let root_index = find(&mut parent, index);
+                parent[root_prev] = root_index;
+            }
+        }
+    }
+    let mut root_to_cluster = HashMap::new();
+    let mut clusters: Vec<Vec<(ConstraintID, Polynomial)>> = Vec::new();
+    for (index, entry) in polynomials.into_iter().enumerate() {
+        let root = find(&mut parent, index);
+        let cluster = *root_to_cluster.entry(root).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(entry);
+    }
+    clusters
+}
+
+// Gaussian elimination over the monomials of the cluster, the constraints that reduce to
+// zero are linear combinations of the ones processed before them
+fn redundant_in_cluster(
+    cluster: Vec<(ConstraintID, Polynomial)>,
+    field: &BigInt,
+) -> Vec<ConstraintID> {
+    let mut redundant = Vec::new();
+    // Rows indexed by their greatest monomial, whose coefficient is 1
+    let mut basis: HashMap<Monomial, Polynomial> = HashMap::new();
+    for (id, mut row) in cluster {
+        let mut bound = None;
+        loop {
+            let range = match bound {
+                Some(key) => row.range((Unbounded, Excluded(key))),
+                None => row.range(..),
+            };
+            let pivot = range
+                .rev()
+                .find(|(key, _)| basis.contains_key(key))
+                .map(|(key, value)| (*key, value.clone()));
+            let (key, value) = match pivot {
+                Some(pivot) => pivot,
+                None => break,
+            };
+            let factor = modular_arithmetic::prefix_sub(&value, field);
+            for (other, coefficient) in &basis[&key] {
+                let term = modular_arithmetic::mul(&factor, coefficient, field);
+                add_term(&mut row, *other, &term, field);
+            }
+            bound = Some(key);
+        }
+        match row
+            .iter()
+            .next_back()
+            .map(|(key, value)| (*key, value.clone()))
+        {
+            None => redundant.push(id),
+            Some((key, value)) => {
+                // The value is not zero, so it has an inverse
+                let inverse = modular_arithmetic::div(&BigInt::from(1), &value, field)
+                    .ok()
+                    .unwrap();
+                for coefficient in row.values_mut() {
+                    *coefficient = modular_arithmetic::mul(coefficient, &inverse, field);
+                }
+                basis.insert(key, row);
+            }
+        }
+    }
+    redundant
+}
+
+fn find_redundant(
+    polynomials: Vec<(ConstraintID, Polynomial)>,
+    field: &BigInt,
+    parallel_flag: bool,
+) -> Vec<ConstraintID> {
+    use std::sync::mpsc;
+    use threadpool::ThreadPool;
+    let monomials: Vec<Vec<_>> = polynomials
+        .iter()
+        .map(|(_, polynomial)| polynomial.keys().cloned().collect())
+        .collect();
+    let polynomials: Vec<_> = polynomials
+        .into_iter()
+        .zip(dependent(&monomials))
+        .filter(|(_, dependent)| *dependent)
+        .map(|(entry, _)| entry)
+        .collect();
+    let clusters = build_clusters(polynomials);
+    let mut redundant = Vec::new();
+    if !parallel_flag {
+        for cluster in clusters {
+            redundant.append(&mut redundant_in_cluster(cluster, field));
+        }
+        return redundant;
+    }
+    let field = Arc::new(field.clone());
+    let (cluster_tx, simplified_rx) = mpsc::channel();
+    let pool = ThreadPool::new(num_cpus::get());
+    let mut no_jobs = 0;
+    for cluster in clusters {
+        if cluster.len() == 1 {
+            redundant.append(&mut redundant_in_cluster(cluster, &field));
+        } else {
+            let cluster_tx = cluster_tx.clone();
+            let field = Arc::clone(&field);
+            let job = move || {
+                let result = redundant_in_cluster(cluster, &field);
+                cluster_tx.send(result).unwrap();
+            };
+            ThreadPool::execute(&pool, job);
+            no_jobs += 1;
+        }
+    }
+    ThreadPool::join(&pool);
+    for _ in 0..no_jobs {
+        let mut result = simplified_rx.recv().unwrap();
+        redundant.append(&mut result);
+    }
+    redundant.sort_unstable();
+    redundant
+}
+
+// A signal that only appears in the C part of a single constraint takes a value for any
+// value of the rest of the signals, so the constraint only computes it. The signal and
+// the constraint can be removed together.
+fn determined_signal(constraint: &C, signal: usize) -> bool {
+    !constraint.a().contains_key(&signal)
+        && !constraint.b().contains_key(&signal)
+        && constraint
+            .c()
+            .get(&signal)
+            .is_some_and(|value| !value.is_zero())
+}
+
+fn remove_determined_signals(
+    storage: &mut ConstraintStorage,
+    mut constraints: HashMap<ConstraintID, C>,
+    forbidden: &HashSet<usize>,
+) -> LinkedList<usize> {
+    let mut erased = LinkedList::new();
+    let mut signal_to_constraints: HashMap<usize, HashSet<ConstraintID>> = HashMap::new();
+    let mut ids: Vec<_> = constraints.keys().cloned().collect();
+    ids.sort_unstable();
+    for id in ids {
+        for signal in C::take_cloned_signals(&constraints[&id]) {
+            signal_to_constraints.entry(signal).or_default().insert(id);
+        }
+    }
+    let mut pending: Vec<usize> = signal_to_constraints.keys().cloned().collect();
+    pending.sort_unstable_by(|a, b| b.cmp(a));
+    while let Some(signal) = pending.pop() {
+        if signal == C::constant_coefficient() || forbidden.contains(&signal) {
+            continue;
+        }
+        let id = match signal_to_constraints.get(&signal) {
+            Some(ids) if ids.len() == 1 => *ids.iter().next().unwrap(),
+            _ => continue,
+        };
+        if !determined_signal(&constraints[&id], signal) {
+            continue;
+        }
+        let constraint = constraints.remove(&id).unwrap();
+        storage.replace(id, C::empty());
+        erased.push_back(signal);
+        // The rest of the signals of the constraint may be determined now
+        for other in C::take_cloned_signals(&constraint) {
+            if let Some(ids) = signal_to_constraints.get_mut(&other) {
+                ids.remove(&id);
+                if other != signal && ids.len() == 1 {
+                    pending.push(other);
+                }
+            }
+        }
+    }
+    erased
+}
+
+// Removes the non-linear constraints that are linear combinations of other constraints,
+// and the signals that a single constraint determines together with that constraint.
+// The removed constraints are left empty in the storage, the removed signals are returned.
 pub fn simplify(
-    _storage: &mut ConstraintStorage,
-    _forbidden: &HashSet<usize>,
-    _field: &BigInt,
+    storage: &mut ConstraintStorage,
+    forbidden: &HashSet<usize>,
+    field: &BigInt,
+    parallel_flag: bool,
 ) -> LinkedList<usize> {
-    LinkedList::new()
+    let mut constraints = HashMap::new();
+    let mut non_linear = Vec::new();
+    for id in storage.get_ids() {
+        let constraint = storage.read_constraint(id).unwrap();
+        if C::is_empty(&constraint) {
+            continue;
+        }
+        if !C::is_linear(&constraint) {
+            non_linear.push(id);
+        }
+        constraints.insert(id, constraint);
+    }
+    // Expanding every constraint takes long, so the ones that have a signal which no other
+    // constraint has are discarded first. A few of them could still be part of a combination
+    // that adds up to zero, when the terms of the signal cancel out in the expansion, but
+    // that only leaves some redundant constraint in place.
+    let signals: Vec<Vec<_>> = non_linear
+        .iter()
+        .map(|id| {
+            C::take_cloned_signals(&constraints[id])
+                .into_iter()
+                .collect()
+        })
+        .collect();
+    let polynomials = non_linear
+        .into_iter()
+        .zip(dependent(&signals))
+        .filter(|(_, dependent)| *dependent)
+        .map(|(id, _)| (id, expand(&constraints[&id], field)))
+        .collect();
+    for id in find_redundant(polynomials, field, parallel_flag) {
+        storage.replace(id, C::empty());
+        constraints.remove(&id);
+    }
+    remove_determined_signals(storage, constraints, forbidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint(a: &[(usize, i64)], b: &[(usize, i64)], c: &[(usize, i64)]) -> C {
+        let to_map = |terms: &[(usize, i64)]| {
+            terms
+                .iter()
+                .map(|(signal, value)| (*signal, BigInt::from(*value)))
+                .collect()
+        };
+        C::new(to_map(a), to_map(b), to_map(c))
+    }
+
+    fn storage(constraints: Vec<C>) -> ConstraintStorage {
+        let mut storage = ConstraintStorage::new();
+        for constraint in constraints {
+            storage.add_constraint(constraint);
+        }
+        storage
+    }
+
+    fn remaining(storage: &ConstraintStorage) -> Vec<ConstraintID> {
+        storage
+            .get_ids()
+            .into_iter()
+            .filter(|id| !storage.read_constraint(*id).unwrap().is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn removes_redundant_constraints_and_determined_signals() {
+        let field = BigInt::from(101);
+        let forbidden: HashSet<usize> = vec![0, 1, 2, 3].into_iter().collect();
+        let mut storage = storage(vec![
+            // s1 * s2 = s3
+            constraint(&[(1, 1)], &[(2, 1)], &[(3, 1)]),
+            // 2 * s1 * s2 = 2 * s3, twice the previous one
+            constraint(&[(1, 2)], &[(2, 1)], &[(3, 2)]),
+            // s1 * s2 + 1 = s4, where s4 is not used anywhere else
+            constraint(&[(1, 1)], &[(2, 1)], &[(4, 1), (0, -1)]),
+            // s1 * s3 = s2, it can not be removed
+            constraint(&[(1, 1)], &[(3, 1)], &[(2, 1)]),
+        ]);
+
+        let erased = simplify(&mut storage, &forbidden, &field, false);
+        assert_eq!(erased.into_iter().collect::<Vec<_>>(), vec![4]);
+        assert_eq!(remaining(&storage), vec![0, 3]);
+    }
+
+    #[test]
+    fn parallel_search_removes_the_same_constraints() {
+        let field = BigInt::from(101);
+        let forbidden: HashSet<usize> = (0..10).collect();
+        let constraints = || {
+            vec![
+                // s1 * s2 = s3, s1 * s4 = s5 and their sum s1 * (s2 + s4) = s3 + s5
+                constraint(&[(1, 1)], &[(2, 1)], &[(3, 1)]),
+                constraint(&[(1, 1)], &[(4, 1)], &[(5, 1)]),
+                constraint(&[(1, 1)], &[(2, 1), (4, 1)], &[(3, 1), (5, 1)]),
+                // s6 * s7 = s8 and 3 * s6 * s7 = 3 * s8
+                constraint(&[(6, 1)], &[(7, 1)], &[(8, 1)]),
+                constraint(&[(6, 3)], &[(7, 1)], &[(8, 3)]),
+                // s6 * s9 = s2, it can not be removed
+                constraint(&[(6, 1)], &[(9, 1)], &[(2, 1)]),
+            ]
+        };
+        let mut serial = storage(constraints());
+        let mut parallel = storage(constraints());
+
+        let serial_erased = simplify(&mut serial, &forbidden, &field, false);
+        let parallel_erased = simplify(&mut parallel, &forbidden, &field, true);
+        assert!(serial_erased.is_empty() && parallel_erased.is_empty());
+        assert_eq!(remaining(&serial), vec![0, 1, 3, 5]);
+        assert_eq!(remaining(&parallel), remaining(&serial));
+    }
+
+    #[test]
+    fn keeps_forbidden_signals_and_their_constraints() {
+        let field = BigInt::from(101);
+        let forbidden: HashSet<usize> = vec![0, 1, 2, 3].into_iter().collect();
+        let mut storage = storage(vec![
+            // s1 * s2 = s3, where s3 is only used here but it is forbidden
+            constraint(&[(1, 1)], &[(2, 1)], &[(3, 1)]),
+            // s1 * s1 = s4, where s4 is only used here
+            constraint(&[(1, 1)], &[(1, 1)], &[(4, 1)]),
+        ]);
+
+        let erased = simplify(&mut storage, &forbidden, &field, false);
+        assert_eq!(erased.into_iter().collect::<Vec<_>>(), vec![4]);
+        assert_eq!(remaining(&storage), vec![0]);
+    }
+
+    #[test]
+    fn removes_signals_determined_after_removing_others() {
+        let field = BigInt::from(101);
+        let forbidden: HashSet<usize> = vec![0, 1, 2].into_iter().collect();
+        let mut storage = storage(vec![
+            // s1 * s2 = s3, s3 is used in the next constraint too
+            constraint(&[(1, 1)], &[(2, 1)], &[(3, 1)]),
+            // s3 * s3 = s4, removing it leaves s3 determined by the previous one
+            constraint(&[(3, 1)], &[(3, 1)], &[(4, 1)]),
+        ]);
+
+        let erased = simplify(&mut storage, &forbidden, &field, false);
+        assert_eq!(erased.into_iter().collect::<Vec<_>>(), vec![4, 3]);
+        assert!(remaining(&storage).is_empty());
+    }
+
+    #[test]
+    fn dependent_discards_elements_with_keys_left_alone() {
+        // The key 5 is alone, removing its element leaves the key 4 alone and then the key 3
+        let elements = vec![
+            vec![1, 2],
+            vec![1, 2],
+            vec![1, 2, 3],
+            vec![3, 4],
+            vec![4, 5],
+        ];
+        assert_eq!(dependent(&elements), vec![true, true, false, false, false]);
+    }
 }
@@ -0,0 +1,23 @@
+use super::{ConstraintList, C};
+use constraint_writers::plonk_writer::{PlonkHeader, PlonkWriter};
+use std::io::Write;
+
+pub fn port_plonk<W: Write>(list: &ConstraintList, output: W, custom_gates: bool) -> Result<W, ()> {
+    let mut plonk =
+        PlonkWriter::from_writer(output, list.field.clone(), ConstraintList::no_wires(list));
+    for c_id in list.constraints.get_ids() {
+        let c = list.constraints.read_constraint(c_id).unwrap();
+        let c = C::apply_correspondence(&c, &list.signal_map);
+        plonk.write_constraint_usize(c.a(), c.b(), c.c());
+    }
+    if custom_gates {
+        let (usage_data, application_data) = super::r1cs_porting::custom_gates_data(list);
+        plonk.write_custom_gates(usage_data, application_data);
+    }
+    let header = PlonkHeader {
+        public_outputs: list.no_public_outputs,
+        public_inputs: list.no_public_inputs,
+        private_inputs: list.no_private_inputs,
+    };
+    PlonkWriter::finish_writing(plonk, header)
+}
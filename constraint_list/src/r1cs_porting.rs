@@ -1,9 +1,15 @@
 use super::{ConstraintList, EncodingIterator, SignalMap, C};
 use constraint_writers::r1cs_writer::{
-    ConstraintSection, CustomGatesAppliedData, HeaderData, R1CSWriter, SignalSection,
+    ConstraintSection, CustomGatesAppliedData, CustomGatesUsedData, HeaderData, R1CSWriter,
+    SignalSection,
 };
+use std::io::{Seek, Write};
 
-pub fn port_r1cs(list: &ConstraintList, output: &str, custom_gates: bool) -> Result<(), ()> {
+pub fn port_r1cs<W: Write + Seek>(
+    list: &ConstraintList,
+    output: W,
+    custom_gates: bool,
+) -> Result<W, ()> {
     use constraint_writers::log_writer::Log;
     let field_size = if list.field.bits() % 64 == 0 {
         list.field.bits() / 8
@@ -17,7 +23,7 @@ pub fn port_r1cs(list: &ConstraintList, output: &str, custom_gates: bool) -> Res
     log.no_public_inputs = list.no_public_inputs;
     log.no_public_outputs = list.no_public_outputs;
 
-    let r1cs = R1CSWriter::new(output.to_string(), field_size, custom_gates)?;
+    let r1cs = R1CSWriter::from_writer(output, field_size, custom_gates)?;
     let mut constraint_section = R1CSWriter::start_constraints_section(r1cs)?;
     let mut written = 0;
 
@@ -52,75 +58,82 @@ pub fn port_r1cs(list: &ConstraintList, output: &str, custom_gates: bool) -> Res
         SignalSection::write_signal_usize(&mut signal_section, id)?;
     }
     let r1cs = signal_section.end_section()?;
-    if !custom_gates {
-        R1CSWriter::finish_writing(r1cs)?;
+    let output = if !custom_gates {
+        R1CSWriter::finish_writing(r1cs)?
     } else {
+        let (usage_data, application_data) = custom_gates_data(list);
         let mut custom_gates_used_section = R1CSWriter::start_custom_gates_used_section(r1cs)?;
-        let (usage_data, occurring_order) = {
-            let mut usage_data = vec![];
-            let mut occurring_order = vec![];
-            for node in &list.dag_encoding.nodes {
-                if node.is_custom_gate {
-                    let mut name = node.name.clone();
-                    occurring_order.push(name.clone());
-                    while name.pop() != Some('(') {}
-                    usage_data.push((name, node.parameters.clone()));
-                }
-            }
-            (usage_data, occurring_order)
-        };
         custom_gates_used_section.write_custom_gates_usages(usage_data)?;
         let r1cs = custom_gates_used_section.end_section()?;
 
         let mut custom_gates_applied_section =
             R1CSWriter::start_custom_gates_applied_section(r1cs)?;
-        let application_data = {
-            fn find_indexes(
-                occurring_order: Vec<String>,
-                application_data: Vec<(String, Vec<usize>)>,
-            ) -> CustomGatesAppliedData {
-                let mut new_application_data = vec![];
-                for (custom_gate_name, signals) in application_data {
-                    let mut index = 0;
-                    while occurring_order[index] != custom_gate_name {
-                        index += 1;
-                    }
-                    new_application_data.push((index, signals));
+        custom_gates_applied_section.write_custom_gates_applications(application_data)?;
+        let r1cs = custom_gates_applied_section.end_section()?;
+        R1CSWriter::finish_writing(r1cs)?
+    };
+    Log::print(&log);
+    Ok(output)
+}
+
+// The custom gates used by the circuit and their applications, in the format of the
+// sections 4 and 5 of the r1cs files
+pub fn custom_gates_data(list: &ConstraintList) -> (CustomGatesUsedData, CustomGatesAppliedData) {
+    let (usage_data, occurring_order) = {
+        let mut usage_data = vec![];
+        let mut occurring_order = vec![];
+        for node in &list.dag_encoding.nodes {
+            if node.is_custom_gate {
+                let mut name = node.name.clone();
+                occurring_order.push(name.clone());
+                while name.pop() != Some('(') {}
+                usage_data.push((name, node.parameters.clone()));
+            }
+        }
+        (usage_data, occurring_order)
+    };
+    let application_data = {
+        fn find_indexes(
+            occurring_order: Vec<String>,
+            application_data: Vec<(String, Vec<usize>)>,
+        ) -> CustomGatesAppliedData {
+            let mut new_application_data = vec![];
+            for (custom_gate_name, signals) in application_data {
+                let mut index = 0;
+                while occurring_order[index] != custom_gate_name {
+                    index += 1;
                 }
-                new_application_data
+                new_application_data.push((index, signals));
             }
+            new_application_data
+        }
 
-            fn iterate(
-                iterator: EncodingIterator,
-                map: &SignalMap,
-                application_data: &mut Vec<(String, Vec<usize>)>,
-            ) {
-                let node = &iterator.encoding.nodes[iterator.node_id];
-                if node.is_custom_gate {
-                    let mut signals = vec![];
-                    for signal in &node.ordered_signals {
-                        let new_signal = signal + iterator.offset;
-                        let signal_numbering = map.get(&new_signal).unwrap();
-                        signals.push(*signal_numbering);
-                    }
-                    application_data.push((node.name.clone(), signals));
-                } else {
-                    for edge in EncodingIterator::edges(&iterator) {
-                        let next = EncodingIterator::next(&iterator, edge);
-                        iterate(next, map, application_data);
-                    }
+        fn iterate(
+            iterator: EncodingIterator,
+            map: &SignalMap,
+            application_data: &mut Vec<(String, Vec<usize>)>,
+        ) {
+            let node = &iterator.encoding.nodes[iterator.node_id];
+            if node.is_custom_gate {
+                let mut signals = vec![];
+                for signal in &node.ordered_signals {
+                    let new_signal = signal + iterator.offset;
+                    let signal_numbering = map.get(&new_signal).unwrap();
+                    signals.push(*signal_numbering);
+                }
+                application_data.push((node.name.clone(), signals));
+            } else {
+                for edge in EncodingIterator::edges(&iterator) {
+                    let next = EncodingIterator::next(&iterator, edge);
+                    iterate(next, map, application_data);
                 }
             }
+        }
 
-            let mut application_data = vec![];
-            let iterator = EncodingIterator::new(&list.dag_encoding);
-            iterate(iterator, &list.signal_map, &mut application_data);
-            find_indexes(occurring_order, application_data)
-        };
-        custom_gates_applied_section.write_custom_gates_applications(application_data)?;
-        let r1cs = custom_gates_applied_section.end_section()?;
-        R1CSWriter::finish_writing(r1cs)?;
-    }
-    Log::print(&log);
-    Ok(())
+        let mut application_data = vec![];
+        let iterator = EncodingIterator::new(&list.dag_encoding);
+        iterate(iterator, &list.signal_map, &mut application_data);
+        find_indexes(occurring_order, application_data)
+    };
+    (usage_data, application_data)
 }
@@ -1,20 +1,19 @@
 use super::{ConstraintList, EncodingIterator, IteratorSignal, SignalMap};
 use circom_algebra::num_traits::AsPrimitive;
 use constraint_writers::sym_writer::*;
+use std::io::Write;
 
-pub fn port_sym(list: &ConstraintList, file_name: &str) -> Result<(), ()> {
+pub fn port_sym<W: Write>(list: &ConstraintList, output: W) -> Result<W, ()> {
     let iter = EncodingIterator::new(&list.dag_encoding);
-    let mut dot_sym = SymFile::new(file_name)?;
+    let mut dot_sym = SymFile::from_writer(output);
     signal_iteration(iter, &list.signal_map, &mut dot_sym)?;
-    SymFile::finish_writing(dot_sym)?;
-    //SymFile::close(dot_sym);
-    Ok(())
+    SymFile::finish_writing(dot_sym)
 }
 
-pub fn signal_iteration(
+pub fn signal_iteration<W: Write>(
     mut iter: EncodingIterator,
     map: &SignalMap,
-    dot_sym: &mut SymFile,
+    dot_sym: &mut SymFile<W>,
 ) -> Result<(), ()> {
     let (signals, _) = EncodingIterator::take(&mut iter);
 
@@ -0,0 +1,332 @@
+use super::ccs_writer::{CCSEncoding, CCSHeader, CCSSignal, CCS_MAGIC, CCS_VERSION};
+use circom_algebra::algebra::Constraint;
+use circom_algebra::num_bigint::{BigInt, Sign};
+use json::JsonValue;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+// A circom constraint system file, with the rank-1 constraints of a circuit
+pub struct ConstraintSystemFile {
+    pub encoding: CCSEncoding,
+    pub version: u32,
+    pub header: CCSHeader,
+    pub public_wires: Vec<usize>,
+    pub constraints: Vec<Constraint<usize>>,
+    pub signals: Vec<CCSSignal>,
+}
+
+// Reads a file written by the CCSWriter in any of its encodings
+pub fn read_ccs(file: &str) -> Result<ConstraintSystemFile, ()> {
+    let contents = std::fs::read(file).map_err(|_err| {})?;
+    parse_ccs(&contents)
+}
+
+// Same as read_ccs, for the contents of the file
+pub fn parse_ccs(contents: &[u8]) -> Result<ConstraintSystemFile, ()> {
+    if contents.starts_with(CCS_MAGIC) {
+        read_binary(contents)
+    } else {
+        let contents = std::str::from_utf8(contents).map_err(|_err| {})?;
+        read_json(&json::parse(contents).map_err(|_err| {})?)
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn bytes(&mut self, size: usize) -> Result<&'a [u8], ()> {
+        if self.position + size > self.bytes.len() {
+            return Result::Err(());
+        }
+        let bytes = &self.bytes[self.position..self.position + size];
+        self.position += size;
+        Result::Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<usize, ()> {
+        let bytes = self.bytes(4)?;
+        Result::Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    fn u64(&mut self) -> Result<u64, ()> {
+        let bytes = self.bytes(8)?;
+        Result::Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, ()> {
+        let bytes = self.bytes(8)?;
+        Result::Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn field_element(&mut self, field_size: usize) -> Result<BigInt, ()> {
+        Result::Ok(BigInt::from_bytes_le(Sign::Plus, self.bytes(field_size)?))
+    }
+}
+
+fn read_binary(contents: &[u8]) -> Result<ConstraintSystemFile, ()> {
+    let mut cursor = Cursor {
+        bytes: contents,
+        position: CCS_MAGIC.len(),
+    };
+    let version = cursor.u32()? as u32;
+    if version != CCS_VERSION {
+        return Result::Err(());
+    }
+    let field_size = cursor.u32()?;
+    let header = CCSHeader {
+        field: cursor.field_element(field_size)?,
+        wires: cursor.u32()?,
+        public_outputs: cursor.u32()?,
+        public_inputs: cursor.u32()?,
+        private_inputs: cursor.u32()?,
+    };
+    let mut public_wires = Vec::new();
+    for _ in 0..cursor.u32()? {
+        public_wires.push(cursor.u32()?);
+    }
+    let mut constraints = Vec::new();
+    for _ in 0..cursor.u64()? {
+        let mut linear_combinations = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let mut linear_combination = HashMap::new();
+            for _ in 0..cursor.u32()? {
+                let wire = cursor.u32()?;
+                linear_combination.insert(wire, cursor.field_element(field_size)?);
+            }
+            linear_combinations.push(linear_combination);
+        }
+        let c = linear_combinations.pop().unwrap();
+        let b = linear_combinations.pop().unwrap();
+        let a = linear_combinations.pop().unwrap();
+        constraints.push(Constraint::new(a, b, c));
+    }
+    let mut signals = Vec::new();
+    for _ in 0..cursor.u64()? {
+        let label = cursor.u64()? as usize;
+        let wire = cursor.i64()?;
+        let component = cursor.u64()? as usize;
+        let length = cursor.u32()?;
+        let name = String::from_utf8(cursor.bytes(length)?.to_vec()).map_err(|_err| {})?;
+        signals.push(CCSSignal {
+            label,
+            wire: if wire < 0 { None } else { Some(wire as usize) },
+            component,
+            name,
+        });
+    }
+    if cursor.position != contents.len() {
+        return Result::Err(());
+    }
+    Result::Ok(ConstraintSystemFile {
+        encoding: CCSEncoding::Binary,
+        version,
+        header,
+        public_wires,
+        constraints,
+        signals,
+    })
+}
+
+fn json_number(value: &JsonValue) -> Result<usize, ()> {
+    value.as_usize().ok_or(())
+}
+
+fn json_field_element(value: &JsonValue) -> Result<BigInt, ()> {
+    let value = value.as_str().ok_or(())?;
+    BigInt::parse_bytes(value.as_bytes(), 10).ok_or(())
+}
+
+fn read_json(contents: &JsonValue) -> Result<ConstraintSystemFile, ()> {
+    if contents["format"].as_str() != Some("ccs") {
+        return Result::Err(());
+    }
+    let version = contents["version"].as_u32().ok_or(())?;
+    if version != CCS_VERSION {
+        return Result::Err(());
+    }
+    let header = CCSHeader {
+        field: json_field_element(&contents["field"])?,
+        wires: json_number(&contents["wires"])?,
+        public_outputs: json_number(&contents["public_outputs"])?,
+        public_inputs: json_number(&contents["public_inputs"])?,
+        private_inputs: json_number(&contents["private_inputs"])?,
+    };
+    let mut public_wires = Vec::new();
+    for wire in contents["public_wires"].members() {
+        public_wires.push(json_number(wire)?);
+    }
+    let mut constraints = Vec::new();
+    for constraint in contents["constraints"].members() {
+        let mut linear_combinations = Vec::with_capacity(3);
+        for json_combination in constraint.members() {
+            let mut linear_combination = HashMap::new();
+            for (wire, value) in json_combination.entries() {
+                let wire = wire.parse::<usize>().map_err(|_err| {})?;
+                linear_combination.insert(wire, json_field_element(value)?);
+            }
+            linear_combinations.push(linear_combination);
+        }
+        if linear_combinations.len() != 3 {
+            return Result::Err(());
+        }
+        let c = linear_combinations.pop().unwrap();
+        let b = linear_combinations.pop().unwrap();
+        let a = linear_combinations.pop().unwrap();
+        constraints.push(Constraint::new(a, b, c));
+    }
+    let mut signals = Vec::new();
+    for signal in contents["signals"].members() {
+        signals.push(CCSSignal {
+            label: json_number(&signal["label"])?,
+            wire: if signal["wire"].is_null() {
+                None
+            } else {
+                Some(json_number(&signal["wire"])?)
+            },
+            component: json_number(&signal["component"])?,
+            name: signal["name"].as_str().ok_or(())?.to_string(),
+        });
+    }
+    Result::Ok(ConstraintSystemFile {
+        encoding: CCSEncoding::Json,
+        version,
+        header,
+        public_wires,
+        constraints,
+        signals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccs_writer::CCSWriter;
+    use std::io::Cursor;
+
+    fn header() -> CCSHeader {
+        CCSHeader {
+            field: BigInt::from(65521),
+            wires: 4,
+            public_outputs: 1,
+            public_inputs: 1,
+            private_inputs: 1,
+        }
+    }
+
+    fn lc(terms: &[(usize, i64)]) -> HashMap<usize, BigInt> {
+        terms
+            .iter()
+            .map(|(wire, value)| (*wire, BigInt::from(*value)))
+            .collect()
+    }
+
+    fn signals() -> Vec<CCSSignal> {
+        vec![
+            CCSSignal {
+                label: 1,
+                wire: Some(1),
+                component: 0,
+                name: "main.out".to_string(),
+            },
+            CCSSignal {
+                label: 4,
+                wire: None,
+                component: 0,
+                name: "main.removed".to_string(),
+            },
+        ]
+    }
+
+    // The constraint w1 * w2 = 3 - w3 and two signals
+    fn write_sample(encoding: CCSEncoding) -> Vec<u8> {
+        let mut writer =
+            CCSWriter::from_writer(Cursor::new(Vec::new()), encoding, &header()).unwrap();
+        writer
+            .write_constraint(&lc(&[(1, 1)]), &lc(&[(2, 1)]), &lc(&[(0, 3), (3, 65520)]))
+            .unwrap();
+        for signal in &signals() {
+            writer.write_signal(signal).unwrap();
+        }
+        writer.finish_writing().unwrap().into_inner()
+    }
+
+    #[test]
+    fn read_written_ccs() {
+        for encoding in [CCSEncoding::Binary, CCSEncoding::Json] {
+            let read = parse_ccs(&write_sample(encoding)).unwrap();
+            assert_eq!(read.encoding, encoding);
+            assert_eq!(read.version, CCS_VERSION);
+            assert_eq!(read.header, header());
+            assert_eq!(read.public_wires, vec![1, 2]);
+            assert_eq!(read.constraints.len(), 1);
+            assert_eq!(read.constraints[0].a(), &lc(&[(1, 1)]));
+            assert_eq!(read.constraints[0].b(), &lc(&[(2, 1)]));
+            assert_eq!(read.constraints[0].c(), &lc(&[(0, 3), (3, 65520)]));
+            assert_eq!(read.signals, signals());
+        }
+    }
+
+    #[test]
+    fn invalid_binary_ccs() {
+        let bytes = write_sample(CCSEncoding::Binary);
+        let read = parse_ccs;
+        assert!(read(&bytes).is_ok());
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'x';
+        assert!(read(&wrong_magic).is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 2;
+        assert!(read(&wrong_version).is_err());
+
+        // the file ends in the middle of the name of the last signal
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read(&bytes[..12]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(read(&trailing).is_err());
+
+        // the name of the last signal is not utf-8
+        let mut wrong_name = bytes.clone();
+        let last = wrong_name.len() - 1;
+        wrong_name[last] = 0xff;
+        assert!(read(&wrong_name).is_err());
+
+        // the field size says that the prime takes more bytes than the file has
+        let mut wrong_field_size = bytes.clone();
+        wrong_field_size[8] = 0xff;
+        assert!(read(&wrong_field_size).is_err());
+    }
+
+    #[test]
+    fn invalid_json_ccs() {
+        let text = String::from_utf8(write_sample(CCSEncoding::Json)).unwrap();
+        let read = |text: &str| parse_ccs(text.as_bytes());
+        assert!(read(&text).is_ok());
+        assert!(read(&text[..text.len() - 2]).is_err());
+
+        let json = json::parse(&text).unwrap();
+        let changed = |key: &str, value: JsonValue| {
+            let mut json = json.clone();
+            json[key] = value;
+            json.dump()
+        };
+        assert!(read(&changed("format", "r1cs".into())).is_err());
+        assert!(read(&changed("version", 2.into())).is_err());
+        assert!(read(&changed("field", 65521.into())).is_err());
+        assert!(read(&changed("wires", "4".into())).is_err());
+        assert!(read(&changed("public_wires", json::array![-1])).is_err());
+        let constraint = json::array![{"1": "1"}, {"2": "1"}];
+        assert!(read(&changed("constraints", json::array![constraint])).is_err());
+        let constraint = json::array![{"w1": "1"}, {"2": "1"}, {}];
+        assert!(read(&changed("constraints", json::array![constraint])).is_err());
+        let constraint = json::array![{"1": "one"}, {"2": "1"}, {}];
+        assert!(read(&changed("constraints", json::array![constraint])).is_err());
+        let signal = json::object! {label: 1, wire: 1, component: 0};
+        assert!(read(&changed("signals", json::array![signal])).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use circom_algebra::num_bigint::BigInt;
+use json::JsonValue;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+// Constraint system files, a format to move the simplified constraints of a circuit to
+// other proving stacks. CCS stands for circom constraint system: the files hold the
+// rank-1 constraints of the circuit, not a Customizable Constraint System, the
+// generalization of R1CS, Plonkish and AIR that shares the acronym. The same content is
+// written either in binary or in json:
+//
+// binary, every number in little endian:
+//   magic "ccs " | version: u32 | field size in bytes n: u32 | prime: n bytes
+//   wires: u32 | public outputs: u32 | public inputs: u32 | private inputs: u32
+//   number of public wires: u32 | public wires in order: u32 each
+//   number of constraints: u64 | constraints, each one the linear combinations A, B and C
+//     of A·B - C = 0, each one written as number of terms: u32 | (wire: u32, value: n bytes)
+//   number of signals: u64 | signals, each one written as
+//     label: u64 | wire: i64, -1 when it was removed | component: u64 | name length: u32 | name
+//
+// json:
+//   {"format": "ccs", "version", "field", "wires", "public_outputs", "public_inputs",
+//    "private_inputs", "public_wires": [...], "constraints": [[A, B, C], ...],
+//    "signals": [{"label", "wire", "component", "name"}, ...]}
+//   where the linear combinations are objects from wire to value, the values are decimal
+//   strings and the wire of the removed signals is null.
+pub const CCS_VERSION: u32 = 1;
+pub const CCS_MAGIC: &[u8] = b"ccs ";
+const PLACE_HOLDER: &[u8] = &[3, 3, 3, 3, 3, 3, 3, 3];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CCSEncoding {
+    Binary,
+    Json,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CCSHeader {
+    pub field: BigInt,
+    pub wires: usize,
+    pub public_outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+}
+
+impl CCSHeader {
+    // The public outputs go first, then the public inputs, after the wire of the constant 1
+    pub fn public_wires(&self) -> Vec<usize> {
+        (1..=self.public_outputs + self.public_inputs).collect()
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CCSSignal {
+    pub label: usize,
+    // None when the simplification removed the signal
+    pub wire: Option<usize>,
+    pub component: usize,
+    pub name: String,
+}
+
+pub fn field_size(field: &BigInt) -> usize {
+    if field.bits() % 64 == 0 {
+        field.bits() / 8
+    } else {
+        (field.bits() / 64 + 1) * 8
+    }
+}
+
+fn bigint_as_bytes(number: &BigInt, with_bytes: usize) -> Vec<u8> {
+    let (_, mut value) = number.to_bytes_le();
+    value.resize(with_bytes, 0);
+    value
+}
+
+fn linear_combination_as_json(linear_combination: &HashMap<usize, BigInt>) -> JsonValue {
+    let mut order: Vec<&usize> = linear_combination.keys().collect();
+    order.sort();
+    let mut json = JsonValue::new_object();
+    for wire in order {
+        json[wire.to_string()] = linear_combination[wire].to_str_radix(10).into();
+    }
+    json
+}
+
+// The constraints have to be written before the signals
+pub struct CCSWriter<W = BufWriter<File>> {
+    writer: W,
+    encoding: CCSEncoding,
+    field_size: usize,
+    // Position of the count of the current list in the binary encoding
+    go_back: u64,
+    written: usize,
+    in_signals: bool,
+}
+
+impl CCSWriter {
+    pub fn new(output: &str, encoding: CCSEncoding, header: &CCSHeader) -> Result<CCSWriter, ()> {
+        let file = File::create(output).map_err(|_err| {})?;
+        CCSWriter::from_writer(BufWriter::new(file), encoding, header)
+    }
+}
+
+impl<W: Write + Seek> CCSWriter<W> {
+    pub fn from_writer(
+        writer: W,
+        encoding: CCSEncoding,
+        header: &CCSHeader,
+    ) -> Result<CCSWriter<W>, ()> {
+        let mut ccs = CCSWriter {
+            writer,
+            encoding,
+            field_size: field_size(&header.field),
+            go_back: 0,
+            written: 0,
+            in_signals: false,
+        };
+        match encoding {
+            CCSEncoding::Binary => {
+                let public_wires = header.public_wires();
+                ccs.write(CCS_MAGIC)?;
+                ccs.write_u32(CCS_VERSION as usize)?;
+                ccs.write_u32(ccs.field_size)?;
+                ccs.write(&bigint_as_bytes(&header.field, ccs.field_size))?;
+                ccs.write_u32(header.wires)?;
+                ccs.write_u32(header.public_outputs)?;
+                ccs.write_u32(header.public_inputs)?;
+                ccs.write_u32(header.private_inputs)?;
+                ccs.write_u32(public_wires.len())?;
+                for wire in public_wires {
+                    ccs.write_u32(wire)?;
+                }
+                ccs.start_list()?;
+            }
+            CCSEncoding::Json => {
+                let preamble = json::object! {
+                    format: "ccs",
+                    version: CCS_VERSION,
+                    field: header.field.to_str_radix(10),
+                    wires: header.wires,
+                    public_outputs: header.public_outputs,
+                    public_inputs: header.public_inputs,
+                    private_inputs: header.private_inputs,
+                    public_wires: header.public_wires(),
+                };
+                // The lists are streamed, the object is closed by finish_writing
+                let preamble = preamble.dump();
+                ccs.write(&preamble.as_bytes()[..preamble.len() - 1])?;
+                ccs.write(b",\n\"constraints\": [")?;
+            }
+        }
+        Result::Ok(ccs)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        self.writer.write_all(bytes).map_err(|_err| {})
+    }
+
+    fn write_u32(&mut self, number: usize) -> Result<(), ()> {
+        self.write(&(number as u32).to_le_bytes())
+    }
+
+    fn write_u64(&mut self, number: u64) -> Result<(), ()> {
+        self.write(&number.to_le_bytes())
+    }
+
+    fn start_list(&mut self) -> Result<(), ()> {
+        self.go_back = self.writer.stream_position().map_err(|_err| {})?;
+        self.written = 0;
+        self.write(PLACE_HOLDER)
+    }
+
+    fn end_list(&mut self) -> Result<(), ()> {
+        let position = self.writer.stream_position().map_err(|_err| {})?;
+        self.writer
+            .seek(SeekFrom::Start(self.go_back))
+            .map_err(|_err| {})?;
+        self.write_u64(self.written as u64)?;
+        self.writer
+            .seek(SeekFrom::Start(position))
+            .map_err(|_err| {})?;
+        Result::Ok(())
+    }
+
+    fn json_separator(&mut self) -> Result<(), ()> {
+        if self.written == 0 {
+            self.write(b"\n")
+        } else {
+            self.write(b",\n")
+        }
+    }
+
+    pub fn write_constraint(
+        &mut self,
+        a: &HashMap<usize, BigInt>,
+        b: &HashMap<usize, BigInt>,
+        c: &HashMap<usize, BigInt>,
+    ) -> Result<(), ()> {
+        if self.in_signals {
+            return Result::Err(());
+        }
+        match self.encoding {
+            CCSEncoding::Binary => {
+                for linear_combination in [a, b, c] {
+                    let mut order: Vec<&usize> = linear_combination.keys().collect();
+                    order.sort();
+                    self.write_u32(order.len())?;
+                    for wire in order {
+                        self.write_u32(*wire)?;
+                        let value = bigint_as_bytes(&linear_combination[wire], self.field_size);
+                        self.write(&value)?;
+                    }
+                }
+            }
+            CCSEncoding::Json => {
+                self.json_separator()?;
+                let constraint = JsonValue::Array(vec![
+                    linear_combination_as_json(a),
+                    linear_combination_as_json(b),
+                    linear_combination_as_json(c),
+                ]);
+                self.write(constraint.dump().as_bytes())?;
+            }
+        }
+        self.written += 1;
+        Result::Ok(())
+    }
+
+    fn start_signals(&mut self) -> Result<(), ()> {
+        self.in_signals = true;
+        match self.encoding {
+            CCSEncoding::Binary => {
+                self.end_list()?;
+                self.start_list()
+            }
+            CCSEncoding::Json => {
+                self.write(b"\n],\n\"signals\": [")?;
+                self.written = 0;
+                Result::Ok(())
+            }
+        }
+    }
+
+    pub fn write_signal(&mut self, signal: &CCSSignal) -> Result<(), ()> {
+        if !self.in_signals {
+            self.start_signals()?;
+        }
+        match self.encoding {
+            CCSEncoding::Binary => {
+                self.write_u64(signal.label as u64)?;
+                let wire = signal.wire.map_or(-1, |wire| wire as i64);
+                self.write(&wire.to_le_bytes())?;
+                self.write_u64(signal.component as u64)?;
+                self.write_u32(signal.name.len())?;
+                self.write(signal.name.as_bytes())?;
+            }
+            CCSEncoding::Json => {
+                self.json_separator()?;
+                let json_signal = json::object! {
+                    label: signal.label,
+                    wire: signal.wire,
+                    component: signal.component,
+                    name: signal.name.as_str(),
+                };
+                self.write(json_signal.dump().as_bytes())?;
+            }
+        }
+        self.written += 1;
+        Result::Ok(())
+    }
+
+    // Returns the destination once everything is written to it
+    pub fn finish_writing(mut self) -> Result<W, ()> {
+        if !self.in_signals {
+            self.start_signals()?;
+        }
+        match self.encoding {
+            CCSEncoding::Binary => self.end_list()?,
+            CCSEncoding::Json => self.write(b"\n]\n}\n")?,
+        }
+        self.writer.flush().map_err(|_err| {})?;
+        Result::Ok(self.writer)
+    }
+}
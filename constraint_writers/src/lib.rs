@@ -1,11 +1,30 @@
+pub mod ccs_reader;
+pub mod ccs_writer;
 pub mod debug_writer;
 pub mod json_writer;
 pub mod log_writer;
+pub mod plonk_writer;
+pub mod r1cs_reader;
 pub mod r1cs_writer;
 pub mod sym_writer;
 
+use std::fs::File;
+use std::io::BufWriter;
+
 pub trait ConstraintExporter {
     fn r1cs(&self, out: &str, custom_gates: bool) -> Result<(), ()>;
+    fn plonk(&self, out: &str, custom_gates: bool) -> Result<(), ()>;
+    fn ccs(&self, out: &str, encoding: ccs_writer::CCSEncoding) -> Result<(), ()>;
     fn json_constraints(&self, writer: &debug_writer::DebugWriter) -> Result<(), ()>;
     fn sym(&self, out: &str) -> Result<(), ()>;
+    // The contents of the files written by the methods above, produced in memory
+    fn r1cs_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()>;
+    fn plonk_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()>;
+    fn ccs_bytes(&self, encoding: ccs_writer::CCSEncoding) -> Result<Vec<u8>, ()>;
+    fn sym_bytes(&self) -> Result<Vec<u8>, ()>;
+}
+
+// The destination of the writers when their output is a file
+pub fn create_file(file: &str) -> Result<BufWriter<File>, ()> {
+    File::create(file).map(BufWriter::new).map_err(|_err| {})
 }
@@ -0,0 +1,502 @@
+use super::r1cs_writer::{CustomGatesAppliedData, CustomGatesUsedData};
+use circom_algebra::modular_arithmetic;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::num_traits::Zero;
+use json::JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Number of wire columns of a gate: a, b and c. The rows of the custom gates may use more
+// columns.
+pub const COLUMNS: usize = 3;
+
+// The row qL·a + qR·b + qO·c + qM·a·b + qC = 0. The columns that the gate does not use
+// have no wire.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Gate {
+    pub q_l: BigInt,
+    pub q_r: BigInt,
+    pub q_o: BigInt,
+    pub q_m: BigInt,
+    pub q_c: BigInt,
+    pub wires: [Option<usize>; COLUMNS],
+}
+
+pub struct PlonkHeader {
+    pub public_outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+}
+
+// A linear combination split into its constant and its terms sorted by wire
+struct Split {
+    constant: BigInt,
+    terms: Vec<(usize, BigInt)>,
+}
+
+// Translates the constraints A·B - C = 0 into gates. The wires of the witness keep their
+// numbers and the linear combinations that do not fit in a gate are accumulated in new
+// internal wires, numbered after the witness ones. Each internal wire is the c wire of
+// the gate that defines it, c = qL·a + qR·b, and it is only used in later gates.
+// Every application of a custom gate is a row after the gates, with all the arithmetic
+// selectors set to zero and the selector of its custom gate set to one.
+pub struct PlonkWriter<W = BufWriter<File>> {
+    writer: W,
+    field: BigInt,
+    witness_wires: usize,
+    internal_wires: usize,
+    gates: Vec<Gate>,
+    custom_gates: Option<(CustomGatesUsedData, CustomGatesAppliedData)>,
+}
+
+impl PlonkWriter {
+    pub fn new(output: String, field: BigInt, witness_wires: usize) -> Result<PlonkWriter, ()> {
+        let file = File::create(output).map_err(|_err| {})?;
+        Result::Ok(PlonkWriter::from_writer(
+            BufWriter::new(file),
+            field,
+            witness_wires,
+        ))
+    }
+}
+
+impl<W: Write> PlonkWriter<W> {
+    pub fn from_writer(writer: W, field: BigInt, witness_wires: usize) -> PlonkWriter<W> {
+        PlonkWriter {
+            writer,
+            field,
+            witness_wires,
+            internal_wires: 0,
+            gates: Vec::new(),
+            custom_gates: None,
+        }
+    }
+
+    pub fn gates_written(&self) -> usize {
+        self.gates.len()
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    // Columns of the table, enough for the wires of every custom gate application
+    pub fn columns(&self) -> usize {
+        let applied = self.custom_gates.iter().flat_map(|(_, applied)| applied);
+        applied
+            .map(|(_, wires)| wires.len())
+            .fold(COLUMNS, usize::max)
+    }
+
+    // The wires of every row, the gates followed by the custom gate applications
+    pub fn rows(&self) -> Vec<Vec<Option<usize>>> {
+        let columns = self.columns();
+        let mut rows: Vec<Vec<Option<usize>>> =
+            self.gates.iter().map(|gate| gate.wires.to_vec()).collect();
+        for (_, wires) in self.custom_gates.iter().flat_map(|(_, applied)| applied) {
+            rows.push(wires.iter().copied().map(Some).collect());
+        }
+        for row in &mut rows {
+            row.resize(columns, None);
+        }
+        rows
+    }
+
+    pub fn write_constraint_usize(
+        &mut self,
+        a: &HashMap<usize, BigInt>,
+        b: &HashMap<usize, BigInt>,
+        c: &HashMap<usize, BigInt>,
+    ) {
+        let (a, b, c) = (self.split(a), self.split(b), self.split(c));
+        if a.terms.is_empty() || b.terms.is_empty() {
+            // A·B is a linear combination
+            let (constant, other) = if a.terms.is_empty() { (a, b) } else { (b, a) };
+            let mut terms = BTreeMap::new();
+            for (wire, value) in other.terms {
+                let value = modular_arithmetic::mul(&value, &constant.constant, &self.field);
+                self.add_term(&mut terms, wire, &value);
+            }
+            for (wire, value) in c.terms {
+                let value = modular_arithmetic::prefix_sub(&value, &self.field);
+                self.add_term(&mut terms, wire, &value);
+            }
+            let product = modular_arithmetic::mul(&constant.constant, &other.constant, &self.field);
+            let constant = modular_arithmetic::sub(&product, &c.constant, &self.field);
+            self.linear(constant, terms.into_iter().collect());
+        } else {
+            // (kA·wA + cA)·(kB·wB + cB) - (kC·wC + cC) = 0
+            let field = self.field.clone();
+            let (w_a, k_a) = self.reduce(a.terms).unwrap();
+            let (w_b, k_b) = self.reduce(b.terms).unwrap();
+            let (w_c, k_c) = match self.reduce(c.terms) {
+                Some((wire, value)) => (Some(wire), value),
+                None => (None, BigInt::zero()),
+            };
+            let q_c = modular_arithmetic::sub(
+                &modular_arithmetic::mul(&a.constant, &b.constant, &field),
+                &c.constant,
+                &field,
+            );
+            self.gates.push(Gate {
+                q_l: modular_arithmetic::mul(&k_a, &b.constant, &field),
+                q_r: modular_arithmetic::mul(&a.constant, &k_b, &field),
+                q_o: modular_arithmetic::prefix_sub(&k_c, &field),
+                q_m: modular_arithmetic::mul(&k_a, &k_b, &field),
+                q_c,
+                wires: [Some(w_a), Some(w_b), w_c],
+            });
+        }
+    }
+
+    // The custom gates used by the circuit and their applications, as in the sections 4
+    // and 5 of the r1cs format. Every application becomes a row of the table.
+    pub fn write_custom_gates(
+        &mut self,
+        used: CustomGatesUsedData,
+        applied: CustomGatesAppliedData,
+    ) {
+        self.custom_gates = Some((used, applied));
+    }
+
+    // Returns the destination once everything is written to it
+    pub fn finish_writing(mut self, header: PlonkHeader) -> Result<W, ()> {
+        let number = |value: &BigInt| JsonValue::from(value.to_str_radix(10));
+        let wire = |wire: &Option<usize>| wire.map_or(JsonValue::Null, JsonValue::from);
+        let no_custom_gates = (Vec::new(), Vec::new());
+        let (used, applied) = self.custom_gates.as_ref().unwrap_or(&no_custom_gates);
+        // the selectors of the custom gates, one for each custom gate used
+        let custom_selectors = |selected: Option<usize>| -> Vec<JsonValue> {
+            (0..used.len())
+                .map(|gate| JsonValue::from(if selected == Some(gate) { "1" } else { "0" }))
+                .collect()
+        };
+        let zero = BigInt::zero();
+        let selectors = self
+            .gates
+            .iter()
+            .map(|gate| {
+                (
+                    [&gate.q_l, &gate.q_r, &gate.q_o, &gate.q_m, &gate.q_c],
+                    None,
+                )
+            })
+            .chain(applied.iter().map(|(gate, _)| ([&zero; 5], Some(*gate))));
+        let mut gates = JsonValue::new_array();
+        for ((q, selected), row) in selectors.zip(self.rows()) {
+            let wires: Vec<_> = row.iter().map(wire).collect();
+            let mut json_gate = json::object! {
+                q_l: number(q[0]),
+                q_r: number(q[1]),
+                q_o: number(q[2]),
+                q_m: number(q[3]),
+                q_c: number(q[4]),
+                wires: wires,
+            };
+            if self.custom_gates.is_some() {
+                json_gate["q_custom"] = custom_selectors(selected).into();
+            }
+            gates.push(json_gate).map_err(|_err| {})?;
+        }
+        let mut permutation = JsonValue::new_array();
+        for column in self.permutation() {
+            let cells: Vec<JsonValue> = column
+                .into_iter()
+                .map(|(column, row)| JsonValue::from(vec![column, row]))
+                .collect();
+            permutation.push(cells).map_err(|_err| {})?;
+        }
+        let public = header.public_outputs + header.public_inputs;
+        let mut plonk = json::object! {
+            field: number(&self.field),
+            columns: self.columns(),
+            wires: self.witness_wires + self.internal_wires,
+            witness_wires: self.witness_wires,
+            public_outputs: header.public_outputs,
+            public_inputs: header.public_inputs,
+            private_inputs: header.private_inputs,
+            public_wires: (1..=public).collect::<Vec<_>>(),
+            gates: gates,
+            permutation: permutation,
+        };
+        if self.custom_gates.is_some() {
+            let mut json_used = JsonValue::new_array();
+            for (name, parameters) in used {
+                let parameters: Vec<_> = parameters.iter().map(number).collect();
+                json_used
+                    .push(json::object! { name: name.as_str(), parameters: parameters })
+                    .map_err(|_err| {})?;
+            }
+            plonk["custom_gates_used"] = json_used;
+        }
+        self.writer
+            .write_all(plonk.pretty(1).as_bytes())
+            .map_err(|_err| {})?;
+        self.writer.flush().map_err(|_err| {})?;
+        Result::Ok(self.writer)
+    }
+
+    // The copy constraints as a permutation of the cells, given for each column and row
+    // as the (column, row) cell it is sent to. The cells of a wire form a cycle in
+    // row-major order and the empty cells are fixed points.
+    pub fn permutation(&self) -> Vec<Vec<(usize, usize)>> {
+        let rows = self.rows();
+        let mut permutation: Vec<Vec<(usize, usize)>> = (0..self.columns())
+            .map(|column| (0..rows.len()).map(|row| (column, row)).collect())
+            .collect();
+        let mut cells: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+        for (row, wires) in rows.iter().enumerate() {
+            for (column, wire) in wires.iter().enumerate() {
+                if let Some(wire) = wire {
+                    cells.entry(*wire).or_default().push((column, row));
+                }
+            }
+        }
+        for cycle in cells.values() {
+            for (index, (column, row)) in cycle.iter().enumerate() {
+                permutation[*column][*row] = cycle[(index + 1) % cycle.len()];
+            }
+        }
+        permutation
+    }
+
+    fn split(&self, linear_combination: &HashMap<usize, BigInt>) -> Split {
+        let mut constant = BigInt::zero();
+        let mut terms = BTreeMap::new();
+        for (wire, value) in linear_combination {
+            if *wire == 0 {
+                constant = modular_arithmetic::add(&constant, value, &self.field);
+            } else {
+                self.add_term(&mut terms, *wire, value);
+            }
+        }
+        Split {
+            constant,
+            terms: terms.into_iter().collect(),
+        }
+    }
+
+    fn add_term(&self, terms: &mut BTreeMap<usize, BigInt>, wire: usize, value: &BigInt) {
+        let prev = terms.remove(&wire).unwrap_or_else(BigInt::zero);
+        let next = modular_arithmetic::add(&prev, value, &self.field);
+        if !next.is_zero() {
+            terms.insert(wire, next);
+        }
+    }
+
+    fn new_internal_wire(&mut self) -> usize {
+        self.internal_wires += 1;
+        self.witness_wires + self.internal_wires - 1
+    }
+
+    // Adds the gates that compute the sum of the terms in an internal wire, the result
+    // is the wire with the coefficient of the sum
+    fn reduce(&mut self, terms: Vec<(usize, BigInt)>) -> Option<(usize, BigInt)> {
+        let minus_one = modular_arithmetic::prefix_sub(&BigInt::from(1), &self.field);
+        let mut terms = terms.into_iter();
+        let (mut wire, mut value) = terms.next()?;
+        for (next_wire, next_value) in terms {
+            let sum = self.new_internal_wire();
+            self.gates.push(Gate {
+                q_l: value,
+                q_r: next_value,
+                q_o: minus_one.clone(),
+                q_m: BigInt::zero(),
+                q_c: BigInt::zero(),
+                wires: [Some(wire), Some(next_wire), Some(sum)],
+            });
+            wire = sum;
+            value = BigInt::from(1);
+        }
+        Some((wire, value))
+    }
+
+    // Adds the gates of the constraint constant + sum of the terms = 0. The last two terms
+    // go to the final gate together with the sum of the rest.
+    fn linear(&mut self, constant: BigInt, mut terms: Vec<(usize, BigInt)>) {
+        if terms.is_empty() && constant.is_zero() {
+            return;
+        }
+        if terms.len() > COLUMNS {
+            let last = terms.split_off(terms.len() - 2);
+            let sum = self.reduce(terms).unwrap();
+            terms = vec![sum];
+            terms.extend(last);
+        }
+        let mut coefficients = vec![BigInt::zero(); COLUMNS];
+        let mut wires = [None; COLUMNS];
+        for (column, (wire, value)) in terms.into_iter().enumerate() {
+            coefficients[column] = value;
+            wires[column] = Some(wire);
+        }
+        let q_o = coefficients.pop().unwrap();
+        let q_r = coefficients.pop().unwrap();
+        let q_l = coefficients.pop().unwrap();
+        self.gates.push(Gate {
+            q_l,
+            q_r,
+            q_o,
+            q_m: BigInt::zero(),
+            q_c: constant,
+            wires,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(value: &BigInt, field: &BigInt) -> BigInt {
+        modular_arithmetic::add(value, &BigInt::zero(), field)
+    }
+
+    fn lc(terms: &[(usize, i64)]) -> HashMap<usize, BigInt> {
+        let field = BigInt::from(101);
+        terms
+            .iter()
+            .map(|(wire, value)| (*wire, element(&BigInt::from(*value), &field)))
+            .collect()
+    }
+
+    // Evaluates the gates with the values of the witness, the internal wires are
+    // computed by the gates that define them
+    fn satisfied(writer: &PlonkWriter<Vec<u8>>, witness: &[i64]) -> bool {
+        let field = &writer.field;
+        let mut values: Vec<BigInt> = witness.iter().map(|value| BigInt::from(*value)).collect();
+        for gate in writer.gates() {
+            let value = |wire: Option<usize>, values: &Vec<BigInt>| {
+                wire.map_or(BigInt::zero(), |wire| values[wire].clone())
+            };
+            let (a, b) = (value(gate.wires[0], &values), value(gate.wires[1], &values));
+            if let Some(wire) = gate.wires[2] {
+                if wire == values.len() {
+                    let sum = &gate.q_l * &a + &gate.q_r * &b;
+                    values.push(element(&sum, field));
+                }
+            }
+            let c = value(gate.wires[2], &values);
+            let result =
+                &gate.q_l * &a + &gate.q_r * &b + &gate.q_o * &c + &gate.q_m * &a * &b + &gate.q_c;
+            if !element(&result, field).is_zero() {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn translate_constraints_into_gates() {
+        let mut writer = PlonkWriter::from_writer(Vec::new(), BigInt::from(101), 6);
+        // (w1 + w2 + 3) * (2 * w3) = w4 + w5
+        writer.write_constraint_usize(
+            &lc(&[(1, 1), (2, 1), (0, 3)]),
+            &lc(&[(3, 2)]),
+            &lc(&[(4, 1), (5, 1)]),
+        );
+        // w1 + w2 + w3 + w4 + w5 = 59
+        writer.write_constraint_usize(
+            &lc(&[]),
+            &lc(&[]),
+            &lc(&[(1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (0, -59)]),
+        );
+        // 2 * (w1 - w2) = w3
+        writer.write_constraint_usize(&lc(&[(0, 2)]), &lc(&[(1, 1), (2, -1)]), &lc(&[(3, 1)]));
+        assert_eq!(writer.internal_wires, 4);
+
+        // w1 = 5, w2 = 4, w3 = 2, w4 = 50 and w5 = -2 satisfy the three constraints
+        assert!(satisfied(&writer, &[1, 5, 4, 2, 50, 99]));
+        assert!(!satisfied(&writer, &[1, 5, 4, 2, 50, 0]));
+
+        // Every wire used in several cells forms a single cycle
+        let permutation = writer.permutation();
+        let mut cell = (0, 0);
+        let mut length = 0;
+        loop {
+            cell = permutation[cell.0][cell.1];
+            length += 1;
+            if cell == (0, 0) {
+                break;
+            }
+        }
+        let uses_of_w1 = writer
+            .gates()
+            .iter()
+            .flat_map(|gate| gate.wires.iter())
+            .filter(|wire| **wire == Some(1))
+            .count();
+        assert_eq!(length, uses_of_w1);
+
+        let gates = writer.gates_written();
+        let bytes = writer
+            .finish_writing(PlonkHeader {
+                public_outputs: 0,
+                public_inputs: 1,
+                private_inputs: 4,
+            })
+            .unwrap();
+        let contents = json::parse(std::str::from_utf8(&bytes).unwrap()).unwrap();
+        assert_eq!(contents["gates"].len(), gates);
+        assert_eq!(contents["permutation"][2].len(), gates);
+        assert_eq!(contents["wires"], 10);
+        assert!(contents["gates"][0]["q_custom"].is_null());
+    }
+
+    #[test]
+    fn rows_of_custom_gates() {
+        let mut writer = PlonkWriter::from_writer(Vec::new(), BigInt::from(101), 6);
+        // w1 * w2 = w3
+        writer.write_constraint_usize(&lc(&[(1, 1)]), &lc(&[(2, 1)]), &lc(&[(3, 1)]));
+        let used = vec![
+            ("Add".to_string(), vec![]),
+            ("Mix".to_string(), vec![BigInt::from(7)]),
+        ];
+        let applied = vec![(1, vec![3, 4, 5, 1]), (0, vec![4, 5])];
+        writer.write_custom_gates(used, applied);
+        assert_eq!(writer.columns(), 4);
+        assert_eq!(
+            writer.rows(),
+            vec![
+                vec![Some(1), Some(2), Some(3), None],
+                vec![Some(3), Some(4), Some(5), Some(1)],
+                vec![Some(4), Some(5), None, None],
+            ]
+        );
+        // the cells of the custom gates are in the cycles of their wires
+        let permutation = writer.permutation();
+        assert_eq!(permutation.len(), 4);
+        assert_eq!(permutation[0][0], (3, 1));
+        assert_eq!(permutation[3][1], (0, 0));
+        assert_eq!(permutation[2][0], (0, 1));
+        assert_eq!(permutation[0][1], (2, 0));
+        assert_eq!(permutation[1][1], (0, 2));
+        assert_eq!(permutation[0][2], (1, 1));
+        assert_eq!(permutation[3][2], (3, 2));
+
+        let bytes = writer
+            .finish_writing(PlonkHeader {
+                public_outputs: 1,
+                public_inputs: 0,
+                private_inputs: 2,
+            })
+            .unwrap();
+        let contents = json::parse(std::str::from_utf8(&bytes).unwrap()).unwrap();
+        assert_eq!(contents["columns"], 4);
+        let gates = &contents["gates"];
+        assert_eq!(gates.len(), 3);
+        assert_eq!(gates[0]["q_m"], "1");
+        assert_eq!(gates[0]["q_custom"], json::array!["0", "0"]);
+        assert_eq!(gates[1]["q_m"], "0");
+        assert_eq!(gates[1]["q_custom"], json::array!["0", "1"]);
+        assert_eq!(gates[1]["wires"], json::array![3, 4, 5, 1]);
+        assert_eq!(gates[2]["q_custom"], json::array!["1", "0"]);
+        assert_eq!(gates[2]["wires"], json::array![4, 5, null, null]);
+        assert_eq!(contents["custom_gates_used"][1]["name"], "Mix");
+        assert_eq!(
+            contents["custom_gates_used"][1]["parameters"],
+            json::array!["7"]
+        );
+        assert_eq!(contents["permutation"][3].len(), 3);
+    }
+}
@@ -0,0 +1,518 @@
+use super::r1cs_writer::{CustomGatesAppliedData, CustomGatesUsedData, HeaderData};
+use circom_algebra::algebra::Constraint;
+use circom_algebra::num_bigint::{BigInt, Sign};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+
+const SECTIONS: usize = 5;
+const MAGIC: &[u8] = b"r1cs";
+const VERSION: usize = 1;
+const HEADER_TYPE: usize = 1;
+const CONSTRAINT_TYPE: usize = 2;
+const WIRE2LABEL_TYPE: usize = 3;
+const CUSTOM_GATES_USED_TYPE: usize = 4;
+const CUSTOM_GATES_APPLIED_TYPE: usize = 5;
+
+fn read_bytes<R: Read>(reader: &mut R, size: usize) -> Result<Vec<u8>, ()> {
+    let mut bytes = vec![0; size];
+    reader.read_exact(&mut bytes).map_err(|_err| {})?;
+    Result::Ok(bytes)
+}
+
+fn read_number<R: Read>(reader: &mut R, size: usize) -> Result<usize, ()> {
+    let bytes = read_bytes(reader, size)?;
+    let mut number: u64 = 0;
+    for byte in bytes.iter().rev() {
+        number = (number << 8) | (*byte as u64);
+    }
+    Result::Ok(number as usize)
+}
+
+#[derive(Copy, Clone)]
+struct SectionInfo {
+    start: u64,
+    size: u64,
+}
+
+// The readers are generic over their source, a file unless another one is given
+pub struct R1CSReader<R = BufReader<File>> {
+    reader: R,
+    field_size: usize,
+    header: HeaderData,
+    sections: [Option<SectionInfo>; SECTIONS],
+}
+
+// Bounded view over one section of the file, every read is checked against the
+// size declared for the section
+struct SectionReader<R> {
+    r1cs: R1CSReader<R>,
+    size: u64,
+    read: u64,
+}
+
+impl<R: Read + Seek> SectionReader<R> {
+    fn new(mut r1cs: R1CSReader<R>, section_type: usize) -> Result<SectionReader<R>, ()> {
+        let info = r1cs.sections[section_type - 1].ok_or(())?;
+        r1cs.reader
+            .seek(SeekFrom::Start(info.start))
+            .map_err(|_err| {})?;
+        Result::Ok(SectionReader {
+            r1cs,
+            size: info.size,
+            read: 0,
+        })
+    }
+
+    fn bytes(&mut self, size: usize) -> Result<Vec<u8>, ()> {
+        if self.read + size as u64 > self.size {
+            return Result::Err(());
+        }
+        self.read += size as u64;
+        read_bytes(&mut self.r1cs.reader, size)
+    }
+
+    fn number(&mut self, size: usize) -> Result<usize, ()> {
+        if self.read + size as u64 > self.size {
+            return Result::Err(());
+        }
+        self.read += size as u64;
+        read_number(&mut self.r1cs.reader, size)
+    }
+
+    // The counts are read from the file, the memory reserved for their elements is bounded
+    // by the number of elements of the given size that the rest of the section can hold
+    fn capacity(&self, count: usize, element_size: usize) -> usize {
+        count.min(((self.size - self.read) / element_size as u64) as usize)
+    }
+
+    fn bigint(&mut self) -> Result<BigInt, ()> {
+        let bytes = self.bytes(self.r1cs.field_size)?;
+        Result::Ok(BigInt::from_bytes_le(Sign::Plus, &bytes))
+    }
+
+    fn string(&mut self) -> Result<String, ()> {
+        let mut bytes = Vec::new();
+        loop {
+            match self.bytes(1)?[0] {
+                0 => break,
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_err| {})
+    }
+
+    fn end_section(self) -> Result<R1CSReader<R>, ()> {
+        if self.read == self.size {
+            Result::Ok(self.r1cs)
+        } else {
+            Result::Err(())
+        }
+    }
+}
+
+pub struct ConstraintSection<R = BufReader<File>> {
+    section: SectionReader<R>,
+    constraints_read: usize,
+}
+
+pub struct SignalSection<R = BufReader<File>> {
+    section: SectionReader<R>,
+    signals_read: usize,
+}
+
+pub struct CustomGatesUsedSection<R = BufReader<File>> {
+    section: SectionReader<R>,
+}
+
+pub struct CustomGatesAppliedSection<R = BufReader<File>> {
+    section: SectionReader<R>,
+}
+
+impl R1CSReader {
+    pub fn new(input_file: String) -> Result<R1CSReader, ()> {
+        let file = File::open(input_file).map_err(|_err| {})?;
+        R1CSReader::from_reader(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> R1CSReader<R> {
+    pub fn from_reader(mut reader: R) -> Result<R1CSReader<R>, ()> {
+        let file_size = reader.seek(SeekFrom::End(0)).map_err(|_err| {})?;
+        reader.rewind().map_err(|_err| {})?;
+        if read_bytes(&mut reader, 4)? != MAGIC || read_number(&mut reader, 4)? != VERSION {
+            return Result::Err(());
+        }
+        let number_of_sections = read_number(&mut reader, 4)?;
+        let mut sections = [None; SECTIONS];
+        let mut position: u64 = 12;
+        for _ in 0..number_of_sections {
+            let section_type = read_number(&mut reader, 4)?;
+            let size = read_number(&mut reader, 8)? as u64;
+            let start = position + 12;
+            let end = match start.checked_add(size) {
+                Some(end) if end <= file_size => end,
+                _ => return Result::Err(()),
+            };
+            // sections of unknown types are skipped
+            if (1..=SECTIONS).contains(&section_type) {
+                if sections[section_type - 1].is_some() {
+                    return Result::Err(());
+                }
+                sections[section_type - 1] = Some(SectionInfo { start, size });
+            }
+            position = end;
+            reader.seek(SeekFrom::Start(position)).map_err(|_err| {})?;
+        }
+        if sections[CONSTRAINT_TYPE - 1].is_none() || sections[WIRE2LABEL_TYPE - 1].is_none() {
+            return Result::Err(());
+        }
+        let header = sections[HEADER_TYPE - 1].ok_or(())?;
+        reader
+            .seek(SeekFrom::Start(header.start))
+            .map_err(|_err| {})?;
+        let field_size = read_number(&mut reader, 4)?;
+        if field_size == 0 || field_size % 8 != 0 || header.size != (field_size + 32) as u64 {
+            return Result::Err(());
+        }
+        let field = BigInt::from_bytes_le(Sign::Plus, &read_bytes(&mut reader, field_size)?);
+        let header = HeaderData {
+            field,
+            total_wires: read_number(&mut reader, 4)?,
+            public_outputs: read_number(&mut reader, 4)?,
+            public_inputs: read_number(&mut reader, 4)?,
+            private_inputs: read_number(&mut reader, 4)?,
+            number_of_labels: read_number(&mut reader, 8)?,
+            number_of_constraints: read_number(&mut reader, 4)?,
+        };
+        Result::Ok(R1CSReader {
+            reader,
+            field_size,
+            header,
+            sections,
+        })
+    }
+
+    pub fn get_header(&self) -> &HeaderData {
+        &self.header
+    }
+
+    pub fn field_size(&self) -> usize {
+        self.field_size
+    }
+
+    pub fn has_custom_gates(&self) -> bool {
+        self.sections[CUSTOM_GATES_USED_TYPE - 1].is_some()
+            && self.sections[CUSTOM_GATES_APPLIED_TYPE - 1].is_some()
+    }
+
+    pub fn start_constraints_section(r1cs: R1CSReader<R>) -> Result<ConstraintSection<R>, ()> {
+        Result::Ok(ConstraintSection {
+            section: SectionReader::new(r1cs, CONSTRAINT_TYPE)?,
+            constraints_read: 0,
+        })
+    }
+
+    pub fn start_signal_section(r1cs: R1CSReader<R>) -> Result<SignalSection<R>, ()> {
+        let section = SectionReader::new(r1cs, WIRE2LABEL_TYPE)?;
+        if section.size != 8 * section.r1cs.header.total_wires as u64 {
+            return Result::Err(());
+        }
+        Result::Ok(SignalSection {
+            section,
+            signals_read: 0,
+        })
+    }
+
+    pub fn start_custom_gates_used_section(
+        r1cs: R1CSReader<R>,
+    ) -> Result<CustomGatesUsedSection<R>, ()> {
+        Result::Ok(CustomGatesUsedSection {
+            section: SectionReader::new(r1cs, CUSTOM_GATES_USED_TYPE)?,
+        })
+    }
+
+    pub fn start_custom_gates_applied_section(
+        r1cs: R1CSReader<R>,
+    ) -> Result<CustomGatesAppliedSection<R>, ()> {
+        Result::Ok(CustomGatesAppliedSection {
+            section: SectionReader::new(r1cs, CUSTOM_GATES_APPLIED_TYPE)?,
+        })
+    }
+}
+
+type LinearCombination = HashMap<usize, BigInt>;
+impl<R: Read + Seek> ConstraintSection<R> {
+    fn read_linear_combination(&mut self) -> Result<LinearCombination, ()> {
+        let non_zero_factors = self.section.number(4)?;
+        let capacity = self
+            .section
+            .capacity(non_zero_factors, 4 + self.section.r1cs.field_size);
+        let mut linear_combination = HashMap::with_capacity(capacity);
+        for _ in 0..non_zero_factors {
+            let signal = self.section.number(4)?;
+            let factor = self.section.bigint()?;
+            if linear_combination.insert(signal, factor).is_some() {
+                return Result::Err(());
+            }
+        }
+        Result::Ok(linear_combination)
+    }
+
+    // Returns None once all the constraints declared in the header have been read
+    pub fn read_constraint(&mut self) -> Result<Option<Constraint<usize>>, ()> {
+        if self.constraints_read == self.section.r1cs.header.number_of_constraints {
+            return Result::Ok(None);
+        }
+        let a = self.read_linear_combination()?;
+        let b = self.read_linear_combination()?;
+        let c = self.read_linear_combination()?;
+        self.constraints_read += 1;
+        Result::Ok(Some(Constraint::new(a, b, c)))
+    }
+
+    pub fn constraints_read(&self) -> usize {
+        self.constraints_read
+    }
+
+    pub fn end_section(self) -> Result<R1CSReader<R>, ()> {
+        self.section.end_section()
+    }
+}
+
+impl<R: Read + Seek> SignalSection<R> {
+    // Returns the label of the next wire, None after the last one
+    pub fn read_signal(&mut self) -> Result<Option<usize>, ()> {
+        if self.signals_read == self.section.r1cs.header.total_wires {
+            return Result::Ok(None);
+        }
+        self.signals_read += 1;
+        self.section.number(8).map(Some)
+    }
+
+    pub fn end_section(self) -> Result<R1CSReader<R>, ()> {
+        self.section.end_section()
+    }
+}
+
+impl<R: Read + Seek> CustomGatesUsedSection<R> {
+    pub fn read_custom_gates_usages(&mut self) -> Result<CustomGatesUsedData, ()> {
+        let no_custom_gates = self.section.number(4)?;
+        // a name ends with a zero byte and is followed by the number of parameters
+        let mut data = Vec::with_capacity(self.section.capacity(no_custom_gates, 5));
+        for _ in 0..no_custom_gates {
+            let custom_gate_name = self.section.string()?;
+            let no_custom_gate_parameters = self.section.number(4)?;
+            let capacity = self
+                .section
+                .capacity(no_custom_gate_parameters, self.section.r1cs.field_size);
+            let mut custom_gate_parameters = Vec::with_capacity(capacity);
+            for _ in 0..no_custom_gate_parameters {
+                custom_gate_parameters.push(self.section.bigint()?);
+            }
+            data.push((custom_gate_name, custom_gate_parameters));
+        }
+        Result::Ok(data)
+    }
+
+    pub fn end_section(self) -> Result<R1CSReader<R>, ()> {
+        self.section.end_section()
+    }
+}
+
+impl<R: Read + Seek> CustomGatesAppliedSection<R> {
+    pub fn read_custom_gates_applications(&mut self) -> Result<CustomGatesAppliedData, ()> {
+        let no_custom_gate_applications = self.section.number(4)?;
+        let mut data = Vec::with_capacity(self.section.capacity(no_custom_gate_applications, 8));
+        for _ in 0..no_custom_gate_applications {
+            let custom_gate_index = self.section.number(4)?;
+            let no_custom_gate_signals = self.section.number(4)?;
+            let mut custom_gate_signals =
+                Vec::with_capacity(self.section.capacity(no_custom_gate_signals, 8));
+            for _ in 0..no_custom_gate_signals {
+                custom_gate_signals.push(self.section.number(8)?);
+            }
+            data.push((custom_gate_index, custom_gate_signals));
+        }
+        Result::Ok(data)
+    }
+
+    pub fn end_section(self) -> Result<R1CSReader<R>, ()> {
+        self.section.end_section()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs_writer::R1CSWriter;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_written_r1cs() {
+        let field = BigInt::from(65521);
+        let one = BigInt::from(1);
+        let minus_one: BigInt = &field - 1;
+        let a: LinearCombination = vec![(1, one.clone())].into_iter().collect();
+        let b: LinearCombination = vec![(2, one.clone())].into_iter().collect();
+        let c: LinearCombination = vec![(0, BigInt::from(3)), (3, minus_one.clone())]
+            .into_iter()
+            .collect();
+
+        let r1cs = R1CSWriter::from_writer(Cursor::new(Vec::new()), 8, true).unwrap();
+        let mut header = R1CSWriter::start_header_section(r1cs).unwrap();
+        header
+            .write_section(HeaderData {
+                field: field.clone(),
+                total_wires: 4,
+                public_outputs: 1,
+                public_inputs: 1,
+                private_inputs: 1,
+                number_of_labels: 5,
+                number_of_constraints: 1,
+            })
+            .unwrap();
+        let r1cs = header.end_section().unwrap();
+        let mut constraints = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        constraints.write_constraint_usize(&a, &b, &c).unwrap();
+        let r1cs = constraints.end_section().unwrap();
+        let mut signals = R1CSWriter::start_signal_section(r1cs).unwrap();
+        for label in [0, 1, 2, 4] {
+            signals.write_signal_usize(label).unwrap();
+        }
+        let r1cs = signals.end_section().unwrap();
+        let mut used = R1CSWriter::start_custom_gates_used_section(r1cs).unwrap();
+        used.write_custom_gates_usages(vec![("Gate".to_string(), vec![one.clone()])])
+            .unwrap();
+        let r1cs = used.end_section().unwrap();
+        let mut applied = R1CSWriter::start_custom_gates_applied_section(r1cs).unwrap();
+        applied
+            .write_custom_gates_applications(vec![(0, vec![1, 2, 3])])
+            .unwrap();
+        let r1cs = applied.end_section().unwrap();
+        let bytes = R1CSWriter::finish_writing(r1cs).unwrap().into_inner();
+
+        let r1cs = R1CSReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(r1cs.field_size(), 8);
+        assert!(r1cs.has_custom_gates());
+        assert_eq!(r1cs.get_header().field, field);
+        assert_eq!(r1cs.get_header().number_of_labels, 5);
+        let mut constraints = R1CSReader::start_constraints_section(r1cs).unwrap();
+        let constraint = constraints.read_constraint().unwrap().unwrap();
+        assert_eq!(
+            (constraint.a(), constraint.b(), constraint.c()),
+            (&a, &b, &c)
+        );
+        assert!(constraints.read_constraint().unwrap().is_none());
+        let r1cs = constraints.end_section().unwrap();
+        let mut signals = R1CSReader::start_signal_section(r1cs).unwrap();
+        let mut labels = Vec::new();
+        while let Some(label) = signals.read_signal().unwrap() {
+            labels.push(label);
+        }
+        assert_eq!(labels, vec![0, 1, 2, 4]);
+        let r1cs = signals.end_section().unwrap();
+        let mut used = R1CSReader::start_custom_gates_used_section(r1cs).unwrap();
+        assert_eq!(
+            used.read_custom_gates_usages().unwrap(),
+            vec![("Gate".to_string(), vec![one])]
+        );
+        let r1cs = used.end_section().unwrap();
+        let mut applied = R1CSReader::start_custom_gates_applied_section(r1cs).unwrap();
+        assert_eq!(
+            applied.read_custom_gates_applications().unwrap(),
+            vec![(0, vec![1, 2, 3])]
+        );
+        applied.end_section().unwrap();
+    }
+
+    // An r1cs file with one constraint and without custom gates
+    fn written_r1cs() -> Vec<u8> {
+        let one = BigInt::from(1);
+        let a: LinearCombination = vec![(1, one.clone())].into_iter().collect();
+        let r1cs = R1CSWriter::from_writer(Cursor::new(Vec::new()), 8, false).unwrap();
+        let mut header = R1CSWriter::start_header_section(r1cs).unwrap();
+        header
+            .write_section(HeaderData {
+                field: BigInt::from(65521),
+                total_wires: 2,
+                public_outputs: 1,
+                public_inputs: 0,
+                private_inputs: 0,
+                number_of_labels: 2,
+                number_of_constraints: 1,
+            })
+            .unwrap();
+        let r1cs = header.end_section().unwrap();
+        let mut constraints = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        constraints.write_constraint_usize(&a, &a, &a).unwrap();
+        let r1cs = constraints.end_section().unwrap();
+        let mut signals = R1CSWriter::start_signal_section(r1cs).unwrap();
+        signals.write_signal_usize(0).unwrap();
+        signals.write_signal_usize(1).unwrap();
+        let r1cs = signals.end_section().unwrap();
+        R1CSWriter::finish_writing(r1cs).unwrap().into_inner()
+    }
+
+    // Reads all the sections of the file
+    fn read_r1cs(bytes: &[u8]) -> Result<(), ()> {
+        let r1cs = R1CSReader::from_reader(Cursor::new(bytes))?;
+        let mut constraints = R1CSReader::start_constraints_section(r1cs)?;
+        while constraints.read_constraint()?.is_some() {}
+        let r1cs = constraints.end_section()?;
+        let mut signals = R1CSReader::start_signal_section(r1cs)?;
+        while signals.read_signal()?.is_some() {}
+        signals.end_section().map(|_| ())
+    }
+
+    #[test]
+    fn invalid_r1cs() {
+        let bytes = written_r1cs();
+        // magic, version and number of sections, then the header section: type, size, field
+        // size, prime, number of wires, outputs, inputs, private inputs, labels and constraints
+        let header = 12;
+        let number_of_constraints = header + 12 + 4 + 8 + 4 * 4 + 8;
+        let constraints = number_of_constraints + 4;
+        assert!(read_r1cs(&bytes).is_ok());
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'x';
+        assert!(read_r1cs(&wrong_magic).is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 2;
+        assert!(read_r1cs(&wrong_version).is_err());
+
+        assert!(read_r1cs(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_r1cs(&bytes[..constraints + 6]).is_err());
+
+        let mut wrong_header_size = bytes.clone();
+        wrong_header_size[header + 4] += 8;
+        assert!(read_r1cs(&wrong_header_size).is_err());
+
+        // the constraints section has one constraint, but the header declares none or two
+        for declared in [0, 2] {
+            let mut wrong_constraints = bytes.clone();
+            wrong_constraints[number_of_constraints] = declared;
+            assert!(read_r1cs(&wrong_constraints).is_err());
+        }
+
+        // counts larger than the section are rejected without reserving memory for them
+        let mut huge_linear_combination = bytes.clone();
+        huge_linear_combination[constraints + 12..constraints + 16].copy_from_slice(&[0xff; 4]);
+        assert!(read_r1cs(&huge_linear_combination).is_err());
+
+        let mut huge_header_size = bytes.clone();
+        huge_header_size[header + 4..header + 12].copy_from_slice(&[0xff; 8]);
+        assert!(read_r1cs(&huge_header_size).is_err());
+
+        let mut wrong_constraints_size = bytes.clone();
+        wrong_constraints_size[constraints + 4] -= 1;
+        assert!(read_r1cs(&wrong_constraints_size).is_err());
+
+        // the header declares two wires, and the signals section has two labels
+        let mut wrong_wires = bytes.clone();
+        wrong_wires[header + 12 + 4 + 8] = 3;
+        assert!(read_r1cs(&wrong_wires).is_err());
+    }
+}
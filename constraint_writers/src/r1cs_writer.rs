@@ -27,7 +27,7 @@ fn bigint_as_bytes(number: &BigInt, with_bytes: usize) -> (Vec<u8>, usize) {
     into_format(&value, with_bytes)
 }
 
-fn initialize_section(writer: &mut BufWriter<File>, header: &[u8]) -> Result<u64, ()> {
+fn initialize_section<W: Write + Seek>(writer: &mut W, header: &[u8]) -> Result<u64, ()> {
     writer.write_all(header).map_err(|_err| {})?;
     //writer.flush().map_err(|_err| {})?;
     let go_back = writer.stream_position().map_err(|_err| {})?;
@@ -36,7 +36,7 @@ fn initialize_section(writer: &mut BufWriter<File>, header: &[u8]) -> Result<u64
     Result::Ok(go_back)
 }
 
-fn end_section(writer: &mut BufWriter<File>, go_back: u64, size: usize) -> Result<(), ()> {
+fn end_section<W: Write + Seek>(writer: &mut W, go_back: u64, size: usize) -> Result<(), ()> {
     let go_back_1 = writer.stream_position().map_err(|_err| {})?;
     writer.seek(SeekFrom::Start(go_back)).map_err(|_err| {})?;
     let (stream, _) = bigint_as_bytes(&BigInt::from(size), 8);
@@ -74,8 +74,8 @@ where
     (block, size)
 }
 
-fn write_constraint<T>(
-    file: &mut BufWriter<File>,
+fn write_constraint<T, W: Write>(
+    file: &mut W,
     a: &HashMap<T, BigInt>,
     b: &HashMap<T, BigInt>,
     c: &HashMap<T, BigInt>,
@@ -96,7 +96,7 @@ where
     Result::Ok(size_a + size_b + size_c)
 }
 
-fn initialize_file(writer: &mut BufWriter<File>, num_sections: u8) -> Result<(), ()> {
+fn initialize_file<W: Write>(writer: &mut W, num_sections: u8) -> Result<(), ()> {
     writer.write_all(MAGIC).map_err(|_err| {})?;
     //writer.flush().map_err(|_err| {})?;
     writer.write_all(VERSION).map_err(|_err| {})?;
@@ -108,14 +108,15 @@ fn initialize_file(writer: &mut BufWriter<File>, num_sections: u8) -> Result<(),
     Result::Ok(())
 }
 
-pub struct R1CSWriter {
+// The writers are generic over their destination, a file unless another one is given
+pub struct R1CSWriter<W = BufWriter<File>> {
     field_size: usize,
-    writer: BufWriter<File>,
+    writer: W,
     sections: [bool; SECTIONS as usize],
 }
 
-pub struct HeaderSection {
-    writer: BufWriter<File>,
+pub struct HeaderSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -123,8 +124,8 @@ pub struct HeaderSection {
     sections: [bool; SECTIONS as usize],
 }
 
-pub struct ConstraintSection {
-    writer: BufWriter<File>,
+pub struct ConstraintSection<W = BufWriter<File>> {
+    writer: W,
     number_of_constraints: usize,
     go_back: u64,
     size: usize,
@@ -133,8 +134,8 @@ pub struct ConstraintSection {
     sections: [bool; SECTIONS as usize],
 }
 
-pub struct SignalSection {
-    writer: BufWriter<File>,
+pub struct SignalSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -142,8 +143,8 @@ pub struct SignalSection {
     sections: [bool; SECTIONS as usize],
 }
 
-pub struct CustomGatesUsedSection {
-    writer: BufWriter<File>,
+pub struct CustomGatesUsedSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -151,8 +152,8 @@ pub struct CustomGatesUsedSection {
     sections: [bool; SECTIONS as usize],
 }
 
-pub struct CustomGatesAppliedSection {
-    writer: BufWriter<File>,
+pub struct CustomGatesAppliedSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -166,11 +167,21 @@ impl R1CSWriter {
         field_size: usize,
         custom_gates: bool,
     ) -> Result<R1CSWriter, ()> {
-        let sections = [false; SECTIONS as usize];
-        let num_sections: u8 = if custom_gates { 5 } else { 3 };
-        let mut writer = File::create(output_file)
+        let writer = File::create(output_file)
             .map_err(|_err| {})
             .map(BufWriter::new)?;
+        R1CSWriter::from_writer(writer, field_size, custom_gates)
+    }
+}
+
+impl<W: Write + Seek> R1CSWriter<W> {
+    pub fn from_writer(
+        mut writer: W,
+        field_size: usize,
+        custom_gates: bool,
+    ) -> Result<R1CSWriter<W>, ()> {
+        let sections = [false; SECTIONS as usize];
+        let num_sections: u8 = if custom_gates { 5 } else { 3 };
         initialize_file(&mut writer, num_sections)?;
         Result::Ok(R1CSWriter {
             writer,
@@ -179,7 +190,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_header_section(mut r1cs: R1CSWriter) -> Result<HeaderSection, ()> {
+    pub fn start_header_section(mut r1cs: R1CSWriter<W>) -> Result<HeaderSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, HEADER_TYPE)?;
         Result::Ok(HeaderSection {
             writer: r1cs.writer,
@@ -191,7 +202,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_constraints_section(mut r1cs: R1CSWriter) -> Result<ConstraintSection, ()> {
+    pub fn start_constraints_section(mut r1cs: R1CSWriter<W>) -> Result<ConstraintSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CONSTRAINT_TYPE)?;
         Result::Ok(ConstraintSection {
             number_of_constraints: 0,
@@ -204,7 +215,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_signal_section(mut r1cs: R1CSWriter) -> Result<SignalSection, ()> {
+    pub fn start_signal_section(mut r1cs: R1CSWriter<W>) -> Result<SignalSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, WIRE2LABEL_TYPE)?;
         Result::Ok(SignalSection {
             writer: r1cs.writer,
@@ -217,8 +228,8 @@ impl R1CSWriter {
     }
 
     pub fn start_custom_gates_used_section(
-        mut r1cs: R1CSWriter,
-    ) -> Result<CustomGatesUsedSection, ()> {
+        mut r1cs: R1CSWriter<W>,
+    ) -> Result<CustomGatesUsedSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CUSTOM_GATES_USED_TYPE)?;
         Result::Ok(CustomGatesUsedSection {
             writer: r1cs.writer,
@@ -231,8 +242,8 @@ impl R1CSWriter {
     }
 
     pub fn start_custom_gates_applied_section(
-        mut r1cs: R1CSWriter,
-    ) -> Result<CustomGatesAppliedSection, ()> {
+        mut r1cs: R1CSWriter<W>,
+    ) -> Result<CustomGatesAppliedSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CUSTOM_GATES_APPLIED_TYPE)?;
         Result::Ok(CustomGatesAppliedSection {
             writer: r1cs.writer,
@@ -244,11 +255,14 @@ impl R1CSWriter {
         })
     }
 
-    pub fn finish_writing(mut r1cs: R1CSWriter) -> Result<(), ()> {
-        r1cs.writer.flush().map_err(|_err| {})
+    // Returns the destination once everything is written to it
+    pub fn finish_writing(mut r1cs: R1CSWriter<W>) -> Result<W, ()> {
+        r1cs.writer.flush().map_err(|_err| {})?;
+        Result::Ok(r1cs.writer)
     }
 }
 
+#[derive(Clone)]
 pub struct HeaderData {
     pub field: BigInt,
     pub total_wires: usize,
@@ -259,7 +273,7 @@ pub struct HeaderData {
     pub number_of_constraints: usize,
 }
 
-impl HeaderSection {
+impl<W: Write + Seek> HeaderSection<W> {
     pub fn write_section(&mut self, data: HeaderData) -> Result<(), ()> {
         let (field_stream, bytes_field) = bigint_as_bytes(&data.field, self.field_size);
         let (length_stream, bytes_size) = bigint_as_bytes(&BigInt::from(self.field_size), 4);
@@ -285,7 +299,7 @@ impl HeaderSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -299,7 +313,7 @@ impl HeaderSection {
 }
 
 type Constraint = HashMap<usize, BigInt>;
-impl ConstraintSection {
+impl<W: Write + Seek> ConstraintSection<W> {
     pub fn write_constraint_usize(
         &mut self,
         a: &Constraint,
@@ -328,7 +342,7 @@ impl ConstraintSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -345,7 +359,7 @@ impl ConstraintSection {
     }
 }
 
-impl SignalSection {
+impl<W: Write + Seek> SignalSection<W> {
     pub fn write_signal<T>(&mut self, bytes: &T) -> Result<(), ()>
     where
         T: AsRef<[u8]>,
@@ -361,7 +375,7 @@ impl SignalSection {
         SignalSection::write_signal(self, &as_bytes)
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -375,7 +389,7 @@ impl SignalSection {
 }
 
 pub type CustomGatesUsedData = Vec<(String, Vec<BigInt>)>;
-impl CustomGatesUsedSection {
+impl<W: Write + Seek> CustomGatesUsedSection<W> {
     pub fn write_custom_gates_usages(&mut self, data: CustomGatesUsedData) -> Result<(), ()> {
         let no_custom_gates = data.len();
         let (no_custom_gates_stream, no_custom_gates_size) =
@@ -418,7 +432,7 @@ impl CustomGatesUsedSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -432,7 +446,7 @@ impl CustomGatesUsedSection {
 }
 
 pub type CustomGatesAppliedData = Vec<(usize, Vec<usize>)>;
-impl CustomGatesAppliedSection {
+impl<W: Write + Seek> CustomGatesAppliedSection<W> {
     pub fn write_custom_gates_applications(
         &mut self,
         data: CustomGatesAppliedData,
@@ -477,7 +491,7 @@ impl CustomGatesAppliedSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -16,18 +16,23 @@ impl ToString for SymElem {
     }
 }
 
-pub struct SymFile {
-    writer: BufWriter<File>,
+pub struct SymFile<W = BufWriter<File>> {
+    writer: W,
 }
 
 impl SymFile {
     pub fn new(file: &str) -> Result<SymFile, ()> {
         let file = File::create(file).map_err(|_err| {})?;
-        let writer = BufWriter::new(file);
-        Result::Ok(SymFile { writer })
+        Result::Ok(SymFile::from_writer(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> SymFile<W> {
+    pub fn from_writer(writer: W) -> SymFile<W> {
+        SymFile { writer }
     }
 
-    pub fn write_sym_elem(sym: &mut SymFile, elem: SymElem) -> Result<(), ()> {
+    pub fn write_sym_elem(sym: &mut SymFile<W>, elem: SymElem) -> Result<(), ()> {
         sym.writer
             .write_all(elem.to_string().as_bytes())
             .map_err(|_err| {})?;
@@ -35,8 +40,10 @@ impl SymFile {
                                                        //sym.writer.flush().map_err(|_err| {})
     }
 
-    pub fn finish_writing(mut sym: SymFile) -> Result<(), ()> {
-        sym.writer.flush().map_err(|_err| {})
+    // Returns the destination once everything is written to it
+    pub fn finish_writing(mut sym: SymFile<W>) -> Result<W, ()> {
+        sym.writer.flush().map_err(|_err| {})?;
+        Result::Ok(sym.writer)
     }
 
     // pub fn close(_sym: SymFile) {}
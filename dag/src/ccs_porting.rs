@@ -0,0 +1,60 @@
+use super::{Tree, DAG};
+use constraint_writers::ccs_writer::{CCSEncoding, CCSHeader, CCSSignal, CCSWriter};
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+// Writes the constraints of the DAG, before the simplification, as a circom constraint
+// system file (ccs), see constraint_writers::ccs_writer
+pub fn write<W: Write + Seek>(dag: &DAG, output: W, encoding: CCSEncoding) -> Result<W, ()> {
+    let tree = Tree::new(dag);
+    let header = CCSHeader {
+        field: tree.field.clone(),
+        // Adding 1 to include the signal used to represent value 1 in the field
+        wires: count_signals(&tree) + 1,
+        public_outputs: dag.public_outputs(),
+        public_inputs: dag.public_inputs(),
+        private_inputs: dag.private_inputs(),
+    };
+    let mut ccs = CCSWriter::from_writer(output, encoding, &header)?;
+    write_constraints(&mut ccs, &tree)?;
+    write_signals(&mut ccs, &tree)?;
+    CCSWriter::finish_writing(ccs)
+}
+
+fn count_signals(tree: &Tree) -> usize {
+    let mut no_signals = tree.signals.len();
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        no_signals += count_signals(&subtree);
+    }
+    no_signals
+}
+
+fn write_constraints<W: Write + Seek>(ccs: &mut CCSWriter<W>, tree: &Tree) -> Result<(), ()> {
+    for c in &tree.constraints {
+        ccs.write_constraint(c.a(), c.b(), c.c())?;
+    }
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        write_constraints(ccs, &subtree)?;
+    }
+    Ok(())
+}
+
+fn write_signals<W: Write + Seek>(ccs: &mut CCSWriter<W>, tree: &Tree) -> Result<(), ()> {
+    for signal in &tree.signals {
+        let name = HashMap::get(&tree.id_to_name, signal).unwrap();
+        let ccs_signal = CCSSignal {
+            label: *signal,
+            wire: Some(*signal),
+            component: tree.node_id,
+            name: format!("{}.{}", tree.path, name),
+        };
+        ccs.write_signal(&ccs_signal)?;
+    }
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        write_signals(ccs, &subtree)?;
+    }
+    Ok(())
+}
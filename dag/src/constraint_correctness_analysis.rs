@@ -68,7 +68,25 @@ fn split_signal_name_index(name: &String) -> String {
     signal_name + split_index_last_component
 }
 
-fn analysis_interpretation(analysis: Analysis, result: &mut AnalysisResult) {
+// Signals of a template, grouped by array, that do not appear in any constraint
+pub struct UnconstrainedSignals {
+    pub template: String,
+    pub signal: String,
+    pub examples: Vec<String>,
+    pub is_local: bool,
+}
+
+impl UnconstrainedSignals {
+    pub fn to_report(&self) -> Report {
+        if self.is_local {
+            UnconstrainedSignal::new(&self.signal, &self.template, &self.examples)
+        } else {
+            UnconstrainedIOSignal::new(&self.signal, &self.template, &self.examples)
+        }
+    }
+}
+
+fn analysis_interpretation(analysis: Analysis, result: &mut Vec<UnconstrainedSignals>) {
     let tmp_name = analysis.template_name;
     let stats = analysis.signal_stats;
 
@@ -89,16 +107,15 @@ fn analysis_interpretation(analysis: Analysis, result: &mut AnalysisResult) {
             }
         }
     }
-    for (name, (xtype, examples)) in signal2unconstrainedex {
-        if xtype == SignalType::Local {
-            result
-                .warnings
-                .push(UnconstrainedSignal::new(&name, &tmp_name, &examples));
-        } else {
-            result
-                .warnings
-                .push(UnconstrainedIOSignal::new(&name, &tmp_name, &examples));
-        }
+    let mut unconstrained: Vec<_> = signal2unconstrainedex.into_iter().collect();
+    unconstrained.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, (xtype, examples)) in unconstrained {
+        result.push(UnconstrainedSignals {
+            template: tmp_name.clone(),
+            signal: name,
+            examples,
+            is_local: xtype == SignalType::Local,
+        });
     }
 }
 
@@ -158,24 +175,26 @@ pub fn clean_constraints(nodes: &mut [Node]) {
     }
 }
 
-pub fn analyse(nodes: &[Node]) -> AnalysisResult {
-    let mut result = AnalysisResult {
-        errors: vec![],
-        warnings: vec![],
-    };
+pub fn unconstrained_signals(nodes: &[Node]) -> Vec<UnconstrainedSignals> {
+    let mut result = Vec::new();
     let mut visited: HashSet<String> = HashSet::new();
     for node in nodes {
         if !node.is_custom_gate() && !visited.contains(&node.template_name.clone()) {
             let analysis = visit_node(node);
-            let mut result2 = AnalysisResult {
-                errors: vec![],
-                warnings: vec![],
-            };
-            analysis_interpretation(analysis, &mut result2);
-            result.errors.append(&mut result2.errors);
-            result.warnings.append(&mut result2.warnings);
+            analysis_interpretation(analysis, &mut result);
             visited.insert(node.template_name.clone());
         }
     }
     result
 }
+
+pub fn analyse(nodes: &[Node]) -> AnalysisResult {
+    let warnings = unconstrained_signals(nodes)
+        .iter()
+        .map(UnconstrainedSignals::to_report)
+        .collect();
+    AnalysisResult {
+        errors: vec![],
+        warnings,
+    }
+}
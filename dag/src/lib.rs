@@ -1,16 +1,23 @@
+mod ccs_porting;
 mod constraint_correctness_analysis;
 mod json_porting;
 mod map_to_constraint_list;
+mod plonk_porting;
 mod r1cs_porting;
 mod sym_porting;
+mod underconstrained_analysis;
 mod witness_producer;
 use circom_algebra::num_bigint::BigInt;
 use constraint_list::ConstraintList;
+use constraint_writers::ccs_writer::CCSEncoding;
 use constraint_writers::debug_writer::DebugWriter;
-use constraint_writers::ConstraintExporter;
+use constraint_writers::{create_file, ConstraintExporter};
 use program_structure::constants::UsefulConstants;
 use program_structure::error_definition::ReportCollection;
 use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+pub use constraint_correctness_analysis::UnconstrainedSignals;
+pub use underconstrained_analysis::UnderconstrainedOutput;
 type Signal = usize;
 type Constraint = circom_algebra::algebra::Constraint<usize>;
 type Substitution = circom_algebra::algebra::Substitution<usize>;
@@ -336,6 +343,14 @@ impl ConstraintExporter for DAG {
         DAG::generate_r1cs_output(self, out, custom_gates)
     }
 
+    fn plonk(&self, out: &str, custom_gates: bool) -> Result<(), ()> {
+        DAG::generate_plonk_output(self, out, custom_gates)
+    }
+
+    fn ccs(&self, out: &str, encoding: CCSEncoding) -> Result<(), ()> {
+        DAG::generate_ccs_output(self, out, encoding)
+    }
+
     fn json_constraints(&self, writer: &DebugWriter) -> Result<(), ()> {
         DAG::generate_json_constraints(self, writer)
     }
@@ -343,6 +358,22 @@ impl ConstraintExporter for DAG {
     fn sym(&self, out: &str) -> Result<(), ()> {
         DAG::generate_sym_output(self, out)
     }
+
+    fn r1cs_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()> {
+        r1cs_porting::write(self, Cursor::new(Vec::new()), custom_gates).map(Cursor::into_inner)
+    }
+
+    fn plonk_bytes(&self, custom_gates: bool) -> Result<Vec<u8>, ()> {
+        plonk_porting::write(self, Vec::new(), custom_gates)
+    }
+
+    fn ccs_bytes(&self, encoding: CCSEncoding) -> Result<Vec<u8>, ()> {
+        ccs_porting::write(self, Cursor::new(Vec::new()), encoding).map(Cursor::into_inner)
+    }
+
+    fn sym_bytes(&self) -> Result<Vec<u8>, ()> {
+        sym_porting::write(self, Vec::new())
+    }
 }
 
 impl DAG {
@@ -504,16 +535,32 @@ impl DAG {
         }
     }
 
+    pub fn unconstrained_signals(&self) -> Vec<UnconstrainedSignals> {
+        constraint_correctness_analysis::unconstrained_signals(&self.nodes)
+    }
+
+    pub fn underconstrained_outputs(&self) -> Vec<UnderconstrainedOutput> {
+        underconstrained_analysis::underconstrained_outputs(self)
+    }
+
     pub fn clean_constraints(&mut self) {
         constraint_correctness_analysis::clean_constraints(&mut self.nodes);
     }
 
     pub fn generate_r1cs_output(&self, output_file: &str, custom_gates: bool) -> Result<(), ()> {
-        r1cs_porting::write(self, output_file, custom_gates)
+        r1cs_porting::write(self, create_file(output_file)?, custom_gates).map(|_| ())
+    }
+
+    pub fn generate_plonk_output(&self, output_file: &str, custom_gates: bool) -> Result<(), ()> {
+        plonk_porting::write(self, create_file(output_file)?, custom_gates).map(|_| ())
+    }
+
+    pub fn generate_ccs_output(&self, output_file: &str, encoding: CCSEncoding) -> Result<(), ()> {
+        ccs_porting::write(self, create_file(output_file)?, encoding).map(|_| ())
     }
 
     pub fn generate_sym_output(&self, output_file: &str) -> Result<(), ()> {
-        sym_porting::write(self, output_file)
+        sym_porting::write(self, create_file(output_file)?).map(|_| ())
     }
 
     pub fn generate_json_constraints(&self, debug: &DebugWriter) -> Result<(), ()> {
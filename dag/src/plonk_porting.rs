@@ -0,0 +1,40 @@
+use super::{Tree, DAG};
+use constraint_writers::plonk_writer::{PlonkHeader, PlonkWriter};
+use std::io::Write;
+
+pub fn write<W: Write>(dag: &DAG, output: W, custom_gates: bool) -> Result<W, ()> {
+    let tree = Tree::new(dag);
+    // Adding 1 to include the signal used to represent value 1 in the field
+    let wires = count_signals(&tree) + 1;
+    let mut plonk = PlonkWriter::from_writer(output, tree.field.clone(), wires);
+    write_constraints(&mut plonk, &tree);
+    if custom_gates {
+        let (usage_data, application_data) = super::r1cs_porting::custom_gates_data(dag, &tree);
+        plonk.write_custom_gates(usage_data, application_data);
+    }
+    let header = PlonkHeader {
+        public_outputs: dag.public_outputs(),
+        public_inputs: dag.public_inputs(),
+        private_inputs: dag.private_inputs(),
+    };
+    PlonkWriter::finish_writing(plonk, header)
+}
+
+fn count_signals(tree: &Tree) -> usize {
+    let mut no_signals = tree.signals.len();
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        no_signals += count_signals(&subtree);
+    }
+    no_signals
+}
+
+fn write_constraints<W: Write>(plonk: &mut PlonkWriter<W>, tree: &Tree) {
+    for c in &tree.constraints {
+        plonk.write_constraint_usize(c.a(), c.b(), c.c());
+    }
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        write_constraints(plonk, &subtree);
+    }
+}
@@ -1,10 +1,11 @@
 use super::{Constraint, Tree, DAG};
 use constraint_writers::log_writer::Log;
 use constraint_writers::r1cs_writer::{
-    ConstraintSection, CustomGatesAppliedData, HeaderData, R1CSWriter,
+    ConstraintSection, CustomGatesAppliedData, CustomGatesUsedData, HeaderData, R1CSWriter,
 };
+use std::io::{Seek, Write};
 
-pub fn write(dag: &DAG, output: &str, custom_gates: bool) -> Result<(), ()> {
+pub fn write<W: Write + Seek>(dag: &DAG, output: W, custom_gates: bool) -> Result<W, ()> {
     let tree = Tree::new(dag);
     let field_size = if tree.field.bits() % 64 == 0 {
         tree.field.bits() / 8
@@ -12,7 +13,7 @@ pub fn write(dag: &DAG, output: &str, custom_gates: bool) -> Result<(), ()> {
         (tree.field.bits() / 64 + 1) * 8
     };
     let mut log = Log::new();
-    let r1cs = R1CSWriter::new(output.to_string(), field_size, custom_gates)?;
+    let r1cs = R1CSWriter::from_writer(output, field_size, custom_gates)?;
 
     let mut constraint_section = R1CSWriter::start_constraints_section(r1cs)?;
     let wires = write_constraint_section(&mut constraint_section, &mut log, &tree)? + 1; // adding 1 to include the signal used to represent value 1 in the field (signal one)
@@ -46,76 +47,83 @@ pub fn write(dag: &DAG, output: &str, custom_gates: bool) -> Result<(), ()> {
     }
     let r1cs = signal_section.end_section()?;
 
-    if !custom_gates {
-        R1CSWriter::finish_writing(r1cs)?;
+    let output = if !custom_gates {
+        R1CSWriter::finish_writing(r1cs)?
     } else {
+        let (usage_data, application_data) = custom_gates_data(dag, &tree);
         let mut custom_gates_used_section = R1CSWriter::start_custom_gates_used_section(r1cs)?;
-        let (usage_data, occurring_order) = {
-            let mut usage_data = vec![];
-            let mut occurring_order = vec![];
-            for node in &dag.nodes {
-                if node.is_custom_gate() {
-                    let mut name = node.template_name.clone();
-                    occurring_order.push(name.clone());
-                    while name.pop() != Some('(') {}
-                    usage_data.push((name, node.parameters().clone()));
-                }
-            }
-            (usage_data, occurring_order)
-        };
         custom_gates_used_section.write_custom_gates_usages(usage_data)?;
         let r1cs = custom_gates_used_section.end_section()?;
 
         let mut custom_gates_applied_section =
             R1CSWriter::start_custom_gates_applied_section(r1cs)?;
-        let application_data = {
-            fn find_indexes(
-                occurring_order: Vec<String>,
-                application_data: Vec<(String, Vec<usize>)>,
-            ) -> CustomGatesAppliedData {
-                let mut new_application_data = vec![];
-                for (custom_gate_name, signals) in application_data {
-                    let mut index = 0;
-                    while occurring_order[index] != custom_gate_name {
-                        index += 1;
-                    }
-                    new_application_data.push((index, signals));
+        custom_gates_applied_section.write_custom_gates_applications(application_data)?;
+        let r1cs = custom_gates_applied_section.end_section()?;
+        R1CSWriter::finish_writing(r1cs)?
+    };
+
+    Log::print(&log);
+    Result::Ok(output)
+}
+
+// The custom gates used by the circuit and their applications, in the format of the
+// sections 4 and 5 of the r1cs files
+pub fn custom_gates_data(dag: &DAG, tree: &Tree) -> (CustomGatesUsedData, CustomGatesAppliedData) {
+    let (usage_data, occurring_order) = {
+        let mut usage_data = vec![];
+        let mut occurring_order = vec![];
+        for node in &dag.nodes {
+            if node.is_custom_gate() {
+                let mut name = node.template_name.clone();
+                occurring_order.push(name.clone());
+                while name.pop() != Some('(') {}
+                usage_data.push((name, node.parameters().clone()));
+            }
+        }
+        (usage_data, occurring_order)
+    };
+    let application_data = {
+        fn find_indexes(
+            occurring_order: Vec<String>,
+            application_data: Vec<(String, Vec<usize>)>,
+        ) -> CustomGatesAppliedData {
+            let mut new_application_data = vec![];
+            for (custom_gate_name, signals) in application_data {
+                let mut index = 0;
+                while occurring_order[index] != custom_gate_name {
+                    index += 1;
                 }
-                new_application_data
+                new_application_data.push((index, signals));
             }
+            new_application_data
+        }
 
-            fn traverse_tree(tree: &Tree, application_data: &mut Vec<(String, Vec<usize>)>) {
-                let node = &tree.dag.nodes[tree.node_id];
-                if node.is_custom_gate() {
-                    let mut signals = vec![];
-                    for signal in &node.ordered_signals {
-                        let signal_numbering = node.signal_correspondence.get(signal).unwrap();
-                        signals.push(*signal_numbering + tree.offset);
-                    }
-                    application_data.push((node.template_name.clone(), signals));
-                } else {
-                    for edge in Tree::get_edges(tree) {
-                        let subtree = Tree::go_to_subtree(tree, edge);
-                        traverse_tree(&subtree, application_data);
-                    }
+        fn traverse_tree(tree: &Tree, application_data: &mut Vec<(String, Vec<usize>)>) {
+            let node = &tree.dag.nodes[tree.node_id];
+            if node.is_custom_gate() {
+                let mut signals = vec![];
+                for signal in &node.ordered_signals {
+                    let signal_numbering = node.signal_correspondence.get(signal).unwrap();
+                    signals.push(*signal_numbering + tree.offset);
+                }
+                application_data.push((node.template_name.clone(), signals));
+            } else {
+                for edge in Tree::get_edges(tree) {
+                    let subtree = Tree::go_to_subtree(tree, edge);
+                    traverse_tree(&subtree, application_data);
                 }
             }
+        }
 
-            let mut application_data = vec![];
-            traverse_tree(&tree, &mut application_data);
-            find_indexes(occurring_order, application_data)
-        };
-        custom_gates_applied_section.write_custom_gates_applications(application_data)?;
-        let r1cs = custom_gates_applied_section.end_section()?;
-        R1CSWriter::finish_writing(r1cs)?;
-    }
-
-    Log::print(&log);
-    Result::Ok(())
+        let mut application_data = vec![];
+        traverse_tree(tree, &mut application_data);
+        find_indexes(occurring_order, application_data)
+    };
+    (usage_data, application_data)
 }
 
-fn write_constraint_section(
-    constraint_section: &mut ConstraintSection,
+fn write_constraint_section<W: Write + Seek>(
+    constraint_section: &mut ConstraintSection<W>,
     log: &mut Log,
     tree: &Tree,
 ) -> Result<usize, ()> {
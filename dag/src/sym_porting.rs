@@ -2,17 +2,16 @@ use super::{Tree, DAG};
 use circom_algebra::num_traits::AsPrimitive;
 use constraint_writers::sym_writer::*;
 use std::collections::HashMap;
+use std::io::Write;
 
-pub fn write(dag: &DAG, file_name: &str) -> Result<(), ()> {
+pub fn write<W: Write>(dag: &DAG, output: W) -> Result<W, ()> {
     let tree = Tree::new(dag);
-    let mut dot_sym = SymFile::new(file_name)?;
+    let mut dot_sym = SymFile::from_writer(output);
     visit_tree(&tree, &mut dot_sym)?;
-    SymFile::finish_writing(dot_sym)?;
-    //SymFile::close(dot_sym);
-    Ok(())
+    SymFile::finish_writing(dot_sym)
 }
 
-fn visit_tree(tree: &Tree, dot_sym: &mut SymFile) -> Result<(), ()> {
+fn visit_tree<W: Write>(tree: &Tree, dot_sym: &mut SymFile<W>) -> Result<(), ()> {
     for signal in &tree.signals {
         let name = HashMap::get(&tree.id_to_name, signal).unwrap();
         let symbol = format!("{}.{}", tree.path, name);
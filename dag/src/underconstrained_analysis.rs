@@ -0,0 +1,514 @@
+use super::{Tree, DAG};
+use circom_algebra::algebra::Constraint;
+use circom_algebra::modular_arithmetic;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::num_traits::{One, Zero};
+use program_structure::constants::UsefulConstants;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::Report;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+type C = Constraint<usize>;
+// Product of two signals, the constant signal 0 stands for 1
+type Monomial = (usize, usize);
+type Polynomial = BTreeMap<Monomial, BigInt>;
+
+const UNDERCONSTRAINED_OUTPUT_CODE: ReportCode = ReportCode::UnderconstrainedOutput;
+const MAX_EXAMPLES: usize = 3;
+
+// Why an output of the main component can take more than one value
+pub enum Cause {
+    // The output and the signals equal to it are in no other constraint
+    NotConstrained,
+    // Signals in a single constraint that does not fix their value
+    FreeSignals,
+    // Signals the output depends on that are not determined either
+    UndeterminedSignals,
+}
+
+// Outputs of the main component, grouped by array, that the constraints do not determine
+// from the inputs. The signals at fault are given together with their template instance.
+pub struct UnderconstrainedOutput {
+    pub template: String,
+    pub signal: String,
+    pub examples: Vec<String>,
+    pub cause: Cause,
+    pub signals_at_fault: Vec<(String, String)>,
+}
+
+impl UnderconstrainedOutput {
+    pub fn to_report(&self) -> Report {
+        let mut report = if self.examples.len() == 1 {
+            let msg = format!(
+                "In template \"{}\": Output signal {} is not uniquely determined by the inputs",
+                self.template, self.examples[0]
+            );
+            Report::warning(msg, UNDERCONSTRAINED_OUTPUT_CODE)
+        } else {
+            let msg = format!("In template \"{}\": Array of output signals {} contains a total of {} signals that are not uniquely determined by the inputs", self.template, self.signal, self.examples.len());
+            let mut report = Report::warning(msg, UNDERCONSTRAINED_OUTPUT_CODE);
+            report.add_note(format!(
+                "For example: {}, {}.",
+                self.examples[0], self.examples[1]
+            ));
+            report
+        };
+        match self.cause {
+            Cause::NotConstrained => {
+                for (signal, template) in &self.signals_at_fault {
+                    report.add_note(format!(
+                        "Signal {} of template \"{}\" does not appear in any constraint, it is only assigned with <--",
+                        signal, template
+                    ));
+                }
+            }
+            Cause::FreeSignals => {
+                for (signal, template) in self.signals_at_fault.iter().take(MAX_EXAMPLES) {
+                    report.add_note(format!(
+                        "Signal {} of template \"{}\" only appears in one constraint, which does not fix its value",
+                        signal, template
+                    ));
+                }
+            }
+            Cause::UndeterminedSignals => {
+                for (signal, template) in self.signals_at_fault.iter().take(MAX_EXAMPLES) {
+                    report.add_note(format!(
+                        "It depends on signal {} of template \"{}\", which is not determined by the inputs either",
+                        signal, template
+                    ));
+                }
+            }
+        }
+        if self.signals_at_fault.len() > MAX_EXAMPLES {
+            report.add_note(format!(
+                "And {} signals more",
+                self.signals_at_fault.len() - MAX_EXAMPLES
+            ));
+        }
+        report
+    }
+}
+
+struct Circuit {
+    field: BigInt,
+    main_template: String,
+    outputs: Vec<usize>,
+    // The constant signal, the inputs of main and the signals of the custom gates
+    known: HashSet<usize>,
+    constraints: Vec<Polynomial>,
+    // Full name and template instance of each signal
+    names: HashMap<usize, (String, String)>,
+}
+
+fn add_term(polynomial: &mut Polynomial, key: Monomial, value: &BigInt, field: &BigInt) {
+    let prev = polynomial.remove(&key).unwrap_or_else(BigInt::zero);
+    let next = modular_arithmetic::add(&prev, value, field);
+    if !next.is_zero() {
+        polynomial.insert(key, next);
+    }
+}
+
+fn expand(constraint: &C, field: &BigInt) -> Polynomial {
+    let mut polynomial = Polynomial::new();
+    for (s, a) in constraint.a() {
+        for (t, b) in constraint.b() {
+            let key = if s <= t { (*s, *t) } else { (*t, *s) };
+            let value = modular_arithmetic::mul(a, b, field);
+            add_term(&mut polynomial, key, &value, field);
+        }
+    }
+    for (s, c) in constraint.c() {
+        let value = modular_arithmetic::prefix_sub(c, field);
+        add_term(&mut polynomial, (0, *s), &value, field);
+    }
+    polynomial
+}
+
+fn signals_of(polynomial: &Polynomial) -> BTreeSet<usize> {
+    let mut signals = BTreeSet::new();
+    for (s, t) in polynomial.keys() {
+        signals.insert(*s);
+        signals.insert(*t);
+    }
+    signals.remove(&0);
+    signals
+}
+
+fn collect(tree: &Tree, circuit: &mut Circuit) {
+    let node = &tree.dag.nodes[tree.node_id];
+    for signal in &tree.signals {
+        let name = format!("{}.{}", tree.path, tree.id_to_name[signal]);
+        circuit
+            .names
+            .insert(*signal, (name, node.template_name.clone()));
+        if node.is_custom_gate() {
+            circuit.known.insert(*signal);
+        }
+    }
+    for constraint in &tree.constraints {
+        let polynomial = expand(constraint, &circuit.field);
+        if !polynomial.is_empty() {
+            circuit.constraints.push(polynomial);
+        }
+    }
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        collect(&subtree, circuit);
+    }
+}
+
+fn build_circuit(dag: &DAG) -> Circuit {
+    let main = dag.get_main().unwrap();
+    let offset = dag.get_entry().unwrap().in_number;
+    let outputs_length = main.number_of_outputs();
+    let mut known: HashSet<usize> = main.io_signals()[outputs_length..]
+        .iter()
+        .map(|s| *s + offset)
+        .collect();
+    known.insert(0);
+    let mut circuit = Circuit {
+        field: UsefulConstants::new(&dag.prime).get_p().clone(),
+        main_template: main.template_name.clone(),
+        outputs: main.io_signals()[..outputs_length]
+            .iter()
+            .map(|s| *s + offset)
+            .collect(),
+        known,
+        constraints: Vec::new(),
+        names: HashMap::new(),
+    };
+    let tree = Tree::new(dag);
+    collect(&tree, &mut circuit);
+    circuit
+}
+
+// s * (s - 1) = 0, the signal is a bit
+fn binary_signal(polynomial: &Polynomial, field: &BigInt) -> Option<usize> {
+    let mut terms = polynomial.iter();
+    match (terms.next(), terms.next(), terms.next()) {
+        (Some(((0, s), a)), Some(((u, v), b)), None) if *s != 0 && s == u && s == v => {
+            if modular_arithmetic::add(a, b, field).is_zero() {
+                Some(*s)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn power_of_two(value: &BigInt) -> Option<usize> {
+    if value.is_zero() {
+        return None;
+    }
+    let exponent = value.bits() - 1;
+    if *value == BigInt::one() << exponent {
+        Some(exponent)
+    } else {
+        None
+    }
+}
+
+// A linear constraint that sums the unknown bits multiplied by different powers of two
+// determines all of them, as long as the sum can not exceed the prime
+fn determines_bits(
+    polynomial: &Polynomial,
+    unknown: &BTreeSet<usize>,
+    bits: &HashSet<usize>,
+    field: &BigInt,
+) -> bool {
+    if !unknown.iter().all(|s| bits.contains(s)) {
+        return false;
+    }
+    let mut coefficients = Vec::new();
+    for ((s, t), value) in polynomial {
+        if unknown.contains(s) || unknown.contains(t) {
+            if *s != 0 {
+                return false;
+            }
+            coefficients.push(value);
+        }
+    }
+    for base in &coefficients {
+        let inverse = match modular_arithmetic::div(&BigInt::one(), base, field) {
+            Ok(inverse) => inverse,
+            Err(_) => continue,
+        };
+        let mut exponents = HashSet::new();
+        let valid = coefficients.iter().all(|value| {
+            let ratio = modular_arithmetic::mul(value, &inverse, field);
+            match power_of_two(&ratio) {
+                Some(exponent) => exponent + 1 < field.bits() && exponents.insert(exponent),
+                None => false,
+            }
+        });
+        if valid {
+            return true;
+        }
+    }
+    false
+}
+
+// Forward propagation of the signals whose value is fixed once the inputs are known.
+// A constraint determines its only unknown signal when the signal is not squared, even
+// if its coefficient depends on other signals, as in the IsZero template.
+fn determined_signals(
+    circuit: &Circuit,
+    signal_to_constraints: &HashMap<usize, Vec<usize>>,
+) -> HashSet<usize> {
+    let field = &circuit.field;
+    let bits: HashSet<usize> = circuit
+        .constraints
+        .iter()
+        .filter_map(|polynomial| binary_signal(polynomial, field))
+        .collect();
+    let mut known = circuit.known.clone();
+    let mut solved = vec![false; circuit.constraints.len()];
+    let mut pending: VecDeque<usize> = (0..circuit.constraints.len()).collect();
+    while let Some(index) = pending.pop_front() {
+        if solved[index] {
+            continue;
+        }
+        let polynomial = &circuit.constraints[index];
+        let unknown: BTreeSet<usize> = signals_of(polynomial)
+            .into_iter()
+            .filter(|s| !known.contains(s))
+            .collect();
+        let determined = match unknown.len() {
+            0 => true,
+            1 => {
+                let signal = *unknown.iter().next().unwrap();
+                !polynomial.contains_key(&(signal, signal))
+            }
+            _ => determines_bits(polynomial, &unknown, &bits, field),
+        };
+        if !determined {
+            continue;
+        }
+        solved[index] = true;
+        for signal in unknown {
+            known.insert(signal);
+            for other in &signal_to_constraints[&signal] {
+                if !solved[*other] {
+                    pending.push_back(*other);
+                }
+            }
+        }
+    }
+    known
+}
+
+// a - b = 0, the usual link between the signals of a component and its subcomponents
+fn equality(polynomial: &Polynomial, field: &BigInt) -> Option<(usize, usize)> {
+    let mut terms = polynomial.iter();
+    match (terms.next(), terms.next(), terms.next()) {
+        (Some(((0, s), a)), Some(((0, t), b)), None) if *s != 0 => {
+            if modular_arithmetic::add(a, b, field).is_zero() {
+                Some((*s, *t))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn find(parent: &mut HashMap<usize, usize>, signal: usize) -> usize {
+    let mut current = signal;
+    while parent[&current] != current {
+        current = parent[&current];
+    }
+    parent.insert(signal, current);
+    current
+}
+
+// The undetermined signals connected to the output are grouped by the equalities between
+// them. The signal that stands for each group is the one of the deepest template instance,
+// where the value is usually assigned.
+fn explain(
+    circuit: &Circuit,
+    output: usize,
+    known: &HashSet<usize>,
+    signal_to_constraints: &HashMap<usize, Vec<usize>>,
+) -> (Cause, Vec<(String, String)>) {
+    let name_of = |signal: &usize| circuit.names[signal].clone();
+    let mut reached = BTreeSet::new();
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![output];
+    reached.insert(output);
+    while let Some(current) = pending.pop() {
+        for index in signal_to_constraints.get(&current).into_iter().flatten() {
+            if !visited.insert(*index) {
+                continue;
+            }
+            for other in signals_of(&circuit.constraints[*index]) {
+                if !known.contains(&other) && reached.insert(other) {
+                    pending.push(other);
+                }
+            }
+        }
+    }
+    let mut parent: HashMap<usize, usize> = reached.iter().map(|s| (*s, *s)).collect();
+    let mut uses: BTreeMap<usize, HashSet<usize>> = BTreeMap::new();
+    for index in &visited {
+        let polynomial = &circuit.constraints[*index];
+        if let Some((s, t)) = equality(polynomial, &circuit.field) {
+            let (root_s, root_t) = (find(&mut parent, s), find(&mut parent, t));
+            parent.insert(root_s, root_t);
+        }
+    }
+    for index in &visited {
+        let polynomial = &circuit.constraints[*index];
+        if equality(polynomial, &circuit.field).is_none() {
+            for signal in signals_of(polynomial) {
+                if reached.contains(&signal) {
+                    let root = find(&mut parent, signal);
+                    uses.entry(root).or_default().insert(*index);
+                }
+            }
+        }
+    }
+    let mut groups: BTreeMap<usize, usize> = BTreeMap::new();
+    for signal in &reached {
+        let root = find(&mut parent, *signal);
+        let depth = |s: &usize| circuit.names[s].0.matches('.').count();
+        let best = groups.entry(root).or_insert(*signal);
+        if depth(signal) > depth(best) {
+            *best = *signal;
+        }
+    }
+    let output_root = find(&mut parent, output);
+    let no_uses = |root: &usize| uses.get(root).map_or(0, HashSet::len);
+    if no_uses(&output_root) == 0 {
+        return (Cause::NotConstrained, vec![name_of(&groups[&output_root])]);
+    }
+    groups
+        .remove(&output_root)
+        .map(|best| {
+            let free: Vec<_> = groups
+                .iter()
+                .filter(|(root, _)| no_uses(root) == 1)
+                .map(|(_, signal)| name_of(signal))
+                .collect();
+            if !free.is_empty() {
+                (Cause::FreeSignals, free)
+            } else if groups.is_empty() {
+                (Cause::FreeSignals, vec![name_of(&best)])
+            } else {
+                let others = groups.values().map(name_of).collect();
+                (Cause::UndeterminedSignals, others)
+            }
+        })
+        .unwrap()
+}
+
+fn array_name(signal: &str) -> &str {
+    signal.split('[').next().unwrap()
+}
+
+pub fn underconstrained_outputs(dag: &DAG) -> Vec<UnderconstrainedOutput> {
+    let circuit = build_circuit(dag);
+    let mut signal_to_constraints: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, polynomial) in circuit.constraints.iter().enumerate() {
+        for signal in signals_of(polynomial) {
+            signal_to_constraints.entry(signal).or_default().push(index);
+        }
+    }
+    let known = determined_signals(&circuit, &signal_to_constraints);
+    let mut result: Vec<UnderconstrainedOutput> = Vec::new();
+    for output in circuit.outputs.iter().filter(|s| !known.contains(s)) {
+        let name = &circuit.names[output].0;
+        if let Some(last) = result.last_mut() {
+            if last.signal == array_name(name) {
+                last.examples.push(name.clone());
+                continue;
+            }
+        }
+        // The outputs of the same array share the explanation of the first one
+        let (cause, signals_at_fault) = explain(&circuit, *output, &known, &signal_to_constraints);
+        result.push(UnderconstrainedOutput {
+            template: circuit.main_template.clone(),
+            signal: array_name(name).to_string(),
+            examples: vec![name.clone()],
+            cause,
+            signals_at_fault,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint(a: &[(usize, i64)], b: &[(usize, i64)], c: &[(usize, i64)]) -> C {
+        let field = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let to_map = |terms: &[(usize, i64)]| {
+            terms
+                .iter()
+                .map(|(signal, value)| {
+                    let value = modular_arithmetic::add(&BigInt::from(*value), &field, &field);
+                    (*signal, value)
+                })
+                .collect()
+        };
+        C::new(to_map(a), to_map(b), to_map(c))
+    }
+
+    #[test]
+    fn finds_outputs_not_determined_by_the_inputs() {
+        let mut dag = DAG::new(&"bn128".to_string());
+        dag.add_node("Main()".to_string(), vec![], vec![], false, false);
+        dag.add_output("out".to_string());
+        dag.add_output("z".to_string());
+        dag.add_input("in".to_string(), false);
+        dag.add_intermediate("inv".to_string());
+        dag.add_intermediate("f".to_string());
+        // IsZero: in * inv = 1 - out and in * out = 0 determine out
+        dag.add_constraint(constraint(&[(3, 1)], &[(4, 1)], &[(0, 1), (1, -1)]));
+        dag.add_constraint(constraint(&[(3, 1)], &[(1, 1)], &[]));
+        // z * f = in admits many values of z
+        dag.add_constraint(constraint(&[(2, 1)], &[(5, 1)], &[(3, 1)]));
+
+        let outputs = dag.underconstrained_outputs();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].examples, vec!["main.z".to_string()]);
+        assert!(matches!(outputs[0].cause, Cause::FreeSignals));
+        assert_eq!(
+            outputs[0].signals_at_fault,
+            vec![("main.f".to_string(), "Main()".to_string())]
+        );
+    }
+
+    #[test]
+    fn fully_constrained_circuit_has_no_reports() {
+        let mut dag = DAG::new(&"bn128".to_string());
+        dag.add_node("Main()".to_string(), vec![], vec![], false, false);
+        dag.add_output("out".to_string());
+        dag.add_input("in".to_string(), false);
+        dag.add_intermediate("b[0]".to_string());
+        dag.add_intermediate("b[1]".to_string());
+        dag.add_intermediate("square".to_string());
+        // b are the bits of in, square = in * in and out = square + b[1]
+        dag.add_constraint(constraint(&[(3, 1)], &[(3, 1), (0, -1)], &[]));
+        dag.add_constraint(constraint(&[(4, 1)], &[(4, 1), (0, -1)], &[]));
+        dag.add_constraint(constraint(&[], &[], &[(3, 1), (4, 2), (2, -1)]));
+        dag.add_constraint(constraint(&[(2, 1)], &[(2, 1)], &[(5, 1)]));
+        dag.add_constraint(constraint(&[], &[], &[(5, 1), (4, 1), (1, -1)]));
+
+        assert!(dag.underconstrained_outputs().is_empty());
+    }
+
+    #[test]
+    fn signal_with_a_coefficient_that_may_be_zero_is_determined() {
+        let mut dag = DAG::new(&"bn128".to_string());
+        dag.add_node("Main()".to_string(), vec![], vec![], false, false);
+        dag.add_output("out".to_string());
+        dag.add_input("in".to_string(), false);
+        // in * out = 0 does not fix out when in is 0, but the check only looks at the
+        // signals of the constraint, the documented false negative
+        dag.add_constraint(constraint(&[(2, 1)], &[(1, 1)], &[]));
+
+        assert!(dag.underconstrained_outputs().is_empty());
+    }
+}
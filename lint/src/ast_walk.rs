@@ -0,0 +1,81 @@
+use program_structure::ast::{Expression, Meta, Statement, VariableType};
+use program_structure::ast_visitor::{walk_expression, walk_statement, Visitor};
+
+struct StatementVisitor<'f, 'a> {
+    f: &'f mut dyn FnMut(&'a Statement),
+}
+
+impl<'f, 'a> Visitor<'a> for StatementVisitor<'f, 'a> {
+    fn visit_statement(&mut self, statement: &'a Statement) {
+        (self.f)(statement);
+        walk_statement(self, statement);
+    }
+    fn visit_expression(&mut self, _expression: &'a Expression) {}
+}
+
+// Calls f with the statement and with every statement nested in it
+pub fn visit_statements<'a>(statement: &'a Statement, f: &mut dyn FnMut(&'a Statement)) {
+    StatementVisitor { f }.visit_statement(statement);
+}
+
+// Collects the expressions reached from a node without entering the nested statements
+// and expressions
+#[derive(Default)]
+struct OwnExpressions<'a> {
+    expressions: Vec<&'a Expression>,
+}
+
+impl<'a> Visitor<'a> for OwnExpressions<'a> {
+    fn visit_statement(&mut self, _statement: &'a Statement) {}
+    fn visit_expression(&mut self, expression: &'a Expression) {
+        self.expressions.push(expression);
+    }
+}
+
+// Expressions that appear directly in the statement, without the nested statements
+pub fn own_expressions(statement: &Statement) -> Vec<&Expression> {
+    let mut own = OwnExpressions::default();
+    walk_statement(&mut own, statement);
+    own.expressions
+}
+
+struct ExpressionVisitor<'f, 'a> {
+    f: &'f mut dyn FnMut(&'a Expression),
+}
+
+impl<'f, 'a> Visitor<'a> for ExpressionVisitor<'f, 'a> {
+    fn visit_expression(&mut self, expression: &'a Expression) {
+        (self.f)(expression);
+        walk_expression(self, expression);
+    }
+}
+
+// Calls f with the expression and with every expression nested in it
+pub fn visit_expressions<'a>(expression: &'a Expression, f: &mut dyn FnMut(&'a Expression)) {
+    ExpressionVisitor { f }.visit_expression(expression);
+}
+
+// Names of the variables, signals and components used in the expression
+pub fn used_names(expression: &Expression) -> Vec<&str> {
+    let mut names = Vec::new();
+    visit_expressions(expression, &mut |e| {
+        if let Expression::Variable { name, .. } = e {
+            names.push(name.as_str());
+        }
+    });
+    names
+}
+
+// Declarations of the body, with the meta of the declaration
+pub fn declarations(body: &Statement) -> Vec<(&str, &VariableType, &Meta)> {
+    let mut declarations = Vec::new();
+    visit_statements(body, &mut |statement| {
+        if let Statement::Declaration {
+            meta, xtype, name, ..
+        } = statement
+        {
+            declarations.push((name.as_str(), xtype, meta));
+        }
+    });
+    declarations
+}
@@ -0,0 +1,264 @@
+mod ast_walk;
+pub mod rules;
+mod suppression;
+
+use dag::DAG;
+use json::JsonValue;
+use program_structure::error_definition::{MessageCategory, ReportCollection};
+use program_structure::program_archive::ProgramArchive;
+use std::collections::HashMap;
+use suppression::Suppressions;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn from_name(name: &str) -> Option<Severity> {
+        match name {
+            "off" => Some(Severity::Off),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        }
+    }
+}
+
+// A check over the program or over the constraints it generates. Rules produce warnings,
+// the linter turns them into errors or drops them following the configuration.
+pub trait Rule {
+    // Name used in the configuration and in the suppression comments
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn check_program(&self, _program: &ProgramArchive) -> ReportCollection {
+        Vec::new()
+    }
+    // Runs over the constraints of every template before they are simplified
+    fn check_dag(&self, _dag: &DAG, _program: &ProgramArchive) -> ReportCollection {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct LintConfig {
+    severities: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    pub fn new() -> LintConfig {
+        LintConfig::default()
+    }
+
+    pub fn set_severity(&mut self, rule: &str, severity: Severity) {
+        self.severities.insert(rule.to_string(), severity);
+    }
+
+    // Reads a setting of the form rule=severity
+    pub fn add_setting(&mut self, setting: &str) -> Result<(), String> {
+        let (rule, name) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid rule setting {}, expected rule=severity", setting))?;
+        let severity = Severity::from_name(name.trim())
+            .ok_or_else(|| format!("Invalid severity {}, expected off, warning or error", name))?;
+        self.set_severity(rule.trim(), severity);
+        Result::Ok(())
+    }
+
+    // Reads a json document of the form {"rules": {"rule": "severity", ...}}
+    pub fn from_json(contents: &str) -> Result<LintConfig, String> {
+        let document = json::parse(contents).map_err(|err| err.to_string())?;
+        let mut config = LintConfig::new();
+        match &document["rules"] {
+            JsonValue::Null => {}
+            JsonValue::Object(rules) => {
+                for (rule, severity) in rules.iter() {
+                    let severity = severity.as_str().unwrap_or_default();
+                    config.add_setting(&format!("{}={}", rule, severity))?;
+                }
+            }
+            _ => return Result::Err("The field rules must be an object".to_string()),
+        }
+        Result::Ok(config)
+    }
+}
+
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    config: LintConfig,
+}
+
+impl Linter {
+    // Linter with the rules of this crate, more can be added with add_rule
+    pub fn new(config: LintConfig) -> Linter {
+        Linter {
+            rules: rules::all(),
+            config,
+        }
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn rules(&self) -> &[Box<dyn Rule>] {
+        &self.rules
+    }
+
+    pub fn severity(&self, rule: &dyn Rule) -> Severity {
+        self.config
+            .severities
+            .get(rule.name())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+
+    // Fails if the configuration names a rule that does not exist
+    pub fn check_config(&self) -> Result<(), String> {
+        for name in self.config.severities.keys() {
+            if !self.rules.iter().any(|rule| rule.name() == name) {
+                return Result::Err(format!("Unknown lint rule {}", name));
+            }
+        }
+        Result::Ok(())
+    }
+
+    pub fn lint_program(&self, program: &ProgramArchive) -> ReportCollection {
+        self.run(program, |rule| rule.check_program(program))
+    }
+
+    pub fn lint_dag(&self, dag: &DAG, program: &ProgramArchive) -> ReportCollection {
+        self.run(program, |rule| rule.check_dag(dag, program))
+    }
+
+    fn run<F>(&self, program: &ProgramArchive, check: F) -> ReportCollection
+    where
+        F: Fn(&dyn Rule) -> ReportCollection,
+    {
+        let mut suppressions = Suppressions::new(program.get_file_library());
+        let mut reports = Vec::new();
+        for rule in &self.rules {
+            let severity = self.severity(rule.as_ref());
+            if severity == Severity::Off {
+                continue;
+            }
+            for mut report in check(rule.as_ref()) {
+                if suppressions.is_suppressed(rule.name(), &report) {
+                    continue;
+                }
+                if severity == Severity::Error {
+                    report.set_category(MessageCategory::Error);
+                }
+                report.add_note(format!("Reported by the lint rule {}", rule.name()));
+                reports.push(report);
+            }
+        }
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_structure::error_definition::Report;
+
+    // Parses and type checks a program written in a single file
+    pub fn program_from_source(src: &str) -> ProgramArchive {
+        use parser::source_provider::MemoryProvider;
+        let mut provider = MemoryProvider::new();
+        provider.add_file("/main.circom", src.to_string());
+        let (mut program, _) = parser::run_parser_with_provider(
+            "/main.circom".to_string(),
+            "2.1.6",
+            Vec::new(),
+            &provider,
+        )
+        .ok()
+        .unwrap();
+        type_analysis::check_types::check_types(&mut program)
+            .ok()
+            .unwrap();
+        program
+    }
+
+    const SOURCE: &str = r#"pragma circom 2.1.6;
+template Inverse(unused) {
+    signal input in;
+    signal output out;
+    signal checked;
+    out <-- 1 / in;
+    // circom-lint-ignore signal-division
+    checked <-- 1 / in;
+    checked * in === 1;
+}
+component main = Inverse(1);
+"#;
+
+    fn codes(reports: &[Report]) -> Vec<String> {
+        reports
+            .iter()
+            .map(|report| report.get_code().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn rules_are_configured_and_suppressed() {
+        let program = program_from_source(SOURCE);
+        let linter = Linter::new(LintConfig::new());
+        let reports = linter.lint_program(&program);
+        assert_eq!(codes(&reports), vec!["L01", "L02", "L03"]);
+        assert!(reports.iter().all(|report| report.is_warning()));
+
+        let config = LintConfig::from_json(
+            r#"{"rules": {"unused-template-parameter": "off", "signal-division": "error"}}"#,
+        )
+        .unwrap();
+        let reports = Linter::new(config).lint_program(&program);
+        assert_eq!(codes(&reports), vec!["L01", "L02"]);
+        assert!(reports[1].is_error());
+
+        let mut config = LintConfig::new();
+        config.add_setting("no-such-rule=off").unwrap();
+        assert!(Linter::new(config).check_config().is_err());
+    }
+
+    #[test]
+    fn dag_rules_find_unconstrained_signals() {
+        use constraint_generation::{generate_dag, BuildConfig};
+        let program = program_from_source(
+            r#"pragma circom 2.1.6;
+template Unused() {
+    signal input a;
+    signal output c;
+    signal b;
+    b <-- a;
+    c <== a * a;
+}
+component main = Unused();
+"#,
+        );
+        let config = BuildConfig {
+            no_rounds: usize::MAX,
+            flag_json_sub: false,
+            flag_s: false,
+            flag_f: false,
+            flag_p: false,
+            flag_verbose: false,
+            flag_old_heuristics: false,
+            inspect_constraints: false,
+            prime: "bn128".to_string(),
+            cache_dir: None,
+            threads: 1,
+        };
+        let (dag, _) = generate_dag(program.clone(), config).ok().unwrap();
+        let reports = Linter::new(LintConfig::new()).lint_dag(&dag, &program);
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].get_message().contains("b"));
+        assert_eq!(reports[0].get_primary().len(), 1);
+    }
+}
@@ -0,0 +1,20 @@
+mod signal_division;
+mod unconstrained_assignment;
+mod unconstrained_signal;
+mod unused_template_parameter;
+
+use super::Rule;
+pub use signal_division::SignalDivision;
+pub use unconstrained_assignment::UnconstrainedAssignment;
+pub use unconstrained_signal::UnconstrainedSignal;
+pub use unused_template_parameter::UnusedTemplateParameter;
+
+// Rules known by the linter, in the order their reports are shown
+pub fn all() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(UnconstrainedAssignment),
+        Box::new(SignalDivision),
+        Box::new(UnusedTemplateParameter),
+        Box::new(UnconstrainedSignal),
+    ]
+}
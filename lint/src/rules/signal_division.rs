@@ -0,0 +1,105 @@
+use crate::ast_walk::{
+    declarations, own_expressions, used_names, visit_expressions, visit_statements,
+};
+use crate::Rule;
+use program_structure::ast::{
+    AssignOp, Expression, ExpressionInfixOpcode, Statement, VariableType,
+};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::program_archive::ProgramArchive;
+use program_structure::template_data::TemplateData;
+use std::collections::HashSet;
+
+// Divisions that depend on signals in the expressions given to signals or constraints.
+// A division by a signal has no value when the signal is zero, and the integer division
+// and the remainder cannot be expressed with constraints, so their result must be
+// checked by other means.
+pub struct SignalDivision;
+
+impl Rule for SignalDivision {
+    fn name(&self) -> &'static str {
+        "signal-division"
+    }
+
+    fn description(&self) -> &'static str {
+        "A signal expression divides by a signal or takes the integer division or remainder of a signal"
+    }
+
+    fn check_program(&self, program: &ProgramArchive) -> ReportCollection {
+        let mut reports = Vec::new();
+        let mut templates: Vec<_> = program.get_templates().values().collect();
+        templates.sort_by_key(|template| template.get_name());
+        for template in templates {
+            check_template(template, &mut reports);
+        }
+        reports
+    }
+}
+
+fn check_template(template: &TemplateData, reports: &mut ReportCollection) {
+    // Components are included because their inputs and outputs are signals
+    let signals: HashSet<&str> = declarations(template.get_body())
+        .into_iter()
+        .filter(|(_, xtype, _)| !matches!(xtype, VariableType::Var))
+        .map(|(name, _, _)| name)
+        .collect();
+    let depends_on_signals = |expression: &Expression| {
+        used_names(expression)
+            .iter()
+            .any(|name| signals.contains(name))
+    };
+    visit_statements(template.get_body(), &mut |statement| {
+        let is_signal_statement = match statement {
+            Statement::Substitution { op, .. }
+            | Statement::MultSubstitution { op, .. }
+            | Statement::UnderscoreSubstitution { op, .. } => *op != AssignOp::AssignVar,
+            Statement::ConstraintEquality { .. } => true,
+            _ => false,
+        };
+        if !is_signal_statement {
+            return;
+        }
+        for expression in own_expressions(statement) {
+            visit_expressions(expression, &mut |e| {
+                if let Expression::InfixOp {
+                    meta,
+                    lhe,
+                    infix_op,
+                    rhe,
+                } = e
+                {
+                    let message = match infix_op {
+                        ExpressionInfixOpcode::Div if depends_on_signals(rhe) => {
+                            "divides by an expression with signals"
+                        }
+                        ExpressionInfixOpcode::IntDiv | ExpressionInfixOpcode::Mod
+                            if depends_on_signals(lhe) || depends_on_signals(rhe) =>
+                        {
+                            "takes the integer division or remainder of an expression with signals"
+                        }
+                        _ => return,
+                    };
+                    let mut report = Report::warning(
+                        format!(
+                            "In template \"{}\": A signal expression {}",
+                            template.get_name(),
+                            message
+                        ),
+                        ReportCode::DivisionInSignalExpression,
+                    );
+                    report.add_primary(
+                        meta.location.clone(),
+                        template.get_file_id(),
+                        "found here".to_string(),
+                    );
+                    report.add_note(
+                        "The constraints must check the result, including the case of a zero divisor"
+                            .to_string(),
+                    );
+                    reports.push(report);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,101 @@
+use crate::ast_walk::{own_expressions, used_names, visit_statements};
+use crate::Rule;
+use program_structure::ast::{Access, AssignOp, Statement};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::program_archive::ProgramArchive;
+use program_structure::template_data::TemplateData;
+use std::collections::HashSet;
+
+// Signals assigned with <-- that are not used in any constraint of the template: the
+// prover can give them any value
+pub struct UnconstrainedAssignment;
+
+impl Rule for UnconstrainedAssignment {
+    fn name(&self) -> &'static str {
+        "unconstrained-assignment"
+    }
+
+    fn description(&self) -> &'static str {
+        "A signal is assigned with <-- but does not appear in any === or <== of the template"
+    }
+
+    fn check_program(&self, program: &ProgramArchive) -> ReportCollection {
+        let mut reports = Vec::new();
+        let mut templates: Vec<_> = program.get_templates().values().collect();
+        templates.sort_by_key(|template| template.get_name());
+        for template in templates {
+            // Custom templates do not have constraints
+            if !template.is_custom_gate() {
+                check_template(template, &mut reports);
+            }
+        }
+        reports
+    }
+}
+
+fn check_template(template: &TemplateData, reports: &mut ReportCollection) {
+    let mut constrained = HashSet::new();
+    visit_statements(template.get_body(), &mut |statement| match statement {
+        Statement::Substitution {
+            var,
+            op: AssignOp::AssignConstraintSignal,
+            ..
+        } => {
+            constrained.insert(var.as_str());
+            for expression in own_expressions(statement) {
+                constrained.extend(used_names(expression));
+            }
+        }
+        Statement::ConstraintEquality { .. }
+        | Statement::MultSubstitution {
+            op: AssignOp::AssignConstraintSignal,
+            ..
+        }
+        | Statement::UnderscoreSubstitution {
+            op: AssignOp::AssignConstraintSignal,
+            ..
+        } => {
+            for expression in own_expressions(statement) {
+                constrained.extend(used_names(expression));
+            }
+        }
+        _ => {}
+    });
+    visit_statements(template.get_body(), &mut |statement| {
+        if let Statement::Substitution {
+            meta,
+            var,
+            access,
+            op: AssignOp::AssignSignal,
+            ..
+        } = statement
+        {
+            // Inputs of subcomponents are constrained in their own template
+            let in_subcomponent = access
+                .iter()
+                .any(|acc| matches!(acc, Access::ComponentAccess(_)));
+            if in_subcomponent || constrained.contains(var.as_str()) {
+                return;
+            }
+            let mut report = Report::warning(
+                format!(
+                    "In template \"{}\": Signal {} is assigned with <-- but it does not appear in any constraint",
+                    template.get_name(),
+                    var
+                ),
+                ReportCode::SignalAssignmentWithoutConstraint,
+            );
+            report.add_primary(
+                meta.location.clone(),
+                template.get_file_id(),
+                "assigned here".to_string(),
+            );
+            report.add_note(format!(
+                "Add a constraint with === that checks the value of {}",
+                var
+            ));
+            reports.push(report);
+        }
+    });
+}
@@ -0,0 +1,49 @@
+use crate::ast_walk::declarations;
+use crate::Rule;
+use dag::DAG;
+use program_structure::error_definition::ReportCollection;
+use program_structure::program_archive::ProgramArchive;
+
+// Signals that do not appear in any constraint of the template that declares them, found
+// in the constraints generated for each template
+pub struct UnconstrainedSignal;
+
+impl Rule for UnconstrainedSignal {
+    fn name(&self) -> &'static str {
+        "unconstrained-signal"
+    }
+
+    fn description(&self) -> &'static str {
+        "A signal, or an input or output of a subcomponent, does not appear in any constraint"
+    }
+
+    fn check_dag(&self, dag: &DAG, program: &ProgramArchive) -> ReportCollection {
+        let mut reports = Vec::new();
+        for unconstrained in dag.unconstrained_signals() {
+            let mut report = unconstrained.to_report();
+            // The nodes are named after the template and its arguments
+            let name = unconstrained.template.split('(').next().unwrap_or("");
+            if program.contains_template(name) {
+                let template = program.get_template_data(name);
+                // The signal of the template or the subcomponent it belongs to
+                let declared = unconstrained
+                    .signal
+                    .split(['.', '['])
+                    .next()
+                    .unwrap_or("");
+                let declaration = declarations(template.get_body())
+                    .into_iter()
+                    .find(|(name, _, _)| *name == declared);
+                if let Some((_, _, meta)) = declaration {
+                    report.add_primary(
+                        meta.location.clone(),
+                        template.get_file_id(),
+                        "declared here".to_string(),
+                    );
+                }
+            }
+            reports.push(report);
+        }
+        reports
+    }
+}
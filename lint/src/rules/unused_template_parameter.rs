@@ -0,0 +1,70 @@
+use crate::ast_walk::{own_expressions, used_names, visit_statements};
+use crate::Rule;
+use codespan_reporting::files::Files;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::FileLocation;
+use program_structure::program_archive::ProgramArchive;
+use std::collections::HashSet;
+
+pub struct UnusedTemplateParameter;
+
+impl Rule for UnusedTemplateParameter {
+    fn name(&self) -> &'static str {
+        "unused-template-parameter"
+    }
+
+    fn description(&self) -> &'static str {
+        "A parameter of a template is not used in its body"
+    }
+
+    fn check_program(&self, program: &ProgramArchive) -> ReportCollection {
+        let mut reports = Vec::new();
+        let mut templates: Vec<_> = program.get_templates().values().collect();
+        templates.sort_by_key(|template| template.get_name());
+        for template in templates {
+            let mut used = HashSet::new();
+            visit_statements(template.get_body(), &mut |statement| {
+                for expression in own_expressions(statement) {
+                    used.extend(used_names(expression));
+                }
+            });
+            let file_id = template.get_file_id();
+            let source = program
+                .get_file_library()
+                .to_storage()
+                .source(file_id)
+                .unwrap_or("");
+            for param in template.get_name_of_params() {
+                if used.contains(param.as_str()) {
+                    continue;
+                }
+                let mut report = Report::warning(
+                    format!(
+                        "In template \"{}\": Parameter {} is never used",
+                        template.get_name(),
+                        param
+                    ),
+                    ReportCode::UnusedTemplateParameter,
+                );
+                let location = param_location(source, template.get_param_location(), param);
+                report.add_primary(location, file_id, "declared here".to_string());
+                reports.push(report);
+            }
+        }
+        reports
+    }
+}
+
+// The template only keeps the location of the whole list of parameters
+fn param_location(source: &str, params: FileLocation, param: &str) -> FileLocation {
+    let list = source.get(params.clone()).unwrap_or("");
+    let mut offset = 0;
+    for name in list.split(|c: char| c == ',' || c.is_whitespace()) {
+        if name == param {
+            return params.start + offset..params.start + offset + name.len();
+        }
+        offset += name.len() + 1;
+    }
+    params
+}
@@ -0,0 +1,140 @@
+use codespan_reporting::files::Files;
+use program_structure::error_definition::Report;
+use program_structure::file_definition::{FileID, FileLibrary};
+use std::collections::HashMap;
+
+// Comments that silence the rules. `// circom-lint-ignore rule...` applies to the line
+// of the comment, and also to the next one when the comment is alone on its line.
+// `// circom-lint-ignore-file rule...` applies to the whole file. Without names the comment
+// applies to every rule.
+const IGNORE: &str = "circom-lint-ignore";
+const IGNORE_FILE: &str = "circom-lint-ignore-file";
+
+// The rules of a comment, an empty list stands for every rule
+type RuleList = Vec<String>;
+
+// A comment of a line: its line, whether it is alone on the line and its rules
+type LineSuppression = (usize, bool, RuleList);
+
+#[derive(Default)]
+struct FileSuppressions {
+    lines: Vec<LineSuppression>,
+    file: Vec<RuleList>,
+}
+
+fn applies(rules: &RuleList, rule: &str) -> bool {
+    rules.is_empty() || rules.iter().any(|name| name == rule)
+}
+
+impl FileSuppressions {
+    fn new(source: &str) -> FileSuppressions {
+        let mut suppressions = FileSuppressions::default();
+        for (index, line) in source.lines().enumerate() {
+            let start = match line.find("//").or_else(|| line.find("/*")) {
+                Some(start) => start,
+                None => continue,
+            };
+            let comment = &line[start..];
+            let position = match comment.find(IGNORE) {
+                Some(position) => position,
+                None => continue,
+            };
+            let (whole_file, rest) = if comment[position..].starts_with(IGNORE_FILE) {
+                (true, &comment[position + IGNORE_FILE.len()..])
+            } else {
+                (false, &comment[position + IGNORE.len()..])
+            };
+            let rest = rest.split("*/").next().unwrap_or("");
+            let rules: RuleList = rest
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_string())
+                .collect();
+            if whole_file {
+                suppressions.file.push(rules);
+            } else {
+                let alone = line[..start].trim().is_empty();
+                suppressions.lines.push((index + 1, alone, rules));
+            }
+        }
+        suppressions
+    }
+
+    fn is_suppressed(&self, rule: &str, line: usize) -> bool {
+        self.file.iter().any(|rules| applies(rules, rule))
+            || self.lines.iter().any(|(comment_line, alone, rules)| {
+                (line == *comment_line || (*alone && line == comment_line + 1))
+                    && applies(rules, rule)
+            })
+    }
+}
+
+pub struct Suppressions<'a> {
+    file_library: &'a FileLibrary,
+    files: HashMap<FileID, FileSuppressions>,
+}
+
+impl<'a> Suppressions<'a> {
+    pub fn new(file_library: &'a FileLibrary) -> Suppressions<'a> {
+        Suppressions {
+            file_library,
+            files: HashMap::new(),
+        }
+    }
+
+    // Reports without location can only be disabled in the configuration
+    pub fn is_suppressed(&mut self, rule: &str, report: &Report) -> bool {
+        let label = match report.get_primary().first() {
+            Some(label) => label,
+            None => return false,
+        };
+        let line = match self.file_library.get_line(label.range.start, label.file_id) {
+            Some(line) => line,
+            None => return false,
+        };
+        let file_library = self.file_library;
+        let suppressions = self.files.entry(label.file_id).or_insert_with(|| {
+            let source = file_library.to_storage().source(label.file_id);
+            FileSuppressions::new(source.unwrap_or(""))
+        });
+        suppressions.is_suppressed(rule, line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_apply_to_their_line_and_to_the_next_one_when_alone() {
+        let suppressions = FileSuppressions::new(
+            "a <-- 1;
+// circom-lint-ignore signal-division
+b <-- a / 2;
+c <-- a / 3; // circom-lint-ignore
+d <-- a \\ 3;
+/* circom-lint-ignore signal-division, unused-template-parameter */
+e <-- a % 3;
+",
+        );
+        assert!(!suppressions.is_suppressed("signal-division", 1));
+        assert!(suppressions.is_suppressed("signal-division", 2));
+        assert!(suppressions.is_suppressed("signal-division", 3));
+        assert!(!suppressions.is_suppressed("unconstrained-assignment", 3));
+        // a comment after the code only applies to its line
+        assert!(suppressions.is_suppressed("unconstrained-assignment", 4));
+        assert!(!suppressions.is_suppressed("signal-division", 5));
+        assert!(suppressions.is_suppressed("unused-template-parameter", 7));
+        assert!(!suppressions.is_suppressed("unconstrained-signal", 7));
+    }
+
+    #[test]
+    fn comments_apply_to_the_whole_file() {
+        let suppressions = FileSuppressions::new(
+            "b <-- a / 2; // circom-lint-ignore-file signal-division\nc <-- a / 3;\n",
+        );
+        assert!(suppressions.is_suppressed("signal-division", 1));
+        assert!(suppressions.is_suppressed("signal-division", 2));
+        assert!(!suppressions.is_suppressed("unconstrained-signal", 2));
+    }
+}
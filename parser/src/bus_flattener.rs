@@ -0,0 +1,1186 @@
+use program_structure::ast::*;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::FileID;
+use program_structure::statement_builders::{build_block, build_declaration};
+use std::collections::{HashMap, HashSet};
+
+// Buses only exist in the parsed files. Every declaration of a bus is replaced by the
+// declarations of the signals it contains, named after the fields that lead to them
+// (p.x, p.path.sel, ...), so the rest of the compiler only deals with signals. The
+// dimensions of an array of buses come before the dimensions of its fields: the signal
+// ps[i].x[j] is ps.x[i][j] after the flattening.
+
+struct Field {
+    name: String,
+    dimensions: Vec<Expression>,
+    tags: TagList,
+    bus: Option<BusType>,
+}
+
+struct BusDefinition {
+    params: Vec<String>,
+    fields: Vec<Field>,
+}
+
+// Buses of a template with the number of dimensions of their declarations
+type BusSymbols = HashMap<String, (String, usize)>;
+
+pub fn flatten_buses(
+    definitions: &mut [(FileID, Vec<Definition>)],
+    main_component: &mut MainComponent,
+) -> Result<(), ReportCollection> {
+    let mut reports = Vec::new();
+    let mut flattener = Flattener::default();
+    let mut callables = HashSet::new();
+    let mut bus_definitions = Vec::new();
+    for (file_id, file_definitions) in definitions.iter_mut() {
+        for definition in std::mem::take(file_definitions) {
+            match definition {
+                Definition::Bus {
+                    meta,
+                    name,
+                    args,
+                    body,
+                    ..
+                } => bus_definitions.push((*file_id, meta, name, args, body)),
+                Definition::Template { ref name, .. } | Definition::Function { ref name, .. } => {
+                    callables.insert(name.clone());
+                    file_definitions.push(definition);
+                }
+            }
+        }
+    }
+    for (file_id, meta, name, args, body) in bus_definitions {
+        if callables.contains(&name) || flattener.buses.contains_key(&name) {
+            let message = format!("{} is already in use", name);
+            add_report(
+                ReportCode::InvalidBusDefinition,
+                &meta,
+                file_id,
+                message,
+                &mut reports,
+            );
+            continue;
+        }
+        match bus_definition(args, body, file_id, &mut reports) {
+            Some(bus) => {
+                flattener.buses.insert(name.clone(), bus);
+                flattener.locations.insert(name, (file_id, meta));
+            }
+            None => continue,
+        }
+    }
+    flattener.check_bus_definitions(&mut reports);
+    if !reports.is_empty() {
+        return Result::Err(reports);
+    }
+
+    for (_, file_definitions) in definitions.iter() {
+        for definition in file_definitions {
+            if let Definition::Template { name, body, .. } = definition {
+                let mut symbols = BusSymbols::new();
+                collect_buses(body, true, &mut symbols);
+                if !symbols.is_empty() {
+                    flattener.template_buses.insert(name.clone(), symbols);
+                }
+            }
+        }
+    }
+    for (file_id, file_definitions) in definitions.iter_mut() {
+        for definition in file_definitions.iter_mut() {
+            let (body, in_template) = match definition {
+                Definition::Template { body, .. } => (body, true),
+                Definition::Function { body, .. } => (body, false),
+                Definition::Bus { .. } => unreachable!(),
+            };
+            let mut scope = Scope::new(&flattener, *file_id, in_template);
+            collect_buses(body, false, &mut scope.buses);
+            collect_components(body, &flattener.template_buses, &mut scope.components);
+            let meta = body.get_meta().clone();
+            let old_body = std::mem::replace(body, build_block(meta, Vec::new()));
+            *body = scope.statement(old_body);
+            reports.append(&mut scope.reports);
+        }
+    }
+    flattener.expand_public_signals(main_component);
+    if reports.is_empty() {
+        Result::Ok(())
+    } else {
+        Result::Err(reports)
+    }
+}
+
+// A bus only contains declarations of signals and buses without initialization
+fn bus_definition(
+    params: Vec<String>,
+    body: Statement,
+    file_id: FileID,
+    reports: &mut ReportCollection,
+) -> Option<BusDefinition> {
+    let mut fields: Vec<Field> = Vec::new();
+    let mut correct = true;
+    let stmts = match body {
+        Statement::Block { stmts, .. } => stmts,
+        _ => unreachable!(),
+    };
+    for stmt in stmts {
+        let (meta, xtype, initializations) = match stmt {
+            Statement::InitializationBlock {
+                meta,
+                xtype,
+                initializations,
+            } => (meta, xtype, initializations),
+            other => {
+                let message = "Only signals and buses can be declared inside a bus".to_string();
+                add_report(
+                    ReportCode::InvalidBusDefinition,
+                    other.get_meta(),
+                    file_id,
+                    message,
+                    reports,
+                );
+                correct = false;
+                continue;
+            }
+        };
+        let (tags, bus) = match xtype {
+            VariableType::Signal(SignalType::Intermediate, tags) => (tags, None),
+            VariableType::Bus(bus, SignalType::Intermediate, tags) => (tags, Some(bus)),
+            VariableType::Signal(..) | VariableType::Bus(..) => {
+                let message = "The fields of a bus can not be inputs or outputs".to_string();
+                add_report(
+                    ReportCode::InvalidBusDefinition,
+                    &meta,
+                    file_id,
+                    message,
+                    reports,
+                );
+                correct = false;
+                continue;
+            }
+            _ => {
+                let message = "Only signals and buses can be declared inside a bus".to_string();
+                add_report(
+                    ReportCode::InvalidBusDefinition,
+                    &meta,
+                    file_id,
+                    message,
+                    reports,
+                );
+                correct = false;
+                continue;
+            }
+        };
+        for initialization in initializations {
+            match initialization {
+                Statement::Declaration {
+                    name, dimensions, ..
+                } => {
+                    if fields.iter().any(|field| field.name == name) {
+                        let message = format!("The field {} is declared twice", name);
+                        add_report(
+                            ReportCode::InvalidBusDefinition,
+                            &meta,
+                            file_id,
+                            message,
+                            reports,
+                        );
+                        correct = false;
+                    }
+                    fields.push(Field {
+                        name,
+                        dimensions,
+                        tags: tags.clone(),
+                        bus: bus.clone(),
+                    });
+                }
+                _ => {
+                    let message = "The fields of a bus can not be initialized".to_string();
+                    add_report(
+                        ReportCode::InvalidBusDefinition,
+                        &meta,
+                        file_id,
+                        message,
+                        reports,
+                    );
+                    correct = false;
+                }
+            }
+        }
+    }
+    if correct {
+        Some(BusDefinition { params, fields })
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct Flattener {
+    buses: HashMap<String, BusDefinition>,
+    locations: HashMap<String, (FileID, Meta)>,
+    // Input and output buses of the templates that have them
+    template_buses: HashMap<String, BusSymbols>,
+}
+
+impl Flattener {
+    // The fields of a bus use its parameters and existing buses, and a bus can not
+    // contain itself
+    fn check_bus_definitions(&self, reports: &mut ReportCollection) {
+        let mut names: Vec<&String> = self.buses.keys().collect();
+        names.sort();
+        for name in names {
+            let bus = &self.buses[name];
+            let (file_id, meta) = &self.locations[name];
+            let mut report = |message: String| {
+                add_report(
+                    ReportCode::InvalidBusDefinition,
+                    meta,
+                    *file_id,
+                    message,
+                    reports,
+                );
+            };
+            for field in &bus.fields {
+                let mut expressions: Vec<&Expression> = field.dimensions.iter().collect();
+                if let Some(inner) = &field.bus {
+                    expressions.extend(inner.args.iter());
+                    match self.buses.get(&inner.name) {
+                        None => report(format!("The bus {} is not defined", inner.name)),
+                        Some(definition) if definition.params.len() != inner.args.len() => {
+                            report(wrong_number_of_arguments(&inner.name, definition, inner))
+                        }
+                        Some(_) => {}
+                    }
+                }
+                for expression in expressions {
+                    let mut unknown = Vec::new();
+                    rebuild(expression.clone(), &mut |expression| {
+                        if let Expression::Variable { name, .. } = &expression {
+                            if !bus.params.contains(name) {
+                                unknown.push(name.clone());
+                            }
+                        }
+                        expression
+                    });
+                    for symbol in unknown {
+                        report(format!(
+                            "The field {} uses {}, only the parameters of the bus can be used in its fields",
+                            field.name, symbol
+                        ));
+                    }
+                }
+            }
+            if self.contains_bus(name, name, &mut HashSet::new()) {
+                report(format!("The bus {} contains itself", name));
+            }
+        }
+    }
+
+    fn contains_bus(&self, bus: &str, target: &str, visited: &mut HashSet<String>) -> bool {
+        if !visited.insert(bus.to_string()) {
+            return false;
+        }
+        let definition = match self.buses.get(bus) {
+            Some(definition) => definition,
+            None => return false,
+        };
+        definition.fields.iter().any(|field| match &field.bus {
+            Some(inner) => inner.name == target || self.contains_bus(&inner.name, target, visited),
+            None => false,
+        })
+    }
+
+    // Path from the bus to each of its signals: .x, .path.sel, ...
+    fn signals(&self, bus: &str) -> Vec<String> {
+        let mut signals = Vec::new();
+        for field in &self.buses[bus].fields {
+            match &field.bus {
+                Some(inner) => {
+                    for signal in self.signals(&inner.name) {
+                        signals.push(format!(".{}{}", field.name, signal));
+                    }
+                }
+                None => signals.push(format!(".{}", field.name)),
+            }
+        }
+        signals
+    }
+
+    fn declare(
+        &self,
+        meta: &Meta,
+        (bus, signal_type, tags): (&BusType, SignalType, &TagList),
+        name: &str,
+        dimensions: &[Expression],
+        declarations: &mut Vec<Statement>,
+    ) -> Result<(), String> {
+        let definition = match self.buses.get(&bus.name) {
+            Some(definition) => definition,
+            None => return Result::Err(format!("The bus {} is not defined", bus.name)),
+        };
+        if definition.params.len() != bus.args.len() {
+            return Result::Err(wrong_number_of_arguments(&bus.name, definition, bus));
+        }
+        let values: HashMap<&String, &Expression> =
+            definition.params.iter().zip(bus.args.iter()).collect();
+        let substitute = |expression: &Expression| {
+            rebuild(expression.clone(), &mut |expression| match &expression {
+                Expression::Variable { name, access, .. }
+                    if access.is_empty() && values.contains_key(name) =>
+                {
+                    values[name].clone()
+                }
+                _ => expression,
+            })
+        };
+        for field in &definition.fields {
+            let field_name = format!("{}.{}", name, field.name);
+            let mut field_dimensions = dimensions.to_vec();
+            field_dimensions.extend(field.dimensions.iter().map(substitute));
+            let mut field_tags = tags.clone();
+            for tag in &field.tags {
+                if !field_tags.contains(tag) {
+                    field_tags.push(tag.clone());
+                }
+            }
+            match &field.bus {
+                Some(inner) => {
+                    let inner = BusType {
+                        name: inner.name.clone(),
+                        args: inner.args.iter().map(substitute).collect(),
+                    };
+                    self.declare(
+                        meta,
+                        (&inner, signal_type, &field_tags),
+                        &field_name,
+                        &field_dimensions,
+                        declarations,
+                    )?;
+                }
+                None => declarations.push(build_declaration(
+                    meta.clone(),
+                    VariableType::Signal(signal_type, field_tags),
+                    field_name,
+                    field_dimensions,
+                )),
+            }
+        }
+        Result::Ok(())
+    }
+
+    // The public inputs of the main component can be buses
+    fn expand_public_signals(&self, main_component: &mut MainComponent) {
+        let (public, call) = main_component;
+        let inputs = match call {
+            Expression::Call { id, .. } => self.template_buses.get(id),
+            _ => None,
+        };
+        let inputs = match inputs {
+            Some(inputs) => inputs,
+            None => return,
+        };
+        let mut expanded = Vec::new();
+        for name in std::mem::take(public) {
+            match inputs.get(&name) {
+                Some((bus, _)) => {
+                    for signal in self.signals(bus) {
+                        expanded.push(format!("{}{}", name, signal));
+                    }
+                }
+                None => expanded.push(name),
+            }
+        }
+        *public = expanded;
+    }
+}
+
+fn wrong_number_of_arguments(name: &str, definition: &BusDefinition, bus: &BusType) -> String {
+    format!(
+        "The bus {} has {} parameters, but {} arguments are given",
+        name,
+        definition.params.len(),
+        bus.args.len()
+    )
+}
+
+// Reference to a whole bus or a subarray of buses once flattened. The signals of the bus
+// are found adding their paths to the variable, or to the field of the component.
+struct BusReference {
+    bus: String,
+    name: String,
+    access: Vec<Access>,
+    field: Option<usize>,
+}
+
+impl BusReference {
+    fn signal(&self, meta: &Meta, path: &str) -> Expression {
+        let mut name = self.name.clone();
+        let mut access = self.access.clone();
+        match self.field {
+            Some(position) => {
+                if let Access::ComponentAccess(field) = &mut access[position] {
+                    field.push_str(path);
+                }
+            }
+            None => name.push_str(path),
+        }
+        Expression::Variable {
+            meta: meta.clone(),
+            name,
+            access,
+        }
+    }
+}
+
+enum Reference {
+    Signal(String, Vec<Access>),
+    Bus(BusReference),
+}
+
+struct Scope<'a> {
+    flattener: &'a Flattener,
+    file_id: FileID,
+    in_template: bool,
+    buses: BusSymbols,
+    // Components whose template has buses
+    components: HashMap<String, String>,
+    reports: ReportCollection,
+}
+
+impl<'a> Scope<'a> {
+    fn new(flattener: &'a Flattener, file_id: FileID, in_template: bool) -> Scope<'a> {
+        Scope {
+            flattener,
+            file_id,
+            in_template,
+            buses: BusSymbols::new(),
+            components: HashMap::new(),
+            reports: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, meta: &Meta, message: String) {
+        add_report(
+            ReportCode::InvalidBusUse,
+            meta,
+            self.file_id,
+            message,
+            &mut self.reports,
+        );
+    }
+
+    fn statement(&mut self, stmt: Statement) -> Statement {
+        let meta = stmt.get_meta().clone();
+        let mut stmts = self.statements(stmt);
+        if stmts.len() == 1 {
+            stmts.pop().unwrap()
+        } else {
+            build_block(meta, stmts)
+        }
+    }
+
+    fn statements(&mut self, stmt: Statement) -> Vec<Statement> {
+        use Statement::*;
+        let stmt = match stmt {
+            IfThenElse {
+                meta,
+                cond,
+                if_case,
+                else_case,
+            } => IfThenElse {
+                meta,
+                cond: self.expression(cond),
+                if_case: Box::new(self.statement(*if_case)),
+                else_case: else_case.map(|else_case| Box::new(self.statement(*else_case))),
+            },
+            While { meta, cond, stmt } => While {
+                meta,
+                cond: self.expression(cond),
+                stmt: Box::new(self.statement(*stmt)),
+            },
+            Return { meta, value } => Return {
+                meta,
+                value: self.expression(value),
+            },
+            InitializationBlock {
+                meta,
+                xtype,
+                initializations,
+            } => {
+                let xtype = match xtype {
+                    VariableType::Bus(_, signal_type, tags) => {
+                        VariableType::Signal(signal_type, tags)
+                    }
+                    xtype => xtype,
+                };
+                let mut new_initializations = Vec::new();
+                for initialization in initializations {
+                    new_initializations.append(&mut self.statements(initialization));
+                }
+                InitializationBlock {
+                    meta,
+                    xtype,
+                    initializations: new_initializations,
+                }
+            }
+            Declaration {
+                meta,
+                xtype: VariableType::Bus(bus, signal_type, tags),
+                name,
+                dimensions,
+                ..
+            } => {
+                if !self.in_template {
+                    self.report(&meta, "Buses can only be declared in templates".to_string());
+                    return Vec::new();
+                }
+                let mut declarations = Vec::new();
+                let declared = self.flattener.declare(
+                    &meta,
+                    (&bus, signal_type, &tags),
+                    &name,
+                    &dimensions,
+                    &mut declarations,
+                );
+                if let Result::Err(message) = declared {
+                    self.report(&meta, message);
+                }
+                return declarations;
+            }
+            Declaration {
+                meta,
+                xtype,
+                name,
+                dimensions,
+                is_constant,
+            } => Declaration {
+                meta,
+                xtype,
+                name,
+                dimensions: dimensions.into_iter().map(|d| self.expression(d)).collect(),
+                is_constant,
+            },
+            Substitution {
+                meta,
+                var,
+                access,
+                op,
+                rhe,
+            } => return self.substitution(meta, var, access, op, rhe),
+            MultSubstitution { meta, lhe, op, rhe } => MultSubstitution {
+                meta,
+                lhe: self.expression(lhe),
+                op,
+                rhe: self.expression(rhe),
+            },
+            UnderscoreSubstitution { meta, op, rhe } => UnderscoreSubstitution {
+                meta,
+                op,
+                rhe: self.expression(rhe),
+            },
+            ConstraintEquality { meta, lhe, rhe } => {
+                let pair = match self.bus_pair(&meta, &lhe, &rhe) {
+                    Result::Ok(pair) => pair,
+                    Result::Err(stmts) => return stmts,
+                };
+                if let Some((lhs, rhs)) = pair {
+                    return self
+                        .flattener
+                        .signals(&lhs.bus)
+                        .iter()
+                        .map(|signal| ConstraintEquality {
+                            meta: meta.clone(),
+                            lhe: lhs.signal(&meta, signal),
+                            rhe: rhs.signal(&meta, signal),
+                        })
+                        .collect();
+                }
+                ConstraintEquality {
+                    meta,
+                    lhe: self.expression(lhe),
+                    rhe: self.expression(rhe),
+                }
+            }
+            LogCall { meta, args } => LogCall {
+                meta,
+                args: args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        LogArgument::LogExp(arg) => LogArgument::LogExp(self.expression(arg)),
+                        arg => arg,
+                    })
+                    .collect(),
+            },
+            Block { meta, stmts } => {
+                let mut new_stmts = Vec::new();
+                for stmt in stmts {
+                    new_stmts.append(&mut self.statements(stmt));
+                }
+                Block {
+                    meta,
+                    stmts: new_stmts,
+                }
+            }
+            Assert { meta, arg } => Assert {
+                meta,
+                arg: self.expression(arg),
+            },
+        };
+        vec![stmt]
+    }
+
+    // The assignment of a whole bus is the assignment of each of its signals
+    fn substitution(
+        &mut self,
+        meta: Meta,
+        var: String,
+        access: Vec<Access>,
+        op: AssignOp,
+        rhe: Expression,
+    ) -> Vec<Statement> {
+        let lhe = Expression::Variable {
+            meta: meta.clone(),
+            name: var.clone(),
+            access: access.clone(),
+        };
+        let pair = match self.bus_pair(&meta, &lhe, &rhe) {
+            Result::Ok(pair) => pair,
+            Result::Err(stmts) => return stmts,
+        };
+        if let Some((lhs, rhs)) = pair {
+            return self
+                .flattener
+                .signals(&lhs.bus)
+                .iter()
+                .map(|signal| match lhs.signal(&meta, signal) {
+                    Expression::Variable { name, access, .. } => Statement::Substitution {
+                        meta: meta.clone(),
+                        var: name,
+                        access,
+                        op,
+                        rhe: rhs.signal(&meta, signal),
+                    },
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        match self.expression(lhe) {
+            Expression::Variable { name, access, .. } => vec![Statement::Substitution {
+                meta,
+                var: name,
+                access,
+                op,
+                rhe: self.expression(rhe),
+            }],
+            _ => unreachable!(),
+        }
+    }
+
+    // Both sides are whole buses of the same type. The error is reported when only one of
+    // them is a bus or their types are different.
+    fn bus_pair(
+        &mut self,
+        meta: &Meta,
+        lhe: &Expression,
+        rhe: &Expression,
+    ) -> Result<Option<(BusReference, BusReference)>, Vec<Statement>> {
+        let (lhs, rhs) = (self.bus_reference(lhe), self.bus_reference(rhe));
+        let message = match (lhs, rhs) {
+            (None, None) => return Result::Ok(None),
+            (Some(lhs), Some(rhs)) if lhs.bus == rhs.bus => return Result::Ok(Some((lhs, rhs))),
+            (Some(lhs), Some(rhs)) => {
+                format!("A bus {} can not be used as a bus {}", rhs.bus, lhs.bus)
+            }
+            (Some(bus), None) | (None, Some(bus)) => {
+                format!("The bus {} can only be used with another bus", bus.bus)
+            }
+        };
+        self.report(meta, message);
+        Result::Err(Vec::new())
+    }
+
+    fn bus_reference(&mut self, expression: &Expression) -> Option<BusReference> {
+        if let Expression::Variable { name, access, .. } = expression {
+            let access = access.iter().cloned().map(|a| self.access(a)).collect();
+            if let Result::Ok(Reference::Bus(reference)) = self.resolve(name.clone(), access) {
+                return Some(reference);
+            }
+        }
+        None
+    }
+
+    fn access(&mut self, access: Access) -> Access {
+        match access {
+            Access::ArrayAccess(index) => Access::ArrayAccess(self.expression(index)),
+            access => access,
+        }
+    }
+
+    fn expression(&mut self, expression: Expression) -> Expression {
+        rebuild(expression, &mut |expression| match expression {
+            Expression::Variable { meta, name, access } => {
+                match self.resolve(name.clone(), access.clone()) {
+                    Result::Ok(Reference::Signal(name, access)) => {
+                        Expression::Variable { meta, name, access }
+                    }
+                    Result::Ok(Reference::Bus(reference)) => {
+                        let message = format!(
+                            "The bus {} can only be assigned to another bus, use its signals instead",
+                            reference.bus
+                        );
+                        self.report(&meta, message);
+                        Expression::Variable { meta, name, access }
+                    }
+                    Result::Err(message) => {
+                        self.report(&meta, message);
+                        Expression::Variable { meta, name, access }
+                    }
+                }
+            }
+            expression => {
+                if let Expression::AnonymousComp { meta, id, .. } = &expression {
+                    if self.flattener.template_buses.contains_key(id) {
+                        let message = format!(
+                            "The template {} has buses, it can not be used as an anonymous component",
+                            id
+                        );
+                        self.report(meta, message);
+                    }
+                }
+                expression
+            }
+        })
+    }
+
+    fn resolve(&self, name: String, access: Vec<Access>) -> Result<Reference, String> {
+        if let Some((bus, dimensions)) = self.buses.get(&name) {
+            let (path, access, bus) = self.flatten_access(bus, *dimensions, access)?;
+            let name = format!("{}{}", name, path);
+            return Result::Ok(match bus {
+                Some(bus) => Reference::Bus(BusReference {
+                    bus,
+                    name,
+                    access,
+                    field: None,
+                }),
+                None => Reference::Signal(name, access),
+            });
+        }
+        let template = self.components.get(&name);
+        let buses = template.and_then(|t| self.flattener.template_buses.get(t));
+        let position = access
+            .iter()
+            .position(|a| matches!(a, Access::ComponentAccess(_)));
+        let (buses, position) = match (buses, position) {
+            (Some(buses), Some(position)) => (buses, position),
+            _ => return Result::Ok(Reference::Signal(name, access)),
+        };
+        let field = match &access[position] {
+            Access::ComponentAccess(field) => field.clone(),
+            _ => unreachable!(),
+        };
+        let (bus, dimensions) = match buses.get(&field) {
+            Some(bus) => bus,
+            None => return Result::Ok(Reference::Signal(name, access)),
+        };
+        let mut new_access = access;
+        let rest = new_access.split_off(position + 1);
+        let (path, mut rest, bus) = self.flatten_access(bus, *dimensions, rest)?;
+        new_access[position] = Access::ComponentAccess(format!("{}{}", field, path));
+        new_access.append(&mut rest);
+        Result::Ok(match bus {
+            Some(bus) => Reference::Bus(BusReference {
+                bus,
+                name,
+                access: new_access,
+                field: Some(position),
+            }),
+            None => Reference::Signal(name, new_access),
+        })
+    }
+
+    // Moves the indexes of the buses in front of the indexes of the signal. Returns the
+    // path to the signal, the new accesses and the bus when they do not reach a signal.
+    fn flatten_access(
+        &self,
+        bus: &str,
+        dimensions: usize,
+        access: Vec<Access>,
+    ) -> Result<(String, Vec<Access>, Option<String>), String> {
+        let mut path = String::new();
+        let mut indexes = Vec::new();
+        let mut current = bus.to_string();
+        let mut current_dimensions = dimensions;
+        let mut access = access.into_iter().peekable();
+        loop {
+            let mut indexed = 0;
+            while indexed < current_dimensions {
+                match access.peek() {
+                    Some(Access::ArrayAccess(_)) => {
+                        indexes.push(access.next().unwrap());
+                        indexed += 1;
+                    }
+                    _ => break,
+                }
+            }
+            let field_name = match access.next() {
+                None => return Result::Ok((path, indexes, Some(current))),
+                Some(Access::ArrayAccess(_)) => {
+                    return Result::Err(format!(
+                        "The array of buses {} has only {} dimensions",
+                        current, current_dimensions
+                    ))
+                }
+                Some(Access::ComponentAccess(field_name)) => field_name,
+            };
+            if indexed < current_dimensions {
+                return Result::Err(format!(
+                    "All the positions of the array of buses must be given to access the field {}",
+                    field_name
+                ));
+            }
+            let field = self.flattener.buses[&current]
+                .fields
+                .iter()
+                .find(|field| field.name == field_name);
+            let field = match field {
+                Some(field) => field,
+                None => {
+                    return Result::Err(format!(
+                        "The bus {} does not have a field {}",
+                        current, field_name
+                    ))
+                }
+            };
+            path.push('.');
+            path.push_str(&field_name);
+            match &field.bus {
+                Some(inner) => {
+                    current = inner.name.clone();
+                    current_dimensions = field.dimensions.len();
+                }
+                None => {
+                    indexes.extend(access);
+                    return Result::Ok((path, indexes, None));
+                }
+            }
+        }
+    }
+}
+
+// Declared buses of a template, only the inputs and outputs if io_only is set
+fn collect_buses(stmt: &Statement, io_only: bool, buses: &mut BusSymbols) {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            collect_buses(if_case, io_only, buses);
+            if let Some(else_case) = else_case {
+                collect_buses(else_case, io_only, buses);
+            }
+        }
+        While { stmt, .. } => collect_buses(stmt, io_only, buses),
+        InitializationBlock {
+            initializations: stmts,
+            ..
+        }
+        | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_buses(stmt, io_only, buses);
+            }
+        }
+        Declaration {
+            xtype: VariableType::Bus(bus, signal_type, _),
+            name,
+            dimensions,
+            ..
+        } if !io_only || *signal_type != SignalType::Intermediate => {
+            buses.insert(name.clone(), (bus.name.clone(), dimensions.len()));
+        }
+        _ => {}
+    }
+}
+
+// Components that are instances of templates with buses
+fn collect_components(
+    stmt: &Statement,
+    template_buses: &HashMap<String, BusSymbols>,
+    components: &mut HashMap<String, String>,
+) {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            collect_components(if_case, template_buses, components);
+            if let Some(else_case) = else_case {
+                collect_components(else_case, template_buses, components);
+            }
+        }
+        While { stmt, .. } => collect_components(stmt, template_buses, components),
+        InitializationBlock {
+            initializations: stmts,
+            ..
+        }
+        | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_components(stmt, template_buses, components);
+            }
+        }
+        Substitution {
+            var,
+            op: AssignOp::AssignVar,
+            rhe,
+            ..
+        } => {
+            let call = match rhe {
+                Expression::ParallelOp { rhe, .. } => rhe.as_ref(),
+                rhe => rhe,
+            };
+            if let Expression::Call { id, .. } = call {
+                if template_buses.contains_key(id) {
+                    components.entry(var.clone()).or_insert_with(|| id.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Applies the function to every subexpression, after the subexpressions it contains
+fn rebuild(expression: Expression, f: &mut dyn FnMut(Expression) -> Expression) -> Expression {
+    use Expression::*;
+    let list = |values: Vec<Expression>, f: &mut dyn FnMut(Expression) -> Expression| {
+        values.into_iter().map(|value| rebuild(value, f)).collect()
+    };
+    let expression = match expression {
+        InfixOp {
+            meta,
+            lhe,
+            infix_op,
+            rhe,
+        } => InfixOp {
+            meta,
+            lhe: Box::new(rebuild(*lhe, f)),
+            infix_op,
+            rhe: Box::new(rebuild(*rhe, f)),
+        },
+        PrefixOp {
+            meta,
+            prefix_op,
+            rhe,
+        } => PrefixOp {
+            meta,
+            prefix_op,
+            rhe: Box::new(rebuild(*rhe, f)),
+        },
+        InlineSwitchOp {
+            meta,
+            cond,
+            if_true,
+            if_false,
+        } => InlineSwitchOp {
+            meta,
+            cond: Box::new(rebuild(*cond, f)),
+            if_true: Box::new(rebuild(*if_true, f)),
+            if_false: Box::new(rebuild(*if_false, f)),
+        },
+        ParallelOp { meta, rhe } => ParallelOp {
+            meta,
+            rhe: Box::new(rebuild(*rhe, f)),
+        },
+        Variable { meta, name, access } => Variable {
+            meta,
+            name,
+            access: access
+                .into_iter()
+                .map(|access| match access {
+                    Access::ArrayAccess(index) => Access::ArrayAccess(rebuild(index, f)),
+                    access => access,
+                })
+                .collect(),
+        },
+        Number(meta, value) => Number(meta, value),
+        Call { meta, id, args } => Call {
+            meta,
+            id,
+            args: list(args, f),
+        },
+        AnonymousComp {
+            meta,
+            id,
+            is_parallel,
+            params,
+            signals,
+            names,
+        } => AnonymousComp {
+            meta,
+            id,
+            is_parallel,
+            params: list(params, f),
+            signals: list(signals, f),
+            names,
+        },
+        ArrayInLine { meta, values } => ArrayInLine {
+            meta,
+            values: list(values, f),
+        },
+        Tuple { meta, values } => Tuple {
+            meta,
+            values: list(values, f),
+        },
+        UniformArray {
+            meta,
+            value,
+            dimension,
+        } => UniformArray {
+            meta,
+            value: Box::new(rebuild(*value, f)),
+            dimension: Box::new(rebuild(*dimension, f)),
+        },
+    };
+    f(expression)
+}
+
+fn add_report(
+    code: ReportCode,
+    meta: &Meta,
+    file_id: FileID,
+    message: String,
+    reports: &mut ReportCollection,
+) {
+    let mut report = Report::error(message.clone(), code);
+    report.add_primary(meta.file_location(), file_id, message);
+    reports.push(report);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser_logic::parse_file;
+
+    const SOURCE: &str = r#"
+bus Point() { signal x; signal {binary} y[2]; }
+bus Path(n) { Point() pts[n]; signal sel; }
+template T(n) {
+    input Path(n) {max} p[3];
+    output Point() q;
+    q <== p[1].pts[0];
+    p[2].pts[1].y[0] === p[0].sel;
+}
+template Main() {
+    component t = T(2);
+    t.p[0].pts[1].x <== 1;
+}
+component main {public [p]} = T(2);
+"#;
+
+    fn declarations(stmt: &Statement, found: &mut Vec<(String, usize, TagList)>) {
+        match stmt {
+            Statement::Block { stmts, .. }
+            | Statement::InitializationBlock {
+                initializations: stmts,
+                ..
+            } => stmts.iter().for_each(|stmt| declarations(stmt, found)),
+            Statement::Declaration {
+                name,
+                dimensions,
+                xtype: VariableType::Signal(_, tags),
+                ..
+            } => found.push((name.clone(), dimensions.len(), tags.clone())),
+            _ => {}
+        }
+    }
+
+    fn variables(stmt: &Statement, found: &mut Vec<String>) {
+        let mut add = |expression: &Expression| {
+            rebuild(expression.clone(), &mut |expression| {
+                if let Expression::Variable { name, access, .. } = &expression {
+                    let mut text = name.clone();
+                    for access in access {
+                        match access {
+                            Access::ComponentAccess(field) => text = format!("{}.{}", text, field),
+                            Access::ArrayAccess(_) => text.push_str("[]"),
+                        }
+                    }
+                    found.push(text);
+                }
+                expression
+            });
+        };
+        match stmt {
+            Statement::Block { stmts, .. }
+            | Statement::InitializationBlock {
+                initializations: stmts,
+                ..
+            } => stmts.iter().for_each(|stmt| variables(stmt, found)),
+            Statement::Substitution {
+                var, access, rhe, ..
+            } => {
+                add(&Expression::Variable {
+                    meta: Meta::new(0, 0),
+                    name: var.clone(),
+                    access: access.clone(),
+                });
+                add(rhe);
+            }
+            Statement::ConstraintEquality { lhe, rhe, .. } => {
+                add(lhe);
+                add(rhe);
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn buses_are_flattened_into_signals() {
+        let ast = parse_file(SOURCE, 0).ok().unwrap();
+        let mut definitions = vec![(0, ast.definitions)];
+        let mut main_component = ast.main_component.unwrap();
+        assert!(flatten_buses(&mut definitions, &mut main_component).is_ok());
+        assert_eq!(definitions[0].1.len(), 2);
+        assert_eq!(main_component.0, vec!["p.pts.x", "p.pts.y", "p.sel"]);
+
+        let bodies: Vec<&Statement> = definitions[0]
+            .1
+            .iter()
+            .map(|definition| match definition {
+                Definition::Template { body, .. } => body,
+                _ => unreachable!(),
+            })
+            .collect();
+        let mut found = Vec::new();
+        declarations(bodies[0], &mut found);
+        let max = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<TagList>();
+        assert_eq!(
+            found,
+            vec![
+                ("p.pts.x".to_string(), 2, max(&["max"])),
+                ("p.pts.y".to_string(), 3, max(&["max", "binary"])),
+                ("p.sel".to_string(), 1, max(&["max"])),
+                ("q.x".to_string(), 0, max(&[])),
+                ("q.y".to_string(), 1, max(&["binary"])),
+            ]
+        );
+
+        let mut found = Vec::new();
+        variables(bodies[0], &mut found);
+        variables(bodies[1], &mut found);
+        assert_eq!(
+            found,
+            vec![
+                "q.x",
+                "p.pts.x[][]",
+                "q.y",
+                "p.pts.y[][]",
+                "p.pts.y[][][]",
+                "p.sel[]",
+                "t",
+                "t.p.pts.x[][]",
+            ]
+        );
+    }
+}
@@ -0,0 +1,863 @@
+use super::parser_logic;
+use program_structure::ast::{
+    Access, AssignOp, Definition, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode,
+    LogArgument, Meta, SignalType, Statement, VariableType, AST,
+};
+use program_structure::error_definition::ReportCollection;
+use program_structure::file_definition::FileID;
+
+// Formatter of circom sources. The file is parsed without removing the syntactic sugar
+// and printed back with a canonical layout. The parser does not keep the comments and
+// turns some shortcuts (for loops, compound assignments, declarations with several
+// symbols) into simpler statements, so the comments are read again from the source and
+// the shortcuts are recognized by the locations that the parser gives to the statements
+// it builds for them.
+
+const INDENT: &str = "    ";
+
+pub fn format_source(src: &str, file_id: FileID) -> Result<String, ReportCollection> {
+    let ast = parser_logic::parse_file(src, file_id)?;
+    let code = parser_logic::preprocess(src, file_id)?;
+    let mut printer = Printer::new(src, &code);
+    printer.program(&ast);
+    Result::Ok(printer.finish())
+}
+
+struct Comment {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+// Finds the comments in the same way as parser_logic::preprocess removes them
+fn collect_comments(src: &str) -> Vec<Comment> {
+    let bytes = src.as_bytes();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        let end = match (bytes[i], bytes[i + 1]) {
+            (b'/', b'/') => src[i..].find('\n').map_or(src.len(), |p| i + p),
+            (b'/', b'*') => src[i + 2..].find("*/").map_or(src.len(), |p| i + p + 4),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        comments.push(Comment {
+            start: i,
+            end,
+            text: src[i..end].trim_end().to_string(),
+        });
+        i = end;
+    }
+    comments
+}
+
+enum HeaderItem {
+    Version,
+    CustomTemplates,
+    Include,
+}
+
+// Pragmas and includes do not keep their location in the AST, they are found in the
+// source without comments
+fn collect_header(code: &str) -> Vec<(HeaderItem, usize, usize)> {
+    let mut items = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = code[pos..].find(|c: char| !c.is_whitespace()) {
+        let start = pos + offset;
+        let end = match code[start..].find(';') {
+            Some(offset) => start + offset + 1,
+            None => break,
+        };
+        let mut words = code[start..end].split(|c: char| c.is_whitespace() || c == ';' || c == '"');
+        let item = match words.next() {
+            Some("include") => HeaderItem::Include,
+            Some("pragma") => match words.find(|w| !w.is_empty()) {
+                Some("circom") => HeaderItem::Version,
+                _ => HeaderItem::CustomTemplates,
+            },
+            _ => break,
+        };
+        items.push((item, start, end));
+        pos = end;
+    }
+    items
+}
+
+// The parser gives the same location to a shortcut and to the statements it is made of
+fn same_location(a: &Meta, b: &Meta) -> bool {
+    a.start == b.start && a.end == b.end
+}
+
+fn assign_op(op: AssignOp) -> &'static str {
+    match op {
+        AssignOp::AssignVar => "=",
+        AssignOp::AssignSignal => "<--",
+        AssignOp::AssignConstraintSignal => "<==",
+    }
+}
+
+fn infix_op(op: ExpressionInfixOpcode) -> &'static str {
+    use ExpressionInfixOpcode::*;
+    match op {
+        Mul => "*",
+        Div => "/",
+        Add => "+",
+        Sub => "-",
+        Pow => "**",
+        IntDiv => "\\",
+        Mod => "%",
+        ShiftL => "<<",
+        ShiftR => ">>",
+        LesserEq => "<=",
+        GreaterEq => ">=",
+        Lesser => "<",
+        Greater => ">",
+        Eq => "==",
+        NotEq => "!=",
+        BoolOr => "||",
+        BoolAnd => "&&",
+        BitOr => "|",
+        BitAnd => "&",
+        BitXor => "^",
+    }
+}
+
+fn prefix_op(op: ExpressionPrefixOpcode) -> &'static str {
+    match op {
+        ExpressionPrefixOpcode::Sub => "-",
+        ExpressionPrefixOpcode::BoolNot => "!",
+        ExpressionPrefixOpcode::Complement => "~",
+    }
+}
+
+// Tiers of the grammar in lang.lalrpop, a lower value binds tighter
+fn precedence(expression: &Expression) -> u8 {
+    use ExpressionInfixOpcode::*;
+    match expression {
+        Expression::InfixOp { infix_op, .. } => match infix_op {
+            Pow => 3,
+            Mul | Div | IntDiv | Mod => 4,
+            Add | Sub => 5,
+            ShiftL | ShiftR => 6,
+            BitAnd => 7,
+            BitXor => 8,
+            BitOr => 9,
+            LesserEq | GreaterEq | Lesser | Greater | Eq | NotEq => 10,
+            BoolAnd => 11,
+            BoolOr => 12,
+        },
+        Expression::PrefixOp { .. } => 2,
+        Expression::InlineSwitchOp { .. } => 13,
+        Expression::ParallelOp { .. } => 14,
+        _ => 1,
+    }
+}
+
+fn signal_type(signal_type: &SignalType) -> &'static str {
+    match signal_type {
+        SignalType::Input => " input",
+        SignalType::Output => " output",
+        SignalType::Intermediate => "",
+    }
+}
+
+struct Printer<'a> {
+    src: &'a str,
+    code: &'a str,
+    comments: Vec<Comment>,
+    next_comment: usize,
+    out: String,
+    indent: usize,
+    line_open: bool,
+    // End of the last item printed, the blank lines that follow it are kept
+    last_end: usize,
+    at_block_start: bool,
+    force_blank: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(src: &'a str, code: &'a str) -> Printer<'a> {
+        Printer {
+            src,
+            code,
+            comments: collect_comments(src),
+            next_comment: 0,
+            out: String::new(),
+            indent: 0,
+            line_open: false,
+            last_end: 0,
+            at_block_start: true,
+            force_blank: false,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.comments_before(usize::MAX);
+        self.end_line();
+        self.out
+    }
+
+    fn write(&mut self, text: &str) {
+        if !self.line_open {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+            self.line_open = true;
+        }
+        self.out.push_str(text);
+    }
+
+    fn end_line(&mut self) {
+        if self.line_open {
+            self.out.push('\n');
+            self.line_open = false;
+        }
+    }
+
+    // Keeps at most one of the blank lines found in the source before an item
+    fn separate(&mut self, start: usize) {
+        let gap = self.src.get(self.last_end..start).unwrap_or("");
+        let blank = self.force_blank || gap.matches('\n').count() > 1;
+        if blank && !self.at_block_start {
+            self.out.push('\n');
+        }
+        self.force_blank = false;
+        self.at_block_start = false;
+    }
+
+    fn comments_before(&mut self, position: usize) -> bool {
+        let mut found = false;
+        while self.next_comment < self.comments.len()
+            && self.comments[self.next_comment].start < position
+        {
+            let (start, end) = {
+                let comment = &self.comments[self.next_comment];
+                (comment.start, comment.end)
+            };
+            self.end_line();
+            self.separate(start);
+            let text = std::mem::take(&mut self.comments[self.next_comment].text);
+            // Only the first line of a block comment is indented again
+            let mut lines = text.lines();
+            if let Some(first) = lines.next() {
+                self.write(first.trim_start());
+                self.end_line();
+            }
+            for line in lines {
+                self.out.push_str(line.trim_end());
+                self.out.push('\n');
+            }
+            self.last_end = end;
+            self.next_comment += 1;
+            found = true;
+        }
+        found
+    }
+
+    // A comment placed after an item in the same line stays there
+    fn trailing_comment(&mut self, end: usize) {
+        let same_line = self.comments.get(self.next_comment).and_then(|comment| {
+            let gap = self.src.get(end..comment.start).unwrap_or("\n");
+            let only_separators = gap
+                .chars()
+                .all(|c| c == ';' || (c.is_whitespace() && c != '\n'));
+            if only_separators && !comment.text.contains('\n') {
+                Some((format!(" {}", comment.text), comment.end))
+            } else {
+                None
+            }
+        });
+        if let Some((text, comment_end)) = same_line {
+            self.write(&text);
+            self.last_end = comment_end;
+            self.next_comment += 1;
+        }
+        self.end_line();
+    }
+
+    fn item_start(&mut self, start: usize) {
+        self.comments_before(start);
+        self.end_line();
+        self.separate(start);
+    }
+
+    fn item_end(&mut self, end: usize) {
+        self.last_end = self.last_end.max(end);
+        if self.comments_before(end) {
+            self.last_end = self.last_end.max(end);
+        } else {
+            self.trailing_comment(end);
+        }
+    }
+
+    fn program(&mut self, ast: &AST) {
+        let mut includes = ast.includes.iter();
+        for (item, start, end) in collect_header(self.code) {
+            let text = match item {
+                HeaderItem::Version => match ast.compiler_version {
+                    Some((major, minor, patch)) => {
+                        format!("pragma circom {}.{}.{};", major, minor, patch)
+                    }
+                    None => continue,
+                },
+                HeaderItem::CustomTemplates => "pragma custom_templates;".to_string(),
+                HeaderItem::Include => match includes.next() {
+                    Some(include) => format!("include \"{}\";", include),
+                    None => continue,
+                },
+            };
+            self.item_start(start);
+            self.write(&text);
+            self.item_end(end);
+        }
+        for definition in &ast.definitions {
+            self.force_blank = true;
+            self.definition(definition);
+        }
+        if let Some((public, call)) = &ast.main_component {
+            let call_start = call.get_meta().start;
+            let start = self.code[..call_start]
+                .rfind("component")
+                .unwrap_or(call_start);
+            self.force_blank = true;
+            self.item_start(start);
+            self.write("component main ");
+            if !public.is_empty() {
+                let list = format!("{{public [{}]}} ", public.join(", "));
+                self.write(&list);
+            }
+            let text = format!("= {};", self.expression(call));
+            self.write(&text);
+            self.item_end(call.get_meta().end);
+        }
+    }
+
+    fn definition(&mut self, definition: &Definition) {
+        let (meta, body, header) = match definition {
+            Definition::Template {
+                meta,
+                name,
+                args,
+                body,
+                parallel,
+                is_custom_gate,
+                ..
+            } => {
+                let mut header = "template ".to_string();
+                if *is_custom_gate {
+                    header.push_str("custom ");
+                }
+                if *parallel {
+                    header.push_str("parallel ");
+                }
+                (
+                    meta,
+                    body,
+                    format!("{}{}({}) ", header, name, args.join(", ")),
+                )
+            }
+            Definition::Function {
+                meta,
+                name,
+                args,
+                body,
+                ..
+            } => (
+                meta,
+                body,
+                format!("function {}({}) ", name, args.join(", ")),
+            ),
+            Definition::Bus {
+                meta,
+                name,
+                args,
+                body,
+                ..
+            } => (meta, body, format!("bus {}({}) ", name, args.join(", "))),
+        };
+        self.item_start(meta.start);
+        self.write(&header);
+        self.statement_body(body);
+        self.item_end(meta.end);
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let meta = statement.get_meta();
+        self.item_start(meta.start);
+        self.statement_body(statement);
+        self.item_end(meta.end);
+    }
+
+    // Prints a block after the text of the current line, the line is left open after
+    // the closing brace
+    fn block(&mut self, meta: &Meta, stmts: &[Statement]) {
+        let has_comments = self
+            .comments
+            .get(self.next_comment)
+            .is_some_and(|comment| comment.start < meta.end);
+        if stmts.is_empty() && !has_comments {
+            self.write("{}");
+            return;
+        }
+        self.write("{");
+        self.trailing_comment(meta.start + 1);
+        self.indent += 1;
+        self.at_block_start = true;
+        for statement in stmts {
+            self.statement(statement);
+        }
+        self.comments_before(meta.end);
+        self.indent -= 1;
+        self.write("}");
+        self.at_block_start = false;
+    }
+
+    // Body of an if, while or for: blocks start in the same line, other statements in
+    // the next one
+    fn branch(&mut self, statement: &Statement) {
+        if let Statement::Block { meta, stmts } = statement {
+            self.write(" ");
+            self.block(meta, stmts);
+        } else {
+            self.end_line();
+            self.indent += 1;
+            self.at_block_start = true;
+            self.statement(statement);
+            self.indent -= 1;
+        }
+    }
+
+    fn statement_body(&mut self, statement: &Statement) {
+        use Statement::*;
+        if let Some((init, cond, step, body)) = Self::as_for_loop(statement) {
+            let text = format!(
+                "for ({}; {}; {})",
+                self.simple_statement(init),
+                self.expression(cond),
+                self.simple_statement(step)
+            );
+            self.write(&text);
+            self.branch(body);
+            return;
+        }
+        match statement {
+            IfThenElse {
+                cond,
+                if_case,
+                else_case,
+                ..
+            } => {
+                let text = format!("if ({})", self.expression(cond));
+                self.write(&text);
+                self.branch(if_case);
+                if let Some(else_case) = else_case {
+                    if self.line_open {
+                        self.write(" else");
+                    } else {
+                        self.write("else");
+                    }
+                    if matches!(else_case.as_ref(), IfThenElse { .. }) {
+                        self.write(" ");
+                        self.statement_body(else_case);
+                    } else {
+                        self.branch(else_case);
+                    }
+                }
+            }
+            While { cond, stmt, .. } => {
+                let text = format!("while ({})", self.expression(cond));
+                self.write(&text);
+                self.branch(stmt);
+            }
+            Block { meta, stmts } => self.block(meta, stmts),
+            _ => {
+                let text = format!("{};", self.simple_statement(statement));
+                self.write(&text);
+            }
+        }
+    }
+
+    // A for loop is turned into a block with the initialization and a while whose body
+    // is a block with the body of the loop and the step
+    fn as_for_loop(
+        statement: &Statement,
+    ) -> Option<(&Statement, &Expression, &Statement, &Statement)> {
+        if let Statement::Block { meta, stmts } = statement {
+            if let [init, Statement::While {
+                meta: while_meta,
+                cond,
+                stmt,
+            }] = stmts.as_slice()
+            {
+                if let Statement::Block {
+                    meta: body_meta,
+                    stmts: body,
+                } = stmt.as_ref()
+                {
+                    if let [body, step] = body.as_slice() {
+                        if same_location(meta, while_meta)
+                            && same_location(body_meta, body.get_meta())
+                        {
+                            return Some((init, cond, step, body));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn simple_statement(&self, statement: &Statement) -> String {
+        use Statement::*;
+        match statement {
+            Return { value, .. } => format!("return {}", self.expression(value)),
+            InitializationBlock {
+                meta,
+                xtype,
+                initializations,
+            } => self.initialization_block(meta, xtype, initializations),
+            Declaration {
+                xtype,
+                name,
+                dimensions,
+                ..
+            } => format!(
+                "{} {}",
+                self.declaration_header(xtype),
+                self.symbol(name, dimensions)
+            ),
+            Substitution {
+                meta,
+                var,
+                access,
+                op,
+                rhe,
+            } => self.substitution(meta, var, access, *op, rhe),
+            MultSubstitution { lhe, op, rhe, .. } => match lhe {
+                // Anonymous components used as statements
+                Expression::Tuple { values, .. } if values.is_empty() => self.expression(rhe),
+                _ => format!(
+                    "{} {} {}",
+                    self.expression(lhe),
+                    assign_op(*op),
+                    self.expression(rhe)
+                ),
+            },
+            UnderscoreSubstitution { op, rhe, .. } => {
+                format!("_ {} {}", assign_op(*op), self.expression(rhe))
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                format!("{} === {}", self.expression(lhe), self.expression(rhe))
+            }
+            LogCall { args, .. } => {
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|arg| match arg {
+                        LogArgument::LogStr(text) => format!("\"{}\"", text),
+                        LogArgument::LogExp(expression) => self.expression(expression),
+                    })
+                    .collect();
+                format!("log({})", args.join(", "))
+            }
+            Assert { arg, .. } => format!("assert({})", self.expression(arg)),
+            IfThenElse { .. } | While { .. } | Block { .. } => String::new(),
+        }
+    }
+
+    // Shortcuts such as x += e or x++ are substitutions whose expression has the same
+    // location as the statement
+    fn substitution(
+        &self,
+        meta: &Meta,
+        var: &str,
+        access: &[Access],
+        op: AssignOp,
+        rhe: &Expression,
+    ) -> String {
+        let variable = format!("{}{}", var, self.accesses(access));
+        if let Expression::InfixOp {
+            meta: infix_meta,
+            lhe,
+            infix_op: operator,
+            rhe: operand,
+        } = rhe
+        {
+            if op == AssignOp::AssignVar
+                && same_location(meta, infix_meta)
+                && same_location(meta, lhe.get_meta())
+            {
+                return match (operator, operand.as_ref()) {
+                    (ExpressionInfixOpcode::Add, Expression::Number(number_meta, _))
+                        if same_location(meta, number_meta) =>
+                    {
+                        format!("{}++", variable)
+                    }
+                    (ExpressionInfixOpcode::Sub, Expression::Number(number_meta, _))
+                        if same_location(meta, number_meta) =>
+                    {
+                        format!("{}--", variable)
+                    }
+                    _ => format!(
+                        "{} {}= {}",
+                        variable,
+                        infix_op(*operator),
+                        self.expression(operand)
+                    ),
+                };
+            }
+        }
+        format!("{} {} {}", variable, assign_op(op), self.expression(rhe))
+    }
+
+    // Declarations are split in one declaration per symbol followed by its
+    // initialization, which has the location of the whole declaration. Variables
+    // without initialization are set to zero with an expression that has that location
+    // too. The initialization of a tuple is a single substitution at the end.
+    fn initialization_block(
+        &self,
+        meta: &Meta,
+        xtype: &VariableType,
+        initializations: &[Statement],
+    ) -> String {
+        let mut symbols = Vec::new();
+        let mut tuple = None;
+        for statement in initializations {
+            match statement {
+                Statement::Declaration {
+                    name, dimensions, ..
+                } => symbols.push(self.symbol(name, dimensions)),
+                Statement::Substitution { op, rhe, .. } if !same_location(meta, rhe.get_meta()) => {
+                    if let Some(symbol) = symbols.last_mut() {
+                        *symbol = format!("{} {} {}", symbol, assign_op(*op), self.expression(rhe));
+                    }
+                }
+                Statement::MultSubstitution { op, rhe, .. } => tuple = Some((*op, rhe)),
+                _ => {}
+            }
+        }
+        let header = self.declaration_header(xtype);
+        match tuple {
+            Some((op, rhe)) => format!(
+                "{} ({}) {} {}",
+                header,
+                symbols.join(", "),
+                assign_op(op),
+                self.expression(rhe)
+            ),
+            None => format!("{} {}", header, symbols.join(", ")),
+        }
+    }
+
+    fn declaration_header(&self, xtype: &VariableType) -> String {
+        match xtype {
+            VariableType::Var => "var".to_string(),
+            VariableType::Component | VariableType::AnonymousComponent => "component".to_string(),
+            VariableType::Signal(st, tags) if tags.is_empty() => {
+                format!("signal{}", signal_type(st))
+            }
+            VariableType::Signal(st, tags) => {
+                format!("signal{} {{{}}}", signal_type(st), tags.join(", "))
+            }
+            VariableType::Bus(bus, st, tags) => {
+                let mut header = format!(
+                    "{} {}({})",
+                    signal_type(st),
+                    bus.name,
+                    self.list(&bus.args)
+                );
+                if !tags.is_empty() {
+                    header = format!("{} {{{}}}", header, tags.join(", "));
+                }
+                header.trim_start().to_string()
+            }
+        }
+    }
+
+    fn symbol(&self, name: &str, dimensions: &[Expression]) -> String {
+        let mut symbol = name.to_string();
+        for dimension in dimensions {
+            symbol = format!("{}[{}]", symbol, self.expression(dimension));
+        }
+        symbol
+    }
+
+    fn accesses(&self, access: &[Access]) -> String {
+        let mut text = String::new();
+        for acc in access {
+            match acc {
+                Access::ArrayAccess(index) => {
+                    text = format!("{}[{}]", text, self.expression(index));
+                }
+                Access::ComponentAccess(name) => {
+                    text = format!("{}.{}", text, name);
+                }
+            }
+        }
+        text
+    }
+
+    fn list(&self, expressions: &[Expression]) -> String {
+        let values: Vec<String> = expressions.iter().map(|e| self.expression(e)).collect();
+        values.join(", ")
+    }
+
+    // Operand that needs parentheses when it does not bind tighter than the given tier
+    fn operand(&self, expression: &Expression, tier: u8) -> String {
+        if precedence(expression) >= tier {
+            format!("({})", self.expression(expression))
+        } else {
+            self.expression(expression)
+        }
+    }
+
+    fn expression(&self, expression: &Expression) -> String {
+        use Expression::*;
+        match expression {
+            InfixOp {
+                lhe,
+                infix_op: op,
+                rhe,
+                ..
+            } => {
+                let tier = precedence(expression);
+                format!(
+                    "{} {} {}",
+                    self.operand(lhe, tier + 1),
+                    infix_op(*op),
+                    self.operand(rhe, tier)
+                )
+            }
+            PrefixOp {
+                prefix_op: op, rhe, ..
+            } => {
+                format!("{}{}", prefix_op(*op), self.operand(rhe, 2))
+            }
+            InlineSwitchOp {
+                cond,
+                if_true,
+                if_false,
+                ..
+            } => format!(
+                "{} ? {} : {}",
+                self.operand(cond, 13),
+                self.operand(if_true, 13),
+                self.operand(if_false, 13)
+            ),
+            ParallelOp { rhe, .. } => format!("parallel {}", self.operand(rhe, 14)),
+            Variable { name, access, .. } => format!("{}{}", name, self.accesses(access)),
+            Number(meta, value) => {
+                if self.src[meta.start..].starts_with("0x") {
+                    format!("0x{}", value.to_str_radix(16))
+                } else {
+                    value.to_string()
+                }
+            }
+            Call { id, args, .. } => format!("{}({})", id, self.list(args)),
+            AnonymousComp {
+                id,
+                is_parallel,
+                params,
+                signals,
+                names,
+                ..
+            } => {
+                let inputs = match names {
+                    Some(names) => {
+                        let inputs: Vec<String> = names
+                            .iter()
+                            .zip(signals.iter())
+                            .map(|((op, name), signal)| {
+                                format!("{} {} {}", name, assign_op(*op), self.expression(signal))
+                            })
+                            .collect();
+                        inputs.join(", ")
+                    }
+                    None => self.list(signals),
+                };
+                let parallel = if *is_parallel { "parallel " } else { "" };
+                format!("{}{}({})({})", parallel, id, self.list(params), inputs)
+            }
+            ArrayInLine { values, .. } => format!("[{}]", self.list(values)),
+            Tuple { values, .. } => format!("({})", self.list(values)),
+            // Only built for the implicit initialization of variables, which is not printed
+            UniformArray { value, .. } => self.expression(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_source;
+
+    const SOURCE: &str = r#"pragma circom 2.1.6;
+pragma custom_templates;
+include "bits.circom";  // helpers
+
+
+
+/* Checks
+   bits */
+template custom Gate(){signal input {binary} in[2]; signal output out;
+    out <-- in[0]*(in[1]+0x1F);
+  }
+template Main(n) { // entry
+    signal input a[n], b;  signal output (c, d);
+    var acc, k = 2; var (x, y) = (1, k);
+    for (var i=0;i<n;i++) { acc += a[i] * (b - 1) ** 2; // sum
+    }
+
+    if (acc == 0) c <== 1; else if (k > 1) { c <== 0; } else c <== -(-k);
+    component g = Gate()(in <== [a[0], b]);
+    (c, d) <== Pair()(a[0], b);
+    Gate()([b, b]);
+    d === k ? acc : 2;
+}
+component main {public [b]} = Main(3);
+"#;
+
+    const FORMATTED: &str = r#"pragma circom 2.1.6;
+pragma custom_templates;
+include "bits.circom"; // helpers
+
+/* Checks
+   bits */
+template custom Gate() {
+    signal input {binary} in[2];
+    signal output out;
+    out <-- in[0] * (in[1] + 0x1f);
+}
+
+template Main(n) { // entry
+    signal input a[n], b;
+    signal output c, d;
+    var acc, k = 2;
+    var (x, y) = (1, k);
+    for (var i = 0; i < n; i++) {
+        acc += a[i] * (b - 1) ** 2; // sum
+    }
+
+    if (acc == 0)
+        c <== 1;
+    else if (k > 1) {
+        c <== 0;
+    } else
+        c <== -(-k);
+    component g = Gate()(in <== [a[0], b]);
+    (c, d) <== Pair()(a[0], b);
+    Gate()([b, b]);
+    d === k ? acc : 2;
+}
+
+component main {public [b]} = Main(3);
+"#;
+
+    #[test]
+    fn format_is_canonical() {
+        let formatted = format_source(SOURCE, 0).ok().unwrap();
+        assert_eq!(formatted, FORMATTED);
+        let again = format_source(&formatted, 0).ok().unwrap();
+        assert_eq!(again, FORMATTED);
+    }
+}
@@ -1,6 +1,7 @@
 use program_structure::ast::produce_report_with_message;
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::Report;
+use super::source_provider::SourceProvider;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
@@ -25,6 +26,7 @@ impl FileStack {
         f_stack: &mut FileStack,
         name: String,
         libraries: &Vec<PathBuf>,
+        provider: &dyn SourceProvider,
     ) -> Result<String, Report> {
         let mut libraries2 = Vec::new();
         libraries2.push(f_stack.current_location.clone());
@@ -33,17 +35,11 @@ impl FileStack {
             let mut path = PathBuf::new();
             path.push(lib);
             path.push(name.clone());
-            let path = std::fs::canonicalize(path);
-            match path {
-                Err(_) => {}
-                Ok(path) => {
-                    if path.is_file() {
-                        if !f_stack.black_paths.contains(&path) {
-                            f_stack.stack.push(path.clone());
-                        }
-                        return Result::Ok(path.to_str().unwrap().to_string());
-                    }
+            if let Some(path) = provider.canonicalize(&path) {
+                if !f_stack.black_paths.contains(&path) {
+                    f_stack.stack.push(path.clone());
                 }
+                return Result::Ok(path.to_str().unwrap().to_string());
             }
         }
         Result::Err(produce_report_with_message(
@@ -97,11 +93,16 @@ impl IncludesGraph {
         }
     }
 
-    pub fn add_edge(&mut self, old_path: String) -> Result<(), Report> {
+    pub fn add_edge(
+        &mut self,
+        old_path: String,
+        provider: &dyn SourceProvider,
+    ) -> Result<(), Report> {
         let mut crr = PathBuf::new();
         crr.push(old_path.clone());
-        let path = std::fs::canonicalize(crr)
-            .map_err(|_e| produce_report_with_message(ReportCode::FileOs, old_path))?;
+        let path = provider
+            .canonicalize(&crr)
+            .ok_or_else(|| produce_report_with_message(ReportCode::FileOs, old_path))?;
         let edges = self.adjacency.entry(path).or_insert(vec![]);
         edges.push(self.nodes.len() - 1);
         Ok(())
@@ -159,8 +160,10 @@ impl IncludesGraph {
             .iter()
             .map(|file| -> String {
                 let file = format!("{}", file.display());
-                let (_, file) = file.rsplit_once('/').unwrap();
-                file.clone().to_string()
+                match file.rsplit_once('/') {
+                    Some((_, file)) => file.to_string(),
+                    None => file,
+                }
             })
             .collect::<Vec<String>>();
         let mut path_covered = path[0].to_string();
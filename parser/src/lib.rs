@@ -8,8 +8,11 @@ extern crate lalrpop_util;
 lalrpop_mod!(pub lang);
 
 mod include_logic;
+pub mod formatter;
 mod parser_logic;
+pub mod source_provider;
 mod syntax_sugar_remover;
+mod bus_flattener;
 
 use include_logic::{FileStack, IncludesGraph};
 use program_structure::ast::{
@@ -21,6 +24,8 @@ use program_structure::error_definition::Report;
 use program_structure::error_definition::ReportCollection;
 use program_structure::file_definition::FileLibrary;
 use program_structure::program_archive::ProgramArchive;
+use bus_flattener::flatten_buses;
+use source_provider::{FileSystemProvider, SourceProvider};
 use std::path::{Path, PathBuf};
 use syntax_sugar_remover::apply_syntactic_sugar;
 
@@ -31,6 +36,7 @@ pub type Version = (usize, usize, usize);
 pub fn find_file(
     crr_file: PathBuf,
     ext_link_libraries: Vec<PathBuf>,
+    provider: &dyn SourceProvider,
 ) -> (bool, String, String, PathBuf, Vec<Report>) {
     let mut found = false;
     let mut path = "".to_string();
@@ -44,7 +50,7 @@ pub fn find_file(
         p.push(aux);
         p.push(crr_file.clone());
         crr_str_file = p;
-        match open_file(crr_str_file.clone()) {
+        match open_file(crr_str_file.clone(), provider) {
             Ok((new_path, new_src)) => {
                 path = new_path;
                 src = new_src;
@@ -63,6 +69,16 @@ pub fn run_parser(
     file: String,
     version: &str,
     link_libraries: Vec<PathBuf>,
+) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
+    run_parser_with_provider(file, version, link_libraries, &FileSystemProvider::new())
+}
+
+// Same as run_parser, but the main file and its includes are obtained from the provider
+pub fn run_parser_with_provider(
+    file: String,
+    version: &str,
+    link_libraries: Vec<PathBuf>,
+    provider: &dyn SourceProvider,
 ) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
     let mut file_library = FileLibrary::new();
     let mut definitions = Vec::new();
@@ -75,7 +91,7 @@ pub fn run_parser(
     ext_link_libraries.append(&mut link_libraries2);
     while let Some(crr_file) = FileStack::take_next(&mut file_stack) {
         let (found, path, src, crr_str_file, reports) =
-            find_file(crr_file, ext_link_libraries.clone());
+            find_file(crr_file, ext_link_libraries.clone(), provider);
         if !found {
             return Result::Err((file_library.clone(), reports));
         }
@@ -94,10 +110,15 @@ pub fn run_parser(
         definitions.push((file_id, program.definitions));
         for include in includes {
             let path_include =
-                FileStack::add_include(&mut file_stack, include.clone(), &link_libraries.clone())
+                FileStack::add_include(
+                    &mut file_stack,
+                    include.clone(),
+                    &link_libraries.clone(),
+                    provider,
+                )
                     .map_err(|e| (file_library.clone(), vec![e]))?;
             includes_graph
-                .add_edge(path_include)
+                .add_edge(path_include, provider)
                 .map_err(|e| (file_library.clone(), vec![e]))?;
         }
         warnings.append(
@@ -141,7 +162,11 @@ pub fn run_parser(
             warnings.append(&mut errors);
             Err((file_library, warnings))
         } else {
-            let (main_id, main_component, custom_gates) = main_components.pop().unwrap();
+            let (main_id, mut main_component, custom_gates) = main_components.pop().unwrap();
+            if let Result::Err(mut reports) = flatten_buses(&mut definitions, &mut main_component) {
+                warnings.append(&mut reports);
+                return Err((file_library, warnings));
+            }
             let result_program_archive = ProgramArchive::new(
                 file_library,
                 main_id,
@@ -194,12 +219,15 @@ fn produce_report_with_main_components(
     r
 }
 
-fn open_file(path: PathBuf) -> Result<(String, String), Report> /* path, src */ {
-    use std::fs::read_to_string;
+fn open_file(
+    path: PathBuf,
+    provider: &dyn SourceProvider,
+) -> Result<(String, String), Report> /* path, src */ {
     let path_str = format!("{:?}", path);
-    read_to_string(path)
+    provider
+        .read(&path)
         .map(|contents| (path_str.clone(), contents))
-        .map_err(|_| produce_report_with_message(ReportCode::FileOs, path_str.clone()))
+        .ok_or_else(|| produce_report_with_message(ReportCode::FileOs, path_str.clone()))
 }
 
 fn parse_number_version(version: &str) -> Version {
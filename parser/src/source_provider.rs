@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+// Gives the parser access to the sources of a project. A provider identifies every
+// source by a canonical path, used to avoid including the same file twice.
+pub trait SourceProvider {
+    // Canonical path of the source, None if there is no source in the given path
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf>;
+    fn read(&self, path: &Path) -> Option<String>;
+}
+
+impl<P: SourceProvider + ?Sized> SourceProvider for Box<P> {
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        (**self).canonicalize(path)
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        (**self).read(path)
+    }
+}
+
+// Reads the sources from the filesystem
+#[derive(Default)]
+pub struct FileSystemProvider;
+
+impl FileSystemProvider {
+    pub fn new() -> FileSystemProvider {
+        FileSystemProvider
+    }
+}
+
+impl SourceProvider for FileSystemProvider {
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        let path = std::fs::canonicalize(path).ok()?;
+        if path.is_file() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+}
+
+// Sources kept in memory. Paths are normalized without accessing the filesystem, so
+// "lib/../main.circom" and "main.circom" refer to the same source.
+#[derive(Default)]
+pub struct MemoryProvider {
+    files: HashMap<PathBuf, String>,
+}
+
+impl MemoryProvider {
+    pub fn new() -> MemoryProvider {
+        MemoryProvider::default()
+    }
+
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, contents: String) {
+        self.files.insert(normalize(path.as_ref()), contents);
+    }
+}
+
+impl SourceProvider for MemoryProvider {
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        if self.files.contains_key(&path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        self.files.get(&normalize(path)).cloned()
+    }
+}
+
+// Looks for every source in the first provider and then in the second one
+pub struct LayeredProvider<A: SourceProvider, B: SourceProvider> {
+    first: A,
+    second: B,
+}
+
+impl<A: SourceProvider, B: SourceProvider> LayeredProvider<A, B> {
+    pub fn new(first: A, second: B) -> LayeredProvider<A, B> {
+        LayeredProvider { first, second }
+    }
+}
+
+impl<A: SourceProvider, B: SourceProvider> SourceProvider for LayeredProvider<A, B> {
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        self.first
+            .canonicalize(path)
+            .or_else(|| self.second.canonicalize(path))
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        match self.first.canonicalize(path) {
+            Some(_) => self.first.read(path),
+            None => self.second.read(path),
+        }
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_paths_are_normalized() {
+        let mut provider = MemoryProvider::new();
+        provider.add_file("lib/square.circom", "template Square() {}".to_string());
+        let path = provider.canonicalize(Path::new("./lib/../lib/square.circom"));
+        assert_eq!(path, Some(PathBuf::from("lib/square.circom")));
+        assert!(provider.canonicalize(Path::new("square.circom")).is_none());
+        assert_eq!(
+            provider.read(Path::new("lib/./square.circom")),
+            Some("template Square() {}".to_string())
+        );
+    }
+}
@@ -711,8 +711,10 @@ pub fn remove_anonymous_from_expression(
                 }
             }
 
-            // generate the substitutions for the inputs
-            for (name_signal, (operator, expr)) in inputs_to_assignments {
+            // generate the substitutions for the inputs, in the order of their declarations so
+            // that the generated code does not depend on the order of the map
+            for (name_signal, _) in template.unwrap().get_declaration_inputs() {
+                let (operator, expr) = inputs_to_assignments.remove(name_signal).unwrap();
                 let mut acc = if var_access.is_none() {
                     Vec::new()
                 } else {
@@ -174,6 +174,13 @@ pub enum Definition {
         arg_location: FileLocation,
         body: Statement,
     },
+    Bus {
+        meta: Meta,
+        name: String,
+        args: Vec<String>,
+        arg_location: FileLocation,
+        body: Statement,
+    },
 }
 pub fn build_template(
     meta: Meta,
@@ -211,6 +218,22 @@ pub fn build_function(
     }
 }
 
+pub fn build_bus(
+    meta: Meta,
+    name: String,
+    args: Vec<String>,
+    arg_location: FileLocation,
+    body: Statement,
+) -> Definition {
+    Definition::Bus {
+        meta,
+        name,
+        args,
+        arg_location,
+        body,
+    }
+}
+
 #[derive(Clone)]
 pub enum Statement {
     IfThenElse {
@@ -286,10 +309,36 @@ pub enum SignalType {
 
 pub type TagList = Vec<String>;
 
+// The bus of a declaration together with the arguments of its instance. The arguments
+// are only known after the execution, so two bus types are compared by their names.
+#[derive(Clone)]
+pub struct BusType {
+    pub name: String,
+    pub args: Vec<Expression>,
+}
+impl PartialEq for BusType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+impl Eq for BusType {}
+impl PartialOrd for BusType {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for BusType {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
 #[derive(Clone, PartialEq, Ord, PartialOrd, Eq)]
 pub enum VariableType {
     Var,
     Signal(SignalType, TagList),
+    // Only found in the parsed files, the parser flattens the buses into their signals
+    Bus(BusType, SignalType, TagList),
     Component,
     AnonymousComponent,
 }
@@ -0,0 +1,441 @@
+// Traversals of the AST. Every visit_ method walks the children of the node by default,
+// so a visitor only overrides the methods of the nodes it is interested in and calls the
+// corresponding walk_ function when it also wants to reach the nested nodes.
+use super::ast::*;
+
+pub trait Visitor<'ast> {
+    fn visit_ast(&mut self, ast: &'ast AST) {
+        walk_ast(self, ast)
+    }
+    fn visit_definition(&mut self, definition: &'ast Definition) {
+        walk_definition(self, definition)
+    }
+    fn visit_statement(&mut self, statement: &'ast Statement) {
+        walk_statement(self, statement)
+    }
+    fn visit_expression(&mut self, expression: &'ast Expression) {
+        walk_expression(self, expression)
+    }
+    fn visit_variable_type(&mut self, xtype: &'ast VariableType) {
+        walk_variable_type(self, xtype)
+    }
+    fn visit_access(&mut self, access: &'ast Access) {
+        walk_access(self, access)
+    }
+    fn visit_log_argument(&mut self, argument: &'ast LogArgument) {
+        walk_log_argument(self, argument)
+    }
+}
+
+pub fn walk_ast<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, ast: &'ast AST) {
+    for definition in &ast.definitions {
+        visitor.visit_definition(definition);
+    }
+    if let Some((_, call)) = &ast.main_component {
+        visitor.visit_expression(call);
+    }
+}
+
+pub fn walk_definition<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    definition: &'ast Definition,
+) {
+    use Definition::*;
+    match definition {
+        Template { body, .. } | Function { body, .. } | Bus { body, .. } => {
+            visitor.visit_statement(body)
+        }
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    statement: &'ast Statement,
+) {
+    use Statement::*;
+    match statement {
+        IfThenElse {
+            cond,
+            if_case,
+            else_case,
+            ..
+        } => {
+            visitor.visit_expression(cond);
+            visitor.visit_statement(if_case);
+            if let Some(else_case) = else_case {
+                visitor.visit_statement(else_case);
+            }
+        }
+        While { cond, stmt, .. } => {
+            visitor.visit_expression(cond);
+            visitor.visit_statement(stmt);
+        }
+        Return { value, .. } => visitor.visit_expression(value),
+        InitializationBlock {
+            xtype,
+            initializations,
+            ..
+        } => {
+            visitor.visit_variable_type(xtype);
+            for initialization in initializations {
+                visitor.visit_statement(initialization);
+            }
+        }
+        Declaration {
+            xtype, dimensions, ..
+        } => {
+            visitor.visit_variable_type(xtype);
+            for dimension in dimensions {
+                visitor.visit_expression(dimension);
+            }
+        }
+        Substitution { access, rhe, .. } => {
+            for acc in access {
+                visitor.visit_access(acc);
+            }
+            visitor.visit_expression(rhe);
+        }
+        MultSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+            visitor.visit_expression(lhe);
+            visitor.visit_expression(rhe);
+        }
+        UnderscoreSubstitution { rhe, .. } => visitor.visit_expression(rhe),
+        LogCall { args, .. } => {
+            for argument in args {
+                visitor.visit_log_argument(argument);
+            }
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                visitor.visit_statement(stmt);
+            }
+        }
+        Assert { arg, .. } => visitor.visit_expression(arg),
+    }
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    expression: &'ast Expression,
+) {
+    use Expression::*;
+    match expression {
+        InfixOp { lhe, rhe, .. } => {
+            visitor.visit_expression(lhe);
+            visitor.visit_expression(rhe);
+        }
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => visitor.visit_expression(rhe),
+        InlineSwitchOp {
+            cond,
+            if_true,
+            if_false,
+            ..
+        } => {
+            visitor.visit_expression(cond);
+            visitor.visit_expression(if_true);
+            visitor.visit_expression(if_false);
+        }
+        Variable { access, .. } => {
+            for acc in access {
+                visitor.visit_access(acc);
+            }
+        }
+        Number(..) => {}
+        Call { args, .. } => {
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        AnonymousComp {
+            params, signals, ..
+        } => {
+            for expression in params.iter().chain(signals.iter()) {
+                visitor.visit_expression(expression);
+            }
+        }
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
+            for value in values {
+                visitor.visit_expression(value);
+            }
+        }
+        UniformArray {
+            value, dimension, ..
+        } => {
+            visitor.visit_expression(value);
+            visitor.visit_expression(dimension);
+        }
+    }
+}
+
+pub fn walk_variable_type<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    xtype: &'ast VariableType,
+) {
+    if let VariableType::Bus(bus_type, ..) = xtype {
+        for arg in &bus_type.args {
+            visitor.visit_expression(arg);
+        }
+    }
+}
+
+pub fn walk_access<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, access: &'ast Access) {
+    if let Access::ArrayAccess(index) = access {
+        visitor.visit_expression(index);
+    }
+}
+
+pub fn walk_log_argument<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    argument: &'ast LogArgument,
+) {
+    if let LogArgument::LogExp(expression) = argument {
+        visitor.visit_expression(expression);
+    }
+}
+
+// Same traversal as Visitor, but the nodes can be modified or replaced
+pub trait VisitorMut {
+    fn visit_ast_mut(&mut self, ast: &mut AST) {
+        walk_ast_mut(self, ast)
+    }
+    fn visit_definition_mut(&mut self, definition: &mut Definition) {
+        walk_definition_mut(self, definition)
+    }
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+    fn visit_variable_type_mut(&mut self, xtype: &mut VariableType) {
+        walk_variable_type_mut(self, xtype)
+    }
+    fn visit_access_mut(&mut self, access: &mut Access) {
+        walk_access_mut(self, access)
+    }
+    fn visit_log_argument_mut(&mut self, argument: &mut LogArgument) {
+        walk_log_argument_mut(self, argument)
+    }
+}
+
+pub fn walk_ast_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut AST) {
+    for definition in &mut ast.definitions {
+        visitor.visit_definition_mut(definition);
+    }
+    if let Some((_, call)) = &mut ast.main_component {
+        visitor.visit_expression_mut(call);
+    }
+}
+
+pub fn walk_definition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, definition: &mut Definition) {
+    use Definition::*;
+    match definition {
+        Template { body, .. } | Function { body, .. } | Bus { body, .. } => {
+            visitor.visit_statement_mut(body)
+        }
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    use Statement::*;
+    match statement {
+        IfThenElse {
+            cond,
+            if_case,
+            else_case,
+            ..
+        } => {
+            visitor.visit_expression_mut(cond);
+            visitor.visit_statement_mut(if_case);
+            if let Some(else_case) = else_case {
+                visitor.visit_statement_mut(else_case);
+            }
+        }
+        While { cond, stmt, .. } => {
+            visitor.visit_expression_mut(cond);
+            visitor.visit_statement_mut(stmt);
+        }
+        Return { value, .. } => visitor.visit_expression_mut(value),
+        InitializationBlock {
+            xtype,
+            initializations,
+            ..
+        } => {
+            visitor.visit_variable_type_mut(xtype);
+            for initialization in initializations {
+                visitor.visit_statement_mut(initialization);
+            }
+        }
+        Declaration {
+            xtype, dimensions, ..
+        } => {
+            visitor.visit_variable_type_mut(xtype);
+            for dimension in dimensions {
+                visitor.visit_expression_mut(dimension);
+            }
+        }
+        Substitution { access, rhe, .. } => {
+            for acc in access {
+                visitor.visit_access_mut(acc);
+            }
+            visitor.visit_expression_mut(rhe);
+        }
+        MultSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+            visitor.visit_expression_mut(lhe);
+            visitor.visit_expression_mut(rhe);
+        }
+        UnderscoreSubstitution { rhe, .. } => visitor.visit_expression_mut(rhe),
+        LogCall { args, .. } => {
+            for argument in args {
+                visitor.visit_log_argument_mut(argument);
+            }
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                visitor.visit_statement_mut(stmt);
+            }
+        }
+        Assert { arg, .. } => visitor.visit_expression_mut(arg),
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    use Expression::*;
+    match expression {
+        InfixOp { lhe, rhe, .. } => {
+            visitor.visit_expression_mut(lhe);
+            visitor.visit_expression_mut(rhe);
+        }
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => visitor.visit_expression_mut(rhe),
+        InlineSwitchOp {
+            cond,
+            if_true,
+            if_false,
+            ..
+        } => {
+            visitor.visit_expression_mut(cond);
+            visitor.visit_expression_mut(if_true);
+            visitor.visit_expression_mut(if_false);
+        }
+        Variable { access, .. } => {
+            for acc in access {
+                visitor.visit_access_mut(acc);
+            }
+        }
+        Number(..) => {}
+        Call { args, .. } => {
+            for arg in args {
+                visitor.visit_expression_mut(arg);
+            }
+        }
+        AnonymousComp {
+            params, signals, ..
+        } => {
+            for expression in params.iter_mut().chain(signals.iter_mut()) {
+                visitor.visit_expression_mut(expression);
+            }
+        }
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
+            for value in values {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        UniformArray {
+            value, dimension, ..
+        } => {
+            visitor.visit_expression_mut(value);
+            visitor.visit_expression_mut(dimension);
+        }
+    }
+}
+
+pub fn walk_variable_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, xtype: &mut VariableType) {
+    if let VariableType::Bus(bus_type, ..) = xtype {
+        for arg in &mut bus_type.args {
+            visitor.visit_expression_mut(arg);
+        }
+    }
+}
+
+pub fn walk_access_mut<V: VisitorMut + ?Sized>(visitor: &mut V, access: &mut Access) {
+    if let Access::ArrayAccess(index) = access {
+        visitor.visit_expression_mut(index);
+    }
+}
+
+pub fn walk_log_argument_mut<V: VisitorMut + ?Sized>(visitor: &mut V, argument: &mut LogArgument) {
+    if let LogArgument::LogExp(expression) = argument {
+        visitor.visit_expression_mut(expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::expression_builders::*;
+    use super::super::statement_builders::*;
+    use super::*;
+
+    struct UsedNames<'ast> {
+        names: Vec<&'ast str>,
+    }
+
+    impl<'ast> Visitor<'ast> for UsedNames<'ast> {
+        fn visit_expression(&mut self, expression: &'ast Expression) {
+            if let Expression::Variable { name, .. } = expression {
+                self.names.push(name);
+            }
+            walk_expression(self, expression);
+        }
+    }
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_expression_mut(&mut self, expression: &mut Expression) {
+            if let Expression::Variable { name, .. } = expression {
+                name.push('_');
+            }
+            walk_expression_mut(self, expression);
+        }
+    }
+
+    fn variable(name: &str, access: Vec<Access>) -> Expression {
+        build_variable(Meta::new(0, 0), name.to_string(), access)
+    }
+
+    // if (a) { x[i] = b + c; } else { log(d); }
+    fn body() -> Statement {
+        let meta = Meta::new(0, 0);
+        let sum = build_infix(
+            meta.clone(),
+            variable("b", Vec::new()),
+            ExpressionInfixOpcode::Add,
+            variable("c", Vec::new()),
+        );
+        let substitution = build_substitution(
+            meta.clone(),
+            "x".to_string(),
+            vec![build_array_access(variable("i", Vec::new()))],
+            AssignOp::AssignVar,
+            sum,
+        );
+        let log = build_log_call(
+            meta.clone(),
+            vec![build_log_expression(variable("d", Vec::new()))],
+        );
+        build_conditional_block(meta, variable("a", Vec::new()), substitution, Some(log))
+    }
+
+    #[test]
+    fn visitors_reach_nested_expressions() {
+        let mut body = body();
+        let mut used = UsedNames { names: Vec::new() };
+        used.visit_statement(&body);
+        assert_eq!(used.names, vec!["a", "i", "b", "c", "d"]);
+
+        Rename.visit_statement_mut(&mut body);
+        let mut used = UsedNames { names: Vec::new() };
+        used.visit_statement(&body);
+        assert_eq!(used.names, vec!["a_", "i_", "b_", "c_", "d_"]);
+    }
+}
@@ -0,0 +1,229 @@
+use super::error_definition::Report;
+use super::file_definition::{FileID, FileLibrary};
+use codespan_reporting::diagnostic::{Label, LabelStyle};
+use codespan_reporting::files::Files;
+use json::{object, JsonValue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// Reports are printed for humans by default. In the machine-readable formats they are
+// collected while the compiler runs and written as a single document by emit_diagnostics.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DiagnosticsFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+static FORMAT: AtomicUsize = AtomicUsize::new(0);
+static COLLECTED: Mutex<Vec<JsonValue>> = Mutex::new(Vec::new());
+
+impl DiagnosticsFormat {
+    pub fn from_name(name: &str) -> Option<DiagnosticsFormat> {
+        match name {
+            "human" => Some(DiagnosticsFormat::Human),
+            "json" => Some(DiagnosticsFormat::Json),
+            "sarif" => Some(DiagnosticsFormat::Sarif),
+            _ => None,
+        }
+    }
+}
+
+pub fn set_diagnostics_format(format: DiagnosticsFormat) {
+    let value = match format {
+        DiagnosticsFormat::Human => 0,
+        DiagnosticsFormat::Json => 1,
+        DiagnosticsFormat::Sarif => 2,
+    };
+    FORMAT.store(value, Ordering::SeqCst);
+}
+
+pub fn diagnostics_format() -> DiagnosticsFormat {
+    match FORMAT.load(Ordering::SeqCst) {
+        1 => DiagnosticsFormat::Json,
+        2 => DiagnosticsFormat::Sarif,
+        _ => DiagnosticsFormat::Human,
+    }
+}
+
+pub fn collect_reports(reports: &[Report], file_library: &FileLibrary) {
+    let mut collected = COLLECTED.lock().unwrap();
+    for report in reports {
+        collected.push(report_to_json(report, file_library));
+    }
+}
+
+// Writes the reports collected so far to the standard error in the selected format
+pub fn emit_diagnostics(tool_version: &str) {
+    let diagnostics = std::mem::take(&mut *COLLECTED.lock().unwrap());
+    let document = match diagnostics_format() {
+        DiagnosticsFormat::Human => return,
+        DiagnosticsFormat::Json => JsonValue::Array(diagnostics),
+        DiagnosticsFormat::Sarif => diagnostics_to_sarif(diagnostics, tool_version),
+    };
+    eprintln!("{}", document.pretty(2));
+}
+
+pub fn report_to_json(report: &Report, file_library: &FileLibrary) -> JsonValue {
+    let category = if report.is_error() {
+        "error"
+    } else {
+        "warning"
+    };
+    let mut labels = JsonValue::new_array();
+    for label in report
+        .get_primary()
+        .iter()
+        .chain(report.get_secondary().iter())
+    {
+        labels.push(label_to_json(label, file_library)).unwrap();
+    }
+    let mut notes = JsonValue::new_array();
+    for note in report.get_notes() {
+        notes.push(note.as_str()).unwrap();
+    }
+    object! {
+        "code" => Report::error_code_to_diagnostic_code(report.get_code()),
+        "category" => category,
+        "message" => report.get_message().as_str(),
+        "labels" => labels,
+        "notes" => notes,
+    }
+}
+
+fn label_to_json(label: &Label<FileID>, file_library: &FileLibrary) -> JsonValue {
+    let files = file_library.to_storage();
+    let style = match label.style {
+        LabelStyle::Primary => "primary",
+        LabelStyle::Secondary => "secondary",
+    };
+    let mut value = object! {
+        "style" => style,
+        "message" => label.message.as_str(),
+        "start" => label.range.start,
+        "end" => label.range.end,
+    };
+    // File names are stored quoted by the parser
+    if let Some(name) = files.name(label.file_id) {
+        value["file"] = name.trim_matches('"').into();
+    }
+    if let Some(start) = files.location(label.file_id, label.range.start) {
+        value["line"] = start.line_number.into();
+        value["column"] = start.column_number.into();
+    }
+    if let Some(end) = files.location(label.file_id, label.range.end) {
+        value["end_line"] = end.line_number.into();
+        value["end_column"] = end.column_number.into();
+    }
+    value
+}
+
+fn diagnostics_to_sarif(diagnostics: Vec<JsonValue>, tool_version: &str) -> JsonValue {
+    let mut rules = Vec::new();
+    let mut results = JsonValue::new_array();
+    for diagnostic in diagnostics {
+        let code = diagnostic["code"].as_str().unwrap_or_default().to_string();
+        if !rules.contains(&code) {
+            rules.push(code.clone());
+        }
+        let mut locations = JsonValue::new_array();
+        let mut related_locations = JsonValue::new_array();
+        for label in diagnostic["labels"].members() {
+            if label["file"].is_null() {
+                continue;
+            }
+            let mut region = object! {
+                "byteOffset" => label["start"].clone(),
+                "byteLength" => label["end"].as_usize().unwrap_or(0)
+                    .saturating_sub(label["start"].as_usize().unwrap_or(0)),
+            };
+            if !label["line"].is_null() {
+                region["startLine"] = label["line"].clone();
+                region["startColumn"] = label["column"].clone();
+                region["endLine"] = label["end_line"].clone();
+                region["endColumn"] = label["end_column"].clone();
+            }
+            let mut location = object! {
+                "physicalLocation" => object! {
+                    "artifactLocation" => object! { "uri" => label["file"].clone() },
+                    "region" => region,
+                },
+            };
+            if !label["message"].is_empty() {
+                location["message"] = object! { "text" => label["message"].clone() };
+            }
+            if label["style"] == "primary" {
+                locations.push(location).unwrap();
+            } else {
+                related_locations.push(location).unwrap();
+            }
+        }
+        let mut result = object! {
+            "ruleId" => code,
+            "level" => diagnostic["category"].clone(),
+            "message" => object! { "text" => diagnostic["message"].clone() },
+            "locations" => locations,
+        };
+        if !related_locations.is_empty() {
+            result["relatedLocations"] = related_locations;
+        }
+        if !diagnostic["notes"].is_empty() {
+            result["properties"] = object! { "notes" => diagnostic["notes"].clone() };
+        }
+        results.push(result).unwrap();
+    }
+    let rules: Vec<JsonValue> = rules.into_iter().map(|id| object! { "id" => id }).collect();
+    let driver = object! {
+        "name" => "circom",
+        "version" => tool_version,
+        "informationUri" => "https://github.com/iden3/circom",
+        "rules" => JsonValue::Array(rules),
+    };
+    let run = object! {
+        "tool" => object! { "driver" => driver },
+        "results" => results,
+    };
+    object! {
+        "$schema" => "https://json.schemastore.org/sarif-2.1.0.json",
+        "version" => "2.1.0",
+        "runs" => JsonValue::Array(vec![run]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_code::ReportCode;
+
+    #[test]
+    fn report_with_location() {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(
+            "\"main.circom\"".to_string(),
+            "pragma circom 2.0.0;\nsignal x;\n".to_string(),
+        );
+        let mut report = Report::error("Unexpected signal".to_string(), ReportCode::MultipleMain);
+        report.add_primary(21..30, file_id, "found here".to_string());
+        report.add_note("a note".to_string());
+        let value = report_to_json(&report, &file_library);
+        assert_eq!(value["code"], "P1002");
+        assert_eq!(value["category"], "error");
+        assert_eq!(value["labels"][0]["file"], "main.circom");
+        assert_eq!(value["labels"][0]["line"], 2);
+        assert_eq!(value["labels"][0]["column"], 1);
+        assert_eq!(value["labels"][0]["end_column"], 10);
+        assert_eq!(value["notes"][0], "a note");
+
+        let sarif = diagnostics_to_sarif(vec![value], "2.1.6");
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "P1002");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            2
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "P1002"
+        );
+    }
+}
@@ -76,6 +76,9 @@ pub enum ReportCode {
     NotAllowedOperation,
     ConstraintGeneratorInFunction,
     WrongSignalTags,
+    TagAssignmentToInput,
+    TagAssignmentAfterInit,
+    TagAssignedTwice,
     InvalidPartialArray,
     MustBeSingleArithmetic(usize),
     MustBeSingleArithmeticT,
@@ -94,8 +97,22 @@ pub enum ReportCode {
     UnconstrainedIOSignal,
     UnusedInput,
     UnusedOutput,
+    UnderconstrainedOutput,
+    // Lint codes
+    SignalAssignmentWithoutConstraint,
+    DivisionInSignalExpression,
+    UnusedTemplateParameter,
 
     ErrorWat2Wasm,
+    ErrorProducingArtifacts,
+    // Witness calculation codes
+    WitnessInputError,
+    WitnessAssertFailed,
+    WitnessRuntimeError,
+    // Constraint checking codes
+    ConstraintCheckFileError,
+    UnsatisfiedConstraint,
+    InvalidCustomGateApplication,
     CustomGateIntermediateSignalWarning,
     CustomGateConstraintError,
     CustomGateSubComponentError,
@@ -104,6 +121,8 @@ pub enum ReportCode {
     AnonymousCompError,
     UnderscoreWithNoSignalWarning,
     TupleError,
+    InvalidBusDefinition,
+    InvalidBusUse,
     InvalidSignalTagAccess,
     UninitializedComponent,
 }
@@ -190,6 +209,9 @@ impl fmt::Display for ReportCode {
             UnreachableTags => "T2049",
             UnreachableSignals => "T2050",
             MainComponentWithTags => "T2051",
+            TagAssignmentToInput => "T2052",
+            TagAssignmentAfterInit => "T2053",
+            TagAssignedTwice => "T2054",
             RuntimeError => "T3001",
             RuntimeWarning => "T3002",
             UnknownDimension => "T20460",
@@ -204,7 +226,18 @@ impl fmt::Display for ReportCode {
             UnconstrainedIOSignal => "CA02",
             UnusedInput => "CA03",
             UnusedOutput => "CA04",
+            UnderconstrainedOutput => "CA05",
+            SignalAssignmentWithoutConstraint => "L01",
+            DivisionInSignalExpression => "L02",
+            UnusedTemplateParameter => "L03",
             ErrorWat2Wasm => "W01",
+            ErrorProducingArtifacts => "W02",
+            WitnessInputError => "WT01",
+            WitnessAssertFailed => "WT02",
+            WitnessRuntimeError => "WT03",
+            ConstraintCheckFileError => "WT04",
+            UnsatisfiedConstraint => "WT05",
+            InvalidCustomGateApplication => "WT06",
             CustomGateIntermediateSignalWarning => "CG01",
             CustomGateConstraintError => "CG02",
             CustomGateSubComponentError => "CG03",
@@ -212,6 +245,8 @@ impl fmt::Display for ReportCode {
             CustomGatesVersionError => "CG05",
             AnonymousCompError => "TAC01",
             TupleError => "TAC02",
+            InvalidBusDefinition => "BUS01",
+            InvalidBusUse => "BUS02",
             UnderscoreWithNoSignalWarning => "TAC03",
         };
         f.write_str(string_format)
@@ -51,7 +51,12 @@ impl Report {
         }
     }
     pub fn print_reports(reports: &[Report], file_library: &FileLibrary) {
+        use super::diagnostics_writer::{self, DiagnosticsFormat};
         use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+        if diagnostics_writer::diagnostics_format() != DiagnosticsFormat::Human {
+            diagnostics_writer::collect_reports(reports, file_library);
+            return;
+        }
         let writer = StandardStream::stderr(ColorChoice::Always);
         let config = term::Config::default();
         let mut diagnostics = Vec::new();
@@ -125,6 +130,10 @@ impl Report {
     pub fn is_warning(&self) -> bool {
         self.get_category().is_warning()
     }
+    pub fn set_category(&mut self, category: MessageCategory) -> &mut Self {
+        self.category = category;
+        self
+    }
     pub fn get_category(&self) -> &MessageCategory {
         &self.category
     }
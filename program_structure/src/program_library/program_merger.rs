@@ -76,6 +76,8 @@ impl Merger {
                         (Option::None, meta)
                     }
                 }
+                // The parser replaces the buses by their signals before building the archive
+                Definition::Bus { .. } => unreachable!(),
             };
             if let Option::Some(definition_name) = name {
                 let mut report = Report::error(
@@ -1,4 +1,5 @@
 use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
 
 const P_BN128: &str =
     "21888242871839275222246405745257275088548364400416034343698204186575808495617";
@@ -24,25 +25,11 @@ impl Clone for UsefulConstants {
 }
 
 impl UsefulConstants {
+    // The prime is either the name of a curve or a decimal or hexadecimal number,
+    // already checked with is_valid_prime
     pub fn new(possible_prime: &String) -> UsefulConstants {
-        let prime_to_use = if possible_prime.eq("bn128") {
-            P_BN128
-        } else if possible_prime.eq("bls12381") {
-            P_BLS12381
-        } else if possible_prime.eq("goldilocks") {
-            P_GOLDILOCKS
-        } else if possible_prime.eq("grumpkin") {
-            P_GRUMPKIN
-        } else if possible_prime.eq("pallas") {
-            P_PALLAS
-        } else if possible_prime.eq("vesta") {
-            P_VESTA
-        } else {
-            unreachable!()
-        };
-
         UsefulConstants {
-            p: BigInt::parse_bytes(prime_to_use.as_bytes(), 10).expect("can not parse p"),
+            p: parse_prime(possible_prime).expect("can not parse p"),
         }
     }
 
@@ -50,3 +37,108 @@ impl UsefulConstants {
         &self.p
     }
 }
+
+fn named_prime(name: &str) -> Option<&'static str> {
+    match name {
+        "bn128" => Some(P_BN128),
+        "bls12381" => Some(P_BLS12381),
+        "goldilocks" => Some(P_GOLDILOCKS),
+        "grumpkin" => Some(P_GRUMPKIN),
+        "pallas" => Some(P_PALLAS),
+        "vesta" => Some(P_VESTA),
+        _ => None,
+    }
+}
+
+// Value of the prime given by its curve name or as a decimal or hexadecimal (0x) number
+pub fn parse_prime(possible_prime: &str) -> Option<BigInt> {
+    if let Some(prime) = named_prime(possible_prime) {
+        return BigInt::parse_bytes(prime.as_bytes(), 10);
+    }
+    let literal = possible_prime.trim();
+    let number = if let Some(hexa) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        BigInt::parse_bytes(hexa.as_bytes(), 16)
+    } else if !literal.is_empty() && literal.bytes().all(|c| c.is_ascii_digit()) {
+        BigInt::parse_bytes(literal.as_bytes(), 10)
+    } else {
+        None
+    };
+    number.filter(|n| n.is_positive())
+}
+
+// The fields need an odd prime: the witness calculators use Montgomery multiplication
+pub fn is_valid_prime(possible_prime: &str) -> bool {
+    match parse_prime(possible_prime) {
+        Some(p) => p > BigInt::from(2) && is_probable_prime(&p),
+        None => false,
+    }
+}
+
+// Miller-Rabin test with the first prime numbers as bases, which is deterministic
+// for numbers below 3.3 * 10^24
+fn is_probable_prime(n: &BigInt) -> bool {
+    const BASES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+    let one = BigInt::one();
+    let two = BigInt::from(2);
+    for base in BASES.iter() {
+        let base = BigInt::from(*base);
+        if *n == base {
+            return true;
+        }
+        if (n % &base).is_zero() {
+            return false;
+        }
+    }
+    let n_minus_one = n - &one;
+    let mut d = n_minus_one.clone();
+    let mut s = 0;
+    while (&d % &two).is_zero() {
+        d /= &two;
+        s += 1;
+    }
+    'bases: for base in BASES.iter() {
+        let mut x = BigInt::from(*base).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primes_by_name_and_literal() {
+        assert_eq!(
+            parse_prime("goldilocks"),
+            parse_prime("18446744069414584321")
+        );
+        assert_eq!(parse_prime("0x7fffffff"), Some(BigInt::from(2147483647)));
+        assert_eq!(parse_prime("0x"), None);
+        assert_eq!(parse_prime("-7"), None);
+        assert_eq!(parse_prime("secp256k1"), None);
+        // BabyBear, Mersenne-31 and the scalar field of secp256k1
+        assert!(is_valid_prime("2013265921"));
+        assert!(is_valid_prime("2147483647"));
+        assert!(is_valid_prime(
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+        ));
+        assert!(is_valid_prime("bls12381"));
+        assert!(!is_valid_prime("2"));
+        assert!(!is_valid_prime("2013265923"));
+        // Carmichael number
+        assert!(!is_valid_prime("561"));
+    }
+}
@@ -197,6 +197,18 @@ impl<C: Clone> MemorySlice<C> {
             number_inserts: 0,
         }
     }
+    // Rebuilds a slice that compares equal to the one the values were taken from
+    pub fn new_array_with_inserts(
+        route: Vec<SliceCapacity>,
+        values: Vec<C>,
+        number_inserts: usize,
+    ) -> MemorySlice<C> {
+        MemorySlice {
+            route,
+            values,
+            number_inserts,
+        }
+    }
     pub fn new_with_route(route: &[SliceCapacity], initial_value: &C) -> MemorySlice<C> {
         let mut length = 1;
         for i in route {
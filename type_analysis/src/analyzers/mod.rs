@@ -4,6 +4,7 @@ pub use functions_free_of_template_elements::free_of_template_elements;
 pub use no_returns_in_template::free_of_returns;
 pub use signal_declaration_analysis::check_signal_correctness;
 pub use symbol_analysis::check_naming_correctness;
+pub use tag_analysis::tag_analysis;
 pub use type_check::type_check;
 pub use unknown_known_analysis::unknown_known_analysis;
 
@@ -13,6 +14,7 @@ pub mod functions_free_of_template_elements;
 pub mod no_returns_in_template;
 pub mod signal_declaration_analysis;
 pub mod symbol_analysis;
+pub mod tag_analysis;
 pub mod type_check;
 pub mod type_given_function;
 pub mod type_register;
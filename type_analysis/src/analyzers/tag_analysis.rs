@@ -1,238 +1,557 @@
-// use program_structure::ast::{
-//     Access, AssignOp, Expression, ExpressionPrefixOpcode, Meta, SignalElementType, SignalType,
-//     Statement, VariableType,
-// };
-// use program_structure::environment::CircomEnvironment;
-// use program_structure::error_code::ReportCode;
-// use program_structure::error_definition::{Report, ReportCollection};
-// use program_structure::file_definition::{generate_file_location, FileID};
-// use program_structure::program_archive::ProgramArchive;
-// use program_structure::template_data::TemplateData;
-// use std::collections::HashMap;
-
-// type TemplateInfo = HashMap<String, TemplateData>;
-// type Environment = CircomEnvironment<Option<String>, SignalElementType, SignalElementType>;
-// enum ExpressionResult {
-//     ArithmeticExpression(SignalElementType),
-//     Template(Option<String>),
-// }
-
-// pub fn tag_analysis(
-//     template_name: &str,
-//     program_archive: &ProgramArchive,
-// ) -> Result<(), ReportCollection> {
-//     let template_body = program_archive.get_template_data(template_name).get_body_as_vec();
-//     let file_id = program_archive.get_template_data(template_name).get_file_id();
-//     let template_info = program_archive.get_templates();
-
-//     let mut environment = Environment::new();
-//     let args = program_archive.get_template_data(template_name).get_name_of_params().clone();
-//     for arg in args.iter() {
-//         environment.add_variable(arg, SignalElementType::FieldElement);
-//     }
-
-//     let mut reports = ReportCollection::new();
-//     treat_sequence_of_statements(
-//         template_body,
-//         file_id,
-//         template_info,
-//         &mut reports,
-//         &mut environment,
-//     );
-//     if reports.is_empty() {
-//         Result::Ok(())
-//     } else {
-//         Result::Err(reports)
-//     }
-// }
-
-// fn statement_inspection(
-//     stmt: &Statement,
-//     file_id: FileID,
-//     template_info: &TemplateInfo,
-//     reports: &mut ReportCollection,
-//     environment: &mut Environment,
-// ) {
-//     use Statement::*;
-//     match stmt {
-//         IfThenElse { if_case, else_case, .. } => {
-//             statement_inspection(if_case, file_id, template_info, reports, environment);
-//             if let Option::Some(else_stmt) = else_case {
-//                 statement_inspection(else_stmt, file_id, template_info, reports, environment);
-//             }
-//         }
-//         While { stmt, .. } => {
-//             statement_inspection(stmt, file_id, template_info, reports, environment);
-//         }
-//         Block { stmts, .. } => {
-//             environment.add_variable_block();
-//             treat_sequence_of_statements(stmts, file_id, template_info, reports, environment);
-//             environment.remove_variable_block();
-//         }
-//         InitializationBlock { initializations, .. } => {
-//             treat_sequence_of_statements(
-//                 initializations,
-//                 file_id,
-//                 template_info,
-//                 reports,
-//                 environment,
-//             );
-//         }
-//         Declaration { xtype, name, meta, .. } => {
-//             use SignalType::*;
-//             use VariableType::*;
-//             match xtype {
-//                 Var => environment.add_variable(name, SignalElementType::FieldElement),
-//                 Component => environment.add_component(name, meta.component_inference.clone()),
-//                 Signal(signal_type, signal_element) => match signal_type {
-//                     Output => environment.add_output(name, *signal_element),
-//                     Intermediate => environment.add_intermediate(name, *signal_element),
-//                     Input => environment.add_input(name, *signal_element),
-//                 },
-//             }
-//         }
-//         Substitution { meta, var, access, rhe, op, .. } => {
-//             use ExpressionResult::*;
-//             let var_info = variable_inspection(var, access, environment, template_info);
-//             let rhe_info = expression_inspection(rhe, template_info, reports, environment);
-//             match (var_info, rhe_info) {
-//                 (Template(_), Template(assign)) => {
-//                     *environment.get_mut_component_or_break(var, file!(), line!()) = assign;
-//                 }
-//                 (ArithmeticExpression(tag_0), ArithmeticExpression(tag_1))
-//                     if tag_0 == SignalElementType::Binary
-//                         && tag_1 == SignalElementType::FieldElement
-//                         && *op == AssignOp::AssignConstraintSignal =>
-//                 {
-//                     add_report(ReportCode::WrongSignalTags, meta, file_id, reports)
-//                 }
-//                 _ => {}
-//             }
-//         }
-//         _ => {}
-//     }
-// }
-
-      
-// fn expression_inspection(
-//     expr: &Expression,
-//     template_info: &TemplateInfo,
-//     reports: &mut ReportCollection,
-//     environment: &Environment,
-// ) -> ExpressionResult {
-//     use Expression::*;
-//     match expr {
-//         InfixOp { .. } => ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement),
-//         PrefixOp { rhe, prefix_op, .. } => {
-//             let rhe_info = expression_inspection(rhe, template_info, reports, environment);
-//             match prefix_op {
-//                 ExpressionPrefixOpcode::BoolNot => rhe_info,
-//                 _ => ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement),
-//             }
-//         }
-//         InlineSwitchOp { if_true, if_false, .. } => {
-//             let if_true_info = expression_inspection(if_true, template_info, reports, environment);
-//             let if_false_info =
-//                 expression_inspection(if_false, template_info, reports, environment);
-//             match (&if_true_info, &if_false_info) {
-//                 (
-//                     ExpressionResult::ArithmeticExpression(tag_0),
-//                     ExpressionResult::ArithmeticExpression(tag_1),
-//                 ) => {
-//                     if let SignalElementType::FieldElement = tag_0 {
-//                         if_true_info
-//                     } else if let SignalElementType::FieldElement = tag_1 {
-//                         if_false_info
-//                     } else {
-//                         if_true_info
-//                     }
-//                 }
-//                 _ => if_true_info,
-//             }
-//         }
-//         Variable { name, access, .. } => {
-//             variable_inspection(name, access, environment, template_info)
-//         }
-//         Number(..) => ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement),
-//         Call { id, .. } => {
-//             if template_info.contains_key(id) {
-//                 ExpressionResult::Template(Option::Some(id.clone()))
-//             } else {
-//                 ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement)
-//             }
-//         }
-//         ArrayInLine { .. } => {
-//             ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement)
-//         }
-//         UniformArray { .. } => {
-//             ExpressionResult::ArithmeticExpression(SignalElementType::FieldElement)
-//         }
-//         _ => {unreachable!("Anonymous calls should not be reachable at this point."); }
-//     }
-// }
-
-// //************************************************* Statement support *************************************************
-// fn treat_sequence_of_statements(
-//     stmts: &[Statement],
-//     file_id: FileID,
-//     template_info: &TemplateInfo,
-//     reports: &mut ReportCollection,
-//     environment: &mut Environment,
-// ) {
-//     for stmt in stmts {
-//         statement_inspection(stmt, file_id, template_info, reports, environment);
-//     }
-// }
-// //************************************************* Expression support *************************************************
-
-// fn variable_inspection(
-//     symbol: &str,
-//     accesses: &[Access],
-//     environment: &Environment,
-//     template_info: &TemplateInfo,
-// ) -> ExpressionResult {
-//     use ExpressionResult::*;
-//     let mut result = if environment.has_component(symbol) {
-//         Template(environment.get_component_or_break(symbol, file!(), line!()).clone())
-//     } else if environment.has_signal(symbol) {
-//         ArithmeticExpression(*environment.get_signal_or_break(symbol, file!(), line!()))
-//     } else {
-//         ArithmeticExpression(SignalElementType::FieldElement)
-//     };
-
-//     for access in accesses {
-//         if let Access::ComponentAccess(signal) = access {
-//             let template =
-//                 environment.get_component_or_break(symbol, file!(), line!()).clone().unwrap();
-//             let input = template_info.get(&template).unwrap().get_input_info(signal);
-//             let output = template_info.get(&template).unwrap().get_output_info(signal);
-//             match (input, output) {
-//                 (Some((_, tag)), _) | (_, Some((_, tag))) => {
-//                     result = ArithmeticExpression(*tag);
-//                 }
-//                 _ => {
-//                     unreachable!()
-//                 }
-//             }
-//         }
-//     }
-//     result
-// }
-
-// //************************************************* Report support *************************************************
-// fn add_report(
-//     error_code: ReportCode,
-//     meta: &Meta,
-//     file_id: FileID,
-//     reports: &mut ReportCollection,
-// ) {
-//     use ReportCode::*;
-//     let mut report = Report::error("Typing error found".to_string(), error_code);
-//     let location = generate_file_location(meta.start, meta.end);
-//     let message = match error_code {
-//         WrongSignalTags => "Can not assign Field values to signals tagged as binary".to_string(),
-//         _ => panic!("Unimplemented error code"),
-//     };
-//     report.add_primary(location, file_id, message);
-//     reports.push(report);
-// }
+use program_structure::ast::{
+    Access, AssignOp, Expression, Meta, SignalType, Statement, VariableType,
+};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::{generate_file_location, FileID};
+use program_structure::program_archive::ProgramArchive;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+// Tags that the value of an expression may carry. The inputs of a template only keep the
+// tags of their declaration, and the parent must give a value with all of them.
+#[derive(Clone, PartialEq, Eq)]
+enum PossibleTags {
+    Any,
+    Some(BTreeSet<String>),
+}
+
+impl PossibleTags {
+    fn none() -> PossibleTags {
+        PossibleTags::Some(BTreeSet::new())
+    }
+
+    fn union(self, other: PossibleTags) -> PossibleTags {
+        match (self, other) {
+            (PossibleTags::Some(mut tags), PossibleTags::Some(other)) => {
+                tags.extend(other);
+                PossibleTags::Some(tags)
+            }
+            _ => PossibleTags::Any,
+        }
+    }
+}
+
+struct Symbols {
+    signals: HashMap<String, (SignalType, Vec<String>)>,
+    // Template of each component, when it is known
+    components: HashMap<String, String>,
+    possible_tags: HashMap<String, PossibleTags>,
+}
+
+// Definite information at a point of the template: signals that already received a value
+// and tags that already got one
+#[derive(Clone, Default)]
+struct State {
+    initialized: HashSet<String>,
+    valued_tags: HashSet<(String, String)>,
+}
+
+impl State {
+    fn intersection(self, other: State) -> State {
+        State {
+            initialized: self
+                .initialized
+                .intersection(&other.initialized)
+                .cloned()
+                .collect(),
+            valued_tags: self
+                .valued_tags
+                .intersection(&other.valued_tags)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+pub fn tag_analysis(
+    template_name: &str,
+    program_archive: &ProgramArchive,
+) -> Result<(), ReportCollection> {
+    let template_data = program_archive.get_template_data(template_name);
+    let template_body = template_data.get_body();
+    let file_id = template_data.get_file_id();
+
+    let mut symbols = Symbols {
+        signals: HashMap::new(),
+        components: HashMap::new(),
+        possible_tags: HashMap::new(),
+    };
+    collect_symbols(template_body, program_archive, &mut symbols);
+    infer_possible_tags(template_body, &mut symbols);
+
+    let mut reports = ReportCollection::new();
+    check_tag_assignments(
+        template_body,
+        file_id,
+        &symbols,
+        State::default(),
+        &mut reports,
+    );
+    check_component_inputs(
+        template_body,
+        file_id,
+        program_archive,
+        &symbols,
+        &mut reports,
+    );
+    if reports.is_empty() {
+        Result::Ok(())
+    } else {
+        Result::Err(reports)
+    }
+}
+
+fn collect_symbols(stmt: &Statement, program_archive: &ProgramArchive, symbols: &mut Symbols) {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            collect_symbols(if_case, program_archive, symbols);
+            if let Option::Some(else_stmt) = else_case {
+                collect_symbols(else_stmt, program_archive, symbols);
+            }
+        }
+        While { stmt, .. } => collect_symbols(stmt, program_archive, symbols),
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_symbols(stmt, program_archive, symbols);
+            }
+        }
+        InitializationBlock {
+            initializations, ..
+        } => {
+            for stmt in initializations {
+                collect_symbols(stmt, program_archive, symbols);
+            }
+        }
+        Declaration {
+            xtype, name, meta, ..
+        } => match xtype {
+            VariableType::Signal(signal_type, tags) => {
+                let possible = PossibleTags::Some(tags.iter().cloned().collect());
+                symbols.possible_tags.insert(name.clone(), possible);
+                symbols
+                    .signals
+                    .insert(name.clone(), (*signal_type, tags.clone()));
+            }
+            VariableType::Component | VariableType::AnonymousComponent => {
+                if let Option::Some(template) = &meta.component_inference {
+                    symbols.components.insert(name.clone(), template.clone());
+                }
+            }
+            VariableType::Var => {}
+            // The parser replaces the buses by their signals
+            VariableType::Bus(..) => unreachable!(),
+        },
+        Substitution {
+            var,
+            op: AssignOp::AssignVar,
+            rhe,
+            ..
+        } => {
+            let call = match rhe {
+                Expression::ParallelOp { rhe, .. } => rhe.as_ref(),
+                _ => rhe,
+            };
+            if let Expression::Call { id, .. } = call {
+                if program_archive.contains_template(id) {
+                    symbols.components.insert(var.clone(), id.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Signals that are not inputs inherit the tags of the values assigned to them with <==
+fn infer_possible_tags(body: &Statement, symbols: &mut Symbols) {
+    let mut assignments = Vec::new();
+    collect_constraint_assignments(body, symbols, &mut assignments);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (var, rhe) in &assignments {
+            let current = symbols.possible_tags[*var].clone();
+            let next = current.clone().union(expression_tags(rhe, symbols));
+            if next != current {
+                symbols.possible_tags.insert(var.to_string(), next);
+                changed = true;
+            }
+        }
+    }
+}
+
+fn collect_constraint_assignments<'a>(
+    stmt: &'a Statement,
+    symbols: &Symbols,
+    assignments: &mut Vec<(&'a str, &'a Expression)>,
+) {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            collect_constraint_assignments(if_case, symbols, assignments);
+            if let Option::Some(else_stmt) = else_case {
+                collect_constraint_assignments(else_stmt, symbols, assignments);
+            }
+        }
+        While { stmt, .. } => collect_constraint_assignments(stmt, symbols, assignments),
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_constraint_assignments(stmt, symbols, assignments);
+            }
+        }
+        InitializationBlock {
+            initializations, ..
+        } => {
+            for stmt in initializations {
+                collect_constraint_assignments(stmt, symbols, assignments);
+            }
+        }
+        Substitution {
+            var,
+            access,
+            op: AssignOp::AssignConstraintSignal,
+            rhe,
+            ..
+        } if symbols.signals.contains_key(var) && component_access(access).is_none() => {
+            assignments.push((var, rhe));
+        }
+        _ => {}
+    }
+}
+
+fn expression_tags(expr: &Expression, symbols: &Symbols) -> PossibleTags {
+    use Expression::*;
+    match expr {
+        Variable { name, access, .. } => {
+            let accessed_names = access
+                .iter()
+                .filter(|acc| matches!(acc, Access::ComponentAccess(_)))
+                .count();
+            if symbols.signals.contains_key(name) {
+                // The value of a tag does not carry tags
+                if accessed_names == 0 {
+                    symbols.possible_tags[name].clone()
+                } else {
+                    PossibleTags::none()
+                }
+            } else if symbols.components.contains_key(name) && accessed_names > 1 {
+                PossibleTags::none()
+            } else {
+                // Variables and the outputs of the subcomponents may carry any tag
+                PossibleTags::Any
+            }
+        }
+        InlineSwitchOp {
+            if_true, if_false, ..
+        } => expression_tags(if_true, symbols).union(expression_tags(if_false, symbols)),
+        // The result of the rest of the expressions never carries tags
+        _ => PossibleTags::none(),
+    }
+}
+
+fn component_access(access: &[Access]) -> Option<&String> {
+    access.iter().find_map(|acc| match acc {
+        Access::ComponentAccess(name) => Option::Some(name),
+        Access::ArrayAccess(_) => Option::None,
+    })
+}
+
+fn check_tag_assignments(
+    stmt: &Statement,
+    file_id: FileID,
+    symbols: &Symbols,
+    mut state: State,
+    reports: &mut ReportCollection,
+) -> State {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            let if_state = check_tag_assignments(if_case, file_id, symbols, state.clone(), reports);
+            let else_state = if let Option::Some(else_stmt) = else_case {
+                check_tag_assignments(else_stmt, file_id, symbols, state, reports)
+            } else {
+                state
+            };
+            if_state.intersection(else_state)
+        }
+        While { stmt, .. } => {
+            // The body may not be executed
+            check_tag_assignments(stmt, file_id, symbols, state.clone(), reports);
+            state
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                state = check_tag_assignments(stmt, file_id, symbols, state, reports);
+            }
+            state
+        }
+        InitializationBlock {
+            initializations, ..
+        } => {
+            for stmt in initializations {
+                state = check_tag_assignments(stmt, file_id, symbols, state, reports);
+            }
+            state
+        }
+        Substitution {
+            meta,
+            var,
+            access,
+            op,
+            ..
+        } => {
+            let (signal_type, _) = match symbols.signals.get(var) {
+                Option::Some(signal) => signal,
+                Option::None => return state,
+            };
+            match component_access(access) {
+                Option::Some(tag) => {
+                    let error_code = if *signal_type == SignalType::Input {
+                        Option::Some(ReportCode::TagAssignmentToInput)
+                    } else if state.initialized.contains(var) {
+                        Option::Some(ReportCode::TagAssignmentAfterInit)
+                    } else if !state.valued_tags.insert((var.clone(), tag.clone())) {
+                        Option::Some(ReportCode::TagAssignedTwice)
+                    } else {
+                        Option::None
+                    };
+                    if let Option::Some(error_code) = error_code {
+                        let message = match error_code {
+                            ReportCode::TagAssignmentToInput => format!(
+                                "The tag {} of the input {} gets its value from the parent component",
+                                tag, var
+                            ),
+                            ReportCode::TagAssignmentAfterInit => format!(
+                                "The signal {} already has a value, its tag {} can not be assigned after it",
+                                var, tag
+                            ),
+                            _ => format!("The tag {} of the signal {} already has a value", tag, var),
+                        };
+                        add_report(error_code, meta, file_id, message, reports);
+                    }
+                }
+                Option::None if *op != AssignOp::AssignVar => {
+                    state.initialized.insert(var.clone());
+                }
+                Option::None => {}
+            }
+            state
+        }
+        _ => state,
+    }
+}
+
+fn check_component_inputs(
+    stmt: &Statement,
+    file_id: FileID,
+    program_archive: &ProgramArchive,
+    symbols: &Symbols,
+    reports: &mut ReportCollection,
+) {
+    use Statement::*;
+    match stmt {
+        IfThenElse {
+            if_case, else_case, ..
+        } => {
+            check_component_inputs(if_case, file_id, program_archive, symbols, reports);
+            if let Option::Some(else_stmt) = else_case {
+                check_component_inputs(else_stmt, file_id, program_archive, symbols, reports);
+            }
+        }
+        While { stmt, .. } => {
+            check_component_inputs(stmt, file_id, program_archive, symbols, reports)
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                check_component_inputs(stmt, file_id, program_archive, symbols, reports);
+            }
+        }
+        InitializationBlock {
+            initializations, ..
+        } => {
+            for stmt in initializations {
+                check_component_inputs(stmt, file_id, program_archive, symbols, reports);
+            }
+        }
+        Substitution {
+            meta,
+            var,
+            access,
+            op,
+            rhe,
+        } if *op != AssignOp::AssignVar => {
+            let template = match symbols.components.get(var) {
+                Option::Some(template) if program_archive.contains_template(template) => {
+                    program_archive.get_template_data(template)
+                }
+                _ => return,
+            };
+            let input = match component_access(access) {
+                Option::Some(input) => input,
+                Option::None => return,
+            };
+            let required = match template.get_input_info(input) {
+                Option::Some((_, required)) => required,
+                Option::None => return,
+            };
+            if let PossibleTags::Some(given) = expression_tags(rhe, symbols) {
+                let mut missing: Vec<_> = required
+                    .iter()
+                    .filter(|tag| !given.contains(*tag))
+                    .cloned()
+                    .collect();
+                if !missing.is_empty() {
+                    missing.sort();
+                    let message = format!(
+                        "The input {} of {} requires the tags {}, that the assigned value does not carry",
+                        input,
+                        template.get_name(),
+                        missing.join(", ")
+                    );
+                    add_report(ReportCode::WrongSignalTags, meta, file_id, message, reports);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+//************************************************* Report support *************************************************
+fn add_report(
+    error_code: ReportCode,
+    meta: &Meta,
+    file_id: FileID,
+    message: String,
+    reports: &mut ReportCollection,
+) {
+    let mut report = Report::error("Typing error found".to_string(), error_code);
+    let location = generate_file_location(meta.start, meta.end);
+    report.add_primary(location, file_id, message);
+    reports.push(report);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check_types::check_types;
+    use parser::source_provider::MemoryProvider;
+
+    const TEMPLATES: &str = "pragma circom 2.1.5;
+        template Binary() {
+            signal input {binary} in;
+            signal output out;
+            out <== in;
+        }
+        template Tagged() {
+            signal input {binary} in;
+            component b = Binary();
+            b.in <== in;
+        }
+        ";
+
+    // The codes of the errors found in the templates followed by the main template
+    fn error_codes(main: &str) -> Vec<String> {
+        let source = format!("{}{}\ncomponent main = Main();", TEMPLATES, main);
+        let mut provider = MemoryProvider::new();
+        provider.add_file("main.circom", source);
+        let (mut program, _) = parser::run_parser_with_provider(
+            "main.circom".to_string(),
+            "2.1.6",
+            Vec::new(),
+            &provider,
+        )
+        .ok()
+        .unwrap();
+        match check_types(&mut program) {
+            Result::Ok(_) => Vec::new(),
+            Result::Err(reports) => reports
+                .iter()
+                .map(|report| report.get_code().to_string())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn tagged_inputs() {
+        // the input of Tagged keeps its tag, so it can be given to Binary
+        let main = "template Main() {
+            signal input x;
+            signal {binary} t;
+            t <== x;
+            component c = Tagged();
+            c.in <== t;
+        }";
+        assert!(error_codes(main).is_empty());
+
+        let main = "template Main() {
+            signal input x;
+            component b = Binary();
+            b.in <== x;
+        }";
+        assert_eq!(error_codes(main), vec!["T2040"]);
+
+        let main = "template Main() {
+            signal input x;
+            component b = Binary();
+            b.in <== x * x;
+        }";
+        assert_eq!(error_codes(main), vec!["T2040"]);
+    }
+
+    #[test]
+    fn tag_assignment_to_input() {
+        let main = "template Max() {
+            signal input {max} in;
+            in.max = 3;
+        }
+        template Main() {
+            signal input x;
+            signal {max} t;
+            t.max = 3;
+            t <== x;
+            component m = Max();
+            m.in <== t;
+        }";
+        assert_eq!(error_codes(main), vec!["T2052"]);
+    }
+
+    #[test]
+    fn tag_assignment_after_init() {
+        let main = "template Main() {
+            signal input x;
+            signal output {max} y;
+            y <== x;
+            y.max = 3;
+        }";
+        assert_eq!(error_codes(main), vec!["T2053"]);
+
+        // the signal may not have a value when the tag is assigned
+        let main = "template Main() {
+            signal input x;
+            signal output {max} y;
+            var n = 1;
+            if (n > 0) {
+                y <== x;
+            }
+            y.max = 3;
+        }";
+        assert!(error_codes(main).is_empty());
+    }
+
+    #[test]
+    fn tag_assigned_twice() {
+        let main = "template Main() {
+            signal input x;
+            signal output {max} y;
+            y.max = 3;
+            y.max = 4;
+            y <== x;
+        }";
+        assert_eq!(error_codes(main), vec!["T2054"]);
+    }
+}
@@ -193,6 +193,8 @@ fn type_statement(
                 VariableType::AnonymousComponent => analysis_information
                     .environment
                     .add_component(name, (meta.component_inference.clone(), dimensions.len())),
+                // The parser replaces the buses by their signals
+                VariableType::Bus(..) => unreachable!(),
             }
         }
         Substitution {
@@ -133,6 +133,9 @@ fn semantic_analyses(
         {
             errors.append(&mut unknown_known_report);
         }
+        if let Result::Err(mut tag_report) = tag_analysis(template_name, program_archive) {
+            errors.append(&mut tag_report);
+        }
         if program_archive
             .get_template_data(template_name)
             .is_custom_gate()
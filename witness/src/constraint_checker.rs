@@ -0,0 +1,460 @@
+use super::wtns_reader::{self, WitnessData};
+use circom_algebra::algebra::Constraint;
+use constraint_writers::r1cs_reader::R1CSReader;
+use num_bigint::BigInt;
+use num_traits::Zero;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+type LinearCombination = HashMap<usize, BigInt>;
+
+// Maps every witness position to the first name given to it in the sym file
+fn read_sym(file: &str) -> Result<HashMap<usize, String>, ()> {
+    let contents = std::fs::read_to_string(file).map_err(|_err| {})?;
+    parse_sym(&contents)
+}
+
+fn parse_sym(contents: &str) -> Result<HashMap<usize, String>, ()> {
+    let mut names = HashMap::new();
+    for line in contents.lines().filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.splitn(4, ',').collect();
+        if fields.len() != 4 {
+            return Result::Err(());
+        }
+        let witness = fields[1].parse::<i64>().map_err(|_err| {})?;
+        if witness >= 0 {
+            names
+                .entry(witness as usize)
+                .or_insert_with(|| fields[3].to_string());
+        }
+    }
+    Result::Ok(names)
+}
+
+struct Checker {
+    field: BigInt,
+    witness: Vec<BigInt>,
+    names: HashMap<usize, String>,
+}
+
+impl Checker {
+    fn signal_name(&self, signal: usize) -> String {
+        match self.names.get(&signal) {
+            Some(name) => name.clone(),
+            None if signal == 0 => "1".to_string(),
+            None => format!("w{}", signal),
+        }
+    }
+
+    fn evaluate(&self, linear_combination: &LinearCombination) -> BigInt {
+        let mut result = BigInt::zero();
+        for (signal, coefficient) in linear_combination {
+            result += coefficient * &self.witness[*signal];
+        }
+        result % &self.field
+    }
+
+    fn linear_combination_to_string(&self, linear_combination: &LinearCombination) -> String {
+        let mut terms: Vec<_> = linear_combination.iter().collect();
+        terms.sort();
+        let mut result = String::new();
+        for (signal, coefficient) in terms {
+            // coefficients over p/2 are shown as negative numbers
+            let negative = coefficient * 2 > self.field;
+            let coefficient = if negative {
+                &self.field - coefficient
+            } else {
+                coefficient.clone()
+            };
+            let term = if *signal == 0 {
+                coefficient.to_string()
+            } else if coefficient == BigInt::from(1) {
+                self.signal_name(*signal)
+            } else {
+                format!("{}*{}", coefficient, self.signal_name(*signal))
+            };
+            match (result.is_empty(), negative) {
+                (true, false) => result = term,
+                (true, true) => result = format!("-{}", term),
+                (false, false) => result = format!("{} + {}", result, term),
+                (false, true) => result = format!("{} - {}", result, term),
+            }
+        }
+        if result.is_empty() {
+            "0".to_string()
+        } else {
+            result
+        }
+    }
+
+    fn check_constraint(&self, index: usize, constraint: &Constraint<usize>) -> Option<Report> {
+        let (a, b, c) = (constraint.a(), constraint.b(), constraint.c());
+        let (value_a, value_b, value_c) = (self.evaluate(a), self.evaluate(b), self.evaluate(c));
+        if (&value_a * &value_b - &value_c) % &self.field == BigInt::zero() {
+            return None;
+        }
+        let mut report = Report::error(
+            format!(
+                "Constraint {} is not satisfied: ({}) * ({}) = {}",
+                index,
+                self.linear_combination_to_string(a),
+                self.linear_combination_to_string(b),
+                self.linear_combination_to_string(c)
+            ),
+            ReportCode::UnsatisfiedConstraint,
+        );
+        report.add_note(format!(
+            "The left side evaluates to {} and the right side to {}",
+            (value_a * value_b) % &self.field,
+            value_c
+        ));
+        let mut signals: Vec<usize> = a
+            .iter()
+            .chain(b)
+            .chain(c)
+            .map(|(signal, _)| *signal)
+            .filter(|s| *s != 0)
+            .collect();
+        signals.sort_unstable();
+        signals.dedup();
+        // the name of a signal in the sym file is the name of its component followed by
+        // the name of the signal in the template
+        let mut components: Vec<&str> = signals
+            .iter()
+            .filter_map(|signal| self.names.get(signal))
+            .filter_map(|name| name.rsplit_once('.').map(|(component, _)| component))
+            .collect();
+        components.sort_unstable();
+        components.dedup();
+        for signal in signals {
+            report.add_note(format!(
+                "{} = {}",
+                self.signal_name(signal),
+                self.witness[signal]
+            ));
+        }
+        if !components.is_empty() {
+            report.add_note(format!(
+                "The signals belong to the components {}",
+                components.join(", ")
+            ));
+        }
+        Some(report)
+    }
+}
+
+fn file_error(message: String) -> Report {
+    Report::error(message, ReportCode::ConstraintCheckFileError)
+}
+
+fn r1cs_error(r1cs_file: &str) -> Report {
+    file_error(format!("Could not read the r1cs file {}", r1cs_file))
+}
+
+// Checks that the witness satisfies every constraint and custom gate application of
+// the r1cs file. The sym file, when given, is used to show the names of the signals.
+pub fn check_constraints(
+    r1cs_file: &str,
+    wtns_file: &str,
+    sym_file: Option<&str>,
+) -> Result<ReportCollection, Report> {
+    let r1cs = R1CSReader::new(r1cs_file.to_string()).map_err(|_| r1cs_error(r1cs_file))?;
+    let witness = wtns_reader::read_wtns(wtns_file)
+        .map_err(|_| file_error(format!("Could not read the wtns file {}", wtns_file)))?;
+    let names = match sym_file {
+        Some(sym_file) => read_sym(sym_file)
+            .map_err(|_| file_error(format!("Could not read the sym file {}", sym_file)))?,
+        None => HashMap::new(),
+    };
+    check_witness(r1cs, r1cs_file, witness, names)
+}
+
+// The name of the r1cs file is only used in the errors
+fn check_witness<R: Read + Seek>(
+    r1cs: R1CSReader<R>,
+    r1cs_file: &str,
+    witness: WitnessData,
+    names: HashMap<usize, String>,
+) -> Result<ReportCollection, Report> {
+    let header = r1cs.get_header();
+    let total_wires = header.total_wires;
+    if witness.prime != header.field {
+        return Result::Err(file_error(
+            "The r1cs and the witness are defined over different prime fields".to_string(),
+        ));
+    }
+    if witness.witness.len() != total_wires {
+        return Result::Err(file_error(format!(
+            "The r1cs has {} wires but the witness has {} values",
+            total_wires,
+            witness.witness.len()
+        )));
+    }
+    let checker = Checker {
+        field: header.field.clone(),
+        witness: witness.witness,
+        names,
+    };
+    let has_custom_gates = r1cs.has_custom_gates();
+
+    let mut reports = ReportCollection::new();
+    let mut constraints =
+        R1CSReader::start_constraints_section(r1cs).map_err(|_| r1cs_error(r1cs_file))?;
+    while let Some(constraint) = constraints
+        .read_constraint()
+        .map_err(|_| r1cs_error(r1cs_file))?
+    {
+        let index = constraints.constraints_read() - 1;
+        let mut signals = constraint
+            .a()
+            .keys()
+            .chain(constraint.b().keys())
+            .chain(constraint.c().keys());
+        if signals.any(|signal| *signal >= total_wires) {
+            return Result::Err(file_error(format!(
+                "Constraint {} refers to a wire that does not exist",
+                index
+            )));
+        }
+        if let Some(report) = checker.check_constraint(index, &constraint) {
+            reports.push(report);
+        }
+    }
+    let r1cs = constraints
+        .end_section()
+        .map_err(|_| r1cs_error(r1cs_file))?;
+    if !has_custom_gates {
+        return Result::Ok(reports);
+    }
+
+    // Custom gates are implemented by the proving system, the r1cs only records which
+    // gates are applied and to which signals
+    let mut section =
+        R1CSReader::start_custom_gates_used_section(r1cs).map_err(|_| r1cs_error(r1cs_file))?;
+    let custom_gates_used = section
+        .read_custom_gates_usages()
+        .map_err(|_| r1cs_error(r1cs_file))?;
+    let r1cs = section.end_section().map_err(|_| r1cs_error(r1cs_file))?;
+    let mut section =
+        R1CSReader::start_custom_gates_applied_section(r1cs).map_err(|_| r1cs_error(r1cs_file))?;
+    let custom_gates_applied = section
+        .read_custom_gates_applications()
+        .map_err(|_| r1cs_error(r1cs_file))?;
+    section.end_section().map_err(|_| r1cs_error(r1cs_file))?;
+    for (index, (gate, signals)) in custom_gates_applied.iter().enumerate() {
+        let message = match custom_gates_used.get(*gate) {
+            Some((name, _)) => signals.iter().find(|s| **s >= total_wires).map(|signal| {
+                format!(
+                    "Application {} of the custom gate {} refers to the wire {} that does not exist",
+                    index, name, signal
+                )
+            }),
+            None => Some(format!(
+                "Application {} refers to the custom gate {} that is not declared",
+                index, gate
+            )),
+        };
+        if let Some(message) = message {
+            reports.push(Report::error(
+                message,
+                ReportCode::InvalidCustomGateApplication,
+            ));
+        }
+    }
+    Result::Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wtns_writer::write_wtns_with;
+    use constraint_writers::r1cs_writer::{
+        CustomGatesAppliedData, CustomGatesUsedData, HeaderData, R1CSWriter,
+    };
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    // A directory of the test, removed when the test ends
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(test: &str) -> TestDir {
+            let dir = std::env::temp_dir().join(format!(
+                "constraint_checker_{}_{}",
+                test,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            TestDir(dir)
+        }
+
+        fn path(&self, file: &str) -> String {
+            self.0.join(file).to_str().unwrap().to_string()
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const SYM: &str = "1,1,1,main.c\n2,2,2,main.m.a\n3,3,2,main.m.b\n4,1,2,main.m.c\n";
+
+    fn prime() -> BigInt {
+        BigInt::from(65521)
+    }
+
+    fn linear_combination(terms: &[(usize, i64)]) -> LinearCombination {
+        terms
+            .iter()
+            .map(|(signal, coefficient)| (*signal, BigInt::from(*coefficient)))
+            .collect()
+    }
+
+    fn witness(values: &[i64]) -> Vec<BigInt> {
+        values.iter().map(|value| BigInt::from(*value)).collect()
+    }
+
+    // The constraint w2 * w3 = w1 between main and its subcomponent main.m, and the custom gates when given
+    fn written_r1cs(
+        custom_gates: Option<(CustomGatesUsedData, CustomGatesAppliedData)>,
+    ) -> Vec<u8> {
+        let r1cs =
+            R1CSWriter::from_writer(Cursor::new(Vec::new()), 8, custom_gates.is_some()).unwrap();
+        let mut header = R1CSWriter::start_header_section(r1cs).unwrap();
+        header
+            .write_section(HeaderData {
+                field: prime(),
+                total_wires: 4,
+                public_outputs: 1,
+                public_inputs: 2,
+                private_inputs: 0,
+                number_of_labels: 4,
+                number_of_constraints: 1,
+            })
+            .unwrap();
+        let r1cs = header.end_section().unwrap();
+        let mut constraints = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        constraints
+            .write_constraint_usize(
+                &linear_combination(&[(2, 1)]),
+                &linear_combination(&[(3, 1)]),
+                &linear_combination(&[(1, 1)]),
+            )
+            .unwrap();
+        let r1cs = constraints.end_section().unwrap();
+        let mut signals = R1CSWriter::start_signal_section(r1cs).unwrap();
+        for label in 0..4 {
+            signals.write_signal_usize(label).unwrap();
+        }
+        let mut r1cs = signals.end_section().unwrap();
+        if let Some((used, applied)) = custom_gates {
+            let mut section = R1CSWriter::start_custom_gates_used_section(r1cs).unwrap();
+            section.write_custom_gates_usages(used).unwrap();
+            let next = section.end_section().unwrap();
+            let mut section = R1CSWriter::start_custom_gates_applied_section(next).unwrap();
+            section.write_custom_gates_applications(applied).unwrap();
+            r1cs = section.end_section().unwrap();
+        }
+        R1CSWriter::finish_writing(r1cs).unwrap().into_inner()
+    }
+
+    fn check(r1cs: &[u8], values: &[i64]) -> Result<ReportCollection, String> {
+        let r1cs = R1CSReader::from_reader(Cursor::new(r1cs)).unwrap();
+        let witness = WitnessData {
+            prime: prime(),
+            witness: witness(values),
+        };
+        check_witness(r1cs, "c.r1cs", witness, parse_sym(SYM).unwrap())
+            .map_err(|report| report.get_message().clone())
+    }
+
+    #[test]
+    fn satisfied_witness() {
+        let r1cs = written_r1cs(None);
+        assert!(check(&r1cs, &[1, 12, 3, 4]).ok().unwrap().is_empty());
+        // the values are compared in the field
+        assert!(check(&r1cs, &[1, 65520, 65520, 1]).ok().unwrap().is_empty());
+    }
+
+    #[test]
+    fn tampered_witness() {
+        let reports = check(&written_r1cs(None), &[1, 13, 3, 4]).ok().unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert!(matches!(
+            report.get_code(),
+            ReportCode::UnsatisfiedConstraint
+        ));
+        assert_eq!(
+            report.get_message(),
+            "Constraint 0 is not satisfied: (main.m.a) * (main.m.b) = main.c"
+        );
+        assert_eq!(
+            report.get_notes(),
+            &vec![
+                "The left side evaluates to 12 and the right side to 13".to_string(),
+                "main.c = 13".to_string(),
+                "main.m.a = 3".to_string(),
+                "main.m.b = 4".to_string(),
+                "The signals belong to the components main, main.m".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_gate_applications() {
+        let used = vec![("Gate".to_string(), vec![BigInt::from(1)])];
+        let r1cs = written_r1cs(Some((used.clone(), vec![(0, vec![1, 2, 3])])));
+        assert!(check(&r1cs, &[1, 12, 3, 4]).ok().unwrap().is_empty());
+
+        let r1cs = written_r1cs(Some((used, vec![(0, vec![1, 4]), (1, vec![2])])));
+        let reports = check(&r1cs, &[1, 12, 3, 4]).ok().unwrap();
+        let messages: Vec<&String> = reports.iter().map(|report| report.get_message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Application 0 of the custom gate Gate refers to the wire 4 that does not exist",
+                "Application 1 refers to the custom gate 1 that is not declared",
+            ]
+        );
+        assert!(reports
+            .iter()
+            .all(|report| matches!(report.get_code(), ReportCode::InvalidCustomGateApplication)));
+    }
+
+    #[test]
+    fn invalid_files() {
+        let error = check(&written_r1cs(None), &[1, 12, 3]).err().unwrap();
+        assert_eq!(error, "The r1cs has 4 wires but the witness has 3 values");
+
+        let dir = TestDir::new("invalid");
+        std::fs::write(dir.path("c.r1cs"), written_r1cs(None)).unwrap();
+        std::fs::write(dir.path("c.sym"), SYM).unwrap();
+        let mut wtns = Vec::new();
+        write_wtns_with(&mut wtns, &prime(), &witness(&[1, 12, 3, 4])).unwrap();
+        std::fs::write(dir.path("c.wtns"), &wtns[..wtns.len() - 1]).unwrap();
+        let sym = dir.path("c.sym");
+        let error = check_constraints(&dir.path("c.r1cs"), &dir.path("c.wtns"), Some(&sym))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.get_code(),
+            ReportCode::ConstraintCheckFileError
+        ));
+        assert!(error
+            .get_message()
+            .starts_with("Could not read the wtns file"));
+
+        let error = check_constraints(&dir.path("c.wtns"), &dir.path("c.wtns"), None)
+            .err()
+            .unwrap();
+        assert!(error
+            .get_message()
+            .starts_with("Could not read the r1cs file"));
+    }
+}
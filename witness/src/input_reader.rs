@@ -0,0 +1,94 @@
+use json::JsonValue;
+use num_bigint::BigInt;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::Report;
+use std::collections::BTreeMap;
+
+// Maps the name of every main input signal to its flattened list of values
+pub type InputValues = BTreeMap<String, Vec<BigInt>>;
+
+pub fn read_inputs(file: &str, prime: &BigInt) -> Result<InputValues, Report> {
+    let contents = std::fs::read_to_string(file).map_err(|err| {
+        Report::error(
+            format!("Could not read the input file {}: {}", file, err),
+            ReportCode::WitnessInputError,
+        )
+    })?;
+    parse_inputs(&contents, prime)
+}
+
+pub fn parse_inputs(contents: &str, prime: &BigInt) -> Result<InputValues, Report> {
+    let parsed = json::parse(contents).map_err(|err| {
+        Report::error(
+            format!("The input is not a valid json file: {}", err),
+            ReportCode::WitnessInputError,
+        )
+    })?;
+    if !parsed.is_object() {
+        return Result::Err(Report::error(
+            "The input must be a json object mapping signal names to values".to_string(),
+            ReportCode::WitnessInputError,
+        ));
+    }
+    let mut inputs = InputValues::new();
+    for (name, value) in parsed.entries() {
+        let mut values = Vec::new();
+        flatten_value(name, value, prime, &mut values)?;
+        inputs.insert(name.to_string(), values);
+    }
+    Result::Ok(inputs)
+}
+
+fn flatten_value(
+    name: &str,
+    value: &JsonValue,
+    prime: &BigInt,
+    values: &mut Vec<BigInt>,
+) -> Result<(), Report> {
+    if value.is_array() {
+        for member in value.members() {
+            flatten_value(name, member, prime, values)?;
+        }
+        return Result::Ok(());
+    }
+    let number = if let Some(string) = value.as_str() {
+        parse_number(string)
+    } else if value.is_number() {
+        parse_number(&value.dump())
+    } else {
+        None
+    };
+    match number {
+        Some(number) => {
+            values.push(((number % prime) + prime) % prime);
+            Result::Ok(())
+        }
+        None => Result::Err(Report::error(
+            format!(
+                "Invalid number in json input for signal {}: {}",
+                name,
+                value.dump()
+            ),
+            ReportCode::WitnessInputError,
+        )),
+    }
+}
+
+fn parse_number(string: &str) -> Option<BigInt> {
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, string),
+    };
+    let (radix, digits) = match digits.get(0..2) {
+        Some("0x") | Some("0X") => (16, &digits[2..]),
+        Some("0o") | Some("0O") => (8, &digits[2..]),
+        Some("0b") | Some("0B") => (2, &digits[2..]),
+        _ => (10, digits),
+    };
+    let number = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+    if negative {
+        Some(-number)
+    } else {
+        Some(number)
+    }
+}
@@ -0,0 +1,8 @@
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+pub mod constraint_checker;
+pub mod input_reader;
+pub mod witness_calculator;
+pub mod wtns_reader;
+pub mod wtns_writer;
@@ -0,0 +1,934 @@
+use super::input_reader::InputValues;
+use circom_algebra::modular_arithmetic::{self, ArithmeticError};
+use compiler::circuit_design::circuit::Circuit;
+use compiler::circuit_design::function::FunctionCodeInfo;
+use compiler::intermediate_representation::ir_interface::*;
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::Report;
+use program_structure::utils::constants::UsefulConstants;
+use std::collections::HashMap;
+
+// Runtime information of a created component, mirrors Circom_Component in calcwit.hpp
+#[derive(Default)]
+struct ComponentMemory {
+    template_id: usize,
+    signal_start: usize,
+    input_counter: usize,
+    component_name: String,
+    id_father: usize,
+    subcomponents: Vec<usize>,
+}
+
+// Local state of a running template or function
+struct Frame<'a> {
+    ctx_index: usize,
+    signal_start: usize,
+    name: &'a str,
+    vars: Vec<BigInt>,
+}
+
+enum Value {
+    Address(usize),
+    Field(Vec<BigInt>),
+}
+
+impl Value {
+    // None if the value does not fit in a usize
+    fn into_address(self) -> Option<usize> {
+        match self {
+            Value::Address(address) => Some(address),
+            Value::Field(values) => values.first().and_then(|v| v.to_usize()),
+        }
+    }
+    fn into_field(self) -> Vec<BigInt> {
+        match self {
+            Value::Address(address) => vec![BigInt::from(address)],
+            Value::Field(values) => values,
+        }
+    }
+    fn into_element(self) -> BigInt {
+        self.into_field().into_iter().next().unwrap_or_default()
+    }
+}
+
+enum Flow {
+    Continue,
+    Return(Vec<BigInt>),
+}
+
+#[derive(Copy, Clone)]
+enum Location {
+    Variable(usize),
+    Signal(usize),
+}
+
+type ExecutionResult<T> = Result<T, Report>;
+
+pub struct WitnessCalculator<'a> {
+    circuit: &'a Circuit,
+    prime: BigInt,
+    constants: Vec<BigInt>,
+    functions: HashMap<&'a str, &'a FunctionCodeInfo>,
+    signal_values: Vec<BigInt>,
+    component_memory: Vec<ComponentMemory>,
+}
+
+impl<'a> WitnessCalculator<'a> {
+    pub fn new(circuit: &'a Circuit) -> WitnessCalculator<'a> {
+        let producer = &circuit.c_producer;
+        let prime = UsefulConstants::new(&producer.prime_str).get_p().clone();
+        // the constants are not reduced in the circuit, the generated code reduces them too
+        let constants = producer
+            .get_field_constant_list()
+            .iter()
+            .map(|c| BigInt::parse_bytes(c.as_bytes(), 10).expect("invalid field constant"))
+            .map(|c| c % &prime)
+            .collect();
+        let mut functions = HashMap::new();
+        for function in &circuit.functions {
+            functions.insert(function.header.as_str(), function.as_ref());
+        }
+        WitnessCalculator {
+            circuit,
+            prime,
+            constants,
+            functions,
+            signal_values: Vec::new(),
+            component_memory: Vec::new(),
+        }
+    }
+
+    pub fn get_prime(&self) -> &BigInt {
+        &self.prime
+    }
+
+    pub fn calculate_witness(&mut self, inputs: &InputValues) -> ExecutionResult<Vec<BigInt>> {
+        let producer = &self.circuit.c_producer;
+        self.signal_values = vec![BigInt::zero(); producer.get_total_number_of_signals()];
+        self.signal_values[0] = BigInt::from(1);
+        self.component_memory = Vec::new();
+        self.component_memory
+            .resize_with(producer.get_number_of_components(), Default::default);
+        self.set_main_inputs(inputs)?;
+
+        let main_id = self
+            .circuit
+            .templates
+            .iter()
+            .position(|t| t.header == producer.get_main_header())
+            .expect("the main template is not in the circuit");
+        let main_signal_start = producer.get_main_signal_offset();
+        let has_inputs = self.circuit.templates[main_id].number_of_inputs > 0;
+        self.create_component(main_id, main_signal_start, 0, "main".to_string(), 0)?;
+        if has_inputs {
+            self.run_template(0)?;
+        }
+
+        let witness = producer
+            .get_witness_to_signal_list()
+            .iter()
+            .map(|signal| self.signal_values[*signal].clone())
+            .collect();
+        Result::Ok(witness)
+    }
+
+    fn set_main_inputs(&mut self, inputs: &InputValues) -> ExecutionResult<()> {
+        let input_list = self.circuit.c_producer.get_main_input_list();
+        for name in inputs.keys() {
+            if !input_list.iter().any(|(input, _, _)| input == name) {
+                return Result::Err(input_error(format!("Signal not found: {}", name)));
+            }
+        }
+        for (name, start, size) in input_list {
+            let values = inputs.get(name).ok_or_else(|| {
+                input_error(format!(
+                    "Not all inputs have been set. Missing signal {}",
+                    name
+                ))
+            })?;
+            if values.len() < *size {
+                return Result::Err(input_error(format!(
+                    "Error loading signal {}: Not enough values",
+                    name
+                )));
+            }
+            if values.len() > *size {
+                return Result::Err(input_error(format!(
+                    "Error loading signal {}: Too many values",
+                    name
+                )));
+            }
+            for (i, value) in values.iter().enumerate() {
+                self.signal_values[start + i] = value.clone();
+            }
+        }
+        Result::Ok(())
+    }
+
+    fn create_component(
+        &mut self,
+        template_id: usize,
+        signal_start: usize,
+        cmp_index: usize,
+        component_name: String,
+        id_father: usize,
+    ) -> ExecutionResult<()> {
+        let template = self.circuit.get_template(template_id);
+        self.component_memory[cmp_index] = ComponentMemory {
+            template_id,
+            signal_start,
+            input_counter: template.number_of_inputs,
+            component_name,
+            id_father,
+            subcomponents: vec![0; template.number_of_components],
+        };
+        if template.number_of_inputs == 0 {
+            self.run_template(cmp_index)?;
+        }
+        Result::Ok(())
+    }
+
+    fn run_template(&mut self, ctx_index: usize) -> ExecutionResult<()> {
+        let circuit = self.circuit;
+        let component = &self.component_memory[ctx_index];
+        let template = circuit.get_template(component.template_id);
+        let mut frame = Frame {
+            ctx_index,
+            signal_start: component.signal_start,
+            name: &template.name,
+            vars: vec![BigInt::zero(); template.var_stack_depth],
+        };
+        self.execute_list(&template.body, &mut frame)?;
+        Result::Ok(())
+    }
+
+    fn get_trace(&self, ctx_index: usize) -> String {
+        let component = &self.component_memory[ctx_index];
+        if ctx_index == 0 {
+            component.component_name.clone()
+        } else {
+            format!(
+                "{}.{}",
+                self.get_trace(component.id_father),
+                component.component_name
+            )
+        }
+    }
+
+    fn execute_list(
+        &mut self,
+        list: &'a InstructionList,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<Flow> {
+        for instruction in list {
+            if let Flow::Return(values) = self.execute(instruction, frame)? {
+                return Result::Ok(Flow::Return(values));
+            }
+        }
+        Result::Ok(Flow::Continue)
+    }
+
+    fn execute(
+        &mut self,
+        instruction: &'a Instruction,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<Flow> {
+        use Instruction::*;
+        match instruction {
+            Store(bucket) => self.execute_store(bucket, frame)?,
+            Call(bucket) => {
+                self.execute_call(bucket, frame)?;
+            }
+            Branch(bucket) => {
+                let condition = self.evaluate(&bucket.cond, frame)?.into_element();
+                let branch = if self.is_true(&condition) {
+                    &bucket.if_branch
+                } else {
+                    &bucket.else_branch
+                };
+                return self.execute_list(branch, frame);
+            }
+            Loop(bucket) => loop {
+                let condition = self
+                    .evaluate(&bucket.continue_condition, frame)?
+                    .into_element();
+                if !self.is_true(&condition) {
+                    break;
+                }
+                if let Flow::Return(values) = self.execute_list(&bucket.body, frame)? {
+                    return Result::Ok(Flow::Return(values));
+                }
+            },
+            Return(bucket) => {
+                let values = self.evaluate(&bucket.value, frame)?.into_field();
+                return Result::Ok(Flow::Return(values));
+            }
+            Assert(bucket) => {
+                let value = self.evaluate(&bucket.evaluate, frame)?.into_element();
+                if !self.is_true(&value) {
+                    return Result::Err(Report::error(
+                        format!(
+                            "Failed assert in template/function {} line {}. Followed trace of components: {}",
+                            frame.name,
+                            bucket.line,
+                            self.get_trace(frame.ctx_index)
+                        ),
+                        ReportCode::WitnessAssertFailed,
+                    ));
+                }
+            }
+            Log(bucket) => self.execute_log(bucket, frame)?,
+            CreateCmp(bucket) => self.execute_create_cmp(bucket, frame)?,
+            Value(_) | Load(_) | Compute(_) => {
+                self.evaluate(instruction, frame)?;
+            }
+        }
+        Result::Ok(Flow::Continue)
+    }
+
+    fn evaluate(
+        &mut self,
+        instruction: &'a Instruction,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<Value> {
+        use Instruction::*;
+        match instruction {
+            Value(bucket) => match bucket.parse_as {
+                ValueType::U32 => Result::Ok(self::Value::Address(bucket.value)),
+                ValueType::BigInt => match self.constants.get(bucket.value) {
+                    Some(constant) => Result::Ok(self::Value::Field(vec![constant.clone()])),
+                    None => Result::Err(self.invalid_address("constant", bucket.line, frame)),
+                },
+            },
+            Load(bucket) => {
+                let (location, _) =
+                    self.resolve(&bucket.address_type, &bucket.src, bucket.line, frame)?;
+                let values = self.read(location, bucket.context.size, bucket.line, frame)?;
+                Result::Ok(self::Value::Field(values))
+            }
+            Compute(bucket) => self.evaluate_compute(bucket, frame),
+            Call(bucket) => self.execute_call(bucket, frame),
+            _ => unreachable!("instruction without value used as an expression"),
+        }
+    }
+
+    fn evaluate_compute(
+        &mut self,
+        bucket: &'a ComputeBucket,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<Value> {
+        use OperatorType::*;
+        let mut operands = Vec::with_capacity(bucket.stack.len());
+        for operand in &bucket.stack {
+            operands.push(self.evaluate(operand, frame)?);
+        }
+        let mut operands = operands.into_iter();
+        let mut next = || operands.next().expect("missing operand in compute bucket");
+        let field = &self.prime;
+        let result = match bucket.op {
+            AddAddress | MulAddress | ToAddress => {
+                let left = next().into_address();
+                let address = match bucket.op {
+                    AddAddress => left
+                        .zip(next().into_address())
+                        .and_then(|(l, r)| l.checked_add(r)),
+                    MulAddress => left
+                        .zip(next().into_address())
+                        .and_then(|(l, r)| l.checked_mul(r)),
+                    _ => left,
+                };
+                return match address {
+                    Some(address) => Result::Ok(Value::Address(address)),
+                    None => Result::Err(self.invalid_address("memory", bucket.line, frame)),
+                };
+            }
+            Eq(n) => {
+                let (left, right) = (next().into_field(), next().into_field());
+                let equal = (0..n).all(|i| {
+                    let zero = BigInt::zero();
+                    let l = left.get(i).unwrap_or(&zero);
+                    let r = right.get(i).unwrap_or(&zero);
+                    modular_arithmetic::eq(l, r, field).is_one()
+                });
+                BigInt::from(equal as u8)
+            }
+            PrefixSub => modular_arithmetic::prefix_sub(&next().into_element(), field),
+            BoolNot => modular_arithmetic::not(&next().into_element(), field),
+            Complement => modular_arithmetic::complement(&next().into_element(), field),
+            op => {
+                let (left, right) = (next().into_element(), next().into_element());
+                let result = match op {
+                    Mul => Result::Ok(modular_arithmetic::mul(&left, &right, field)),
+                    Div => modular_arithmetic::div(&left, &right, field),
+                    Add => Result::Ok(modular_arithmetic::add(&left, &right, field)),
+                    Sub => Result::Ok(modular_arithmetic::sub(&left, &right, field)),
+                    Pow => Result::Ok(modular_arithmetic::pow(&left, &right, field)),
+                    IntDiv => modular_arithmetic::idiv(&left, &right, field),
+                    Mod => modular_arithmetic::mod_op(&left, &right, field),
+                    ShiftL => modular_arithmetic::shift_l(&left, &right, field),
+                    ShiftR => modular_arithmetic::shift_r(&left, &right, field),
+                    LesserEq => Result::Ok(modular_arithmetic::lesser_eq(&left, &right, field)),
+                    GreaterEq => Result::Ok(modular_arithmetic::greater_eq(&left, &right, field)),
+                    Lesser => Result::Ok(modular_arithmetic::lesser(&left, &right, field)),
+                    Greater => Result::Ok(modular_arithmetic::greater(&left, &right, field)),
+                    NotEq => Result::Ok(modular_arithmetic::not_eq(&left, &right, field)),
+                    BoolOr => Result::Ok(modular_arithmetic::bool_or(&left, &right, field)),
+                    BoolAnd => Result::Ok(modular_arithmetic::bool_and(&left, &right, field)),
+                    BitOr => Result::Ok(modular_arithmetic::bit_or(&left, &right, field)),
+                    BitAnd => Result::Ok(modular_arithmetic::bit_and(&left, &right, field)),
+                    BitXor => Result::Ok(modular_arithmetic::bit_xor(&left, &right, field)),
+                    _ => unreachable!(),
+                };
+                result.map_err(|err| {
+                    let message = match err {
+                        ArithmeticError::DivisionByZero => "Division by zero",
+                        ArithmeticError::BitOverFlowInShift => "Overflow in shift operation",
+                    };
+                    Report::error(
+                        format!(
+                            "{} in template/function {} line {}. Followed trace of components: {}",
+                            message,
+                            frame.name,
+                            bucket.line,
+                            self.get_trace(frame.ctx_index)
+                        ),
+                        ReportCode::WitnessRuntimeError,
+                    )
+                })?
+            }
+        };
+        Result::Ok(Value::Field(vec![result]))
+    }
+
+    // Returns the position referenced by the address and, for subcomponent signals, the
+    // index of the subcomponent in the component memory
+    fn resolve(
+        &mut self,
+        address_type: &'a AddressType,
+        location: &'a LocationRule,
+        line: usize,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<(Location, Option<usize>)> {
+        let sub_cmp = if let AddressType::SubcmpSignal { cmp_address, .. } = address_type {
+            let cmp_index = self.evaluate_address(cmp_address, line, frame)?;
+            Some(self.subcomponent(frame, cmp_index, line)?)
+        } else {
+            None
+        };
+        let index = match location {
+            LocationRule::Indexed { location, .. } => {
+                self.evaluate_address(location, line, frame)?
+            }
+            LocationRule::Mapped {
+                signal_code,
+                indexes,
+            } => {
+                let io_def = sub_cmp.and_then(|sub_cmp| self.io_def(sub_cmp, *signal_code));
+                let (offset, lengths) = match io_def {
+                    Some(io_def) => io_def,
+                    None => return Result::Err(self.invalid_address("signal", line, frame)),
+                };
+                let mut map_index: Option<usize> = Some(0);
+                for (i, index) in indexes.iter().enumerate() {
+                    let index = self.evaluate_address(index, line, frame)?;
+                    map_index = if i == 0 {
+                        Some(index)
+                    } else {
+                        lengths
+                            .get(i)
+                            .and_then(|length| map_index?.checked_mul(*length))
+                            .and_then(|position| position.checked_add(index))
+                    };
+                }
+                match map_index.and_then(|index| index.checked_add(offset)) {
+                    Some(index) => index,
+                    None => return Result::Err(self.invalid_address("signal", line, frame)),
+                }
+            }
+        };
+        let location = match address_type {
+            AddressType::Variable => Some(Location::Variable(index)),
+            AddressType::Signal => frame.signal_start.checked_add(index).map(Location::Signal),
+            AddressType::SubcmpSignal { .. } => {
+                let start = self.component_memory[sub_cmp.unwrap()].signal_start;
+                start.checked_add(index).map(Location::Signal)
+            }
+        };
+        match location {
+            Some(location) => Result::Ok((location, sub_cmp)),
+            None => Result::Err(self.invalid_address("signal", line, frame)),
+        }
+    }
+
+    fn evaluate_address(
+        &mut self,
+        instruction: &'a Instruction,
+        line: usize,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<usize> {
+        match self.evaluate(instruction, frame)?.into_address() {
+            Some(address) => Result::Ok(address),
+            None => Result::Err(self.invalid_address("memory", line, frame)),
+        }
+    }
+
+    // Position in the component memory of the subcomponent with the given index
+    fn subcomponent(
+        &self,
+        frame: &Frame<'a>,
+        cmp_index: usize,
+        line: usize,
+    ) -> ExecutionResult<usize> {
+        let subcomponents = &self.component_memory[frame.ctx_index].subcomponents;
+        match subcomponents.get(cmp_index) {
+            Some(sub_cmp) if *sub_cmp < self.component_memory.len() => Result::Ok(*sub_cmp),
+            _ => Result::Err(self.invalid_address("component", line, frame)),
+        }
+    }
+
+    // Offset and lengths of the signal with the given code in the subcomponent
+    fn io_def(&self, sub_cmp: usize, signal_code: usize) -> Option<(usize, Vec<usize>)> {
+        let template_id = self.component_memory[sub_cmp].template_id;
+        let io_list = self.circuit.c_producer.get_io_map().get(&template_id)?;
+        let def = io_list.iter().find(|def| def.code == signal_code)?;
+        Some((def.offset, def.lengths.clone()))
+    }
+
+    fn read(
+        &self,
+        location: Location,
+        size: usize,
+        line: usize,
+        frame: &Frame<'a>,
+    ) -> ExecutionResult<Vec<BigInt>> {
+        let (memory, start, kind) = match location {
+            Location::Variable(start) => (&frame.vars, start, "variable"),
+            Location::Signal(start) => (&self.signal_values, start, "signal"),
+        };
+        match start
+            .checked_add(size)
+            .and_then(|end| memory.get(start..end))
+        {
+            Some(values) => Result::Ok(values.to_vec()),
+            None => Result::Err(self.invalid_address(kind, line, frame)),
+        }
+    }
+
+    fn write(
+        &mut self,
+        location: Location,
+        values: Vec<BigInt>,
+        size: usize,
+        line: usize,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<()> {
+        let (memory, start, kind) = match location {
+            Location::Variable(start) => (&mut frame.vars, start, "variable"),
+            Location::Signal(start) => (&mut self.signal_values, start, "signal"),
+        };
+        match start
+            .checked_add(size)
+            .and_then(|end| memory.get_mut(start..end))
+        {
+            Some(destination) => {
+                for (place, value) in destination.iter_mut().zip(values) {
+                    *place = value;
+                }
+                Result::Ok(())
+            }
+            None => Result::Err(self.invalid_address(kind, line, frame)),
+        }
+    }
+
+    // The generated code does not check the addresses, so an invalid one means that the
+    // IR is not consistent with the circuit
+    fn invalid_address(&self, kind: &str, line: usize, frame: &Frame<'a>) -> Report {
+        Report::error(
+            format!(
+                "Invalid {} address in template/function {} line {}. Followed trace of components: {}",
+                kind,
+                frame.name,
+                line,
+                self.get_trace(frame.ctx_index)
+            ),
+            ReportCode::WitnessRuntimeError,
+        )
+    }
+
+    fn store_result(
+        &mut self,
+        address_type: &'a AddressType,
+        location: &'a LocationRule,
+        size: usize,
+        values: Vec<BigInt>,
+        line: usize,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<()> {
+        let (dest, sub_cmp) = self.resolve(address_type, location, line, frame)?;
+        self.write(dest, values, size, line, frame)?;
+        if let Location::Signal(start) = dest {
+            self.trace_signals(start, size);
+        }
+        if let AddressType::SubcmpSignal {
+            input_information, ..
+        } = address_type
+        {
+            // like in the generated code, update the counters and run the subcomponent if needed
+            let sub_cmp = sub_cmp.unwrap();
+            if let InputInformation::Input { status } = input_information {
+                let counter = &mut self.component_memory[sub_cmp].input_counter;
+                *counter = counter.saturating_sub(size);
+                let run = match status {
+                    StatusInput::NoLast => false,
+                    StatusInput::Last => true,
+                    StatusInput::Unknown => *counter == 0,
+                };
+                if run {
+                    self.run_template(sub_cmp)?;
+                }
+            }
+        }
+        Result::Ok(())
+    }
+
+    // Same output as traceSignals in the generated code
+    fn trace_signals(&self, start: usize, size: usize) {
+        let names = self.circuit.c_producer.get_trace_signal_names();
+        for signal in start..start + size {
+            if let Some(name) = names.get(&signal) {
+                println!("[trace] {} = {}", name, self.signal_values[signal]);
+            }
+        }
+    }
+
+    fn execute_store(
+        &mut self,
+        bucket: &'a StoreBucket,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<()> {
+        if bucket.context.size == 0 {
+            return Result::Ok(());
+        }
+        let values = self.evaluate(&bucket.src, frame)?.into_field();
+        self.store_result(
+            &bucket.dest_address_type,
+            &bucket.dest,
+            bucket.context.size,
+            values,
+            bucket.line,
+            frame,
+        )
+    }
+
+    fn execute_call(
+        &mut self,
+        bucket: &'a CallBucket,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<Value> {
+        let function = match self.functions.get(bucket.symbol.as_str()) {
+            Some(function) => *function,
+            None => return Result::Err(self.invalid_address("function", bucket.line, frame)),
+        };
+        let arena_size = std::cmp::max(bucket.arena_size, function.max_number_of_vars);
+        let mut callee = Frame {
+            ctx_index: frame.ctx_index,
+            signal_start: frame.signal_start,
+            name: &function.name,
+            vars: vec![BigInt::zero(); arena_size],
+        };
+        let mut count = 0;
+        for (argument, argument_type) in bucket.arguments.iter().zip(&bucket.argument_types) {
+            let values = self.evaluate(argument, frame)?.into_field();
+            let size = argument_type.size;
+            let location = Location::Variable(count);
+            self.write(location, values, size, bucket.line, &mut callee)?;
+            count += size;
+        }
+        let result = match self.execute_list(&function.body, &mut callee)? {
+            Flow::Return(values) => values,
+            Flow::Continue => Vec::new(),
+        };
+        match &bucket.return_info {
+            ReturnType::Intermediate { .. } => Result::Ok(Value::Field(vec![result
+                .into_iter()
+                .next()
+                .unwrap_or_default()])),
+            ReturnType::Final(data) => {
+                self.store_result(
+                    &data.dest_address_type,
+                    &data.dest,
+                    data.context.size,
+                    result,
+                    bucket.line,
+                    frame,
+                )?;
+                Result::Ok(Value::Field(Vec::new()))
+            }
+        }
+    }
+
+    fn execute_create_cmp(
+        &mut self,
+        bucket: &'a CreateCmpBucket,
+        frame: &mut Frame<'a>,
+    ) -> ExecutionResult<()> {
+        let aux_create = self.evaluate_address(&bucket.sub_cmp_id, bucket.line, frame)?;
+        let positions: Vec<usize> = if bucket.defined_positions.len() == bucket.number_of_cmp {
+            (0..bucket.number_of_cmp).collect()
+        } else {
+            bucket
+                .defined_positions
+                .iter()
+                .map(|(position, _)| *position)
+                .collect()
+        };
+        let mut cmp_num = bucket.component_offset + frame.ctx_index + 1;
+        let mut signal_offset = frame.signal_start + bucket.signal_offset;
+        for i in positions {
+            let name = if bucket.number_of_cmp > 1 {
+                format!(
+                    "{}{}",
+                    bucket.name_subcomponent,
+                    position_as_string(&bucket.dimensions, i)
+                )
+            } else {
+                bucket.name_subcomponent.clone()
+            };
+            let position = aux_create.checked_add(i);
+            let subcomponents = &mut self.component_memory[frame.ctx_index].subcomponents;
+            match position.and_then(|position| subcomponents.get_mut(position)) {
+                Some(subcomponent) => *subcomponent = cmp_num,
+                None => return Result::Err(self.invalid_address("component", bucket.line, frame)),
+            }
+            if cmp_num >= self.component_memory.len()
+                || bucket.template_id >= self.circuit.templates.len()
+            {
+                return Result::Err(self.invalid_address("component", bucket.line, frame));
+            }
+            self.create_component(
+                bucket.template_id,
+                signal_offset,
+                cmp_num,
+                name,
+                frame.ctx_index,
+            )?;
+            signal_offset += bucket.signal_offset_jump;
+            cmp_num += bucket.component_offset_jump;
+        }
+        Result::Ok(())
+    }
+
+    fn execute_log(&mut self, bucket: &'a LogBucket, frame: &mut Frame<'a>) -> ExecutionResult<()> {
+        let mut arguments = Vec::new();
+        for argument in &bucket.argsprint {
+            match argument {
+                LogBucketArg::LogExp(exp) => {
+                    let value = self.evaluate(exp, frame)?.into_element();
+                    arguments.push(value.to_str_radix(10));
+                }
+                LogBucketArg::LogStr(string_id) => {
+                    arguments.push(self.circuit.c_producer.get_string_table()[*string_id].clone());
+                }
+            }
+        }
+        println!("{}", arguments.join(" "));
+        Result::Ok(())
+    }
+
+    fn is_true(&self, value: &BigInt) -> bool {
+        modular_arithmetic::as_bool(value, &self.prime)
+    }
+}
+
+// Same naming as generate_position_array in calcwit.cpp
+fn position_as_string(dimensions: &[usize], mut index: usize) -> String {
+    let mut positions = String::new();
+    for dimension in dimensions.iter().rev() {
+        positions = format!("[{}]{}", index % dimension, positions);
+        index /= dimension;
+    }
+    positions
+}
+
+fn input_error(message: String) -> Report {
+    Report::error(message, ReportCode::WitnessInputError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_reader::parse_inputs;
+    use compiler::compiler_interface::{run_compiler, Config, OptimizationLevel};
+    use constraint_generation::{build_circuit, BuildConfig};
+
+    const SOURCE: &str = "pragma circom 2.0.0;
+        template Square() {
+            signal input in;
+            signal output out;
+            out <== in * in;
+        }
+        template Main(n) {
+            signal input a[n];
+            signal output b[n];
+            signal output c;
+            component sq[n];
+            var acc = 0;
+            for (var i = 0; i < n; i++) {
+                sq[i] = Square();
+                sq[i].in <== a[i];
+                b[i] <== sq[i].out;
+                acc += a[i];
+            }
+            c <-- acc \\ 2;
+            assert(c != 7);
+        }
+        component main = Main(3);";
+
+    fn build_test_circuit() -> Circuit {
+        use parser::source_provider::MemoryProvider;
+        let mut provider = MemoryProvider::new();
+        provider.add_file("main.circom", SOURCE.to_string());
+        let (mut program, _) = parser::run_parser_with_provider(
+            "main.circom".to_string(),
+            "2.1.6",
+            Vec::new(),
+            &provider,
+        )
+        .ok()
+        .unwrap();
+        type_analysis::check_types::check_types(&mut program)
+            .ok()
+            .unwrap();
+        let config = BuildConfig {
+            no_rounds: usize::MAX,
+            flag_json_sub: false,
+            flag_s: false,
+            flag_f: false,
+            flag_p: false,
+            flag_verbose: false,
+            flag_old_heuristics: false,
+            inspect_constraints: false,
+            prime: "bn128".to_string(),
+            cache_dir: None,
+            threads: 1,
+        };
+        let (_, vcp) = build_circuit(program, config).unwrap();
+        let config = Config {
+            produce_input_log: false,
+            wat_flag: false,
+            trace: None,
+            ir_optimization: OptimizationLevel::O0,
+        };
+        run_compiler(vcp, config, "2.1.6").unwrap()
+    }
+
+    #[test]
+    fn witness_calculation() {
+        let circuit = build_test_circuit();
+        let mut calculator = WitnessCalculator::new(&circuit);
+        let prime = calculator.get_prime().clone();
+
+        let inputs = parse_inputs("{\"a\": [\"3\", 4, -2]}", &prime)
+            .ok()
+            .unwrap();
+        let witness = calculator.calculate_witness(&inputs).ok().unwrap();
+        // main outputs, then main inputs
+        let mut expected: Vec<BigInt> = vec![1, 9, 16, 4, 2, 3, 4]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        expected.push(&prime - 2);
+        assert_eq!(witness[..expected.len()], expected[..]);
+
+        let inputs = parse_inputs("{\"a\": [10, 4, 0]}", &prime).ok().unwrap();
+        assert!(calculator.calculate_witness(&inputs).is_err());
+        let inputs = parse_inputs("{\"a\": [1, 2]}", &prime).ok().unwrap();
+        assert!(calculator.calculate_witness(&inputs).is_err());
+    }
+
+    fn u32_value(value: usize) -> InstructionPointer {
+        ValueBucket {
+            line: 0,
+            message_id: 0,
+            parse_as: ValueType::U32,
+            op_aux_no: 0,
+            value,
+        }
+        .allocate()
+    }
+
+    fn indexed(value: usize) -> LocationRule {
+        LocationRule::Indexed {
+            location: u32_value(value),
+            template_header: None,
+        }
+    }
+
+    #[test]
+    fn invalid_addresses() {
+        let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let inputs = parse_inputs("{\"a\": [1, 2, 3]}", &prime).ok().unwrap();
+        let load_signal = LoadBucket {
+            line: 0,
+            message_id: 0,
+            address_type: AddressType::Signal,
+            src: indexed(100000),
+            context: InstrContext { size: 1 },
+        };
+        let store_variable = StoreBucket {
+            line: 0,
+            message_id: 0,
+            context: InstrContext { size: 1 },
+            dest_is_output: false,
+            dest_address_type: AddressType::Variable,
+            dest: indexed(100000),
+            src: u32_value(1),
+        };
+        let load_subcomponent = LoadBucket {
+            line: 0,
+            message_id: 0,
+            address_type: AddressType::SubcmpSignal {
+                cmp_address: u32_value(100000),
+                uniform_parallel_value: None,
+                is_output: true,
+                input_information: InputInformation::NoInput,
+            },
+            src: indexed(0),
+            context: InstrContext { size: 1 },
+        };
+        let huge_address = ComputeBucket {
+            line: 0,
+            message_id: 0,
+            op: OperatorType::MulAddress,
+            op_aux_no: 0,
+            stack: vec![u32_value(usize::MAX), u32_value(2)],
+        };
+        for instruction in [
+            load_signal.allocate(),
+            store_variable.allocate(),
+            load_subcomponent.allocate(),
+            huge_address.allocate(),
+        ] {
+            let mut circuit = build_test_circuit();
+            let main = circuit.templates.len() - 1;
+            circuit.templates[main].body.push(instruction);
+            let error = WitnessCalculator::new(&circuit)
+                .calculate_witness(&inputs)
+                .err()
+                .unwrap();
+            assert!(error.get_message().starts_with("Invalid"));
+        }
+    }
+
+    #[test]
+    fn constants_are_reduced() {
+        let mut circuit = build_test_circuit();
+        // 10 * p + 5
+        let constant = format!("{}5", circuit.c_producer.prime);
+        circuit.c_producer.field_tracking.push(constant);
+        let calculator = WitnessCalculator::new(&circuit);
+        assert_eq!(calculator.constants.last(), Some(&BigInt::from(5)));
+    }
+}
@@ -0,0 +1,136 @@
+use num_bigint::{BigInt, Sign};
+use std::convert::TryInto;
+
+const MAGIC: &[u8] = b"wtns";
+const HEADER_TYPE: u32 = 1;
+const WITNESS_TYPE: u32 = 2;
+
+pub struct WitnessData {
+    pub prime: BigInt,
+    pub witness: Vec<BigInt>,
+}
+
+fn read_u32(data: &[u8], position: usize) -> Result<u32, ()> {
+    let bytes = data.get(position..position + 4).ok_or(())?;
+    Result::Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], position: usize) -> Result<u64, ()> {
+    let bytes = data.get(position..position + 8).ok_or(())?;
+    Result::Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_bigint(data: &[u8], position: usize, field_size: usize) -> Result<BigInt, ()> {
+    let bytes = data.get(position..position + field_size).ok_or(())?;
+    Result::Ok(BigInt::from_bytes_le(Sign::Plus, bytes))
+}
+
+pub fn read_wtns(file: &str) -> Result<WitnessData, ()> {
+    let data = std::fs::read(file).map_err(|_err| {})?;
+    parse_wtns(&data)
+}
+
+pub fn parse_wtns(data: &[u8]) -> Result<WitnessData, ()> {
+    if data.get(0..4) != Some(MAGIC) {
+        return Result::Err(());
+    }
+    let number_of_sections = read_u32(&data, 8)?;
+    let mut position = 12;
+    let mut header = None;
+    let mut witness_section = None;
+    for _ in 0..number_of_sections {
+        let section_type = read_u32(&data, position)?;
+        let section_size = read_u64(&data, position + 4)? as usize;
+        let start = position + 12;
+        let end = match start.checked_add(section_size) {
+            Some(end) if end <= data.len() => end,
+            _ => return Result::Err(()),
+        };
+        match section_type {
+            HEADER_TYPE => header = Some(start),
+            WITNESS_TYPE => witness_section = Some((start, section_size)),
+            _ => {}
+        }
+        position = end;
+    }
+
+    let header = header.ok_or(())?;
+    let (start, size) = witness_section.ok_or(())?;
+    let field_size = read_u32(&data, header)? as usize;
+    if field_size == 0 || size % field_size != 0 {
+        return Result::Err(());
+    }
+    let prime = read_bigint(&data, header + 4, field_size)?;
+    let number_of_values = read_u32(&data, header + 4 + field_size)? as usize;
+    // the values fill the witness section, which is inside the file, before reserving memory
+    if number_of_values.checked_mul(field_size) != Some(size) {
+        return Result::Err(());
+    }
+    let mut witness = Vec::with_capacity(number_of_values);
+    for i in 0..number_of_values {
+        witness.push(read_bigint(&data, start + i * field_size, field_size)?);
+    }
+    Result::Ok(WitnessData { prime, witness })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wtns_writer::write_wtns_with;
+
+    // The bytes of the witness written with the writer
+    fn written(witness: &[i64]) -> Vec<u8> {
+        let witness: Vec<BigInt> = witness.iter().map(|value| BigInt::from(*value)).collect();
+        let mut bytes = Vec::new();
+        write_wtns_with(&mut bytes, &BigInt::from(65521), &witness).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn read_written_wtns() {
+        let data = parse_wtns(&written(&[1, 12, 3, 65520])).ok().unwrap();
+        assert_eq!(data.prime, BigInt::from(65521));
+        let expected: Vec<BigInt> = vec![1, 12, 3, 65520]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        assert_eq!(data.witness, expected);
+    }
+
+    #[test]
+    fn invalid_wtns() {
+        let bytes = written(&[1, 12, 3]);
+        // magic, version and number of sections, then the header section: type, size, field
+        // size, prime and number of values, and the witness section: type, size and values
+        let witness_section = 12 + 12 + 4 + 8 + 4;
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'x';
+        assert!(parse_wtns(&wrong_magic).is_err());
+
+        assert!(parse_wtns(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse_wtns(&bytes[..20]).is_err());
+
+        let mut wrong_count = bytes.clone();
+        wrong_count[12 + 12 + 4 + 8] = 4;
+        assert!(parse_wtns(&wrong_count).is_err());
+
+        let mut wrong_size = bytes.clone();
+        wrong_size[witness_section + 4] = 20;
+        assert!(parse_wtns(&wrong_size).is_err());
+
+        let mut huge_size = bytes.clone();
+        huge_size[witness_section + 4..witness_section + 12].copy_from_slice(&[0xff; 8]);
+        assert!(parse_wtns(&huge_size).is_err());
+
+        let mut huge_count = bytes.clone();
+        huge_count[12 + 12 + 4 + 8..12 + 12 + 4 + 8 + 4].copy_from_slice(&[0xff; 4]);
+        assert!(parse_wtns(&huge_count).is_err());
+
+        let mut no_witness = bytes.clone();
+        no_witness[witness_section] = 3;
+        assert!(parse_wtns(&no_witness).is_err());
+
+        assert!(parse_wtns(&bytes).is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+use num_bigint::BigInt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const MAGIC: &[u8] = b"wtns";
+const VERSION: &[u8] = &[2, 0, 0, 0];
+const SECTIONS: &[u8] = &[2, 0, 0, 0];
+const HEADER_TYPE: &[u8] = &[1, 0, 0, 0];
+const WITNESS_TYPE: &[u8] = &[2, 0, 0, 0];
+
+// Number of bytes used to store a field element, always a multiple of 8
+pub fn field_size(prime: &BigInt) -> usize {
+    (prime.bits() - 1) / 64 * 8 + 8
+}
+
+fn bigint_as_bytes(number: &BigInt, with_bytes: usize) -> Vec<u8> {
+    let (_, mut value) = number.to_bytes_le();
+    value.resize(with_bytes, 0);
+    value
+}
+
+pub fn write_wtns(file: &str, prime: &BigInt, witness: &[BigInt]) -> Result<(), ()> {
+    let file = File::create(file).map_err(|_err| {})?;
+    write_wtns_with(&mut BufWriter::new(file), prime, witness)
+}
+
+pub fn write_wtns_with(
+    writer: &mut dyn Write,
+    prime: &BigInt,
+    witness: &[BigInt],
+) -> Result<(), ()> {
+    let field_size = field_size(prime);
+    writer.write_all(MAGIC).map_err(|_err| {})?;
+    writer.write_all(VERSION).map_err(|_err| {})?;
+    writer.write_all(SECTIONS).map_err(|_err| {})?;
+
+    // Header: field size, prime and number of witness values
+    let header_size = (8 + field_size) as u64;
+    writer.write_all(HEADER_TYPE).map_err(|_err| {})?;
+    writer
+        .write_all(&header_size.to_le_bytes())
+        .map_err(|_err| {})?;
+    writer
+        .write_all(&(field_size as u32).to_le_bytes())
+        .map_err(|_err| {})?;
+    writer
+        .write_all(&bigint_as_bytes(prime, field_size))
+        .map_err(|_err| {})?;
+    writer
+        .write_all(&(witness.len() as u32).to_le_bytes())
+        .map_err(|_err| {})?;
+
+    // Witness values in little endian, normal (non-Montgomery) form
+    let witness_size = (field_size * witness.len()) as u64;
+    writer.write_all(WITNESS_TYPE).map_err(|_err| {})?;
+    writer
+        .write_all(&witness_size.to_le_bytes())
+        .map_err(|_err| {})?;
+    for value in witness {
+        writer
+            .write_all(&bigint_as_bytes(value, field_size))
+            .map_err(|_err| {})?;
+    }
+    writer.flush().map_err(|_err| {})
+}